@@ -0,0 +1,46 @@
+// Generate the C header with cbindgen when the "header" feature is enabled.
+//
+// The header is written to the directory given by the
+// DEMES_FORWARD_CAPI_HEADER_DIR environment variable.
+// Otherwise, it is written to OUT_DIR/include.
+//
+// Failures to generate the header are reported as
+// warnings so that they never fail the build of the library.
+
+#[cfg(feature = "header")]
+fn generate_header() -> Result<(), String> {
+    use std::path::PathBuf;
+
+    let crate_dir = PathBuf::from(
+        std::env::var_os("CARGO_MANIFEST_DIR").ok_or("CARGO_MANIFEST_DIR is not set")?,
+    );
+    let header_dir = match std::env::var_os("DEMES_FORWARD_CAPI_HEADER_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            PathBuf::from(std::env::var_os("OUT_DIR").ok_or("OUT_DIR is not set")?).join("include")
+        }
+    };
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))?;
+    std::fs::create_dir_all(&header_dir)
+        .map_err(|e| format!("could not create {}: {}", header_dir.to_string_lossy(), e))?;
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .map_err(|e| e.to_string())?
+        .write_to_file(header_dir.join("demes_forward.h"));
+    Ok(())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "header")]
+    {
+        println!("cargo:rerun-if-changed=src");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        println!("cargo:rerun-if-env-changed=DEMES_FORWARD_CAPI_HEADER_DIR");
+        if let Err(e) = generate_header() {
+            println!("cargo:warning=could not generate demes_forward.h: {}", e);
+        }
+    }
+}
@@ -31,8 +31,7 @@ process_model(const char* file)
     int32_t status;
     double end_time;
     const double* model_time;
-    const double* parental_deme_sizes;
-    const double* offspring_deme_sizes;
+    struct forward_graph_state state;
     const double* ancestry_proportions;
     intptr_t num_demes;
     size_t child;
@@ -72,23 +71,21 @@ process_model(const char* file)
                     goto out;
                 }
             assert(!forward_graph_is_error_state(graph));
-            parental_deme_sizes = forward_graph_parental_deme_sizes(graph, &status);
-            if (status != 0)
-                {
-                    goto out;
-                }
-            assert(parental_deme_sizes != NULL);
-            offspring_deme_sizes = forward_graph_offspring_deme_sizes(graph, &status);
+            /* Get the deme sizes and selfing/cloning rates with one call */
+            status = forward_graph_get_state(graph, &state);
             if (status != 0)
                 {
                     goto out;
                 }
+            assert(state.time == *model_time);
+            assert(state.num_demes == (size_t)num_demes);
+            assert(state.parental_deme_sizes != NULL);
             if (*model_time < end_time - 1.0)
                 {
-                    assert(offspring_deme_sizes != NULL);
+                    assert(state.offspring_deme_sizes != NULL);
                     for (child = 0; child < num_demes; ++child)
                         {
-                            if (offspring_deme_sizes[child] > 0.0)
+                            if (state.offspring_deme_sizes[child] > 0.0)
                                 {
                                     ancestry_proportions
                                         = forward_graph_ancestry_proportions(
@@ -97,15 +94,15 @@ process_model(const char* file)
                                         {
                                             goto out;
                                         }
-                                    validate_ancestry_proportions(ancestry_proportions,
-                                                                  parental_deme_sizes,
-                                                                  num_demes);
+                                    validate_ancestry_proportions(
+                                        ancestry_proportions, state.parental_deme_sizes,
+                                        num_demes);
                                 }
                         }
                 }
             else
                 {
-                    assert(offspring_deme_sizes == NULL);
+                    assert(state.offspring_deme_sizes == NULL);
                 }
         }
 out:
@@ -4,10 +4,16 @@ use std::ffi::CStr;
 use std::ffi::CString;
 use std::io::Read;
 
+mod sync;
+
+pub use sync::*;
+
 /// ## Not Send/Sync
 ///
 /// This type is meant to be used in an FFI context.
-/// We therefore deny Send/Sync:
+/// We therefore deny Send/Sync.
+/// See [`OpaqueForwardGraphSync`] for a type that
+/// may be shared between threads.
 ///
 /// ```compile_fail
 /// fn is_send<T: Send>()  {}
@@ -20,9 +26,11 @@ use std::io::Read;
 ///
 /// is_send::<demes_forward_capi::OpaqueForwardGraph>();
 /// ```
+#[derive(Clone)]
 pub struct OpaqueForwardGraph {
     graph: Option<demes_forward::ForwardGraph>,
     error: Option<CString>,
+    error_code: ForwardGraphErrorCode,
     current_time: Option<f64>,
     // Rust types containing raw pointers
     // do not get blanket Send/Sync impl.
@@ -31,20 +39,105 @@ pub struct OpaqueForwardGraph {
     deny_send_sync: std::marker::PhantomData<*const ()>,
 }
 
+/// The model state at one parental generation.
+///
+/// Filled by [`forward_graph_get_state`].
+/// All arrays have length `num_demes`.
+/// The pointers are invalidated by any function
+/// that changes the state of the graph.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct forward_graph_state {
+    /// The parental generation time, or NaN if
+    /// the state has not been updated.
+    pub time: f64,
+    /// The number of demes in the model.
+    pub num_demes: usize,
+    /// The parental deme sizes.
+    pub parental_deme_sizes: *const f64,
+    /// The offspring deme sizes.
+    /// NULL if there are no offspring demes.
+    pub offspring_deme_sizes: *const f64,
+    /// The selfing rates of the offspring demes.
+    /// NULL if there are no offspring demes.
+    pub selfing_rates: *const f64,
+    /// The cloning rates of the offspring demes.
+    /// NULL if there are no offspring demes.
+    pub cloning_rates: *const f64,
+}
+
+/// Error codes.
+///
+/// Functions returning an `int32_t`, or setting a `status`
+/// argument, use these values.
+/// Zero means success and all errors are negative.
+///
+/// A NULL `status` argument cannot receive a code.
+/// In that case, functions return their error value
+/// (NULL, NaN, zero, or `false`) without doing anything else.
 #[repr(i32)]
-enum ErrorCode {
-    // GraphUninitialized = -1,
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ForwardGraphErrorCode {
+    /// No error.
+    Success = 0,
+    /// The graph has not been initialized from a model,
+    /// or initialization failed.
+    GraphUninitialized = -1,
+    /// The pointer to the graph is NULL.
     GraphIsNull = -2,
+    /// The input could not be read or parsed.
+    ParseError = -3,
+    /// The input does not describe a valid demes graph,
+    /// or the graph cannot be iterated forwards in time.
+    InvalidGraph = -4,
+    /// [`forward_graph_iterate_time`] was called before
+    /// [`forward_graph_initialize_time_iteration`].
+    IterationNotInitialized = -5,
+    /// A deme index is out of range.
+    IndexOutOfRange = -6,
+    /// A time value is not valid for the model.
+    InvalidTime = -7,
+    /// An output buffer is too small.
+    BufferTooSmall = -8,
+    /// A pointer argument other than the graph,
+    /// such as an output buffer, is NULL.
+    NullPointer = -9,
+}
+
+impl From<&demes::DemesError> for ForwardGraphErrorCode {
+    fn from(value: &demes::DemesError) -> Self {
+        match value {
+            demes::DemesError::YamlError(_) => ForwardGraphErrorCode::ParseError,
+            _ => ForwardGraphErrorCode::InvalidGraph,
+        }
+    }
+}
+
+impl From<&demes_forward::DemesForwardError> for ForwardGraphErrorCode {
+    fn from(value: &demes_forward::DemesForwardError) -> Self {
+        match value {
+            demes_forward::DemesForwardError::TimeError(_) => ForwardGraphErrorCode::InvalidTime,
+            _ => ForwardGraphErrorCode::InvalidGraph,
+        }
+    }
 }
 
 impl OpaqueForwardGraph {
-    fn update(&mut self, graph: Option<demes_forward::ForwardGraph>, error: Option<String>) {
+    fn update(
+        &mut self,
+        graph: Option<demes_forward::ForwardGraph>,
+        error: Option<(ForwardGraphErrorCode, String)>,
+    ) {
         self.graph = graph;
         self.update_error(error);
     }
 
-    fn update_error(&mut self, error: Option<String>) {
-        self.error = error.map(|e| {
+    fn update_error(&mut self, error: Option<(ForwardGraphErrorCode, String)>) {
+        self.error_code = match &error {
+            Some((code, _)) => *code,
+            None => ForwardGraphErrorCode::Success,
+        };
+        self.error = error.map(|(_, e)| {
             CString::new(
                 e.chars()
                     .filter(|c| c.is_ascii() && c != &'"')
@@ -53,6 +146,14 @@ impl OpaqueForwardGraph {
             .unwrap()
         });
     }
+
+    // The code to report when there is no graph.
+    fn uninitialized_error_code(&self) -> ForwardGraphErrorCode {
+        match self.error_code {
+            ForwardGraphErrorCode::Success => ForwardGraphErrorCode::GraphUninitialized,
+            code => code,
+        }
+    }
 }
 
 /// Allocate an [`OpaqueForwardGraph`]
@@ -70,11 +171,41 @@ pub extern "C" fn forward_graph_allocate() -> *mut OpaqueForwardGraph {
     Box::into_raw(Box::new(OpaqueForwardGraph {
         graph: None,
         error: None,
+        error_code: ForwardGraphErrorCode::Success,
         current_time: None,
         deny_send_sync: std::marker::PhantomData,
     }))
 }
 
+/// Allocate a deep copy of an [`OpaqueForwardGraph`].
+///
+/// The copy includes the model state, the state of time
+/// iteration, and any error state.
+/// Thus, iteration over the copy continues from where
+/// iteration over `graph` was when the copy was made.
+///
+/// # Returns
+///
+/// * NULL if `graph` is NULL.
+///
+/// # Panics
+///
+/// This function will panic if the pointer allocation fails.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer or NULL.
+/// The pointer returned is freed by [`forward_graph_deallocate`].
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_clone(
+    graph: *const OpaqueForwardGraph,
+) -> *mut OpaqueForwardGraph {
+    if graph.is_null() {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new((*graph).clone()))
+}
+
 unsafe fn yaml_to_owned(yaml: *const c_char) -> Option<String> {
     if yaml.is_null() {
         return None;
@@ -91,20 +222,27 @@ unsafe fn process_input_yaml(
     graph: *mut OpaqueForwardGraph,
 ) -> (i32, Option<demes::Graph>) {
     if graph.is_null() {
-        return (ErrorCode::GraphIsNull as i32, None);
+        return (ForwardGraphErrorCode::GraphIsNull as i32, None);
     }
     let yaml = match yaml_to_owned(yaml) {
         Some(s) => s,
         None => {
-            (*graph).update(None, Some("could not convert c_char to String".to_string()));
-            return (-1, None);
+            (*graph).update(
+                None,
+                Some((
+                    ForwardGraphErrorCode::ParseError,
+                    "could not convert c_char to String".to_string(),
+                )),
+            );
+            return (ForwardGraphErrorCode::ParseError as i32, None);
         }
     };
     match demes::loads(&yaml) {
         Ok(graph) => (0, Some(graph)),
         Err(e) => {
-            (*graph).update(None, Some(format!("{e}")));
-            (-1, None)
+            let code = ForwardGraphErrorCode::from(&e);
+            (*graph).update(None, Some((code, format!("{e}"))));
+            (code as i32, None)
         }
     }
 }
@@ -128,15 +266,23 @@ pub unsafe extern "C" fn forward_graph_initialize_from_yaml(
         }
         (y, None) => {
             assert!(y < 0);
-            return -1;
+            return y;
         }
     };
 
     match demes_forward::ForwardGraph::new_discrete_time(dg, burnin) {
-        Ok(fgraph) => (*graph).update(Some(fgraph), None),
-        Err(e) => (*graph).update(None, Some(format!("{e}"))),
-    };
-    0
+        Ok(fgraph) => {
+            (*graph).update(Some(fgraph), None);
+            0
+        }
+        Err(e) => {
+            (*graph).update(
+                None,
+                Some((ForwardGraphErrorCode::InvalidGraph, format!("{e}"))),
+            );
+            ForwardGraphErrorCode::InvalidGraph as i32
+        }
+    }
 }
 
 /// Initialize and round epoch start/end sizes.
@@ -162,26 +308,38 @@ pub unsafe extern "C" fn forward_graph_initialize_from_yaml_round_epoch_sizes(
         }
         (y, None) => {
             assert!(y < 0);
-            return -1;
+            return y;
         }
     };
     let dg = match dg.into_integer_start_end_sizes() {
         Ok(graph) => graph,
         Err(e) => {
-            (*graph).update(None, Some(format!("{e}")));
-            return -1;
+            (*graph).update(
+                None,
+                Some((ForwardGraphErrorCode::InvalidGraph, format!("{e}"))),
+            );
+            return ForwardGraphErrorCode::InvalidGraph as i32;
         }
     };
     match demes_forward::ForwardGraph::new_discrete_time(dg, burnin) {
-        Ok(fgraph) => (*graph).update(Some(fgraph), None),
-        Err(e) => (*graph).update(None, Some(format!("{e}"))),
-    };
-    0
+        Ok(fgraph) => {
+            (*graph).update(Some(fgraph), None);
+            0
+        }
+        Err(e) => {
+            (*graph).update(
+                None,
+                Some((ForwardGraphErrorCode::InvalidGraph, format!("{e}"))),
+            );
+            ForwardGraphErrorCode::InvalidGraph as i32
+        }
+    }
 }
 
 /// # Safety
 ///
-/// * `file_name` must be a non-NULL pointer to valid utf8.
+/// * `file_name` must be a pointer to valid utf8 or NULL.
+///   If it is NULL, [`ForwardGraphErrorCode::NullPointer`] is returned.
 /// * `graph` must be a valid pointer to an [`OpaqueForwardGraph`].
 #[no_mangle]
 pub unsafe extern "C" fn forward_graph_initialize_from_yaml_file(
@@ -190,14 +348,20 @@ pub unsafe extern "C" fn forward_graph_initialize_from_yaml_file(
     graph: *mut OpaqueForwardGraph,
 ) -> i32 {
     if graph.is_null() {
-        return ErrorCode::GraphIsNull as i32;
+        return ForwardGraphErrorCode::GraphIsNull as i32;
+    }
+    if file_name.is_null() {
+        return ForwardGraphErrorCode::NullPointer as i32;
     }
     let filename_cstr = CStr::from_ptr(file_name);
     let filename = match filename_cstr.to_str() {
         Ok(string) => string,
         Err(e) => {
-            (*graph).update(None, Some(format!("{e}")));
-            return -1;
+            (*graph).update(
+                None,
+                Some((ForwardGraphErrorCode::ParseError, format!("{e}"))),
+            );
+            return ForwardGraphErrorCode::ParseError as i32;
         }
     };
     match std::fs::File::open(filename) {
@@ -210,14 +374,20 @@ pub unsafe extern "C" fn forward_graph_initialize_from_yaml_file(
                     forward_graph_initialize_from_yaml(ptr, burnin, graph)
                 }
                 Err(e) => {
-                    (*graph).update(None, Some(format!("{e}")));
-                    -1
+                    (*graph).update(
+                        None,
+                        Some((ForwardGraphErrorCode::ParseError, format!("{e}"))),
+                    );
+                    ForwardGraphErrorCode::ParseError as i32
                 }
             }
         }
         Err(e) => {
-            (*graph).update(None, Some(format!("{e}")));
-            -1
+            (*graph).update(
+                None,
+                Some((ForwardGraphErrorCode::ParseError, format!("{e}"))),
+            );
+            ForwardGraphErrorCode::ParseError as i32
         }
     }
 }
@@ -246,6 +416,9 @@ pub unsafe extern "C" fn forward_graph_get_error_message(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
 ) -> *const c_char {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     *status = 0;
     if !graph.is_null() {
         match &(*graph).error {
@@ -253,11 +426,33 @@ pub unsafe extern "C" fn forward_graph_get_error_message(
             None => std::ptr::null(),
         }
     } else {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         std::ptr::null()
     }
 }
 
+/// Get the code of the last error.
+///
+/// # Returns
+///
+/// * [`ForwardGraphErrorCode::Success`] if the graph is not in an error state.
+/// * [`ForwardGraphErrorCode::GraphIsNull`] if `graph` is NULL.
+/// * The code of the last error otherwise.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer or NULL
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_last_error_code(
+    graph: *const OpaqueForwardGraph,
+) -> ForwardGraphErrorCode {
+    if graph.is_null() {
+        ForwardGraphErrorCode::GraphIsNull
+    } else {
+        (*graph).error_code
+    }
+}
+
 /// Pointer to first element of selfing rates array.
 ///
 /// The length of the array is equal to [`forward_graph_number_of_demes`].
@@ -270,6 +465,9 @@ pub unsafe extern "C" fn forward_graph_selfing_rates(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
 ) -> *const f64 {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     *status = 0;
     if !graph.is_null() {
         match &(*graph).graph {
@@ -278,12 +476,12 @@ pub unsafe extern "C" fn forward_graph_selfing_rates(
                 None => std::ptr::null(),
             },
             None => {
-                *status = -1;
+                *status = (*graph).uninitialized_error_code() as i32;
                 std::ptr::null()
             }
         }
     } else {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         std::ptr::null()
     }
 }
@@ -300,6 +498,9 @@ pub unsafe extern "C" fn forward_graph_cloning_rates(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
 ) -> *const f64 {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     *status = 0;
     if !graph.is_null() {
         match &(*graph).graph {
@@ -308,12 +509,12 @@ pub unsafe extern "C" fn forward_graph_cloning_rates(
                 None => std::ptr::null(),
             },
             None => {
-                *status = -1;
+                *status = (*graph).uninitialized_error_code() as i32;
                 std::ptr::null()
             }
         }
     } else {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         std::ptr::null()
     }
 }
@@ -330,6 +531,9 @@ pub unsafe extern "C" fn forward_graph_parental_deme_sizes(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
 ) -> *const f64 {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     *status = 0;
     if !graph.is_null() {
         match &(*graph).graph {
@@ -338,12 +542,12 @@ pub unsafe extern "C" fn forward_graph_parental_deme_sizes(
                 None => std::ptr::null(),
             },
             None => {
-                *status = -1;
+                *status = (*graph).uninitialized_error_code() as i32;
                 std::ptr::null()
             }
         }
     } else {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         std::ptr::null()
     }
 }
@@ -360,6 +564,9 @@ pub unsafe extern "C" fn forward_graph_offspring_deme_sizes(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
 ) -> *const f64 {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     *status = 0;
     if !graph.is_null() {
         match &(*graph).graph {
@@ -368,12 +575,12 @@ pub unsafe extern "C" fn forward_graph_offspring_deme_sizes(
                 None => std::ptr::null(),
             },
             None => {
-                *status = -1;
+                *status = (*graph).uninitialized_error_code() as i32;
                 std::ptr::null()
             }
         }
     } else {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         std::ptr::null()
     }
 }
@@ -388,17 +595,20 @@ pub unsafe extern "C" fn forward_graph_any_extant_offspring_demes(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
 ) -> bool {
+    if status.is_null() {
+        return false;
+    }
     *status = 0;
     if !graph.is_null() {
         match &(*graph).graph {
             Some(graph) => graph.any_extant_offspring_demes(),
             None => {
-                *status = -1;
+                *status = (*graph).uninitialized_error_code() as i32;
                 false
             }
         }
     } else {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         false
     }
 }
@@ -413,17 +623,20 @@ pub unsafe extern "C" fn forward_graph_any_extant_parent_demes(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
 ) -> bool {
+    if status.is_null() {
+        return false;
+    }
     *status = 0;
     if !graph.is_null() {
         match &(*graph).graph {
             Some(graph) => graph.any_extant_parental_demes(),
             None => {
-                *status = -1;
+                *status = (*graph).uninitialized_error_code() as i32;
                 false
             }
         }
     } else {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         false
     }
 }
@@ -433,16 +646,19 @@ pub unsafe extern "C" fn forward_graph_any_extant_parent_demes(
 /// # Returns
 ///
 /// [`isize`] > 0 if the graph is not in an error state.
-/// Returns `-1` otherwise.
+/// Returns a negative [`ForwardGraphErrorCode`] otherwise.
 ///
 /// # Safety
 ///
 /// `graph` must be a valid pointer
 #[no_mangle]
 pub unsafe extern "C" fn forward_graph_number_of_demes(graph: *const OpaqueForwardGraph) -> isize {
+    if graph.is_null() {
+        return ForwardGraphErrorCode::GraphIsNull as isize;
+    }
     match &(*graph).graph {
-        Some(graph) => graph.num_demes_in_model() as isize,
-        None => -1,
+        Some(fgraph) => fgraph.num_demes_in_model() as isize,
+        None => (*graph).uninitialized_error_code() as isize,
     }
 }
 
@@ -459,16 +675,185 @@ pub unsafe extern "C" fn forward_graph_update_state(
     if !graph.is_null() {
         match &mut (*graph).graph {
             Some(fgraph) => match fgraph.update_state(time) {
-                Ok(()) => 0,
+                Ok(_) => 0,
                 Err(e) => {
-                    (*graph).update(None, Some(format!("{e}")));
-                    -1
+                    let code = ForwardGraphErrorCode::from(&e);
+                    (*graph).update(None, Some((code, format!("{e}"))));
+                    code as i32
                 }
             },
-            None => -1,
+            None => (*graph).uninitialized_error_code() as i32,
         }
     } else {
-        ErrorCode::GraphIsNull as i32
+        ForwardGraphErrorCode::GraphIsNull as i32
+    }
+}
+
+/// Get the size of a parental deme at a given time.
+///
+/// Unlike [`forward_graph_parental_deme_sizes`], this function
+/// does not require updating the model state.
+/// The state of `graph` is not changed.
+///
+/// # Parameters
+///
+/// * `deme_index`: the index of a deme.
+/// * `time`: a parental generation time, as passed to
+///   [`forward_graph_update_state`].
+///
+/// # Returns
+///
+/// * The size of the deme, which is zero if the deme
+///   does not exist at `time`.
+/// * NaN upon error, in which case `status` is set to
+///   [`ForwardGraphErrorCode::IndexOutOfRange`] if `deme_index`
+///   is out of range or to [`ForwardGraphErrorCode::InvalidTime`]
+///   if `time` is not a parental generation time of the model.
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `status` must be a valid pointer to an `i32` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_size_at(
+    graph: *const OpaqueForwardGraph,
+    deme_index: usize,
+    time: f64,
+    status: *mut i32,
+) -> f64 {
+    if status.is_null() {
+        return f64::NAN;
+    }
+    if graph.is_null() {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        return f64::NAN;
+    }
+    *status = 0;
+    let fgraph = match &(*graph).graph {
+        Some(fgraph) => fgraph,
+        None => {
+            *status = (*graph).uninitialized_error_code() as i32;
+            return f64::NAN;
+        }
+    };
+    if deme_index >= fgraph.num_demes_in_model() {
+        *status = ForwardGraphErrorCode::IndexOutOfRange as i32;
+        return f64::NAN;
+    }
+    if time.fract() != 0.0 || time >= fgraph.end_time().value() {
+        *status = ForwardGraphErrorCode::InvalidTime as i32;
+        return f64::NAN;
+    }
+    let size = match fgraph.time_to_backward(time) {
+        Ok(Some(backward_time)) => fgraph.size_at(deme_index, backward_time),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+    match size {
+        Ok(size) => size.map(f64::from).unwrap_or(0.0),
+        Err(e) => {
+            *status = ForwardGraphErrorCode::from(&e) as i32;
+            f64::NAN
+        }
+    }
+}
+
+/// Get the model state at the last time passed to
+/// [`forward_graph_update_state`].
+///
+/// This function fills `out` with the values returned by
+/// [`forward_graph_parental_deme_sizes`],
+/// [`forward_graph_offspring_deme_sizes`],
+/// [`forward_graph_selfing_rates`], and
+/// [`forward_graph_cloning_rates`].
+/// If the state has not been updated, all pointers are NULL.
+///
+/// # Return values
+///
+/// * 0 on success
+/// * [`ForwardGraphErrorCode::GraphIsNull`] if `graph` is NULL
+/// * [`ForwardGraphErrorCode::NullPointer`] if `out` is NULL
+/// * A [`ForwardGraphErrorCode`] otherwise,
+///   in which case `out` is not changed.
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `out` must be a valid pointer to a [`forward_graph_state`] or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_get_state(
+    graph: *const OpaqueForwardGraph,
+    out: *mut forward_graph_state,
+) -> i32 {
+    if graph.is_null() {
+        return ForwardGraphErrorCode::GraphIsNull as i32;
+    }
+    if out.is_null() {
+        return ForwardGraphErrorCode::NullPointer as i32;
+    }
+    let fgraph = match &(*graph).graph {
+        Some(fgraph) => fgraph,
+        None => return (*graph).uninitialized_error_code() as i32,
+    };
+    let state = match fgraph.state() {
+        Ok(state) => state,
+        Err(e) => return ForwardGraphErrorCode::from(&e) as i32,
+    };
+    let mut rv = forward_graph_state {
+        time: f64::NAN,
+        num_demes: fgraph.num_demes_in_model(),
+        parental_deme_sizes: std::ptr::null(),
+        offspring_deme_sizes: std::ptr::null(),
+        selfing_rates: std::ptr::null(),
+        cloning_rates: std::ptr::null(),
+    };
+    if let Some(state) = state {
+        rv.time = state.time().value();
+        rv.parental_deme_sizes = state.parental_deme_sizes().as_ptr() as *const f64;
+        if let (Some(sizes), Some(selfing_rates), Some(cloning_rates)) = (
+            state.offspring_deme_sizes(),
+            state.selfing_rates(),
+            state.cloning_rates(),
+        ) {
+            rv.offspring_deme_sizes = sizes.as_ptr() as *const f64;
+            rv.selfing_rates = selfing_rates.as_ptr() as *const f64;
+            rv.cloning_rates = cloning_rates.as_ptr() as *const f64;
+        }
+    }
+    *out = rv;
+    0
+}
+
+/// Restore the graph to its state just after initialization.
+///
+/// Any time iteration is cleared and the model state is rewound,
+/// without parsing the model again.
+/// An error state is cleared if the graph is still initialized.
+///
+/// # Return values
+///
+/// * 0 on success
+/// * A [`ForwardGraphErrorCode`] if `graph` is NULL or
+///   not initialized.
+///   Errors during initialization or
+///   [`forward_graph_update_state`] leave the graph uninitialized.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer or NULL
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_reset(graph: *mut OpaqueForwardGraph) -> i32 {
+    if graph.is_null() {
+        return ForwardGraphErrorCode::GraphIsNull as i32;
+    }
+    match &mut (*graph).graph {
+        Some(fgraph) => {
+            fgraph.reset();
+            (*graph).current_time = None;
+            (*graph).update_error(None);
+            0
+        }
+        None => (*graph).uninitialized_error_code() as i32,
     }
 }
 
@@ -494,10 +879,53 @@ pub unsafe extern "C" fn forward_graph_initialize_time_iteration(
                 }
                 0
             }
-            None => -1,
+            None => (*graph).uninitialized_error_code() as i32,
+        }
+    } else {
+        ForwardGraphErrorCode::GraphIsNull as i32
+    }
+}
+
+/// Initialize graph to begin iterating over model
+/// at a given parental generation time.
+///
+/// This function updates the model state to `time`,
+/// so there is no need to call [`forward_graph_update_state`]
+/// first.
+/// The first call to [`forward_graph_iterate_time`] returns `time`.
+///
+/// # Return values
+///
+/// * 0 on success
+/// * [`ForwardGraphErrorCode::InvalidTime`] if `time` is not a valid
+///   parental generation time, in which case the graph is in an error state.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_initialize_time_iteration_at(
+    time: f64,
+    graph: *mut OpaqueForwardGraph,
+) -> i32 {
+    if !graph.is_null() {
+        match (*graph).graph.take() {
+            Some(fgraph) => match fgraph.with_start_time(time) {
+                Ok(fgraph) => {
+                    (*graph).graph = Some(fgraph);
+                    (*graph).current_time = Some(time - 1.0);
+                    0
+                }
+                Err(e) => {
+                    let code = ForwardGraphErrorCode::from(&e);
+                    (*graph).update(None, Some((code, format!("{e}"))));
+                    code as i32
+                }
+            },
+            None => (*graph).uninitialized_error_code() as i32,
         }
     } else {
-        ErrorCode::GraphIsNull as i32
+        ForwardGraphErrorCode::GraphIsNull as i32
     }
 }
 
@@ -508,6 +936,9 @@ pub unsafe extern "C" fn forward_graph_initialize_time_iteration(
 /// * null = done iterating
 /// * not null = still iterating
 ///
+/// If iteration has not been initialized, `status` is set to
+/// [`ForwardGraphErrorCode::IterationNotInitialized`].
+///
 /// # Safety
 ///
 /// `graph` must be a valid pointer
@@ -516,16 +947,20 @@ pub unsafe extern "C" fn forward_graph_iterate_time(
     graph: *mut OpaqueForwardGraph,
     status: *mut i32,
 ) -> *const f64 {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     if graph.is_null() {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         return std::ptr::null();
     }
     *status = 0;
     if (*graph).current_time.is_none() {
-        *status = -1;
-        (*graph).update_error(Some(
+        *status = ForwardGraphErrorCode::IterationNotInitialized as i32;
+        (*graph).update_error(Some((
+            ForwardGraphErrorCode::IterationNotInitialized,
             "forward_graph_initialize_time_iteration has not been called".to_string(),
-        ));
+        )));
         return std::ptr::null();
     }
     let tref: &mut f64 = (*graph).current_time.as_mut().unwrap();
@@ -540,38 +975,46 @@ pub unsafe extern "C" fn forward_graph_iterate_time(
             }
         }
         None => {
-            *status = -1;
+            *status = (*graph).uninitialized_error_code() as i32;
             std::ptr::null()
         }
     }
 }
 
+/// Return the ancestry proportions of an offspring deme.
+///
+/// If `offspring_deme` is out of range, `status` is set to
+/// [`ForwardGraphErrorCode::IndexOutOfRange`].
+///
 /// # Safety
 ///
 /// `graph` must be a valid pointer to an [`OpaqueForwardGraph`].
-/// `status` must be a valid pointer to an `i32`.
+/// `status` must be a valid pointer to an `i32` or NULL.
 #[no_mangle]
 pub unsafe extern "C" fn forward_graph_ancestry_proportions(
     offspring_deme: usize,
     status: *mut i32,
     graph: *mut OpaqueForwardGraph,
 ) -> *const f64 {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     if graph.is_null() {
-        *status = ErrorCode::GraphIsNull as i32;
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
         return std::ptr::null();
     }
     *status = 0;
     if (*graph).error.is_some() {
-        *status = -1;
+        *status = (*graph).error_code as i32;
         return std::ptr::null();
     }
     match &(*graph).graph {
         Some(fgraph) => {
             if offspring_deme >= fgraph.num_demes_in_model() {
-                *status = -1;
-                (*graph).update_error(Some(format!(
-                    "offspring deme index {} out of range",
-                    offspring_deme
+                *status = ForwardGraphErrorCode::IndexOutOfRange as i32;
+                (*graph).update_error(Some((
+                    ForwardGraphErrorCode::IndexOutOfRange,
+                    format!("offspring deme index {} out of range", offspring_deme),
                 )));
                 std::ptr::null()
             } else {
@@ -582,84 +1025,352 @@ pub unsafe extern "C" fn forward_graph_ancestry_proportions(
             }
         }
         None => {
-            *status = -1;
+            *status = (*graph).uninitialized_error_code() as i32;
             std::ptr::null()
         }
     }
 }
 
-/// Get the model end time.
+/// Return a pointer to the first element of the migration matrix.
 ///
-/// The value returned is one generation after the
-/// last parental generation.
-/// Thus, this value defines a half-open interval
-/// during which parental demes exist.
+/// The matrix is stored in row-major order and has
+/// [`forward_graph_number_of_demes`] rows and columns.
+/// Element `(i, j)` is the proportion of offspring in deme `i`
+/// whose parents are migrants from deme `j`.
+/// The diagonal element `(i, i)` is the proportion of offspring
+/// in deme `i` whose parents are not migrants.
+///
+/// Unlike [`forward_graph_ancestry_proportions`], the matrix does not
+/// include ancestry from pulses or from the ancestors of demes that
+/// first appear in the offspring generation.
+///
+/// # Returns
+///
+/// * null if there are no extant offspring demes.
 ///
 /// # Safety
 ///
-/// `graph` must be a valid pointer to an [`OpaqueForwardGraph`].
-/// `status` must be a valid pointer to an `i32`.
+/// `graph` must be a valid pointer
 #[no_mangle]
-pub unsafe extern "C" fn forward_graph_model_end_time(
+pub unsafe extern "C" fn forward_graph_migration_matrix(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
-) -> f64 {
+) -> *const f64 {
+    if status.is_null() {
+        return std::ptr::null();
+    }
     *status = 0;
-    if (*graph).error.is_some() || (*graph).graph.is_none() {
-        *status = -1;
-        f64::NAN
-    } else {
+    if !graph.is_null() {
         match &(*graph).graph {
-            Some(fgraph) => fgraph.end_time().value(),
+            Some(graph) => match graph.migration_matrix() {
+                Some(matrix) => matrix.as_slice().as_ptr(),
+                None => std::ptr::null(),
+            },
             None => {
-                *status = -1;
-                f64::NAN
+                *status = (*graph).uninitialized_error_code() as i32;
+                std::ptr::null()
             }
         }
+    } else {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        std::ptr::null()
     }
 }
 
-/// Get the underlying [`demes::Graph`].
+// Get a pulse applied to the current offspring generation,
+// setting status upon error.
+unsafe fn current_pulse<'graph>(
+    graph: *const OpaqueForwardGraph,
+    pulse: usize,
+    status: *mut i32,
+) -> Option<(&'graph demes_forward::ForwardGraph, &'graph demes::Pulse)> {
+    if status.is_null() {
+        return None;
+    }
+    if graph.is_null() {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        return None;
+    }
+    *status = 0;
+    match &(*graph).graph {
+        Some(fgraph) => match fgraph.pulses_at_current_time().nth(pulse) {
+            Some(p) => Some((fgraph, p)),
+            None => {
+                *status = ForwardGraphErrorCode::IndexOutOfRange as i32;
+                None
+            }
+        },
+        None => {
+            *status = (*graph).uninitialized_error_code() as i32;
+            None
+        }
+    }
+}
+
+unsafe fn deme_index_of(
+    graph: &demes_forward::ForwardGraph,
+    name: &str,
+    status: *mut i32,
+) -> usize {
+    match graph.deme_index(name) {
+        Some(index) => index,
+        None => {
+            *status = ForwardGraphErrorCode::InvalidGraph as i32;
+            0
+        }
+    }
+}
+
+/// Get the number of pulses applied to the current
+/// offspring generation.
 ///
-/// # Returns
+/// The offspring generation is the one whose parents are
+/// at the last time passed to [`forward_graph_update_state`].
+/// The ancestry from pulses is already included in
+/// [`forward_graph_ancestry_proportions`].
+/// The functions taking a pulse index allow treating
+/// pulses as discrete events.
 ///
-/// * A new `libc::c_char` representation of the graph upon success.
-/// * `std::ptr::null` upon error.
+/// Pulses are indexed from zero in the order that
+/// they are applied.
 ///
-/// # Side effects
+/// # Returns
 ///
-/// * An error will set `status` to -1.
-/// * Success will set `status` to 0.
+/// * The number of pulses, which is zero if the
+///   state has not been updated.
+/// * Zero upon error, in which case `status` is set
+///   to a [`ForwardGraphErrorCode`].
 ///
 /// # Safety
 ///
-/// `graph` must be a valid pointer to an [`OpaqueForwardGraph`].
-/// `status` must be a valid pointer to an `i32`.
-///
-/// # Note
-///
-/// If not NULL, the return value must be freed in order to avoid
-/// leaking memory.
+/// * `graph` must be a valid pointer or NULL
+/// * `status` must be a valid pointer to an `i32` or NULL.
 #[no_mangle]
-pub unsafe extern "C" fn forward_graph_get_demes_graph(
+pub unsafe extern "C" fn forward_graph_number_of_pulses(
     graph: *const OpaqueForwardGraph,
     status: *mut i32,
-) -> *mut c_char {
+) -> usize {
+    if status.is_null() {
+        return 0;
+    }
+    if graph.is_null() {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        return 0;
+    }
+    *status = 0;
     match &(*graph).graph {
-        None => std::ptr::null_mut(),
-        Some(g) => {
-            let demes_graph = match g.demes_graph().as_string() {
-                Ok(g) => g,
-                Err(_) => {
-                    *status = -1;
-                    return std::ptr::null_mut();
-                }
-            };
-            let c_str = match CString::new(demes_graph) {
-                Ok(c) => c,
-                Err(_) => {
-                    *status = -1;
-                    return std::ptr::null_mut();
+        Some(fgraph) => fgraph.pulses_at_current_time().len(),
+        None => {
+            *status = (*graph).uninitialized_error_code() as i32;
+            0
+        }
+    }
+}
+
+/// Get the index of the destination deme of a pulse.
+///
+/// See [`forward_graph_number_of_pulses`].
+///
+/// # Returns
+///
+/// * Zero upon error, in which case `status` is set
+///   to a [`ForwardGraphErrorCode`].
+///   If `pulse` is out of range, the code is
+///   [`ForwardGraphErrorCode::IndexOutOfRange`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `status` must be a valid pointer to an `i32` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_pulse_dest(
+    graph: *const OpaqueForwardGraph,
+    pulse: usize,
+    status: *mut i32,
+) -> usize {
+    match current_pulse(graph, pulse, status) {
+        Some((fgraph, p)) => deme_index_of(fgraph, p.dest(), status),
+        None => 0,
+    }
+}
+
+/// Get the number of source demes of a pulse.
+///
+/// See [`forward_graph_number_of_pulses`].
+///
+/// # Returns
+///
+/// See [`forward_graph_pulse_dest`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `status` must be a valid pointer to an `i32` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_pulse_number_of_sources(
+    graph: *const OpaqueForwardGraph,
+    pulse: usize,
+    status: *mut i32,
+) -> usize {
+    match current_pulse(graph, pulse, status) {
+        Some((_, p)) => p.sources().len(),
+        None => 0,
+    }
+}
+
+/// Get the index of a source deme of a pulse.
+///
+/// See [`forward_graph_number_of_pulses`].
+///
+/// # Parameters
+///
+/// * `pulse`: the index of a pulse.
+/// * `source`: the index of a source, which must be less than
+///   [`forward_graph_pulse_number_of_sources`].
+///
+/// # Returns
+///
+/// See [`forward_graph_pulse_dest`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `status` must be a valid pointer to an `i32` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_pulse_source(
+    graph: *const OpaqueForwardGraph,
+    pulse: usize,
+    source: usize,
+    status: *mut i32,
+) -> usize {
+    match current_pulse(graph, pulse, status) {
+        Some((fgraph, p)) => match p.sources().get(source) {
+            Some(name) => deme_index_of(fgraph, name, status),
+            None => {
+                *status = ForwardGraphErrorCode::IndexOutOfRange as i32;
+                0
+            }
+        },
+        None => 0,
+    }
+}
+
+/// Pointer to the first element of the proportions of a pulse.
+///
+/// Element `i` is the proportion of ancestry of the destination deme
+/// coming from source `i`.
+/// The length of the array is equal to [`forward_graph_pulse_number_of_sources`].
+///
+/// # Returns
+///
+/// * NULL upon error, in which case `status` is set as for
+///   [`forward_graph_pulse_dest`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `status` must be a valid pointer to an `i32` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_pulse_proportions(
+    graph: *const OpaqueForwardGraph,
+    pulse: usize,
+    status: *mut i32,
+) -> *const f64 {
+    match current_pulse(graph, pulse, status) {
+        Some((_, p)) => p.proportions().as_ptr() as *const f64,
+        None => std::ptr::null(),
+    }
+}
+
+/// Get the model end time.
+///
+/// The value returned is one generation after the
+/// last parental generation.
+/// Thus, this value defines a half-open interval
+/// during which parental demes exist.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer to an [`OpaqueForwardGraph`].
+/// `status` must be a valid pointer to an `i32` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_model_end_time(
+    graph: *const OpaqueForwardGraph,
+    status: *mut i32,
+) -> f64 {
+    if status.is_null() {
+        return f64::NAN;
+    }
+    if graph.is_null() {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        return f64::NAN;
+    }
+    *status = 0;
+    if (*graph).error.is_some() {
+        *status = (*graph).error_code as i32;
+        f64::NAN
+    } else {
+        match &(*graph).graph {
+            Some(fgraph) => fgraph.end_time().value(),
+            None => {
+                *status = (*graph).uninitialized_error_code() as i32;
+                f64::NAN
+            }
+        }
+    }
+}
+
+/// Get the underlying [`demes::Graph`].
+///
+/// # Returns
+///
+/// * A new `libc::c_char` representation of the graph upon success.
+/// * `std::ptr::null` upon error.
+///
+/// # Side effects
+///
+/// * An error will set `status` to a [`ForwardGraphErrorCode`].
+/// * Success will set `status` to 0.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer to an [`OpaqueForwardGraph`].
+/// `status` must be a valid pointer to an `i32` or NULL.
+///
+/// # Note
+///
+/// If not NULL, the return value must be freed in order to avoid
+/// leaking memory.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_get_demes_graph(
+    graph: *const OpaqueForwardGraph,
+    status: *mut i32,
+) -> *mut c_char {
+    if status.is_null() {
+        return std::ptr::null_mut();
+    }
+    if graph.is_null() {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        return std::ptr::null_mut();
+    }
+    match &(*graph).graph {
+        None => {
+            *status = (*graph).uninitialized_error_code() as i32;
+            std::ptr::null_mut()
+        }
+        Some(g) => {
+            let demes_graph = match g.demes_graph().as_string() {
+                Ok(g) => g,
+                Err(_) => {
+                    *status = ForwardGraphErrorCode::InvalidGraph as i32;
+                    return std::ptr::null_mut();
+                }
+            };
+            let c_str = match CString::new(demes_graph) {
+                Ok(c) => c,
+                Err(_) => {
+                    *status = ForwardGraphErrorCode::InvalidGraph as i32;
+                    return std::ptr::null_mut();
                 }
             };
             *status = 0;
@@ -740,9 +1451,19 @@ demes:
         let rust_message = unsafe { CStr::from_ptr(message) };
         let rust_message: &str = rust_message.to_str().unwrap();
         assert_eq!(
-            rust_message,
-            "deme A has finite start time but no ancestors"
+            unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+            ForwardGraphErrorCode::InvalidGraph
         );
+        // The message is that of the error from demes,
+        // whose kind, rather than its text, identifies the problem.
+        let error = demes_forward::demes::loads(yaml).unwrap_err();
+        let context = error.context().unwrap();
+        assert_eq!(
+            context.kind(),
+            demes_forward::demes::ErrorKind::Inconsistent
+        );
+        assert_eq!(context.deme(), Some("A"));
+        assert_eq!(rust_message, context.message());
     }
 
     #[test]
@@ -1131,6 +1852,132 @@ demes:
         }
     }
 
+    #[test]
+    fn test_iteration_with_start_time() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+";
+        for start_time in [0.0, 5.0, 10.0] {
+            let mut graph = GraphHolder::new();
+            assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+            let mut status: i32 = 0;
+            let mut ngens = 0;
+
+            // Updates the internal state and initializes iteration
+            assert_eq!(
+                unsafe {
+                    forward_graph_initialize_time_iteration_at(start_time, graph.as_mut_ptr())
+                },
+                0,
+            );
+            assert!(!unsafe {
+                forward_graph_parental_deme_sizes(graph.as_ptr(), &mut status).is_null()
+            });
+            assert_eq!(status, 0);
+            let ptime = unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status) };
+            assert_eq!(status, 0);
+            assert_eq!(unsafe { *ptime }, start_time);
+
+            while unsafe { forward_graph_any_extant_offspring_demes(graph.as_ptr(), &mut status) } {
+                assert_eq!(status, 0);
+                let ptime = unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status) };
+                assert_eq!(
+                    unsafe { forward_graph_update_state(*ptime, graph.as_mut_ptr()) },
+                    0
+                );
+                ngens += 1;
+            }
+            assert_eq!(ngens, (10.0 - start_time) as i32);
+        }
+    }
+
+    #[test]
+    fn test_iteration_with_invalid_start_time() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+";
+        for start_time in [-1.0, 0.5, 11.0, f64::NAN] {
+            let mut graph = GraphHolder::new();
+            assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+            assert_eq!(
+                unsafe {
+                    forward_graph_initialize_time_iteration_at(start_time, graph.as_mut_ptr())
+                },
+                ForwardGraphErrorCode::InvalidTime as i32,
+            );
+            assert!(unsafe { forward_graph_is_error_state(graph.as_ptr()) });
+            assert_eq!(
+                unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+                ForwardGraphErrorCode::InvalidTime
+            );
+        }
+        let mut graph = GraphHolder::new();
+        assert_eq!(
+            unsafe { forward_graph_initialize_time_iteration_at(0.0, graph.as_mut_ptr()) },
+            ForwardGraphErrorCode::GraphUninitialized as i32,
+        );
+    }
+
+    #[test]
+    fn test_migration_matrix() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+ - name: B
+   epochs:
+   - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 0.25
+   start_time: 5
+";
+        let mut graph = GraphHolder::new();
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        let mut status = -1;
+        assert!(unsafe { forward_graph_migration_matrix(graph.as_ptr(), &mut status) }.is_null());
+        assert_eq!(status, 0);
+        let end_time = unsafe { forward_graph_model_end_time(graph.as_ptr(), &mut status) };
+        for time in 0..(end_time as i32) {
+            assert_eq!(
+                unsafe { forward_graph_update_state(time as f64, graph.as_mut_ptr()) },
+                0
+            );
+            status = -1;
+            let matrix = unsafe { forward_graph_migration_matrix(graph.as_ptr(), &mut status) };
+            assert_eq!(status, 0);
+            if time == end_time as i32 - 1 {
+                assert!(matrix.is_null());
+                continue;
+            }
+            let matrix = unsafe { std::slice::from_raw_parts(matrix, 4) };
+            let expected = unsafe { &(*graph.graph) }
+                .graph
+                .as_ref()
+                .unwrap()
+                .migration_matrix()
+                .unwrap()
+                .as_slice();
+            assert_eq!(matrix, expected);
+            if time >= 10 {
+                assert_eq!(matrix, [1.0, 0.0, 0.25, 0.75]);
+            } else {
+                assert_eq!(matrix, [1.0, 0.0, 0.0, 1.0]);
+            }
+        }
+    }
+
     #[test]
     fn test_model_with_bad_time_rounding() {
         let yaml = "
@@ -1143,7 +1990,10 @@ demes:
   - {end_time: 0, start_size: 3}
 ";
         let mut graph = GraphHolder::new();
-        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        assert_eq!(
+            graph.init_with_yaml(10.0, yaml),
+            ForwardGraphErrorCode::InvalidGraph as i32
+        );
         let x = graph.as_ptr();
         assert!(unsafe { forward_graph_is_error_state(x) });
     }
@@ -1163,7 +2013,10 @@ migrations: []
 ";
         {
             let mut graph = GraphHolder::new();
-            assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+            assert_eq!(
+                graph.init_with_yaml(10.0, yaml),
+                ForwardGraphErrorCode::InvalidGraph as i32
+            );
             let x = graph.as_ptr();
             assert!(unsafe { forward_graph_is_error_state(x) });
         }
@@ -1174,4 +2027,442 @@ migrations: []
             assert!(!unsafe { forward_graph_is_error_state(x) });
         }
     }
+
+    #[test]
+    fn test_error_codes() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+";
+        let mut graph = GraphHolder::new();
+        assert_eq!(
+            unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+            ForwardGraphErrorCode::Success
+        );
+        assert_eq!(
+            graph.init_with_yaml(10.0, "time_units: [generations"),
+            ForwardGraphErrorCode::ParseError as i32
+        );
+        assert_eq!(
+            unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+            ForwardGraphErrorCode::ParseError
+        );
+
+        // A successful initialization clears the error
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        assert_eq!(
+            unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+            ForwardGraphErrorCode::Success
+        );
+
+        let mut status = 0;
+        let t = unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status) };
+        assert!(t.is_null());
+        assert_eq!(
+            status,
+            ForwardGraphErrorCode::IterationNotInitialized as i32
+        );
+        assert_eq!(
+            unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+            ForwardGraphErrorCode::IterationNotInitialized
+        );
+
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        assert_eq!(
+            unsafe { forward_graph_update_state(0.0, graph.as_mut_ptr()) },
+            0
+        );
+        let p = unsafe { forward_graph_ancestry_proportions(1, &mut status, graph.as_mut_ptr()) };
+        assert!(p.is_null());
+        assert_eq!(status, ForwardGraphErrorCode::IndexOutOfRange as i32);
+        assert_eq!(
+            unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+            ForwardGraphErrorCode::IndexOutOfRange
+        );
+
+        assert_eq!(
+            unsafe { forward_graph_update_state(-1.0, graph.as_mut_ptr()) },
+            ForwardGraphErrorCode::InvalidTime as i32
+        );
+        assert_eq!(
+            unsafe { forward_graph_last_error_code(graph.as_ptr()) },
+            ForwardGraphErrorCode::InvalidTime
+        );
+        // The graph is no longer usable and the last error is reported
+        let _ = unsafe { forward_graph_parental_deme_sizes(graph.as_ptr(), &mut status) };
+        assert_eq!(status, ForwardGraphErrorCode::InvalidTime as i32);
+    }
+
+    #[test]
+    fn test_clone() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+     end_time: 5
+   - start_size: 200
+";
+        let mut graph = GraphHolder::new();
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        assert_eq!(
+            unsafe { forward_graph_initialize_time_iteration_at(8.0, graph.as_mut_ptr()) },
+            0
+        );
+        let mut status = 0;
+        let t = unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status) };
+        assert_eq!(unsafe { *t }, 8.0);
+        assert_eq!(
+            unsafe { forward_graph_update_state(*t, graph.as_mut_ptr()) },
+            0
+        );
+
+        let mut clone = GraphHolder {
+            graph: unsafe { forward_graph_clone(graph.as_ptr()) },
+        };
+        assert!(!clone.as_ptr().is_null());
+
+        // Iterating over the clone does not affect the original
+        let mut times = vec![];
+        let mut sizes = vec![];
+        while let Some(t) =
+            unsafe { forward_graph_iterate_time(clone.as_mut_ptr(), &mut status).as_ref() }
+        {
+            assert_eq!(status, 0);
+            assert_eq!(
+                unsafe { forward_graph_update_state(*t, clone.as_mut_ptr()) },
+                0
+            );
+            let p = unsafe { forward_graph_parental_deme_sizes(clone.as_ptr(), &mut status) };
+            times.push(*t);
+            sizes.push(unsafe { *p });
+        }
+        assert_eq!(times, [9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0]);
+        assert_eq!(sizes, [100.0, 100.0, 200.0, 200.0, 200.0, 200.0, 200.0]);
+
+        let t = unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status) };
+        assert_eq!(unsafe { *t }, 9.0);
+
+        assert!(unsafe { forward_graph_clone(std::ptr::null()) }.is_null());
+    }
+
+    #[test]
+    fn test_size_at() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+     end_time: 10
+   - start_size: 200
+     end_size: 400
+ - name: B
+   start_time: 5
+   ancestors: [A]
+   epochs:
+   - start_size: 50
+";
+        let mut graph = GraphHolder::new();
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        let mut status = -1;
+        let end_time = unsafe { forward_graph_model_end_time(graph.as_ptr(), &mut status) };
+        let mut expected = vec![];
+        for time in 0..end_time as i32 {
+            assert_eq!(
+                unsafe { forward_graph_update_state(time as f64, graph.as_mut_ptr()) },
+                0
+            );
+            let sizes = unsafe { forward_graph_parental_deme_sizes(graph.as_ptr(), &mut status) };
+            assert_eq!(status, 0);
+            expected.push(unsafe { std::slice::from_raw_parts(sizes, 2) }.to_vec());
+        }
+
+        // Query the sizes from a new graph, in reverse order
+        let mut graph = GraphHolder::new();
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        for (time, sizes) in expected.iter().enumerate().rev() {
+            for (deme, &expected_size) in sizes.iter().enumerate() {
+                let size = unsafe {
+                    forward_graph_size_at(graph.as_ptr(), deme, time as f64, &mut status)
+                };
+                assert_eq!(status, 0);
+                assert_eq!(size, expected_size, "{time} {deme}");
+            }
+        }
+        let sizes = unsafe { forward_graph_parental_deme_sizes(graph.as_ptr(), &mut status) };
+        assert!(sizes.is_null());
+
+        let size = unsafe { forward_graph_size_at(graph.as_ptr(), 2, 0.0, &mut status) };
+        assert!(size.is_nan());
+        assert_eq!(status, ForwardGraphErrorCode::IndexOutOfRange as i32);
+        for time in [-1.0, 0.5, end_time, f64::NAN, f64::INFINITY] {
+            let size = unsafe { forward_graph_size_at(graph.as_ptr(), 0, time, &mut status) };
+            assert!(size.is_nan());
+            assert_eq!(status, ForwardGraphErrorCode::InvalidTime as i32, "{time}");
+        }
+        assert!(!unsafe { forward_graph_is_error_state(graph.as_ptr()) });
+    }
+
+    #[test]
+    fn test_pulses() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+ - name: B
+   epochs:
+   - start_size: 100
+ - name: C
+   epochs:
+   - start_size: 100
+pulses:
+ - sources: [A, B]
+   dest: C
+   time: 5
+   proportions: [0.25, 0.125]
+ - sources: [C]
+   dest: A
+   time: 5
+   proportions: [0.5]
+";
+        let mut graph = GraphHolder::new();
+        let mut status = -1;
+        assert_eq!(
+            unsafe { forward_graph_number_of_pulses(graph.as_ptr(), &mut status) },
+            0
+        );
+        assert_eq!(status, ForwardGraphErrorCode::GraphUninitialized as i32);
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        assert_eq!(
+            unsafe { forward_graph_number_of_pulses(graph.as_ptr(), &mut status) },
+            0
+        );
+        assert_eq!(status, 0);
+
+        let mut pulses_seen = 0;
+        assert_eq!(
+            unsafe { forward_graph_initialize_time_iteration(graph.as_mut_ptr()) },
+            0
+        );
+        while let Some(t) =
+            unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status).as_ref() }
+        {
+            assert_eq!(
+                unsafe { forward_graph_update_state(*t, graph.as_mut_ptr()) },
+                0
+            );
+            let num_pulses = unsafe { forward_graph_number_of_pulses(graph.as_ptr(), &mut status) };
+            assert_eq!(status, 0);
+            if num_pulses == 0 {
+                continue;
+            }
+            pulses_seen += 1;
+            assert_eq!(num_pulses, 2);
+            let expected = [
+                (2, vec![0, 1], [0.25, 0.125].as_slice()),
+                (0, vec![2], &[0.5]),
+            ];
+            for (pulse, (dest, sources, proportions)) in expected.iter().enumerate() {
+                assert_eq!(
+                    unsafe { forward_graph_pulse_dest(graph.as_ptr(), pulse, &mut status) },
+                    *dest
+                );
+                assert_eq!(status, 0);
+                let num_sources = unsafe {
+                    forward_graph_pulse_number_of_sources(graph.as_ptr(), pulse, &mut status)
+                };
+                assert_eq!(num_sources, sources.len());
+                for (source, &deme) in sources.iter().enumerate() {
+                    assert_eq!(
+                        unsafe {
+                            forward_graph_pulse_source(graph.as_ptr(), pulse, source, &mut status)
+                        },
+                        deme
+                    );
+                    assert_eq!(status, 0);
+                }
+                let _ = unsafe {
+                    forward_graph_pulse_source(graph.as_ptr(), pulse, num_sources, &mut status)
+                };
+                assert_eq!(status, ForwardGraphErrorCode::IndexOutOfRange as i32);
+                let p =
+                    unsafe { forward_graph_pulse_proportions(graph.as_ptr(), pulse, &mut status) };
+                assert_eq!(status, 0);
+                assert_eq!(
+                    unsafe { std::slice::from_raw_parts(p, num_sources) },
+                    *proportions
+                );
+            }
+            let p = unsafe { forward_graph_pulse_proportions(graph.as_ptr(), 2, &mut status) };
+            assert!(p.is_null());
+            assert_eq!(status, ForwardGraphErrorCode::IndexOutOfRange as i32);
+            let _ = unsafe { forward_graph_pulse_dest(graph.as_ptr(), 2, &mut status) };
+            assert_eq!(status, ForwardGraphErrorCode::IndexOutOfRange as i32);
+        }
+        assert_eq!(pulses_seen, 1);
+    }
+
+    #[test]
+    fn test_get_state() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+     end_time: 5
+   - start_size: 200
+     cloning_rate: 0.5
+ - name: B
+   epochs:
+   - start_size: 50
+     selfing_rate: 0.25
+";
+        let empty = || forward_graph_state {
+            time: 0.0,
+            num_demes: 0,
+            parental_deme_sizes: std::ptr::null(),
+            offspring_deme_sizes: std::ptr::null(),
+            selfing_rates: std::ptr::null(),
+            cloning_rates: std::ptr::null(),
+        };
+        let mut state = empty();
+        let mut graph = GraphHolder::new();
+        assert_eq!(
+            unsafe { forward_graph_get_state(graph.as_ptr(), &mut state) },
+            ForwardGraphErrorCode::GraphUninitialized as i32
+        );
+        assert_eq!(
+            unsafe { forward_graph_get_state(std::ptr::null(), &mut state) },
+            ForwardGraphErrorCode::GraphIsNull as i32
+        );
+        assert_eq!(state.num_demes, 0);
+
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+        assert_eq!(
+            unsafe { forward_graph_get_state(graph.as_ptr(), &mut state) },
+            0
+        );
+        assert!(state.time.is_nan());
+        assert_eq!(state.num_demes, 2);
+        assert!(state.parental_deme_sizes.is_null());
+        assert!(state.offspring_deme_sizes.is_null());
+
+        let mut status = -1;
+        assert_eq!(
+            unsafe { forward_graph_initialize_time_iteration(graph.as_mut_ptr()) },
+            0
+        );
+        while let Some(t) =
+            unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status).as_ref() }
+        {
+            assert_eq!(
+                unsafe { forward_graph_update_state(*t, graph.as_mut_ptr()) },
+                0
+            );
+            let mut state = empty();
+            assert_eq!(
+                unsafe { forward_graph_get_state(graph.as_ptr(), &mut state) },
+                0
+            );
+            assert_eq!(state.time, *t);
+            assert_eq!(state.num_demes, 2);
+            assert_eq!(state.parental_deme_sizes, unsafe {
+                forward_graph_parental_deme_sizes(graph.as_ptr(), &mut status)
+            });
+            assert_eq!(state.offspring_deme_sizes, unsafe {
+                forward_graph_offspring_deme_sizes(graph.as_ptr(), &mut status)
+            });
+            assert_eq!(state.selfing_rates, unsafe {
+                forward_graph_selfing_rates(graph.as_ptr(), &mut status)
+            });
+            assert_eq!(state.cloning_rates, unsafe {
+                forward_graph_cloning_rates(graph.as_ptr(), &mut status)
+            });
+            assert!(!state.parental_deme_sizes.is_null());
+            if state.offspring_deme_sizes.is_null() {
+                assert!(state.selfing_rates.is_null());
+                assert!(state.cloning_rates.is_null());
+            } else {
+                let selfing_rates = unsafe { std::slice::from_raw_parts(state.selfing_rates, 2) };
+                assert_eq!(selfing_rates, [0.0, 0.25]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+     end_time: 5
+   - start_size: 200
+";
+        let mut graph = GraphHolder::new();
+        assert_eq!(
+            unsafe { forward_graph_reset(graph.as_mut_ptr()) },
+            ForwardGraphErrorCode::GraphUninitialized as i32
+        );
+        assert_eq!(
+            unsafe { forward_graph_reset(std::ptr::null_mut()) },
+            ForwardGraphErrorCode::GraphIsNull as i32
+        );
+        assert_eq!(graph.init_with_yaml(10.0, yaml), 0);
+
+        let mut status = -1;
+        let mut replicates = vec![];
+        for _ in 0..2 {
+            let mut sizes = vec![];
+            assert_eq!(
+                unsafe { forward_graph_initialize_time_iteration(graph.as_mut_ptr()) },
+                0
+            );
+            while let Some(t) =
+                unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status).as_ref() }
+            {
+                assert_eq!(
+                    unsafe { forward_graph_update_state(*t, graph.as_mut_ptr()) },
+                    0
+                );
+                let p = unsafe { forward_graph_parental_deme_sizes(graph.as_ptr(), &mut status) };
+                sizes.push((*t, unsafe { *p }));
+                if *t == 12.0 {
+                    // Stop this replicate early, with an error
+                    let _ = unsafe {
+                        forward_graph_ancestry_proportions(1, &mut status, graph.as_mut_ptr())
+                    };
+                    assert!(unsafe { forward_graph_is_error_state(graph.as_ptr()) });
+                    break;
+                }
+            }
+            replicates.push(sizes);
+            assert_eq!(unsafe { forward_graph_reset(graph.as_mut_ptr()) }, 0);
+            assert!(!unsafe { forward_graph_is_error_state(graph.as_ptr()) });
+            assert!(
+                unsafe { forward_graph_parental_deme_sizes(graph.as_ptr(), &mut status) }.is_null()
+            );
+            assert_eq!(status, 0);
+            // Iteration must be initialized again
+            let t = unsafe { forward_graph_iterate_time(graph.as_mut_ptr(), &mut status) };
+            assert!(t.is_null());
+            assert_eq!(
+                status,
+                ForwardGraphErrorCode::IterationNotInitialized as i32
+            );
+            assert_eq!(unsafe { forward_graph_reset(graph.as_mut_ptr()) }, 0);
+        }
+        assert_eq!(replicates[0], replicates[1]);
+        assert_eq!(replicates[0].len(), 13);
+        assert_eq!(replicates[0][0], (0.0, 100.0));
+    }
 }
@@ -0,0 +1,453 @@
+// A handle to a ForwardGraph that may be shared between threads.
+//
+// The graph is behind a RwLock.
+// Queries take a read lock and copy values into buffers
+// owned by the caller, so that no pointers into the graph
+// outlive the lock.
+
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+
+use crate::ForwardGraphErrorCode;
+use crate::OpaqueForwardGraph;
+use demes_forward::ForwardGraph;
+
+/// ## Send/Sync
+///
+/// A thread-safe alternative to [`OpaqueForwardGraph`].
+///
+/// All functions take a const pointer and lock the graph internally.
+/// Thus, many threads may query one instance at the same time.
+/// Queries copy values into buffers owned by the caller.
+///
+/// Unlike [`OpaqueForwardGraph`], this type does not store error
+/// messages.
+/// Errors are reported by [`ForwardGraphErrorCode`] only.
+///
+/// ```
+/// fn is_send<T: Send>()  {}
+/// fn is_sync<T: Sync>()  {}
+///
+/// is_send::<demes_forward_capi::OpaqueForwardGraphSync>();
+/// is_sync::<demes_forward_capi::OpaqueForwardGraphSync>();
+/// ```
+pub struct OpaqueForwardGraphSync {
+    graph: RwLock<ForwardGraph>,
+}
+
+impl OpaqueForwardGraphSync {
+    fn read(&self) -> RwLockReadGuard<'_, ForwardGraph> {
+        // A panic while holding the lock cannot leave
+        // the graph in an invalid state.
+        self.graph
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+unsafe fn copy_values<T>(
+    graph: *const OpaqueForwardGraphSync,
+    buffer: *mut f64,
+    length: usize,
+    values: impl FnOnce(&ForwardGraph) -> Result<Option<&[T]>, ForwardGraphErrorCode>,
+) -> isize
+where
+    T: Copy,
+    f64: From<T>,
+{
+    if graph.is_null() {
+        return ForwardGraphErrorCode::GraphIsNull as isize;
+    }
+    let fgraph = (*graph).read();
+    match values(&fgraph) {
+        Ok(Some(values)) => {
+            if buffer.is_null() {
+                return ForwardGraphErrorCode::NullPointer as isize;
+            }
+            if length < values.len() {
+                return ForwardGraphErrorCode::BufferTooSmall as isize;
+            }
+            let buffer = std::slice::from_raw_parts_mut(buffer, values.len());
+            for (b, &v) in buffer.iter_mut().zip(values.iter()) {
+                *b = f64::from(v);
+            }
+            values.len() as isize
+        }
+        Ok(None) => 0,
+        Err(code) => code as isize,
+    }
+}
+
+/// Allocate an [`OpaqueForwardGraphSync`] from a copy of an
+/// initialized [`OpaqueForwardGraph`].
+///
+/// The copy includes the current state of `graph`.
+///
+/// # Returns
+///
+/// * NULL if `graph` is NULL or has not been initialized,
+///   in which case `status` is set to a [`ForwardGraphErrorCode`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer to an [`OpaqueForwardGraph`].
+/// * `status` must be a valid pointer to an `i32` or NULL.
+///
+/// The pointer returned is freed by [`forward_graph_sync_deallocate`].
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_allocate(
+    graph: *const OpaqueForwardGraph,
+    status: *mut i32,
+) -> *mut OpaqueForwardGraphSync {
+    if status.is_null() {
+        return std::ptr::null_mut();
+    }
+    *status = 0;
+    if graph.is_null() {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        return std::ptr::null_mut();
+    }
+    match &(*graph).graph {
+        Some(fgraph) => Box::into_raw(Box::new(OpaqueForwardGraphSync {
+            graph: RwLock::new(fgraph.clone()),
+        })),
+        None => {
+            *status = (*graph).uninitialized_error_code() as i32;
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+///
+/// `graph` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_deallocate(graph: *mut OpaqueForwardGraphSync) {
+    let _ = Box::from_raw(graph);
+}
+
+/// Get the total number of demes in the model.
+///
+/// # Returns
+///
+/// [`isize`] > 0 if `graph` is not NULL.
+/// Returns [`ForwardGraphErrorCode::GraphIsNull`] otherwise.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer or NULL
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_number_of_demes(
+    graph: *const OpaqueForwardGraphSync,
+) -> isize {
+    if graph.is_null() {
+        return ForwardGraphErrorCode::GraphIsNull as isize;
+    }
+    (*graph).read().num_demes_in_model() as isize
+}
+
+/// Get the model end time.
+///
+/// See [`forward_graph_model_end_time`](crate::forward_graph_model_end_time).
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `status` must be a valid pointer to an `i32` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_model_end_time(
+    graph: *const OpaqueForwardGraphSync,
+    status: *mut i32,
+) -> f64 {
+    if status.is_null() {
+        return f64::NAN;
+    }
+    if graph.is_null() {
+        *status = ForwardGraphErrorCode::GraphIsNull as i32;
+        return f64::NAN;
+    }
+    *status = 0;
+    (*graph).read().end_time().value()
+}
+
+/// Update the model state to a given time.
+///
+/// This function blocks until no other thread
+/// is querying `graph`.
+///
+/// # Return values
+///
+/// * 0 on success
+/// * A [`ForwardGraphErrorCode`] otherwise, in which case
+///   the values returned by queries are unspecified until
+///   the next successful update.
+///
+/// # Safety
+///
+/// `graph` must be a valid pointer or NULL
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_update_state(
+    time: f64,
+    graph: *const OpaqueForwardGraphSync,
+) -> i32 {
+    if graph.is_null() {
+        return ForwardGraphErrorCode::GraphIsNull as i32;
+    }
+    let mut fgraph = (*graph)
+        .graph
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match fgraph.update_state(time) {
+        Ok(_) => 0,
+        Err(e) => ForwardGraphErrorCode::from(&e) as i32,
+    }
+}
+
+/// Copy the parental deme sizes into `buffer`.
+///
+/// # Returns
+///
+/// * The number of values copied, which is
+///   [`forward_graph_sync_number_of_demes`], or zero
+///   if the state has not been updated.
+/// * [`ForwardGraphErrorCode::BufferTooSmall`] if `length` is less
+///   than the number of values.
+/// * [`ForwardGraphErrorCode::NullPointer`] if `buffer` is NULL
+///   and there are values to copy.
+/// * Another negative [`ForwardGraphErrorCode`] otherwise.
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `buffer` must be a valid pointer to at least `length` values.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_copy_parental_deme_sizes(
+    graph: *const OpaqueForwardGraphSync,
+    buffer: *mut f64,
+    length: usize,
+) -> isize {
+    copy_values(graph, buffer, length, |g| Ok(g.parental_deme_sizes()))
+}
+
+/// Copy the offspring deme sizes into `buffer`.
+///
+/// # Returns
+///
+/// * Zero if there are no offspring demes.
+/// * Otherwise, the same values as
+///   [`forward_graph_sync_copy_parental_deme_sizes`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `buffer` must be a valid pointer to at least `length` values.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_copy_offspring_deme_sizes(
+    graph: *const OpaqueForwardGraphSync,
+    buffer: *mut f64,
+    length: usize,
+) -> isize {
+    copy_values(graph, buffer, length, |g| Ok(g.offspring_deme_sizes()))
+}
+
+/// Copy the selfing rates of the offspring demes into `buffer`.
+///
+/// # Returns
+///
+/// See [`forward_graph_sync_copy_offspring_deme_sizes`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `buffer` must be a valid pointer to at least `length` values.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_copy_selfing_rates(
+    graph: *const OpaqueForwardGraphSync,
+    buffer: *mut f64,
+    length: usize,
+) -> isize {
+    copy_values(graph, buffer, length, |g| Ok(g.selfing_rates()))
+}
+
+/// Copy the cloning rates of the offspring demes into `buffer`.
+///
+/// # Returns
+///
+/// See [`forward_graph_sync_copy_offspring_deme_sizes`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `buffer` must be a valid pointer to at least `length` values.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_copy_cloning_rates(
+    graph: *const OpaqueForwardGraphSync,
+    buffer: *mut f64,
+    length: usize,
+) -> isize {
+    copy_values(graph, buffer, length, |g| Ok(g.cloning_rates()))
+}
+
+/// Copy the ancestry proportions of an offspring deme into `buffer`.
+///
+/// # Returns
+///
+/// * [`ForwardGraphErrorCode::IndexOutOfRange`] if
+///   `offspring_deme` is out of range.
+/// * Otherwise, see [`forward_graph_sync_copy_offspring_deme_sizes`].
+///
+/// # Safety
+///
+/// * `graph` must be a valid pointer or NULL
+/// * `buffer` must be a valid pointer to at least `length` values.
+#[no_mangle]
+pub unsafe extern "C" fn forward_graph_sync_copy_ancestry_proportions(
+    graph: *const OpaqueForwardGraphSync,
+    offspring_deme: usize,
+    buffer: *mut f64,
+    length: usize,
+) -> isize {
+    copy_values(graph, buffer, length, |g| {
+        if offspring_deme >= g.num_demes_in_model() {
+            Err(ForwardGraphErrorCode::IndexOutOfRange)
+        } else {
+            Ok(g.ancestry_proportions(offspring_deme))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use std::ffi::CString;
+
+    fn yaml() -> CString {
+        CString::new(
+            "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+   - start_size: 100
+ - name: B
+   epochs:
+   - start_size: 50
+migrations:
+ - source: A
+   dest: B
+   rate: 0.25
+",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_allocate() {
+        let graph = forward_graph_allocate();
+        let mut status = 0;
+        let sync = unsafe { forward_graph_sync_allocate(graph, &mut status) };
+        assert!(sync.is_null());
+        assert_eq!(status, ForwardGraphErrorCode::GraphUninitialized as i32);
+        let sync = unsafe { forward_graph_sync_allocate(std::ptr::null(), &mut status) };
+        assert!(sync.is_null());
+        assert_eq!(status, ForwardGraphErrorCode::GraphIsNull as i32);
+        unsafe { forward_graph_deallocate(graph) };
+    }
+
+    #[test]
+    fn test_queries_from_many_threads() {
+        let graph = forward_graph_allocate();
+        let yaml = yaml();
+        assert_eq!(
+            unsafe { forward_graph_initialize_from_yaml(yaml.as_ptr(), 10.0, graph) },
+            0
+        );
+        assert_eq!(unsafe { forward_graph_update_state(5.0, graph) }, 0);
+        let mut status = -1;
+        let sync = unsafe { forward_graph_sync_allocate(graph, &mut status) };
+        assert_eq!(status, 0);
+        unsafe { forward_graph_deallocate(graph) };
+
+        assert_eq!(unsafe { forward_graph_sync_number_of_demes(sync) }, 2);
+        assert_eq!(
+            unsafe { forward_graph_sync_model_end_time(sync, &mut status) },
+            11.0
+        );
+        assert_eq!(status, 0);
+
+        // Raw pointers are not Send
+        let address = sync as usize;
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(move || {
+                    let sync = address as *const OpaqueForwardGraphSync;
+                    let mut buffer = [0.0; 2];
+                    assert_eq!(
+                        unsafe {
+                            forward_graph_sync_copy_parental_deme_sizes(
+                                sync,
+                                buffer.as_mut_ptr(),
+                                2,
+                            )
+                        },
+                        2
+                    );
+                    assert_eq!(buffer, [100.0, 50.0]);
+                    assert_eq!(
+                        unsafe {
+                            forward_graph_sync_copy_ancestry_proportions(
+                                sync,
+                                1,
+                                buffer.as_mut_ptr(),
+                                2,
+                            )
+                        },
+                        2
+                    );
+                    assert_eq!(buffer, [0.25, 0.75]);
+                });
+            }
+        });
+
+        let mut buffer = [0.0; 2];
+        for copy in [
+            forward_graph_sync_copy_offspring_deme_sizes,
+            forward_graph_sync_copy_selfing_rates,
+            forward_graph_sync_copy_cloning_rates,
+        ] {
+            assert_eq!(unsafe { copy(sync, buffer.as_mut_ptr(), 2) }, 2);
+            assert_eq!(
+                unsafe { copy(sync, buffer.as_mut_ptr(), 1) },
+                ForwardGraphErrorCode::BufferTooSmall as isize
+            );
+            assert_eq!(
+                unsafe { copy(std::ptr::null(), buffer.as_mut_ptr(), 2) },
+                ForwardGraphErrorCode::GraphIsNull as isize
+            );
+            assert_eq!(
+                unsafe { copy(sync, std::ptr::null_mut(), 2) },
+                ForwardGraphErrorCode::NullPointer as isize
+            );
+        }
+        assert_eq!(
+            unsafe {
+                forward_graph_sync_copy_ancestry_proportions(sync, 2, buffer.as_mut_ptr(), 2)
+            },
+            ForwardGraphErrorCode::IndexOutOfRange as isize
+        );
+
+        // The last parental generation has no offspring
+        assert_eq!(unsafe { forward_graph_sync_update_state(10.0, sync) }, 0);
+        assert_eq!(
+            unsafe { forward_graph_sync_copy_offspring_deme_sizes(sync, buffer.as_mut_ptr(), 2) },
+            0
+        );
+        assert_eq!(
+            unsafe { forward_graph_sync_update_state(-1.0, sync) },
+            ForwardGraphErrorCode::InvalidTime as i32
+        );
+
+        unsafe { forward_graph_sync_deallocate(sync) };
+    }
+}
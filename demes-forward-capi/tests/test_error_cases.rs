@@ -4,12 +4,17 @@ use demes_forward_capi::*;
 fn test_initialize_from_non_existant_file() {
     let graph = forward_graph_allocate();
     let filename = "no_way_this_exists";
-    unsafe {
+    let status = unsafe {
         forward_graph_initialize_from_yaml_file(filename.as_ptr() as *const i8, 100.0, graph)
     };
+    assert_eq!(status, ForwardGraphErrorCode::ParseError as i32);
 
     let is_error = unsafe { forward_graph_is_error_state(graph) };
     assert!(is_error);
+    assert_eq!(
+        unsafe { forward_graph_last_error_code(graph) },
+        ForwardGraphErrorCode::ParseError
+    );
 
     unsafe {
         forward_graph_deallocate(graph);
@@ -22,6 +27,7 @@ fn test_errors_const_api_with_uninitialized_graph() {
     let mut status = 0;
     let _ = unsafe { forward_graph_selfing_rates(graph as *const OpaqueForwardGraph, &mut status) };
     assert!(status < 0); // make sure we are in an error state
+    assert_eq!(status, ForwardGraphErrorCode::GraphUninitialized as i32);
 
     status = 0;
     let _ = unsafe { forward_graph_cloning_rates(graph as *const OpaqueForwardGraph, &mut status) };
@@ -56,6 +62,11 @@ fn test_errors_const_api_with_uninitialized_graph() {
         unsafe { forward_graph_model_end_time(graph as *const OpaqueForwardGraph, &mut status) };
     assert!(status < 0);
 
+    status = 0;
+    let _ =
+        unsafe { forward_graph_migration_matrix(graph as *const OpaqueForwardGraph, &mut status) };
+    assert!(status < 0);
+
     unsafe {
         forward_graph_deallocate(graph);
     }
@@ -71,7 +82,7 @@ macro_rules! make_test_of_const_api_with_null {
             unsafe {
                 $function(std::ptr::null() as *const OpaqueForwardGraph, &mut status);
             }
-            assert!(status < 0);
+            assert_eq!(status, ForwardGraphErrorCode::GraphIsNull as i32);
         }
     };
 }
@@ -94,6 +105,8 @@ make_test_of_const_api_with_null!(
 );
 make_test_of_const_api_with_null!(test_ub_cloning_rates, forward_graph_cloning_rates);
 make_test_of_const_api_with_null!(test_ub_selfing_rates, forward_graph_selfing_rates);
+make_test_of_const_api_with_null!(test_ub_migration_matrix, forward_graph_migration_matrix);
+make_test_of_const_api_with_null!(test_ub_number_of_pulses, forward_graph_number_of_pulses);
 
 fn simple_yaml() -> std::ffi::CString {
     let yaml = "
@@ -115,3 +128,65 @@ fn test_initialize_from_yaml_with_null_graph() {
         unsafe { forward_graph_initialize_from_yaml(cstr.as_ptr(), 100.0, std::ptr::null_mut()) };
     assert!(status < 0);
 }
+
+#[test]
+fn test_last_error_code_with_null_graph() {
+    assert_eq!(
+        unsafe { forward_graph_last_error_code(std::ptr::null()) },
+        ForwardGraphErrorCode::GraphIsNull
+    );
+}
+
+#[test]
+fn test_get_state_with_null_pointers() {
+    let mut state = forward_graph_state {
+        time: 0.0,
+        num_demes: 0,
+        parental_deme_sizes: std::ptr::null(),
+        offspring_deme_sizes: std::ptr::null(),
+        selfing_rates: std::ptr::null(),
+        cloning_rates: std::ptr::null(),
+    };
+    assert_eq!(
+        unsafe { forward_graph_get_state(std::ptr::null(), &mut state) },
+        ForwardGraphErrorCode::GraphIsNull as i32
+    );
+
+    let graph = forward_graph_allocate();
+    let cstr = simple_yaml();
+    let status = unsafe { forward_graph_initialize_from_yaml(cstr.as_ptr(), 100.0, graph) };
+    assert_eq!(status, 0);
+    assert_eq!(
+        unsafe { forward_graph_get_state(graph, std::ptr::null_mut()) },
+        ForwardGraphErrorCode::NullPointer as i32
+    );
+    unsafe { forward_graph_deallocate(graph) };
+}
+
+#[test]
+fn test_null_status() {
+    let graph = forward_graph_allocate();
+    let cstr = simple_yaml();
+    let status = unsafe { forward_graph_initialize_from_yaml(cstr.as_ptr(), 100.0, graph) };
+    assert_eq!(status, 0);
+    let status = std::ptr::null_mut();
+    unsafe {
+        assert!(forward_graph_parental_deme_sizes(graph, status).is_null());
+        assert!(forward_graph_model_end_time(graph, status).is_nan());
+        assert!(forward_graph_size_at(graph, 0, 0.0, status).is_nan());
+        assert_eq!(forward_graph_number_of_pulses(graph, status), 0);
+        assert_eq!(forward_graph_pulse_dest(graph, 0, status), 0);
+        assert!(forward_graph_get_demes_graph(graph, status).is_null());
+        forward_graph_deallocate(graph);
+    }
+}
+
+#[test]
+fn test_initialize_from_yaml_file_with_null_file_name() {
+    let graph = forward_graph_allocate();
+    assert_eq!(
+        unsafe { forward_graph_initialize_from_yaml_file(std::ptr::null(), 100.0, graph) },
+        ForwardGraphErrorCode::NullPointer as i32
+    );
+    unsafe { forward_graph_deallocate(graph) };
+}
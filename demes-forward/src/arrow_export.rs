@@ -0,0 +1,366 @@
+// Export of TrajectoryTables to Arrow and Parquet.
+//
+// The tables are written in long format, with one row
+// per generation and deme, or pair of demes, so that they
+// can be filtered and grouped by tools that consume Arrow data.
+
+use std::sync::Arc;
+
+use arrow_array::ArrayRef;
+use arrow_array::Float64Array;
+use arrow_array::RecordBatch;
+use arrow_array::StringArray;
+use arrow_array::UInt64Array;
+use arrow_schema::DataType;
+use arrow_schema::Field;
+use arrow_schema::Schema;
+
+use crate::DemesForwardError;
+use crate::TrajectoryTables;
+
+fn export_error<E: std::fmt::Display>(error: E) -> DemesForwardError {
+    DemesForwardError::ExportError(error.to_string())
+}
+
+impl TrajectoryTables {
+    /// Export the deme sizes and rates as an Arrow record batch.
+    ///
+    /// There is one row per generation and deme, with columns:
+    ///
+    /// * `generation`: the parental generation time.
+    /// * `deme`: the deme name.
+    /// * `parental_size`: the size of the parental deme.
+    /// * `offspring_size`, `selfing_rate`, `cloning_rate`: the values
+    ///   for the offspring deme, which are null for the last
+    ///   parental generation.
+    ///
+    /// Requires the `arrow` feature.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError::ExportError`] if the record batch
+    /// cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 5).unwrap();
+    /// let batch = graph.precompute().unwrap().deme_sizes_to_arrow().unwrap();
+    /// assert_eq!(batch.num_rows(), 16);
+    /// assert_eq!(batch.num_columns(), 6);
+    /// ```
+    pub fn deme_sizes_to_arrow(&self) -> Result<RecordBatch, DemesForwardError> {
+        let num_demes = self.num_demes();
+        let num_rows = self.num_generations() * num_demes;
+        let generation = (0..num_rows)
+            .map(|row| (row / num_demes) as u64)
+            .collect::<UInt64Array>();
+        let deme = (0..num_rows)
+            .map(|row| Some(self.deme_names()[row % num_demes].as_str()))
+            .collect::<StringArray>();
+        let parental_size = self
+            .parental_deme_sizes_table()
+            .iter()
+            .map(|&size| f64::from(size))
+            .collect::<Float64Array>();
+        // Null values pad the offspring columns
+        // at the last generation.
+        let offspring_column = |values: Vec<f64>| {
+            values
+                .into_iter()
+                .map(Some)
+                .chain(std::iter::repeat(None).take(num_demes))
+                .collect::<Float64Array>()
+        };
+        let offspring_size = offspring_column(
+            self.offspring_deme_sizes_table()
+                .iter()
+                .map(|&size| f64::from(size))
+                .collect(),
+        );
+        let selfing_rate = offspring_column(
+            self.selfing_rates_table()
+                .iter()
+                .map(|&rate| f64::from(rate))
+                .collect(),
+        );
+        let cloning_rate = offspring_column(
+            self.cloning_rates_table()
+                .iter()
+                .map(|&rate| f64::from(rate))
+                .collect(),
+        );
+        let schema = Schema::new(vec![
+            Field::new("generation", DataType::UInt64, false),
+            Field::new("deme", DataType::Utf8, false),
+            Field::new("parental_size", DataType::Float64, false),
+            Field::new("offspring_size", DataType::Float64, true),
+            Field::new("selfing_rate", DataType::Float64, true),
+            Field::new("cloning_rate", DataType::Float64, true),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(generation),
+            Arc::new(deme),
+            Arc::new(parental_size),
+            Arc::new(offspring_size),
+            Arc::new(selfing_rate),
+            Arc::new(cloning_rate),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns).map_err(export_error)
+    }
+
+    /// Export the ancestry proportions as an Arrow record batch.
+    ///
+    /// There is one row per generation, offspring deme, and
+    /// parental deme with a nonzero proportion, with columns:
+    ///
+    /// * `generation`: the parental generation time.
+    /// * `offspring_deme`: the name of the offspring deme.
+    /// * `parental_deme`: the name of the parental deme.
+    /// * `proportion`: the proportion of the ancestry of the
+    ///   offspring deme from the parental deme.
+    ///   See [`ForwardGraph::ancestry_proportions`](crate::ForwardGraph::ancestry_proportions).
+    ///
+    /// Requires the `arrow` feature.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError::ExportError`] if the record batch
+    /// cannot be created.
+    pub fn ancestry_proportions_to_arrow(&self) -> Result<RecordBatch, DemesForwardError> {
+        let num_demes = self.num_demes();
+        let mut generation = vec![];
+        let mut offspring_deme = vec![];
+        let mut parental_deme = vec![];
+        let mut proportion = vec![];
+        for (index, &value) in self.ancestry_proportions_table().iter().enumerate() {
+            if value > 0.0 {
+                generation.push((index / (num_demes * num_demes)) as u64);
+                offspring_deme.push(self.deme_names()[(index / num_demes) % num_demes].as_str());
+                parental_deme.push(self.deme_names()[index % num_demes].as_str());
+                proportion.push(value);
+            }
+        }
+        let schema = Schema::new(vec![
+            Field::new("generation", DataType::UInt64, false),
+            Field::new("offspring_deme", DataType::Utf8, false),
+            Field::new("parental_deme", DataType::Utf8, false),
+            Field::new("proportion", DataType::Float64, false),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from(generation)),
+            Arc::new(StringArray::from(offspring_deme)),
+            Arc::new(StringArray::from(parental_deme)),
+            Arc::new(Float64Array::from(proportion)),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns).map_err(export_error)
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet<W: std::io::Write + Send>(
+    batch: RecordBatch,
+    writer: W,
+) -> Result<(), DemesForwardError> {
+    let mut writer =
+        parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None).map_err(export_error)?;
+    writer.write(&batch).map_err(export_error)?;
+    writer.close().map_err(export_error)?;
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+impl TrajectoryTables {
+    /// Write the deme sizes and rates in Parquet format.
+    ///
+    /// The columns are those of [`TrajectoryTables::deme_sizes_to_arrow`].
+    ///
+    /// Requires the `parquet` feature.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError::ExportError`] if writing fails.
+    pub fn write_deme_sizes_parquet<W: std::io::Write + Send>(
+        &self,
+        writer: W,
+    ) -> Result<(), DemesForwardError> {
+        write_parquet(self.deme_sizes_to_arrow()?, writer)
+    }
+
+    /// Write the ancestry proportions in Parquet format.
+    ///
+    /// The columns are those of
+    /// [`TrajectoryTables::ancestry_proportions_to_arrow`].
+    ///
+    /// Requires the `parquet` feature.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError::ExportError`] if writing fails.
+    pub fn write_ancestry_proportions_parquet<W: std::io::Write + Send>(
+        &self,
+        writer: W,
+    ) -> Result<(), DemesForwardError> {
+        write_parquet(self.ancestry_proportions_to_arrow()?, writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ForwardGraph;
+    use arrow_array::Array;
+
+    fn model() -> demes::Graph {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 20
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 50
+ - name: C
+   ancestors: [A]
+   epochs:
+    - start_size: 10
+      cloning_rate: 0.25
+migrations:
+ - demes: [B, C]
+   rate: 1e-2
+";
+        demes::loads(yaml).unwrap()
+    }
+
+    fn tables() -> TrajectoryTables {
+        ForwardGraph::new_discrete_time(model(), 5)
+            .unwrap()
+            .precompute()
+            .unwrap()
+    }
+
+    fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> &'a T {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<T>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_deme_sizes() {
+        let tables = tables();
+        let batch = tables.deme_sizes_to_arrow().unwrap();
+        assert_eq!(batch.num_rows(), tables.num_generations() * 3);
+        let generation = column::<UInt64Array>(&batch, "generation");
+        let deme = column::<StringArray>(&batch, "deme");
+        let parental_size = column::<Float64Array>(&batch, "parental_size");
+        let offspring_size = column::<Float64Array>(&batch, "offspring_size");
+        let cloning_rate = column::<Float64Array>(&batch, "cloning_rate");
+        for row in 0..batch.num_rows() {
+            let time = generation.value(row) as f64;
+            let index = row % 3;
+            assert_eq!(deme.value(row), tables.deme_names()[index]);
+            assert_eq!(
+                parental_size.value(row),
+                tables.parental_deme_sizes(time).unwrap()[index]
+            );
+            match tables.offspring_deme_sizes(time) {
+                Some(sizes) => {
+                    assert_eq!(offspring_size.value(row), sizes[index]);
+                    assert_eq!(
+                        cloning_rate.value(row),
+                        f64::from(tables.cloning_rates(time).unwrap()[index])
+                    );
+                }
+                None => {
+                    assert!(offspring_size.is_null(row));
+                    assert!(cloning_rate.is_null(row));
+                }
+            }
+        }
+        assert_eq!(offspring_size.null_count(), 3);
+    }
+
+    #[test]
+    fn test_ancestry_proportions() {
+        let tables = tables();
+        let batch = tables.ancestry_proportions_to_arrow().unwrap();
+        let generation = column::<UInt64Array>(&batch, "generation");
+        let offspring_deme = column::<StringArray>(&batch, "offspring_deme");
+        let parental_deme = column::<StringArray>(&batch, "parental_deme");
+        let proportion = column::<Float64Array>(&batch, "proportion");
+        let index = |name: &str| tables.deme_names().iter().position(|n| n == name).unwrap();
+        let mut sums = std::collections::HashMap::<(u64, &str), f64>::new();
+        for row in 0..batch.num_rows() {
+            let expected = tables
+                .ancestry_proportions(
+                    generation.value(row) as f64,
+                    index(offspring_deme.value(row)),
+                )
+                .unwrap()[index(parental_deme.value(row))];
+            assert!(proportion.value(row) > 0.0);
+            assert_eq!(proportion.value(row), expected);
+            *sums
+                .entry((generation.value(row), offspring_deme.value(row)))
+                .or_default() += proportion.value(row);
+        }
+        assert!(sums.values().all(|sum| (sum - 1.0).abs() < 1e-12));
+        // All extant offspring demes are present
+        let num_extant = tables
+            .offspring_deme_sizes_table()
+            .iter()
+            .filter(|&&size| size > 0.0)
+            .count();
+        assert_eq!(sums.len(), num_extant);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let tables = tables();
+        let expected = [
+            tables.deme_sizes_to_arrow().unwrap(),
+            tables.ancestry_proportions_to_arrow().unwrap(),
+        ];
+        let path = std::env::temp_dir().join(format!(
+            "demes_forward_arrow_export_{}.parquet",
+            std::process::id()
+        ));
+        for (i, expected) in expected.iter().enumerate() {
+            let file = std::fs::File::create(&path).unwrap();
+            if i == 0 {
+                tables.write_deme_sizes_parquet(file).unwrap();
+            } else {
+                tables.write_ancestry_proportions_parquet(file).unwrap();
+            }
+            let file = std::fs::File::open(&path).unwrap();
+            let batches = ParquetRecordBatchReaderBuilder::try_new(file)
+                .unwrap()
+                .build()
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            // The tables are smaller than the default batch size
+            assert_eq!(batches.len(), 1);
+            assert_eq!(&batches[0], expected);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+// Serialization of a ForwardGraph for checkpointing.
+//
+// The internal buffers of a ForwardGraph are fully determined
+// by the graph, the construction parameters, the subset of
+// demes, whether cumulative ancestry is tracked, and the last
+// time passed to update_state.
+// Only those values are stored.
+// Deserializing restores the buffers with a single call to
+// update_state.
+
+use serde::de::Error;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::ForwardGraph;
+
+#[derive(Serialize)]
+struct CheckpointRef<'graph> {
+    graph: &'graph demes::Graph,
+    burnin_time: f64,
+    time_step: f64,
+    focal_demes: Option<Vec<&'graph str>>,
+    cumulative_ancestry: bool,
+    last_time_updated: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Checkpoint {
+    graph: demes::Graph,
+    burnin_time: f64,
+    time_step: f64,
+    #[serde(default)]
+    focal_demes: Option<Vec<String>>,
+    #[serde(default)]
+    cumulative_ancestry: bool,
+    last_time_updated: Option<f64>,
+}
+
+impl Serialize for ForwardGraph {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        CheckpointRef {
+            graph: self.demes_graph(),
+            burnin_time: self.burn_in_duration(),
+            time_step: self.time_step(),
+            focal_demes: self.focal_deme_names(),
+            cumulative_ancestry: self.tracks_cumulative_ancestry(),
+            last_time_updated: self.last_time_updated().map(|time| time.value()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ForwardGraph {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let checkpoint = Checkpoint::deserialize(deserializer)?;
+        let mut graph = ForwardGraph::new_discrete_time_with_time_step(
+            checkpoint.graph,
+            checkpoint.burnin_time,
+            checkpoint.time_step,
+        )
+        .map_err(D::Error::custom)?;
+        if let Some(focal_demes) = &checkpoint.focal_demes {
+            graph = graph
+                .with_deme_subset(focal_demes.iter().map(|name| name.as_str()))
+                .map_err(D::Error::custom)?;
+        }
+        if checkpoint.cumulative_ancestry {
+            graph = graph.with_cumulative_ancestry().map_err(D::Error::custom)?;
+        }
+        if let Some(time) = checkpoint.last_time_updated {
+            graph.update_state(time).map_err(D::Error::custom)?;
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same_state(a: &ForwardGraph, b: &ForwardGraph) {
+        assert_eq!(a.last_time_updated(), b.last_time_updated());
+        assert_eq!(a.time_step(), b.time_step());
+        assert_eq!(a.end_time(), b.end_time());
+        assert_eq!(a.parental_deme_sizes(), b.parental_deme_sizes());
+        assert_eq!(a.offspring_deme_sizes(), b.offspring_deme_sizes());
+        assert_eq!(a.selfing_rates(), b.selfing_rates());
+        assert_eq!(a.cloning_rates(), b.cloning_rates());
+        assert!(a.pulses_at_current_time().eq(b.pulses_at_current_time()));
+        for deme in 0..a.num_demes_in_model() {
+            assert_eq!(a.ancestry_proportions(deme), b.ancestry_proportions(deme));
+            assert_eq!(
+                a.cumulative_ancestry_proportions(deme),
+                b.cumulative_ancestry_proportions(deme)
+            );
+        }
+    }
+
+    fn model() -> demes::Graph {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 50
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      end_size: 200
+ - name: C
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [B, C]
+   rate: 1e-3
+pulses:
+ - sources: [B]
+   dest: C
+   time: 20
+   proportions: [0.5]
+";
+        demes::loads(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for time_step in [1.0, 3.0] {
+            let mut graph =
+                ForwardGraph::new_discrete_time_with_time_step(model(), 10, time_step).unwrap();
+            let yaml = serde_yaml::to_string(&graph).unwrap();
+            let restored: ForwardGraph = serde_yaml::from_str(&yaml).unwrap();
+            assert!(restored.last_time_updated().is_none());
+            assert_same_state(&graph, &restored);
+
+            for time in graph.time_iterator().collect::<Vec<_>>() {
+                graph.update_state(time).unwrap();
+                let yaml = serde_yaml::to_string(&graph).unwrap();
+                let mut restored: ForwardGraph = serde_yaml::from_str(&yaml).unwrap();
+                assert_same_state(&graph, &restored);
+
+                // Continuing from the checkpoint gives the same states
+                let mut original = graph.clone();
+                for next in original.time_iterator().skip(1).take(5).collect::<Vec<_>>() {
+                    original.update_state(next).unwrap();
+                    restored.update_state(next).unwrap();
+                    assert_same_state(&original, &restored);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        graph.update_state(5).unwrap();
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: ForwardGraph = serde_json::from_str(&json).unwrap();
+        assert_same_state(&graph, &restored);
+    }
+
+    #[test]
+    fn test_deme_subset() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10)
+            .unwrap()
+            .with_deme_subset(["A", "C"])
+            .unwrap();
+        graph.update_state(45).unwrap();
+        let yaml = serde_yaml::to_string(&graph).unwrap();
+        let restored: ForwardGraph = serde_yaml::from_str(&yaml).unwrap();
+        assert_same_state(&graph, &restored);
+        assert_eq!(restored.parental_deme_sizes().unwrap()[1], 0.0);
+
+        // Checkpoints without a subset are still valid
+        let graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        let yaml = serde_yaml::to_string(&graph).unwrap();
+        assert!(yaml.contains("focal_demes: null\n"));
+        let yaml = yaml.replace("focal_demes: null\n", "");
+        let yaml = yaml.replace("cumulative_ancestry: false\n", "");
+        let restored: ForwardGraph = serde_yaml::from_str(&yaml).unwrap();
+        assert_same_state(&graph, &restored);
+    }
+
+    #[test]
+    fn test_cumulative_ancestry() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10)
+            .unwrap()
+            .with_cumulative_ancestry()
+            .unwrap();
+        for time in graph.time_iterator().take(35).collect::<Vec<_>>() {
+            graph.update_state(time).unwrap();
+        }
+        let json = serde_json::to_string(&graph).unwrap();
+        let mut restored: ForwardGraph = serde_json::from_str(&json).unwrap();
+        assert!(restored.cumulative_ancestry_proportions(2).is_some());
+        assert_same_state(&graph, &restored);
+        for time in graph.time_iterator().collect::<Vec<_>>() {
+            graph.update_state(time).unwrap();
+            restored.update_state(time).unwrap();
+            assert_same_state(&graph, &restored);
+        }
+    }
+
+    #[test]
+    fn test_invalid_checkpoint() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        graph.update_state(5).unwrap();
+        let yaml = serde_yaml::to_string(&graph).unwrap();
+        let yaml = yaml.replace("time_step: 1.0", "time_step: 0.5");
+        assert!(serde_yaml::from_str::<ForwardGraph>(&yaml).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::square_matrix::SquareMatrix;
+use crate::time::BackwardTimeWrapper;
+use crate::DemesForwardError;
+
+fn backward_time<T: Into<BackwardTimeWrapper>>(time: T) -> Result<demes::Time, DemesForwardError> {
+    match time.into() {
+        BackwardTimeWrapper::Float(value) => Ok(value.try_into()?),
+        BackwardTimeWrapper::Time(value) => Ok(value),
+    }
+}
+
+/// Continuous-time representation of a [`demes::Graph`].
+///
+/// Unlike [`ForwardGraph`](crate::ForwardGraph), times are not
+/// rounded to integer generations and queries may be made at
+/// any non-negative time.
+/// This type is intended for models that are not discretized
+/// into generations, such as Moran models or diffusions.
+///
+/// Time is measured backwards in time from 0.0 in units of
+/// generations.
+/// A deme or migration is active at times in
+/// `[end_time, start_time)`.
+///
+/// # Examples
+///
+/// ```
+/// let yaml = "
+/// time_units: years
+/// generation_time: 25
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+///       end_time: 1010
+///     - start_size: 100
+///       end_size: 200
+///       size_function: linear
+/// ";
+/// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+/// let graph = demes_forward::ContinuousGraph::new(demes_graph).unwrap();
+/// // 1010 years is 40.4 generations
+/// assert_eq!(graph.size_at(0, 20.2).unwrap().unwrap(), 150.0);
+/// assert_eq!(graph.size_at(0, 40.4).unwrap().unwrap(), 100.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContinuousGraph {
+    graph: demes::Graph,
+}
+
+impl ContinuousGraph {
+    /// Constructor
+    ///
+    /// # Parameters
+    ///
+    /// * graph: a [`demes::Graph`].
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError::DemesError`] if converting the time
+    /// units of `graph` into generations fails.
+    pub fn new(graph: demes::Graph) -> Result<Self, DemesForwardError> {
+        let graph = graph.into_generations()?;
+        Ok(Self { graph })
+    }
+
+    /// The total number of demes in the graph.
+    pub fn num_demes_in_model(&self) -> usize {
+        self.graph.num_demes()
+    }
+
+    /// Obtain the size of a given deme at a given time.
+    ///
+    /// # Parameters
+    ///
+    /// * `deme`: a deme identifier
+    /// * `time`: a time
+    ///
+    /// # Returns
+    ///
+    /// * `Some(DemeSize)` if the deme exists at `time`.
+    /// * `None` if `deme` does not exist or the deme exists but is
+    ///   not in existence at `time`.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError`] if `time` is not a valid value or
+    /// if the calculation of deme size fails.
+    pub fn size_at<'a, I: Into<demes::DemeId<'a>>, T: Into<BackwardTimeWrapper>>(
+        &self,
+        deme: I,
+        time: T,
+    ) -> Result<Option<demes::DemeSize>, DemesForwardError> {
+        let time = backward_time(time)?;
+        match self.graph.get_deme(deme) {
+            Some(deme) => Ok(deme.size_at(time)?),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtain the migration rate from `source` to `dest` at
+    /// a given time.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(rate)` if both demes exist in the graph.
+    ///   The rate is zero if there is no migration at `time`.
+    /// * `None` if either deme does not exist in the graph.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError`] if `time` is not a valid value.
+    pub fn migration_rate<'a, 'b, I, J, T>(
+        &self,
+        source: I,
+        dest: J,
+        time: T,
+    ) -> Result<Option<f64>, DemesForwardError>
+    where
+        I: Into<demes::DemeId<'a>>,
+        J: Into<demes::DemeId<'b>>,
+        T: Into<BackwardTimeWrapper>,
+    {
+        let time = backward_time(time)?;
+        let (source, dest) = match (self.graph.get_deme(source), self.graph.get_deme(dest)) {
+            (Some(source), Some(dest)) => (source.name(), dest.name()),
+            _ => return Ok(None),
+        };
+        Ok(Some(
+            self.graph
+                .migrations()
+                .iter()
+                .find(|m| {
+                    m.source() == source
+                        && m.dest() == dest
+                        && time >= m.end_time()
+                        && time < m.start_time()
+                })
+                .map_or(0.0, |m| f64::from(m.rate())),
+        ))
+    }
+
+    /// The migration rates at a given time.
+    ///
+    /// Element `(i, j)` is the rate of migration from deme `j`
+    /// into deme `i`.
+    /// Unlike [`ForwardGraph::migration_matrix`](crate::ForwardGraph::migration_matrix),
+    /// the diagonal is zero.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError`] if `time` is not a valid value.
+    pub fn migration_matrix<T: Into<BackwardTimeWrapper>>(
+        &self,
+        time: T,
+    ) -> Result<SquareMatrix, DemesForwardError> {
+        let time = backward_time(time)?;
+        let mut matrix = SquareMatrix::zeros(self.graph.num_demes());
+        let index = |name: &str| {
+            self.graph
+                .demes()
+                .iter()
+                .position(|deme| deme.name() == name)
+                .ok_or_else(|| {
+                    DemesForwardError::InternalError(format!("could not find deme {name}"))
+                })
+        };
+        for m in self
+            .graph
+            .migrations()
+            .iter()
+            .filter(|m| time >= m.end_time() && time < m.start_time())
+        {
+            matrix.set(index(m.dest())?, index(m.source())?, m.rate().into());
+        }
+        Ok(matrix)
+    }
+
+    /// Access to the underlying [`demes::Graph`]
+    ///
+    /// The time units of the graph are generations.
+    pub fn demes_graph(&self) -> &demes::Graph {
+        &self.graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> demes::Graph {
+        let yaml = "
+time_units: years
+generation_time: 2
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 101
+    - start_size: 100
+      end_size: 400
+      size_function: exponential
+ - name: B
+   ancestors: [A]
+   start_time: 101
+   epochs:
+    - start_size: 50
+ - name: C
+   ancestors: [A]
+   start_time: 101
+   epochs:
+    - start_size: 50
+migrations:
+ - source: A
+   dest: B
+   rate: 0.01
+   start_time: 51
+ - source: A
+   dest: C
+   rate: 0.02
+   start_time: 21
+   end_time: 11
+";
+        demes::loads(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_size_at() {
+        let graph = ContinuousGraph::new(model()).unwrap();
+        assert_eq!(
+            graph.demes_graph().time_units(),
+            demes::TimeUnits::Generations
+        );
+        assert_eq!(graph.size_at("A", f64::INFINITY).unwrap().unwrap(), 100.0);
+        assert_eq!(graph.size_at("A", 50.5).unwrap().unwrap(), 100.0);
+        assert_eq!(graph.size_at("A", 0.0).unwrap().unwrap(), 400.0);
+        let size = f64::from(graph.size_at("A", 25.25).unwrap().unwrap());
+        assert!((size - 200.0).abs() < 1e-9, "{size}");
+        assert!(graph.size_at("B", 50.6).unwrap().is_none());
+        assert_eq!(graph.size_at(1, 50.4).unwrap().unwrap(), 50.0);
+        assert!(graph.size_at("D", 0.0).unwrap().is_none());
+        assert!(graph.size_at("A", -1.0).is_err());
+    }
+
+    #[test]
+    fn test_migration_rates() {
+        let graph = ContinuousGraph::new(model()).unwrap();
+        assert_eq!(graph.migration_rate("A", "B", 25.5).unwrap(), Some(0.0));
+        assert_eq!(graph.migration_rate("A", "B", 25.4).unwrap(), Some(0.01));
+        assert_eq!(graph.migration_rate("A", "C", 10.0).unwrap(), Some(0.02));
+        assert_eq!(graph.migration_rate(0, 2, 5.5).unwrap(), Some(0.02));
+        assert_eq!(graph.migration_rate("A", "C", 5.0).unwrap(), Some(0.0));
+        assert_eq!(graph.migration_rate("B", "A", 10.0).unwrap(), Some(0.0));
+        assert_eq!(graph.migration_rate("A", "D", 10.0).unwrap(), None);
+        assert!(graph.migration_rate("A", "B", f64::NAN).is_err());
+
+        let matrix = graph.migration_matrix(10.0).unwrap();
+        assert_eq!(
+            matrix.as_slice(),
+            [0.0, 0.0, 0.0, 0.01, 0.0, 0.0, 0.02, 0.0, 0.0]
+        );
+        let matrix = graph.migration_matrix(30.0).unwrap();
+        assert_eq!(matrix.as_slice(), [0.0; 9]);
+    }
+}
@@ -30,6 +30,12 @@ impl TryFrom<demes::DemeSize> for CurrentSize {
     }
 }
 
+impl From<CurrentSize> for f64 {
+    fn from(value: CurrentSize) -> Self {
+        value.0
+    }
+}
+
 impl PartialEq<CurrentSize> for f64 {
     fn eq(&self, other: &CurrentSize) -> bool {
         self.eq(&other.0)
@@ -0,0 +1,169 @@
+use crate::square_matrix::SquareMatrix;
+use crate::CurrentSize;
+use crate::DemesForwardError;
+use crate::ForwardTime;
+
+/// The demography of a [`ForwardGraph`](crate::ForwardGraph)
+/// at one parental generation.
+///
+/// This type bundles the arrays of the graph, which
+/// all have one element (or one row and column) per deme
+/// in the model.
+/// The dimensions are checked when the state is created.
+///
+/// See [`ForwardGraph::update_state`](crate::ForwardGraph::update_state),
+/// [`ForwardGraph::state`](crate::ForwardGraph::state), and
+/// [`GenerationState::as_demography_state`](crate::GenerationState::as_demography_state).
+#[derive(Debug, Copy, Clone)]
+pub struct DemographyState<'graph> {
+    time: ForwardTime,
+    parental_deme_sizes: &'graph [CurrentSize],
+    offspring: Option<OffspringDemography<'graph>>,
+}
+
+/// The offspring arrays of a [`DemographyState`].
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct OffspringDemography<'graph> {
+    pub(crate) deme_sizes: &'graph [CurrentSize],
+    pub(crate) ancestry_proportions: &'graph SquareMatrix,
+    pub(crate) selfing_rates: &'graph [demes::SelfingRate],
+    pub(crate) cloning_rates: &'graph [demes::CloningRate],
+}
+
+impl<'graph> DemographyState<'graph> {
+    pub(crate) fn new(
+        time: ForwardTime,
+        parental_deme_sizes: &'graph [CurrentSize],
+        offspring: Option<OffspringDemography<'graph>>,
+    ) -> Result<Self, DemesForwardError> {
+        let num_demes = parental_deme_sizes.len();
+        if let Some(offspring) = &offspring {
+            for (name, len) in [
+                ("offspring deme sizes", offspring.deme_sizes.len()),
+                (
+                    "ancestry proportions",
+                    offspring.ancestry_proportions.nrows(),
+                ),
+                ("selfing rates", offspring.selfing_rates.len()),
+                ("cloning rates", offspring.cloning_rates.len()),
+            ] {
+                if len != num_demes {
+                    return Err(DemesForwardError::InternalError(format!(
+                        "{name} has length {len}, expected {num_demes}"
+                    )));
+                }
+            }
+        }
+        Ok(Self {
+            time,
+            parental_deme_sizes,
+            offspring,
+        })
+    }
+
+    /// The parental generation time.
+    pub fn time(&self) -> ForwardTime {
+        self.time
+    }
+
+    /// The number of demes in the model.
+    ///
+    /// This is the length of each array.
+    pub fn num_demes(&self) -> usize {
+        self.parental_deme_sizes.len()
+    }
+
+    /// The sizes of each parental deme.
+    pub fn parental_deme_sizes(&self) -> &'graph [CurrentSize] {
+        self.parental_deme_sizes
+    }
+
+    /// The sizes of each offspring deme.
+    ///
+    /// Returns `None` if there are no offspring demes,
+    /// which is the case for the last parental generation.
+    pub fn offspring_deme_sizes(&self) -> Option<&'graph [CurrentSize]> {
+        self.offspring.map(|o| o.deme_sizes)
+    }
+
+    /// The ancestry proportions of an offspring deme.
+    ///
+    /// Returns `None` if there are no offspring demes or
+    /// if `offspring_deme` is out of range.
+    pub fn ancestry_proportions(&self, offspring_deme: usize) -> Option<&'graph [f64]> {
+        match self.offspring {
+            Some(o) if offspring_deme < o.ancestry_proportions.nrows() => {
+                Some(o.ancestry_proportions.row(offspring_deme))
+            }
+            _ => None,
+        }
+    }
+
+    /// The ancestry proportions of all offspring demes.
+    ///
+    /// Row `i` contains the ancestry proportions of
+    /// offspring deme `i`.
+    ///
+    /// Returns `None` if there are no offspring demes.
+    pub fn ancestry_proportions_matrix(&self) -> Option<&'graph SquareMatrix> {
+        self.offspring.map(|o| o.ancestry_proportions)
+    }
+
+    /// The selfing rates of each offspring deme.
+    ///
+    /// Returns `None` if there are no offspring demes.
+    pub fn selfing_rates(&self) -> Option<&'graph [demes::SelfingRate]> {
+        self.offspring.map(|o| o.selfing_rates)
+    }
+
+    /// The cloning rates of each offspring deme.
+    ///
+    /// Returns `None` if there are no offspring demes.
+    pub fn cloning_rates(&self) -> Option<&'graph [demes::CloningRate]> {
+        self.offspring.map(|o| o.cloning_rates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimension_checks() {
+        let sizes = vec![CurrentSize::try_from(10.0).unwrap(); 2];
+        let ancestry_proportions = SquareMatrix::zeros(2);
+        let rates = vec![demes::SelfingRate::try_from(0.0).unwrap(); 2];
+        let cloning_rates = vec![demes::CloningRate::try_from(0.0).unwrap(); 2];
+        let offspring = OffspringDemography {
+            deme_sizes: &sizes,
+            ancestry_proportions: &ancestry_proportions,
+            selfing_rates: &rates,
+            cloning_rates: &cloning_rates,
+        };
+        let state = DemographyState::new(0.0.into(), &sizes, Some(offspring)).unwrap();
+        assert_eq!(state.num_demes(), 2);
+        assert_eq!(state.ancestry_proportions(1), Some([0.0, 0.0].as_slice()));
+        assert!(state.ancestry_proportions(2).is_none());
+
+        let state = DemographyState::new(0.0.into(), &sizes[..1], None).unwrap();
+        assert!(state.offspring_deme_sizes().is_none());
+        assert!(state.ancestry_proportions(0).is_none());
+
+        assert!(matches!(
+            DemographyState::new(0.0.into(), &sizes[..1], Some(offspring)),
+            Err(DemesForwardError::InternalError(_))
+        ));
+        let ancestry_proportions = SquareMatrix::zeros(3);
+        assert!(matches!(
+            DemographyState::new(
+                0.0.into(),
+                &sizes,
+                Some(OffspringDemography {
+                    ancestry_proportions: &ancestry_proportions,
+                    ..offspring
+                })
+            ),
+            Err(DemesForwardError::InternalError(_))
+        ));
+    }
+}
@@ -23,4 +23,13 @@ pub enum DemesForwardError {
     /// that should be reported.
     #[error("{0:?}")]
     InternalError(String),
+    /// Errors related to restricting a model
+    /// to a subset of its demes.
+    /// See [`ForwardGraph::with_deme_subset`](crate::ForwardGraph::with_deme_subset).
+    #[error("{0:?}")]
+    DemeSubsetError(String),
+    /// Errors related to exporting a model
+    /// to Arrow or Parquet.
+    #[error("{0:?}")]
+    ExportError(String),
 }
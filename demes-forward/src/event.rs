@@ -0,0 +1,30 @@
+/// A change to the model between the parental and
+/// offspring generations.
+///
+/// See [`ForwardGraph::events`](crate::ForwardGraph::events).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum DemographicEvent<'graph> {
+    /// A deme has offspring but no parents.
+    Appears {
+        /// The index of the deme
+        deme: usize,
+    },
+    /// A deme has parents but no offspring.
+    Extinct {
+        /// The index of the deme
+        deme: usize,
+    },
+    /// The offspring of a deme are in a later epoch
+    /// than the parents.
+    EpochChange {
+        /// The index of the deme
+        deme: usize,
+        /// The epoch index of the parents
+        parental_epoch: usize,
+        /// The epoch index of the offspring
+        offspring_epoch: usize,
+    },
+    /// A pulse is applied to the offspring.
+    Pulse(&'graph demes::Pulse),
+}
@@ -1,9 +1,15 @@
+use crate::demography_state::DemographyState;
+use crate::demography_state::OffspringDemography;
 use crate::iterators::DemeSizeHistory;
+use crate::iterators::GenerationState;
+use crate::iterators::GenerationStates;
 use crate::square_matrix::SquareMatrix;
 use crate::time::ModelTime;
+use crate::time::TimeRounding;
 use crate::CurrentSize;
 use crate::DemeSizeAt;
 use crate::DemesForwardError;
+use crate::DemographicEvent;
 use crate::ForwardTime;
 
 enum Generation {
@@ -167,12 +173,15 @@ enum DemeStatus {
 
 impl Deme {
     fn new(deme: demes::Deme) -> Self {
+        // Reserve space for the ancestors so that
+        // later updates do not allocate.
+        let num_ancestors = deme.ancestor_names().len();
         Self {
             deme,
             status: DemeStatus::Before,
             backwards_time: None,
-            ancestors: vec![],
-            proportions: vec![],
+            ancestors: Vec::with_capacity(num_ancestors),
+            proportions: Vec::with_capacity(num_ancestors),
         }
     }
 
@@ -180,6 +189,27 @@ impl Deme {
         matches!(self.status, DemeStatus::During(_))
     }
 
+    // Same as replacing self with Deme::new(self.deme)
+    // but without allocating.
+    fn reset(&mut self) {
+        self.status = DemeStatus::Before;
+        self.backwards_time = None;
+        self.ancestors.clear();
+        self.proportions.clear();
+    }
+
+    // Same as cloning other, which must be the same deme,
+    // but without allocating once the capacity of
+    // self is large enough.
+    fn copy_state_from(&mut self, other: &Deme) {
+        self.status = other.status.clone();
+        self.backwards_time = other.backwards_time;
+        self.ancestors.clear();
+        self.ancestors.extend_from_slice(&other.ancestors);
+        self.proportions.clear();
+        self.proportions.extend_from_slice(&other.proportions);
+    }
+
     fn epoch_index_for_update(&self) -> usize {
         match self.status {
             DemeStatus::Before => 0,
@@ -249,7 +279,7 @@ impl Deme {
                             .iter()
                             .zip(self.deme.proportions().iter())
                         {
-                            let index = *deme_to_index.get(name).ok_or_else(|| {
+                            let index = *deme_to_index.get(name.as_ref()).ok_or_else(|| {
                                 DemesForwardError::InternalError(format!(
                                     "could not get deme {name} from deme_to_index map",
                                 ))
@@ -307,7 +337,50 @@ fn update_demes(
     Ok(())
 }
 
+// Indexes of the pulses whose times are in [most_recent, most_ancient].
+fn pulses_between(
+    graph: &demes::Graph,
+    most_ancient: Option<demes::Time>,
+    most_recent: Option<demes::Time>,
+) -> impl Iterator<Item = usize> + '_ {
+    graph
+        .pulses()
+        .iter()
+        .enumerate()
+        .filter(move |(_, pulse)| match (most_ancient, most_recent) {
+            (Some(ancient), Some(recent)) => !(pulse.time() > ancient || pulse.time() < recent),
+            _ => false,
+        })
+        .map(|(index, _)| index)
+}
+
+// Indexes of the pulses applied to the offspring generations
+// of a time step starting at parental_time.
+fn pulses_in_step<'graph>(
+    graph: &'graph demes::Graph,
+    model_times: &ModelTime,
+    parental_time: f64,
+    step_length: f64,
+) -> impl Iterator<Item = usize> + 'graph {
+    let convert = |time: f64| model_times.convert(ForwardTime::from(time)).ok().flatten();
+    let most_ancient = convert(parental_time + 1.0);
+    let most_recent = convert(parental_time + step_length);
+    pulses_between(graph, most_ancient, most_recent)
+}
+
 /// Forward-time representation of a [`demes::Graph`].
+///
+/// # Serialization
+///
+/// With the `serde` feature, this type implements
+/// `Serialize` and `Deserialize`.
+/// A serialized graph stores the model, the burn-in time,
+/// the time step, the subset of demes (see
+/// [`ForwardGraph::with_deme_subset`]), whether cumulative ancestry
+/// is tracked (see [`ForwardGraph::with_cumulative_ancestry`]),
+/// and the last time passed to [`ForwardGraph::update_state`].
+/// Deserializing restores the internal state at that time,
+/// allowing simulations to be checkpointed and resumed.
 #[derive(Debug, Clone)]
 pub struct ForwardGraph {
     graph: demes::Graph,
@@ -315,15 +388,142 @@ pub struct ForwardGraph {
     parent_demes: Vec<Deme>,
     child_demes: Vec<Deme>,
     last_time_updated: Option<ForwardTime>,
+    last_generation_updated: Option<ForwardTime>,
+    time_step: f64,
     deme_to_index: std::collections::HashMap<String, usize>,
-    pulses: Vec<demes::Pulse>,
-    migrations: Vec<demes::AsymmetricMigration>,
+    // Indexes into graph.pulses() and graph.migrations()
+    pulses: Vec<usize>,
+    migrations: Vec<usize>,
     ancestry_proportions: SquareMatrix,
     migration_matrix: SquareMatrix,
     cloning_rates: Vec<demes::CloningRate>,
     selfing_rates: Vec<demes::SelfingRate>,
     parental_deme_sizes: Vec<CurrentSize>,
     child_deme_sizes: Vec<CurrentSize>,
+    step_buffers: StepBuffers,
+    // None means that all demes are simulated.
+    // See with_deme_subset.
+    focal_demes: Option<Vec<bool>>,
+    // None means that cumulative ancestry is not tracked.
+    // See with_cumulative_ancestry.
+    cumulative_ancestry: Option<CumulativeAncestry>,
+    // See deme_lifetimes.
+    deme_lifetimes: Vec<std::ops::Range<ForwardTime>>,
+}
+
+// Ancestry with respect to the root demes.
+#[derive(Debug, Clone)]
+struct CumulativeAncestry {
+    parents: SquareMatrix,
+    offspring: SquareMatrix,
+    scratch: SquareMatrix,
+    // The times of the generations stored in
+    // parents and offspring.
+    // None means that the values are not valid.
+    parental_time: Option<ForwardTime>,
+    offspring_time: Option<ForwardTime>,
+}
+
+impl CumulativeAncestry {
+    fn new(graph: &demes::Graph) -> Self {
+        let num_demes = graph.num_demes();
+        Self {
+            parents: SquareMatrix::zeros(num_demes),
+            offspring: SquareMatrix::zeros(num_demes),
+            scratch: SquareMatrix::zeros(num_demes),
+            parental_time: None,
+            offspring_time: None,
+        }
+    }
+
+    // The parents at time zero are the root demes.
+    fn initialize(&mut self, graph: &demes::Graph) {
+        self.parents.fill(0.0);
+        for (i, deme) in graph.demes().iter().enumerate() {
+            if deme.ancestor_names().is_empty() {
+                self.parents.set(i, i, 1.0);
+            }
+        }
+        self.parental_time = Some(ForwardTime::from(0.0));
+        self.offspring_time = None;
+    }
+
+    fn update_offspring(
+        &mut self,
+        ancestry_proportions: &SquareMatrix,
+        parental_time: ForwardTime,
+        offspring_time: ForwardTime,
+    ) {
+        self.offspring.copy_from(&self.parents);
+        self.offspring
+            .left_multiply(ancestry_proportions, &mut self.scratch);
+        self.parental_time = Some(parental_time);
+        self.offspring_time = Some(offspring_time);
+    }
+
+    fn invalidate(&mut self) {
+        self.parental_time = None;
+        self.offspring_time = None;
+    }
+
+    // The offspring become the parents.
+    fn advance(&mut self) {
+        std::mem::swap(&mut self.parents, &mut self.offspring);
+        self.parental_time = self.offspring_time.take();
+    }
+}
+
+// Values saved and accumulated by update_time_step.
+#[derive(Debug, Clone)]
+struct StepBuffers {
+    parent_demes: Vec<Deme>,
+    parental_deme_sizes: Vec<CurrentSize>,
+    ancestry_proportions: SquareMatrix,
+    migration_matrix: SquareMatrix,
+    scratch: SquareMatrix,
+    // Per offspring deme: the number of generations in which
+    // the deme exists and the sums of 1/size and of the rates
+    // over those generations.
+    generations: Vec<usize>,
+    inverse_size_sums: Vec<f64>,
+    selfing_rate_sums: Vec<f64>,
+    cloning_rate_sums: Vec<f64>,
+}
+
+impl StepBuffers {
+    fn reset_offspring_sums(&mut self, num_demes: usize) {
+        for sums in [
+            &mut self.inverse_size_sums,
+            &mut self.selfing_rate_sums,
+            &mut self.cloning_rate_sums,
+        ] {
+            sums.clear();
+            sums.resize(num_demes, 0.0);
+        }
+        self.generations.clear();
+        self.generations.resize(num_demes, 0);
+    }
+
+    fn add_offspring_generation(
+        &mut self,
+        sizes: &[CurrentSize],
+        selfing_rates: &[demes::SelfingRate],
+        cloning_rates: &[demes::CloningRate],
+    ) {
+        for (i, ((&size, &selfing_rate), &cloning_rate)) in sizes
+            .iter()
+            .zip(selfing_rates.iter())
+            .zip(cloning_rates.iter())
+            .enumerate()
+        {
+            if size > 0.0 {
+                self.generations[i] += 1;
+                self.inverse_size_sums[i] += 1.0 / f64::from(size);
+                self.selfing_rate_sums[i] += f64::from(selfing_rate);
+                self.cloning_rate_sums[i] += f64::from(cloning_rate);
+            }
+        }
+    }
 }
 
 impl ForwardGraph {
@@ -337,6 +537,174 @@ impl ForwardGraph {
         graph: demes::Graph,
         burnin_time: F,
     ) -> Result<Self, crate::DemesForwardError> {
+        Self::new_discrete_time_with_time_step(graph, burnin_time, 1.0)
+    }
+
+    /// Constructor with an integer time step of one or more generations.
+    ///
+    /// # Parameters
+    ///
+    /// * graph: a [`demes::Graph`].
+    /// * burnin_time: Burn-in time for the model.
+    /// * time_step: the number of generations per time step,
+    ///   which must be a whole number.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`ForwardGraph::new_discrete_time`],
+    /// [`DemesForwardError::TimeError`] if `time_step` is not a
+    /// positive integer.
+    ///
+    /// # Scope
+    ///
+    /// Steps shorter than one generation are out of scope.
+    /// The model is discretized to integer generations, so there
+    /// is nothing to integrate within a fraction of a generation.
+    /// Diffusion approximations should instead take steps of
+    /// whole generations, whose sizes and rates are aggregated
+    /// as described below.
+    ///
+    /// # Notes
+    ///
+    /// Each call to [`ForwardGraph::update_state`] advances the
+    /// offspring generation by `time_step` generations, or fewer
+    /// for the last step of the model.
+    /// Within a step:
+    ///
+    /// * Parental values refer to the time passed to
+    ///   [`ForwardGraph::update_state`].
+    /// * The offspring demes that exist are those of the
+    ///   generation born at the end of the step.
+    /// * The size of an offspring deme is the harmonic mean of
+    ///   its sizes over the generations of the step in which it
+    ///   exists, rounded to the nearest integer.
+    ///   This is the effective size of the deme over the step.
+    /// * Selfing and cloning rates are the means of the rates
+    ///   over the same generations, so that each generation
+    ///   has equal weight.
+    /// * [`ForwardGraph::ancestry_proportions`] give the ancestry
+    ///   of the offspring in terms of the parents, accumulated
+    ///   over each generation of the step.
+    /// * [`ForwardGraph::migration_matrix`] is the product of the
+    ///   migration matrices of each generation of the step.
+    /// * [`ForwardGraph::pulses_at_current_time`] lists all
+    ///   pulses that happen during the step.
+    ///
+    /// [`ForwardGraph::time_iterator`] and
+    /// [`ForwardGraph::backward_time_iterator`] visit the
+    /// start of each time step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let graph =
+    ///     demes_forward::ForwardGraph::new_discrete_time_with_time_step(demes_graph, 0, 5.0)
+    ///         .unwrap();
+    /// let times = graph
+    ///     .time_iterator()
+    ///     .map(|time| time.value())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(times, [0.0, 5.0, 10.0]);
+    /// ```
+    pub fn new_discrete_time_with_time_step<F: Into<ForwardTime> + std::fmt::Debug + Copy>(
+        graph: demes::Graph,
+        burnin_time: F,
+        time_step: f64,
+    ) -> Result<Self, crate::DemesForwardError> {
+        Self::new_discrete_time_with_rounding(graph, burnin_time, time_step, TimeRounding::Nearest)
+    }
+
+    /// Constructor with a policy for rounding times to integer generations.
+    ///
+    /// The time units of `graph` need not be generations.
+    /// Times are divided by the generation time of `graph` and
+    /// rounded to integer generations according to `rounding`.
+    /// The other constructors use [`TimeRounding::Nearest`].
+    ///
+    /// # Parameters
+    ///
+    /// * graph: a [`demes::Graph`].
+    /// * burnin_time: Burn-in time for the model.
+    /// * time_step: the number of generations per time step.
+    ///   See [`ForwardGraph::new_discrete_time_with_time_step`].
+    /// * rounding: the rounding policy.
+    ///
+    /// # Errors
+    ///
+    /// * The errors of [`ForwardGraph::new_discrete_time_with_time_step`].
+    /// * [`DemesForwardError::DemesError`] if rounding results in an
+    ///   epoch or a migration of length zero, unless `rounding` is
+    ///   [`TimeRounding::NearestMergingZeroLength`].
+    ///   For [`TimeRounding::Nearest`], use
+    ///   [`demes::Graph::check_integer_generations`] to list all
+    ///   such issues.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes_forward::TimeRounding;
+    ///
+    /// let yaml = "
+    /// time_units: years
+    /// generation_time: 25
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 1000
+    ///     - start_size: 200
+    ///       end_time: 990
+    ///     - start_size: 300
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// // 1000 and 990 years both round to 40 generations
+    /// assert!(demes_forward::ForwardGraph::new_discrete_time_with_rounding(
+    ///     demes_graph.clone(),
+    ///     0,
+    ///     1.0,
+    ///     TimeRounding::Nearest
+    /// )
+    /// .is_err());
+    /// // Rounding down gives epochs ending at 40 and 39 generations
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time_with_rounding(
+    ///     demes_graph.clone(),
+    ///     0,
+    ///     1.0,
+    ///     TimeRounding::Down,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(graph.demes_graph().deme(0).num_epochs(), 3);
+    /// // Merging removes the second epoch
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time_with_rounding(
+    ///     demes_graph,
+    ///     0,
+    ///     1.0,
+    ///     TimeRounding::NearestMergingZeroLength,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(graph.demes_graph().deme(0).num_epochs(), 2);
+    /// ```
+    pub fn new_discrete_time_with_rounding<F: Into<ForwardTime> + std::fmt::Debug + Copy>(
+        graph: demes::Graph,
+        burnin_time: F,
+        time_step: f64,
+        rounding: TimeRounding,
+    ) -> Result<Self, crate::DemesForwardError> {
+        if !time_step.is_finite() || time_step < 1.0 || time_step.fract() != 0.0 {
+            return Err(DemesForwardError::TimeError(format!(
+                "time step must be a positive integer number of generations, got: {time_step}"
+            )));
+        }
         if let Some((name, index)) = graph.has_non_integer_sizes() {
             let deme = graph.get_deme(name).unwrap();
             let epoch = deme.epochs()[index];
@@ -352,48 +720,468 @@ impl ForwardGraph {
                 "invalid time value: {burnin_time:?}",
             )));
         }
-        let graph = graph.into_integer_generations()?;
+        let graph = rounding.apply(graph)?;
 
         validate_model_times(&graph)?;
 
         let model_times = ModelTime::new_from_graph(burnin_time, &graph)?;
+        let deme_lifetimes = graph
+            .demes()
+            .iter()
+            .map(|deme| model_times.offspring_lifetime(deme))
+            .collect();
         let child_demes = vec![];
         let parent_demes = vec![];
         let mut deme_to_index = std::collections::HashMap::default();
         for (i, deme) in graph.demes().iter().enumerate() {
             deme_to_index.insert(deme.name().to_string(), i);
         }
-        let pulses = vec![];
+        let pulses = Vec::with_capacity(graph.pulses().len());
+        let migrations = Vec::with_capacity(graph.migrations().len());
         let ancestry_proportions = SquareMatrix::zeros(deme_to_index.len());
         let migration_matrix = SquareMatrix::zeros(deme_to_index.len());
+        let step_buffers = StepBuffers {
+            parent_demes: vec![],
+            parental_deme_sizes: vec![],
+            ancestry_proportions: SquareMatrix::zeros(deme_to_index.len()),
+            migration_matrix: SquareMatrix::zeros(deme_to_index.len()),
+            scratch: SquareMatrix::zeros(deme_to_index.len()),
+            generations: vec![],
+            inverse_size_sums: vec![],
+            selfing_rate_sums: vec![],
+            cloning_rate_sums: vec![],
+        };
         Ok(Self {
             graph,
             model_times,
             parent_demes,
             child_demes,
             last_time_updated: None,
+            last_generation_updated: None,
+            time_step,
             deme_to_index,
             pulses,
-            migrations: vec![],
+            migrations,
             ancestry_proportions,
             migration_matrix,
             cloning_rates: vec![],
             selfing_rates: vec![],
             parental_deme_sizes: vec![],
             child_deme_sizes: vec![],
+            step_buffers,
+            focal_demes: None,
+            cumulative_ancestry: None,
+            deme_lifetimes,
         })
     }
 
-    fn update_pulses(&mut self, backwards_time: Option<demes::Time>) {
+    /// Restrict the model to a subset of its demes.
+    ///
+    /// The demes not in the subset are treated as external to the
+    /// model.
+    /// They are never extant, their sizes, selfing rates, and
+    /// cloning rates are zero, and their rows and columns of
+    /// [`ForwardGraph::ancestry_proportions`] and
+    /// [`ForwardGraph::migration_matrix`] are zero.
+    /// Ancestry that a deme in the subset would get from the
+    /// external demes, via its ancestors, pulses, or migration, is
+    /// absorbed by rescaling its ancestry proportions to sum to one.
+    /// Likewise, migrants from external demes are counted as
+    /// non-migrants on the diagonal of the migration matrix.
+    /// Pulses into external demes are not reported by
+    /// [`ForwardGraph::pulses_at_current_time`] or
+    /// [`ForwardGraph::pulses_at_next_time`].
+    ///
+    /// Deme indexes still refer to all demes in the model.
+    ///
+    /// If the state has been updated, it is recalculated for
+    /// the last time passed to [`ForwardGraph::update_state`].
+    ///
+    /// # Parameters
+    ///
+    /// * `demes`: the names or indexes of the demes to simulate.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesForwardError::DemesError`] if a deme does not exist.
+    /// * [`DemesForwardError::DemeSubsetError`] if `demes` is empty
+    ///   or if a deme in the subset has ancestors, none of which are
+    ///   in the subset.
+    /// * [`DemesForwardError::DemeSubsetError`] from
+    ///   [`ForwardGraph::update_state`] if all of the ancestry of
+    ///   an offspring deme in the subset is from external demes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: C
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - source: A
+    ///    dest: C
+    ///    rate: 0.1
+    ///  - source: B
+    ///    dest: C
+    ///    rate: 0.1
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10)
+    ///     .unwrap()
+    ///     .with_deme_subset(["B", "C"])
+    ///     .unwrap();
+    /// graph.update_state(0).unwrap();
+    /// assert_eq!(graph.num_extant_offspring_demes(), 2);
+    /// assert_eq!(graph.offspring_deme_sizes().unwrap()[0], 0.0);
+    /// // Migrants from A are absorbed into C
+    /// let ancestry = graph.ancestry_proportions(2).unwrap();
+    /// assert_eq!(ancestry[0], 0.0);
+    /// assert!((ancestry[1] - 0.1 / 0.9).abs() < 1e-12);
+    /// assert!((ancestry[2] - 0.8 / 0.9).abs() < 1e-12);
+    /// ```
+    pub fn with_deme_subset<'a, I, D>(self, demes: I) -> Result<Self, DemesForwardError>
+    where
+        I: IntoIterator<Item = D>,
+        D: Into<demes::DemeId<'a>>,
+    {
+        let mut graph = self;
+        let mut focal_demes = vec![false; graph.num_demes_in_model()];
+        for deme in demes {
+            let index = graph.get_deme_index(deme.into())?;
+            focal_demes[index] = true;
+        }
+        if !focal_demes.iter().any(|&is_focal| is_focal) {
+            return Err(DemesForwardError::DemeSubsetError(
+                "the subset of demes is empty".to_string(),
+            ));
+        }
+        for (deme, _) in graph
+            .graph
+            .demes()
+            .iter()
+            .zip(focal_demes.iter())
+            .filter(|(_, &is_focal)| is_focal)
+        {
+            if !deme.ancestor_names().is_empty()
+                && !deme
+                    .ancestor_names()
+                    .iter()
+                    .any(|name| focal_demes[graph.deme_to_index[name.as_ref()]])
+            {
+                return Err(DemesForwardError::DemeSubsetError(format!(
+                    "none of the ancestors of deme {} are in the subset",
+                    deme.name()
+                )));
+            }
+        }
+        for (lifetime, _) in graph
+            .deme_lifetimes
+            .iter_mut()
+            .zip(focal_demes.iter())
+            .filter(|(_, &is_focal)| !is_focal)
+        {
+            *lifetime = lifetime.start..lifetime.start;
+        }
+        graph.focal_demes = Some(focal_demes);
+
+        // Start over so that no state from before
+        // the restriction remains.
+        graph.parent_demes.clear();
+        graph.child_demes.clear();
+        graph.last_generation_updated = None;
+        if let Some(cumulative_ancestry) = &mut graph.cumulative_ancestry {
+            cumulative_ancestry.invalidate();
+        }
+        if let Some(time) = graph.last_time_updated.take() {
+            graph.update_state(time)?;
+        }
+        Ok(graph)
+    }
+
+    /// Track the ancestry of each deme with respect to the
+    /// root demes of the model.
+    ///
+    /// The root demes are those without ancestors.
+    /// See [`ForwardGraph::cumulative_ancestry_proportions`].
+    ///
+    /// If the state has been updated, the cumulative ancestry
+    /// is calculated for the last time passed to
+    /// [`ForwardGraph::update_state`].
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`ForwardGraph::update_state`].
+    ///
+    /// # Notes
+    ///
+    /// Updating to the next time of [`ForwardGraph::time_iterator`],
+    /// or to the same time as the last update, costs one product
+    /// of matrices with one row and column per deme and does not
+    /// allocate.
+    /// Other updates replay the model one generation at a time,
+    /// starting from the last update when moving forwards in time
+    /// and from time zero when moving backwards in time.
+    pub fn with_cumulative_ancestry(self) -> Result<Self, DemesForwardError> {
+        let mut graph = self;
+        graph.cumulative_ancestry = Some(CumulativeAncestry::new(&graph.graph));
+        if let Some(time) = graph.last_time_updated {
+            graph.update_state(time)?;
+        }
+        Ok(graph)
+    }
+
+    /// The cumulative ancestry of an offspring deme at the current
+    /// time with respect to the root demes of the model.
+    ///
+    /// Element `j` is the expected proportion of the ancestry
+    /// of the offspring in deme `offspring_deme` that traces back
+    /// to root deme `j`.
+    /// The root demes are the demes without ancestors.
+    /// Elements for other demes are zero.
+    /// The values for an extant offspring deme sum to one.
+    ///
+    /// Tracking must be enabled with
+    /// [`ForwardGraph::with_cumulative_ancestry`].
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&[f64])` if tracking is enabled, `offspring_deme`
+    ///   is a valid index, and offspring demes exist.
+    /// * `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: C
+    ///    ancestors: [A, B]
+    ///    proportions: [0.5, 0.5]
+    ///    start_time: 10
+    ///    epochs:
+    ///     - start_size: 100
+    /// pulses:
+    ///  - sources: [A]
+    ///    dest: C
+    ///    time: 5
+    ///    proportions: [0.5]
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10)
+    ///     .unwrap()
+    ///     .with_cumulative_ancestry()
+    ///     .unwrap();
+    /// for time in graph.time_iterator().collect::<Vec<_>>() {
+    ///     graph.update_state(time).unwrap();
+    /// }
+    /// // The last update has no offspring
+    /// assert!(graph.cumulative_ancestry_proportions(2).is_none());
+    /// let last = graph.end_time().value() - 2.0;
+    /// graph.update_state(last).unwrap();
+    /// assert_eq!(
+    ///     graph.cumulative_ancestry_proportions(2),
+    ///     Some([0.75, 0.25, 0.0].as_slice())
+    /// );
+    /// ```
+    pub fn cumulative_ancestry_proportions(&self, offspring_deme: usize) -> Option<&[f64]> {
+        let cumulative_ancestry = self.cumulative_ancestry.as_ref()?;
+        if offspring_deme >= self.num_demes_in_model() || self.child_demes.is_empty() {
+            return None;
+        }
+        Some(cumulative_ancestry.offspring.row(offspring_deme))
+    }
+
+    // Bring the cumulative ancestry of the parents to
+    // parental_generation_time.
+    fn update_cumulative_ancestry_of_parents(
+        &mut self,
+        parental_generation_time: ForwardTime,
+    ) -> Result<(), DemesForwardError> {
+        let cumulative_ancestry = match &mut self.cumulative_ancestry {
+            Some(cumulative_ancestry) => cumulative_ancestry,
+            None => return Ok(()),
+        };
+        if cumulative_ancestry.parental_time == Some(parental_generation_time) {
+            return Ok(());
+        }
+        if cumulative_ancestry.offspring_time == Some(parental_generation_time) {
+            cumulative_ancestry.advance();
+            return Ok(());
+        }
+        let mut time = match cumulative_ancestry.offspring_time {
+            Some(time) if time < parental_generation_time => {
+                cumulative_ancestry.advance();
+                time
+            }
+            _ => {
+                cumulative_ancestry.initialize(&self.graph);
+                ForwardTime::from(0.0)
+            }
+        };
+        let last_parental_time = ForwardTime::from(self.end_time().value() - 1.0);
+        while time < parental_generation_time && time < last_parental_time {
+            self.update_one_generation(time)?;
+            let offspring_time = ForwardTime::from(time.value() + 1.0);
+            // NOTE: the unwrap is fine because of the
+            // match at the top of the function.
+            let cumulative_ancestry = self.cumulative_ancestry.as_mut().unwrap();
+            cumulative_ancestry.update_offspring(&self.ancestry_proportions, time, offspring_time);
+            cumulative_ancestry.advance();
+            time = offspring_time;
+        }
+        Ok(())
+    }
+
+    /// Initialize the state at a given parental generation time.
+    ///
+    /// This is the same as calling [`ForwardGraph::update_state`],
+    /// but may be chained with the constructor.
+    /// [`ForwardGraph::time_iterator`] then starts at `time`.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError::TimeError`] if `time` is not a
+    /// valid parental generation time, meaning that it is
+    /// negative, not an integer, or not less than
+    /// [`ForwardGraph::end_time`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 0)
+    ///     .unwrap()
+    ///     .with_start_time(5)
+    ///     .unwrap();
+    /// assert_eq!(graph.last_time_updated(), Some(5.0.into()));
+    /// assert_eq!(graph.parental_deme_sizes().unwrap()[0], 200.0);
+    /// let times = graph.time_iterator().map(|t| t.value()).collect::<Vec<_>>();
+    /// assert_eq!(times, [5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+    /// ```
+    pub fn with_start_time<F: Into<ForwardTime> + std::fmt::Debug + Copy>(
+        self,
+        time: F,
+    ) -> Result<Self, DemesForwardError> {
+        let mut graph = self;
+        let time = time.into();
+        if !time.valid() || time.value().fract() != 0.0 || time >= graph.end_time() {
+            return Err(DemesForwardError::TimeError(format!(
+                "invalid start time: {time:?}, the end time of the model is {:?}",
+                graph.end_time()
+            )));
+        }
+        graph.update_state(time)?;
+        Ok(graph)
+    }
+
+    // A copy of self that advances one generation per
+    // update, does not track cumulative ancestry, and whose
+    // state has not been updated.
+    pub(crate) fn clone_with_single_generation_steps(&self) -> Self {
+        let mut graph = self.clone();
+        graph.time_step = 1.0;
+        graph.cumulative_ancestry = None;
+        graph.reset();
+        graph
+    }
+
+    /// Restore the state to that of a newly constructed graph.
+    ///
+    /// The model, time step, subset of demes, and tracking of
+    /// cumulative ancestry are kept.
+    /// Thus, resetting is cheaper than constructing a new graph,
+    /// for example when simulating replicates of a model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10)
+    ///     .unwrap()
+    ///     .with_start_time(5)
+    ///     .unwrap();
+    /// graph.reset();
+    /// assert!(graph.last_time_updated().is_none());
+    /// assert!(graph.parental_deme_sizes().is_none());
+    /// assert_eq!(graph.time_iterator().next(), Some(0.0.into()));
+    /// ```
+    pub fn reset(&mut self) {
+        self.parent_demes.clear();
+        self.child_demes.clear();
         self.pulses.clear();
-        match backwards_time {
-            None => (),
-            Some(time) => self.graph.pulses().iter().for_each(|pulse| {
-                if !(time > pulse.time() || time < pulse.time()) {
-                    self.pulses.push(pulse.clone());
+        self.migrations.clear();
+        self.last_time_updated = None;
+        self.last_generation_updated = None;
+        if self.cumulative_ancestry.is_some() {
+            self.cumulative_ancestry = Some(CumulativeAncestry::new(&self.graph));
+        }
+    }
+
+    fn is_focal_deme(&self, name: &str) -> bool {
+        match &self.focal_demes {
+            Some(focal_demes) => focal_demes[self.deme_to_index[name]],
+            None => true,
+        }
+    }
+
+    fn get_deme_index(&self, deme: demes::DemeId) -> Result<usize, DemesForwardError> {
+        let deme_index = match deme {
+            demes::DemeId::Index(index) => usize::from(index),
+            demes::DemeId::Name(name) => match self.deme_to_index.get(name) {
+                Some(&index) => index,
+                None => {
+                    return Err(demes::DemesError::DemeError(
+                        format!("invalid deme id {name}").into(),
+                    )
+                    .into());
                 }
-            }),
+            },
+        };
+        if deme_index >= self.graph.num_demes() {
+            return Err(demes::DemesError::DemeError(
+                format!("invalid deme index {deme_index}").into(),
+            )
+            .into());
         }
+        Ok(deme_index)
+    }
+
+    fn update_pulses(&mut self, backwards_time: Option<demes::Time>) {
+        self.pulses.clear();
+        self.pulses
+            .extend(pulses_between(&self.graph, backwards_time, backwards_time));
     }
 
     // NOTE: performance here is poop emoji.
@@ -409,11 +1197,17 @@ impl ForwardGraph {
         self.migrations.clear();
         match backwards_time {
             None => (),
-            Some(time) => self.graph.migrations().iter().for_each(|migration| {
-                if time >= migration.end_time() && time < migration.start_time() {
-                    self.migrations.push(migration.clone());
-                }
-            }),
+            Some(time) => {
+                self.graph
+                    .migrations()
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, migration)| {
+                        if time >= migration.end_time() && time < migration.start_time() {
+                            self.migrations.push(index);
+                        }
+                    })
+            }
         }
     }
 
@@ -440,11 +1234,8 @@ impl ForwardGraph {
         &mut self,
         parental_generation_time: ForwardTime,
     ) -> Result<(), DemesForwardError> {
-        let mut sources = vec![];
-        let mut proportions = vec![];
-        for pulse in &self.pulses {
-            sources.clear();
-            proportions.clear();
+        for &pulse_index in &self.pulses {
+            let pulse = &self.graph.pulses()[pulse_index];
             let dest = *self.deme_to_index.get(pulse.dest()).ok_or_else(|| {
                 DemesForwardError::InternalError(format!(
                     "could not fetch {} from deme_to_index map",
@@ -470,7 +1261,7 @@ impl ForwardGraph {
             let mut sum = 0.0;
 
             for (source, proportion) in pulse.sources().iter().zip(pulse.proportions().iter()) {
-                let index: usize = *self.deme_to_index.get(source).ok_or_else(|| {
+                let index: usize = *self.deme_to_index.get(source.as_ref()).ok_or_else(|| {
                     DemesForwardError::InternalError(format!(
                         "could not fetch deme {source} from deme_to_index map",
                     ))
@@ -485,22 +1276,19 @@ impl ForwardGraph {
                             parental_generation_time, self.model_times.convert(parental_generation_time),
                         )));
                 }
-                sources.push(index);
-                let p = f64::from(*proportion);
-                sum += p;
-                proportions.push(p);
+                sum += f64::from(*proportion);
             }
 
             self.ancestry_proportions
                 .row_mut(dest)
                 .iter_mut()
                 .for_each(|v| *v *= 1. - sum);
-            sources
-                .iter()
-                .zip(proportions.iter())
-                .for_each(|(source, proportion)| {
-                    self.ancestry_proportions.set(dest, *source, *proportion);
-                });
+            // NOTE: the sources were validated above
+            for (source, proportion) in pulse.sources().iter().zip(pulse.proportions().iter()) {
+                let index = self.deme_to_index[source.as_ref()];
+                self.ancestry_proportions
+                    .set(dest, index, f64::from(*proportion));
+            }
         }
         Ok(())
     }
@@ -510,7 +1298,8 @@ impl ForwardGraph {
         parental_generation_time: ForwardTime,
     ) -> Result<(), DemesForwardError> {
         self.migration_matrix.fill(0.0);
-        for migration in &self.migrations {
+        for &migration_index in &self.migrations {
+            let migration = &self.graph.migrations()[migration_index];
             let source = self.deme_to_index.get(migration.source()).ok_or_else(|| {
                 DemesForwardError::InternalError(format!(
                     "could not fetch deme {} from deme_to_index map",
@@ -569,6 +1358,98 @@ impl ForwardGraph {
         }
     }
 
+    // NOTE: this must be called after
+    // update_ancestry_proportions_from_migration_matrix,
+    // which expects the diagonal to be zero.
+    fn update_migration_matrix_diagonal(&mut self) {
+        for (i, deme) in self.child_demes.iter().enumerate() {
+            if deme.is_extant() {
+                let sum: f64 = self.migration_matrix.row(i).iter().sum();
+                self.migration_matrix.set(i, i, 1. - sum);
+            }
+        }
+    }
+
+    // Treat the demes not in the subset as external.
+    // See with_deme_subset.
+    fn restrict_to_focal_demes(
+        &mut self,
+        parental_generation_time: ForwardTime,
+    ) -> Result<(), DemesForwardError> {
+        let focal_demes = match &self.focal_demes {
+            Some(focal_demes) => focal_demes,
+            None => return Ok(()),
+        };
+        for (i, &is_focal) in focal_demes.iter().enumerate() {
+            let ancestry = self.ancestry_proportions.row_mut(i);
+            let migration = self.migration_matrix.row_mut(i);
+            if !is_focal {
+                ancestry.fill(0.0);
+                migration.fill(0.0);
+                continue;
+            }
+            ancestry
+                .iter_mut()
+                .zip(migration.iter_mut())
+                .zip(focal_demes.iter())
+                .filter(|(_, &is_focal)| !is_focal)
+                .for_each(|((a, m), _)| {
+                    *a = 0.0;
+                    *m = 0.0;
+                });
+            if self.child_demes.get(i).is_some_and(Deme::is_extant) {
+                let sum: f64 = ancestry.iter().sum();
+                if sum > 0.0 {
+                    ancestry.iter_mut().for_each(|a| *a /= sum);
+                } else {
+                    return Err(DemesForwardError::DemeSubsetError(format!(
+                        "deme {} has no ancestry from the subset of demes at forward time {:?}",
+                        self.graph.demes()[i].name(),
+                        parental_generation_time,
+                    )));
+                }
+                migration[i] = 0.0;
+                let migrants: f64 = migration.iter().sum();
+                migration[i] = 1. - migrants;
+            }
+        }
+        for (i, _) in focal_demes
+            .iter()
+            .enumerate()
+            .filter(|(_, &is_focal)| !is_focal)
+        {
+            if let Some(deme) = self.parent_demes.get_mut(i) {
+                deme.reset();
+            }
+            if let Some(deme) = self.child_demes.get_mut(i) {
+                deme.reset();
+            }
+            if let Some(size) = self.parental_deme_sizes.get_mut(i) {
+                *size = CurrentSize::try_from(0.0)?;
+            }
+            if let Some(size) = self.child_deme_sizes.get_mut(i) {
+                *size = CurrentSize::try_from(0.0)?;
+            }
+            if let Some(rate) = self.selfing_rates.get_mut(i) {
+                *rate = demes::SelfingRate::try_from(0.0)?;
+            }
+            if let Some(rate) = self.cloning_rates.get_mut(i) {
+                *rate = demes::CloningRate::try_from(0.0)?;
+            }
+        }
+        self.retain_focal_pulses();
+        Ok(())
+    }
+
+    fn retain_focal_pulses(&mut self) {
+        if let Some(focal_demes) = &self.focal_demes {
+            let graph = &self.graph;
+            let deme_to_index = &self.deme_to_index;
+            self.pulses
+                .retain(|&index| focal_demes[deme_to_index[graph.pulses()[index].dest()]]);
+        }
+    }
+
     fn deme_slice(&self, generation: Generation) -> &[Deme] {
         match generation {
             Generation::Parent => self.parent_demes.as_slice(),
@@ -600,10 +1481,27 @@ impl ForwardGraph {
 
     /// Update the internal state of the graph to the *parental*
     /// generation time `parental_generation_time`.
+    ///
+    /// # Allocation
+    ///
+    /// The internal buffers are allocated by the first call to
+    /// this function and by the first call that advances by more
+    /// than one generation (see [`ForwardGraph::time_step`]).
+    /// Later calls reuse these buffers and perform no heap
+    /// allocation, whether moving forwards or backwards in time.
+    /// The exceptions are calls that return an error and the
+    /// first call that moves backwards in time after updating to
+    /// the last parental generation, at which time the offspring
+    /// demes are discarded.
+    ///
+    /// # Returns
+    ///
+    /// The updated state.
+    /// See [`ForwardGraph::state`].
     pub fn update_state<F: Into<ForwardTime> + std::fmt::Debug + Copy>(
         &mut self,
         parental_generation_time: F,
-    ) -> Result<(), DemesForwardError> {
+    ) -> Result<Option<DemographyState<'_>>, DemesForwardError> {
         let parental_generation_time = parental_generation_time.into();
         if parental_generation_time.value().is_sign_negative()
             || !parental_generation_time.value().is_finite()
@@ -612,11 +1510,189 @@ impl ForwardGraph {
                 "invalid time for update_state: {parental_generation_time:?}",
             )));
         }
-        if let Some(time) = self.last_time_updated {
-            if parental_generation_time < time {
-                // gotta reset...
-                self.parent_demes.clear();
-                self.child_demes.clear();
+        let last_parental_time = self.end_time().value() - 1.0;
+        let step_length = self
+            .time_step
+            .min(last_parental_time - parental_generation_time.value())
+            .max(1.0);
+        self.update_cumulative_ancestry_of_parents(parental_generation_time)?;
+        if step_length > 1.0 {
+            self.update_time_step(parental_generation_time, step_length)?;
+        } else {
+            self.update_one_generation(parental_generation_time)?;
+        }
+        if let Some(cumulative_ancestry) = &mut self.cumulative_ancestry {
+            if !self.child_demes.is_empty() {
+                cumulative_ancestry.update_offspring(
+                    &self.ancestry_proportions,
+                    parental_generation_time,
+                    ForwardTime::from(parental_generation_time.value() + step_length),
+                );
+            }
+        }
+        self.last_time_updated = Some(parental_generation_time);
+        self.state()
+    }
+
+    /// The state at the last time passed to
+    /// [`ForwardGraph::update_state`].
+    ///
+    /// The state bundles [`ForwardGraph::parental_deme_sizes`],
+    /// [`ForwardGraph::offspring_deme_sizes`],
+    /// [`ForwardGraph::ancestry_proportions`],
+    /// [`ForwardGraph::selfing_rates`], and
+    /// [`ForwardGraph::cloning_rates`].
+    /// Unlike the individual functions, the presence of the
+    /// offspring arrays is checked once.
+    ///
+    /// # Returns
+    ///
+    /// * `None` if the state has not been updated or if
+    ///   the last update was after the end of the model.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesForwardError::InternalError`] if the arrays do not
+    /// all have one element per deme in the model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 0).unwrap();
+    /// assert!(graph.state().unwrap().is_none());
+    /// for time in graph.time_iterator().collect::<Vec<_>>() {
+    ///     let state = graph.update_state(time).unwrap().unwrap();
+    ///     assert_eq!(state.time(), time);
+    ///     assert_eq!(state.num_demes(), 1);
+    ///     match state.offspring_deme_sizes() {
+    ///         Some(sizes) => {
+    ///             assert!(sizes[0] == 100.0 || sizes[0] == 200.0);
+    ///             assert_eq!(state.ancestry_proportions(0), Some([1.0].as_slice()));
+    ///         }
+    ///         None => assert_eq!(time, graph.end_time() - 1.0.into()),
+    ///     }
+    /// }
+    /// ```
+    pub fn state(&self) -> Result<Option<DemographyState<'_>>, DemesForwardError> {
+        let (time, parental_deme_sizes) = match (self.last_time_updated, self.parental_deme_sizes())
+        {
+            (Some(time), Some(sizes)) => (time, sizes),
+            _ => return Ok(None),
+        };
+        let offspring = self
+            .offspring_deme_sizes()
+            .map(|deme_sizes| OffspringDemography {
+                deme_sizes,
+                ancestry_proportions: &self.ancestry_proportions,
+                selfing_rates: &self.selfing_rates,
+                cloning_rates: &self.cloning_rates,
+            });
+        DemographyState::new(time, parental_deme_sizes, offspring).map(Some)
+    }
+
+    // Advance the offspring generation by step_length generations,
+    // keeping the parental state at parental_generation_time.
+    // Offspring sizes and rates are aggregated over the generations
+    // of the step, while the ancestry proportions and
+    // migration matrices are multiplied over all generations.
+    fn update_time_step(
+        &mut self,
+        parental_generation_time: ForwardTime,
+        step_length: f64,
+    ) -> Result<(), DemesForwardError> {
+        self.update_one_generation(parental_generation_time)?;
+        let buffers = &mut self.step_buffers;
+        if buffers.parent_demes.len() == self.parent_demes.len() {
+            buffers
+                .parent_demes
+                .iter_mut()
+                .zip(self.parent_demes.iter())
+                .for_each(|(saved, deme)| saved.copy_state_from(deme));
+        } else {
+            buffers.parent_demes.clone_from(&self.parent_demes);
+        }
+        buffers.parental_deme_sizes.clear();
+        buffers
+            .parental_deme_sizes
+            .extend_from_slice(&self.parental_deme_sizes);
+        buffers
+            .ancestry_proportions
+            .copy_from(&self.ancestry_proportions);
+        buffers.migration_matrix.copy_from(&self.migration_matrix);
+        buffers.reset_offspring_sums(self.child_deme_sizes.len());
+        buffers.add_offspring_generation(
+            &self.child_deme_sizes,
+            &self.selfing_rates,
+            &self.cloning_rates,
+        );
+        for generation in 1..step_length as usize {
+            let time = ForwardTime::from(parental_generation_time.value() + generation as f64);
+            self.update_one_generation(time)?;
+            let buffers = &mut self.step_buffers;
+            buffers
+                .ancestry_proportions
+                .left_multiply(&self.ancestry_proportions, &mut buffers.scratch);
+            buffers
+                .migration_matrix
+                .left_multiply(&self.migration_matrix, &mut buffers.scratch);
+            buffers.add_offspring_generation(
+                &self.child_deme_sizes,
+                &self.selfing_rates,
+                &self.cloning_rates,
+            );
+        }
+        let buffers = &self.step_buffers;
+        for (i, size) in self.child_deme_sizes.iter_mut().enumerate() {
+            if *size > 0.0 {
+                let generations = buffers.generations[i] as f64;
+                *size =
+                    CurrentSize::try_from((generations / buffers.inverse_size_sums[i]).round())?;
+                self.selfing_rates[i] =
+                    demes::SelfingRate::try_from(buffers.selfing_rate_sums[i] / generations)?;
+                self.cloning_rates[i] =
+                    demes::CloningRate::try_from(buffers.cloning_rate_sums[i] / generations)?;
+            }
+        }
+        self.parent_demes
+            .iter_mut()
+            .zip(buffers.parent_demes.iter())
+            .for_each(|(deme, saved)| deme.copy_state_from(saved));
+        self.parental_deme_sizes.clear();
+        self.parental_deme_sizes
+            .extend_from_slice(&buffers.parental_deme_sizes);
+        self.ancestry_proportions
+            .copy_from(&buffers.ancestry_proportions);
+        self.migration_matrix.copy_from(&buffers.migration_matrix);
+        self.pulses.clear();
+        self.pulses.extend(pulses_in_step(
+            &self.graph,
+            &self.model_times,
+            parental_generation_time.value(),
+            step_length,
+        ));
+        self.retain_focal_pulses();
+        Ok(())
+    }
+
+    fn update_one_generation(
+        &mut self,
+        parental_generation_time: ForwardTime,
+    ) -> Result<(), DemesForwardError> {
+        if let Some(time) = self.last_generation_updated {
+            if parental_generation_time < time {
+                // gotta reset...
+                self.parent_demes.iter_mut().for_each(Deme::reset);
+                self.child_demes.iter_mut().for_each(Deme::reset);
             }
         }
         let backwards_time = self.model_times.convert(parental_generation_time)?;
@@ -662,7 +1738,9 @@ impl ForwardGraph {
         self.update_ancestry_proportions_from_pulses(parental_generation_time)?;
         self.update_migration_matrix(parental_generation_time)?;
         self.update_ancestry_proportions_from_migration_matrix();
-        self.last_time_updated = Some(parental_generation_time);
+        self.update_migration_matrix_diagonal();
+        self.restrict_to_focal_demes(parental_generation_time)?;
+        self.last_generation_updated = Some(parental_generation_time);
 
         Ok(())
     }
@@ -694,6 +1772,207 @@ impl ForwardGraph {
         }
     }
 
+    /// The migration matrix at the current time.
+    ///
+    /// Element `(i, j)` is the proportion of offspring in deme `i`
+    /// whose parents are migrants from deme `j`.
+    /// The diagonal element `(i, i)` is the proportion of
+    /// offspring in deme `i` whose parents are not migrants.
+    /// Rows of extant offspring demes sum to one and all other
+    /// rows are zero.
+    ///
+    /// Unlike [`ForwardGraph::ancestry_proportions`], the matrix
+    /// does not include ancestry from pulses or from the ancestors
+    /// of demes that first appear in the offspring generation.
+    ///
+    /// Returns `None` if there are no extant offspring demes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - source: A
+    ///    dest: B
+    ///    rate: 0.25
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+    /// graph.update_state(5).unwrap();
+    /// let matrix = graph.migration_matrix().unwrap();
+    /// assert_eq!(matrix.row(0), &[1.0, 0.0]);
+    /// assert_eq!(matrix.row(1), &[0.25, 0.75]);
+    /// ```
+    pub fn migration_matrix(&self) -> Option<&SquareMatrix> {
+        if !self.child_demes.is_empty() {
+            Some(&self.migration_matrix)
+        } else {
+            None
+        }
+    }
+
+    /// The pulses applied to the offspring generation
+    /// at the current time.
+    ///
+    /// Pulses are listed in the order that they are applied,
+    /// which is their order in the [`demes::Graph`].
+    /// The iterator is empty if there are no pulses at the
+    /// current time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// pulses:
+    ///  - sources: [A]
+    ///    dest: B
+    ///    time: 5
+    ///    proportions: [0.25]
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+    /// let at = graph.time_to_forward(6.0).unwrap().unwrap();
+    /// graph.update_state(at).unwrap();
+    /// let pulses = graph.pulses_at_current_time().collect::<Vec<_>>();
+    /// assert_eq!(pulses.len(), 1);
+    /// assert_eq!(pulses[0].dest(), "B");
+    /// assert_eq!(pulses[0].proportions()[0], 0.25);
+    /// ```
+    pub fn pulses_at_current_time(&self) -> impl ExactSizeIterator<Item = &demes::Pulse> {
+        self.pulses.iter().map(|&index| &self.graph.pulses()[index])
+    }
+
+    /// The pulses that will be applied to the offspring
+    /// generation at the next time.
+    ///
+    /// The next time is one time step after the last time
+    /// passed to [`ForwardGraph::update_state`], or time zero
+    /// if the state has not been updated.
+    /// Pulses are listed in the order that they will be applied.
+    pub fn pulses_at_next_time(&self) -> impl Iterator<Item = &demes::Pulse> {
+        let next_parental_time = match self.last_time_updated {
+            Some(time) => time.value() + self.time_step,
+            None => 0.0,
+        };
+        let last_parental_time = self.end_time().value() - 1.0;
+        let step_length = self
+            .time_step
+            .min(last_parental_time - next_parental_time)
+            .max(1.0);
+        pulses_in_step(
+            &self.graph,
+            &self.model_times,
+            next_parental_time,
+            step_length,
+        )
+        .map(|index| &self.graph.pulses()[index])
+        .filter(|pulse| self.is_focal_deme(pulse.dest()))
+    }
+
+    /// The changes to the model between the parental and offspring
+    /// generations at the current time.
+    ///
+    /// Events for demes are listed in order of deme index
+    /// and are followed by the pulses, in the order of
+    /// [`ForwardGraph::pulses_at_current_time`].
+    ///
+    /// The iterator is empty if there are no offspring demes,
+    /// which is the case for the last parental generation.
+    /// Demes that exist in the first parental generation do not
+    /// have a [`DemographicEvent::Appears`] event.
+    ///
+    /// # Notes
+    ///
+    /// When the time step is longer than one generation
+    /// (see [`ForwardGraph::new_discrete_time_with_time_step`]),
+    /// parents are compared to the offspring at the end of the step.
+    /// A deme that appears and goes extinct within a step has
+    /// no events, and several epoch changes within a step are
+    /// reported as one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes_forward::DemographicEvent;
+    ///
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 5
+    ///  - name: B
+    ///    ancestors: [A]
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+    /// for time in graph.time_iterator() {
+    ///     graph.update_state(time).unwrap();
+    ///     let events = graph.events().collect::<Vec<_>>();
+    ///     if time.value() == 10.0 {
+    ///         assert_eq!(
+    ///             events,
+    ///             [
+    ///                 DemographicEvent::Extinct { deme: 0 },
+    ///                 DemographicEvent::Appears { deme: 1 }
+    ///             ]
+    ///         );
+    ///     } else {
+    ///         assert!(events.is_empty());
+    ///     }
+    /// }
+    /// ```
+    pub fn events(&self) -> impl Iterator<Item = DemographicEvent<'_>> {
+        let deme_events = self
+            .parent_demes
+            .iter()
+            .zip(self.child_demes.iter())
+            .enumerate()
+            .filter_map(
+                |(deme, (parent, child))| match (&parent.status, &child.status) {
+                    (DemeStatus::During(parental_epoch), DemeStatus::During(offspring_epoch)) => {
+                        if parental_epoch != offspring_epoch {
+                            Some(DemographicEvent::EpochChange {
+                                deme,
+                                parental_epoch: *parental_epoch,
+                                offspring_epoch: *offspring_epoch,
+                            })
+                        } else {
+                            None
+                        }
+                    }
+                    (DemeStatus::During(_), _) => Some(DemographicEvent::Extinct { deme }),
+                    (_, DemeStatus::During(_)) => Some(DemographicEvent::Appears { deme }),
+                    _ => None,
+                },
+            );
+        // NOTE: there are no pulses when there are no offspring demes
+        deme_events.chain(self.pulses_at_current_time().map(DemographicEvent::Pulse))
+    }
+
+    pub(crate) fn ancestry_proportions_matrix(&self) -> &SquareMatrix {
+        &self.ancestry_proportions
+    }
+
     /// Get cloning rates of all offspring demes.
     ///
     /// Returns `None` if there are no extant offspring
@@ -724,12 +2003,204 @@ impl ForwardGraph {
         (burnin_gen + model_duration).into()
     }
 
+    /// The lifetime of each deme.
+    ///
+    /// Element `i` is the interval of forward times of the
+    /// offspring generations that belong to deme `i`.
+    /// Offspring at time `t` are the children of the parents
+    /// at time `t - 1`, so that, with a time step of one generation,
+    /// the size of offspring deme `i` is nonzero after
+    /// [`ForwardGraph::update_state`] is called with `t - 1`.
+    /// The intervals are empty for demes that are excluded by
+    /// [`ForwardGraph::with_deme_subset`].
+    ///
+    /// The lifetimes are calculated when the graph is constructed,
+    /// allowing simulations to plan ahead without updating the state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///  - name: B
+    ///    ancestors: [A]
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 5
+    ///  - name: C
+    ///    ancestors: [A]
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 20).unwrap();
+    /// let lifetimes = graph
+    ///     .deme_lifetimes()
+    ///     .iter()
+    ///     .map(|lifetime| (lifetime.start.value(), lifetime.end.value()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(lifetimes, [(1.0, 21.0), (21.0, 26.0), (21.0, 31.0)]);
+    /// assert_eq!(graph.end_time().value(), 31.0);
+    /// ```
+    pub fn deme_lifetimes(&self) -> &[std::ops::Range<ForwardTime>] {
+        &self.deme_lifetimes
+    }
+
     /// Return an iterator over time values.
     ///
     /// The iterator starts at the last updated time and
     /// continues until the end time.
+    /// Times are separated by the time step of the graph,
+    /// except for the last time, which is always included.
     pub fn time_iterator(&self) -> impl Iterator<Item = ForwardTime> {
-        self.model_times.time_iterator(self.last_time_updated)
+        let start = self.last_time_updated.map_or(0.0, |time| time.value());
+        let time_step = self.time_step;
+        let last_parental_time = self.end_time().value() - 1.0;
+        self.model_times
+            .time_iterator(self.last_time_updated)
+            .filter(move |time| {
+                (time.value() - start) % time_step == 0.0 || time.value() == last_parental_time
+            })
+    }
+
+    /// Return an iterator over time values in backward order.
+    ///
+    /// The iterator starts at the most recent parental
+    /// generation of the model and continues back to time zero.
+    /// The values are forward times and may be passed to
+    /// [`ForwardGraph::update_state`], which gives the
+    /// same state at each time as iterating forwards.
+    ///
+    /// Unlike [`ForwardGraph::time_iterator`], the values
+    /// do not depend on the last updated time.
+    ///
+    /// # Note
+    ///
+    /// Moving backwards in time requires recalculating
+    /// the state of each deme from the start of its history.
+    /// Iterating backwards is therefore slower than
+    /// iterating forwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 0).unwrap();
+    /// let mut sizes = vec![];
+    /// for time in graph.backward_time_iterator() {
+    ///     graph.update_state(time).unwrap();
+    ///     sizes.push(graph.parental_deme_sizes().unwrap()[0]);
+    /// }
+    /// assert_eq!(sizes.len(), 11);
+    /// assert_eq!(sizes[0], 200.0);
+    /// assert_eq!(sizes[10], 100.0);
+    /// ```
+    pub fn backward_time_iterator(&self) -> impl Iterator<Item = ForwardTime> {
+        let time_step = self.time_step;
+        let last_parental_time = self.end_time().value() - 1.0;
+        self.model_times
+            .backward_time_iterator()
+            .filter(move |time| {
+                time.value() % time_step == 0.0 || time.value() == last_parental_time
+            })
+    }
+
+    /// The number of generations of burn-in.
+    ///
+    /// This is the `burnin_time` passed to the constructor.
+    pub fn burn_in_duration(&self) -> f64 {
+        self.model_times.burnin_generation()
+    }
+
+    /// The first parental generation after the burn-in.
+    ///
+    /// Parental generations at earlier times are part of the
+    /// burn-in.
+    /// The value is equal to [`ForwardGraph::burn_in_duration`]
+    /// because the first parental generation is at time zero.
+    ///
+    /// See [`ForwardGraph::backwards_burn_in_time`] for the
+    /// corresponding time in the [`demes::Graph`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 5).unwrap();
+    /// assert_eq!(graph.burn_in_duration(), 5.0);
+    /// assert_eq!(graph.burn_in_end_time(), 5.0.into());
+    /// assert!(!graph.in_burn_in());
+    /// graph.update_state(4).unwrap();
+    /// assert!(graph.in_burn_in());
+    /// graph.update_state(5).unwrap();
+    /// assert!(!graph.in_burn_in());
+    /// // The first change in the model is to the offspring
+    /// // of the first parents after the burn-in.
+    /// assert_eq!(graph.offspring_deme_sizes().unwrap()[0], 200.0);
+    /// ```
+    pub fn burn_in_end_time(&self) -> ForwardTime {
+        ForwardTime::from(self.burn_in_duration())
+    }
+
+    /// Whether the last time passed to
+    /// [`ForwardGraph::update_state`] is part of the burn-in.
+    ///
+    /// Returns `false` if the state has not been updated.
+    ///
+    /// See [`ForwardGraph::burn_in_end_time`].
+    pub fn in_burn_in(&self) -> bool {
+        match self.last_time_updated {
+            Some(time) => time < self.burn_in_end_time(),
+            None => false,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn tracks_cumulative_ancestry(&self) -> bool {
+        self.cumulative_ancestry.is_some()
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn focal_deme_names(&self) -> Option<Vec<&str>> {
+        self.focal_demes.as_ref().map(|focal_demes| {
+            self.graph
+                .demes()
+                .iter()
+                .zip(focal_demes.iter())
+                .filter(|(_, &is_focal)| is_focal)
+                .map(|(deme, _)| deme.name())
+                .collect()
+        })
+    }
+
+    /// The number of generations per time step.
+    ///
+    /// See [`ForwardGraph::new_discrete_time_with_time_step`].
+    pub fn time_step(&self) -> f64 {
+        self.time_step
     }
 
     /// Obtain the sizes of each parental deme.
@@ -804,6 +2275,10 @@ impl ForwardGraph {
     ///   to "infinity". The first parental generation is considered
     ///   to exist at a time one generation prior to the start of events
     ///   in the graph plus the burn-in time.
+    /// * The size is calculated from the model and the state of the
+    ///   graph is not changed.
+    ///   Thus, sizes may be queried at any time during iteration
+    ///   over [`ForwardGraph::time_iterator`].
     pub fn size_at<'a, I: Into<demes::DemeId<'a>>, T: Into<crate::time::BackwardTimeWrapper>>(
         &self,
         deme: I,
@@ -823,8 +2298,8 @@ impl ForwardGraph {
         }
         let id = deme.into();
         let deme = match self.graph.get_deme(id) {
-            Some(d) => d,
-            None => return Ok(None),
+            Some(d) if self.is_focal_deme(d.name()) => d,
+            _ => return Ok(None),
         };
         if let Some((index, _epoch)) = deme
             .epochs()
@@ -874,25 +2349,107 @@ impl ForwardGraph {
     where
         I: std::fmt::Debug + Into<demes::DemeId<'a>>,
     {
-        let deme_index = match deme.into() {
-            demes::DemeId::Index(index) => index,
-            demes::DemeId::Name(name) => match self.deme_to_index.get(name) {
-                Some(&index) => index,
-                None => {
-                    return Err(
-                        demes::DemesError::DemeError(format!("invalid deme id {name}")).into(),
-                    );
-                }
-            },
-        };
-        if deme_index >= self.graph.num_demes() {
-            return Err(
-                demes::DemesError::DemeError(format!("invalid deme index {deme_index}")).into(),
-            );
-        }
+        let deme_index = self.get_deme_index(deme.into())?;
         let graph = self.clone();
-        let forward_model_start_time = f64::from(self.model_times.model_start_time());
-        DemeSizeHistory::new(graph, deme_index, forward_model_start_time)
+        DemeSizeHistory::new(graph, deme_index)
+    }
+
+    /// Generate an iterator over the state of the model
+    /// at each parental generation.
+    ///
+    /// # Returns
+    ///
+    /// An iterator over instances of [`GenerationState`].
+    ///
+    /// # Note
+    ///
+    /// The iterated values are lazily evaluated.
+    /// First `self` is cloned.
+    /// Then, the entire model history is iterated over,
+    /// starting from time zero.
+    /// The state of `self` is not changed.
+    ///
+    /// # Panics
+    ///
+    /// * Cloning the graph requires allocations which will
+    ///   panic if the system runs out of memory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 0).unwrap();
+    /// for state in graph.iter_states() {
+    ///     assert_eq!(state.parental_deme_sizes().len(), 1);
+    ///     match state.offspring_deme_sizes() {
+    ///         Some(_) => assert_eq!(state.ancestry_proportions(0), Some([1.0].as_slice())),
+    ///         None => assert_eq!(state.backwards_time(), 0.0),
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_states(&self) -> impl Iterator<Item = GenerationState> {
+        GenerationStates::new(self.clone())
+    }
+
+    /// Copy the state at the last time passed to
+    /// [`ForwardGraph::update_state`].
+    ///
+    /// The copy does not borrow `self` and is `Send` and `Sync`.
+    /// Thus, it may be handed to other threads while `self`
+    /// is updated to later times.
+    ///
+    /// # Cost
+    ///
+    /// Each call allocates and copies the whole state.
+    /// For a model with `N` demes, that is `O(N)` values for
+    /// the deme sizes and rates plus the `N x N` matrix of
+    /// ancestry proportions, so the cost is `O(N²)` in time
+    /// and memory regardless of how many demes are extant.
+    /// No buffers are shared with `self`.
+    /// For models with many demes, take snapshots only of the
+    /// times that are needed, and wrap a snapshot in
+    /// [`std::sync::Arc`] to share it between several threads
+    /// without further copies.
+    ///
+    /// # Returns
+    ///
+    /// * `None` if the state has not been updated or if
+    ///   the last update was after the end of the model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 0).unwrap();
+    /// assert!(graph.snapshot().is_none());
+    /// graph.update_state(0).unwrap();
+    /// let snapshot = graph.snapshot().unwrap();
+    /// let worker = std::thread::spawn(move || snapshot.parental_deme_sizes()[0]);
+    /// graph.update_state(1).unwrap();
+    /// assert_eq!(worker.join().unwrap(), 100.0);
+    /// ```
+    pub fn snapshot(&self) -> Option<GenerationState> {
+        let time = self.last_time_updated?;
+        self.parental_deme_sizes()?;
+        Some(GenerationState::new(self, time))
     }
 
     /// Return the time in the past when the first generation
@@ -973,6 +2530,36 @@ impl ForwardGraph {
         self.graph.deme_names()
     }
 
+    /// Get the index of a deme from its name.
+    ///
+    /// The index is the position of the deme in
+    /// [`ForwardGraph::deme_names`] and in the slices returned
+    /// by functions such as [`ForwardGraph::parental_deme_sizes`].
+    ///
+    /// Returns `None` if there is no deme with that name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 0).unwrap();
+    /// assert_eq!(graph.deme_index("B"), Some(1));
+    /// assert_eq!(graph.deme_index("C"), None);
+    /// ```
+    pub fn deme_index(&self, name: &str) -> Option<usize> {
+        self.deme_to_index.get(name).copied()
+    }
+
     /// Access to the underlying [`demes::Graph`]
     pub fn demes_graph(&self) -> &demes::Graph {
         &self.graph
@@ -1130,6 +2717,57 @@ demes:
         ));
     }
 
+    #[test]
+    fn time_rounding_policies() {
+        let demes_graph = two_epoch_model_invalid_conversion_to_generations();
+        let end_times = |rounding| {
+            let graph = ForwardGraph::new_discrete_time_with_rounding(
+                demes_graph.clone(),
+                100.0,
+                1.0,
+                rounding,
+            )
+            .unwrap();
+            assert_eq!(
+                graph.demes_graph().time_units(),
+                demes::TimeUnits::Generations
+            );
+            graph
+                .demes_graph()
+                .deme(0)
+                .end_times()
+                .map(f64::from)
+                .collect::<Vec<_>>()
+        };
+        assert!(ForwardGraph::new_discrete_time_with_rounding(
+            demes_graph.clone(),
+            100.0,
+            1.0,
+            TimeRounding::Nearest
+        )
+        .is_err());
+        assert!(ForwardGraph::new_discrete_time_with_rounding(
+            demes_graph.clone(),
+            100.0,
+            1.0,
+            TimeRounding::Down
+        )
+        .is_err());
+        assert_eq!(end_times(TimeRounding::Up), [1.0, 0.0]);
+        assert_eq!(end_times(TimeRounding::NearestMergingZeroLength), [0.0]);
+
+        let mut graph = ForwardGraph::new_discrete_time_with_rounding(
+            demes_graph,
+            100.0,
+            1.0,
+            TimeRounding::Up,
+        )
+        .unwrap();
+        graph.update_state(100.0).unwrap();
+        assert_eq!(graph.parental_deme_sizes().unwrap()[0], 200.0);
+        assert_eq!(graph.offspring_deme_sizes().unwrap()[0], 100.0);
+    }
+
     #[test]
     fn invalid_forward_time() {
         {
@@ -1610,6 +3248,63 @@ pulses:
             }
         }
     }
+
+    #[test]
+    fn test_pulse_accessors() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 50
+ - name: B
+   epochs:
+    - start_size: 50
+pulses:
+ - sources: [A]
+   dest: B
+   time: 10
+   proportions: [0.5]
+ - sources: [B]
+   dest: A
+   time: 10
+   proportions: [0.25]
+ - sources: [A]
+   dest: B
+   time: 5
+   proportions: [0.1]
+";
+        let demes_g = demes::loads(yaml).unwrap();
+        let mut g = ForwardGraph::new_discrete_time(demes_g, 10).unwrap();
+        let mut previous_next = g.pulses_at_next_time().cloned().collect::<Vec<_>>();
+        for time in g.time_iterator() {
+            g.update_state(time).unwrap();
+            assert_eq!(
+                g.pulses_at_current_time().cloned().collect::<Vec<_>>(),
+                previous_next
+            );
+            let backwards_time = g.time_to_backward(time).unwrap().unwrap();
+            let expected_times = if backwards_time == 11.0 {
+                vec![10.0, 10.0]
+            } else if backwards_time == 6.0 {
+                vec![5.0]
+            } else {
+                vec![]
+            };
+            assert_eq!(
+                g.pulses_at_current_time()
+                    .map(|p| f64::from(p.time()))
+                    .collect::<Vec<_>>(),
+                expected_times
+            );
+            previous_next = g.pulses_at_next_time().cloned().collect::<Vec<_>>();
+        }
+        let at = g.time_to_forward(11.0).unwrap().unwrap();
+        g.update_state(at).unwrap();
+        let pulses = g.pulses_at_current_time().collect::<Vec<_>>();
+        assert_eq!(pulses[0].dest(), "B");
+        assert_eq!(pulses[1].dest(), "A");
+    }
 }
 
 #[cfg(test)]
@@ -2163,6 +3858,54 @@ migrations:
             }
         }
     }
+
+    #[test]
+    fn test_migration_matrix_accessor() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 1000
+      end_time: 10
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 1000
+ - name: C
+   ancestors: [A]
+   epochs:
+    - start_size: 1000
+migrations:
+- source: B
+  dest: C
+  rate: 0.1
+  start_time: 5
+";
+        let demes_graph = demes::loads(yaml).unwrap();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 0).unwrap();
+        assert!(graph.migration_matrix().is_none());
+        for time in graph.time_iterator() {
+            graph.update_state(time).unwrap();
+            let backwards_time = graph.time_to_backward(time).unwrap().unwrap();
+            match graph.migration_matrix() {
+                Some(matrix) => {
+                    assert_eq!(matrix.nrows(), 3);
+                    let expected = if backwards_time > 10.0 {
+                        [1., 0., 0., 0., 0., 0., 0., 0., 0.]
+                    } else if backwards_time > 5.0 {
+                        [0., 0., 0., 0., 1., 0., 0., 0., 1.]
+                    } else {
+                        [0., 0., 0., 0., 1., 0., 0., 0.1, 0.9]
+                    };
+                    assert_eq!(matrix.as_slice(), expected, "{backwards_time}");
+                    assert_eq!(matrix.get(2, 1), Some(expected[7]));
+                    assert!(matrix.get(3, 0).is_none());
+                }
+                None => assert_eq!(backwards_time, 0.0),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -2203,3 +3946,717 @@ demes:
         }
     }
 }
+
+#[cfg(test)]
+mod test_time_steps {
+    use super::*;
+
+    #[test]
+    fn test_invalid_time_steps() {
+        for time_step in [0.0, 0.5, 2.5, -1.0, f64::NAN, f64::INFINITY] {
+            let graph = graphs_for_testing::four_deme_model();
+            assert!(
+                matches!(
+                    ForwardGraph::new_discrete_time_with_time_step(graph, 10, time_step),
+                    Err(DemesForwardError::TimeError(_))
+                ),
+                "{time_step}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_step_of_one() {
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph.clone(), 10).unwrap();
+        let mut stepped =
+            ForwardGraph::new_discrete_time_with_time_step(demes_graph, 10, 1.0).unwrap();
+        assert_eq!(
+            graph.time_iterator().collect::<Vec<_>>(),
+            stepped.time_iterator().collect::<Vec<_>>()
+        );
+        for time in graph.time_iterator() {
+            graph.update_state(time).unwrap();
+            stepped.update_state(time).unwrap();
+            assert_eq!(graph.parental_deme_sizes(), stepped.parental_deme_sizes());
+            assert_eq!(graph.offspring_deme_sizes(), stepped.offspring_deme_sizes());
+            for deme in 0..graph.num_demes_in_model() {
+                assert_eq!(
+                    graph.ancestry_proportions(deme),
+                    stepped.ancestry_proportions(deme)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sizes_and_times() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 20
+    - start_size: 100
+      end_size: 200
+ - name: B
+   ancestors: [A]
+   start_time: 11
+   epochs:
+    - start_size: 50
+";
+        let demes_graph = demes::loads(yaml).unwrap();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph.clone(), 7).unwrap();
+        let mut stepped =
+            ForwardGraph::new_discrete_time_with_time_step(demes_graph, 7, 3.0).unwrap();
+        assert_eq!(stepped.time_step(), 3.0);
+        let times = stepped.time_iterator().collect::<Vec<_>>();
+        let last_parental_time = graph.end_time() - 1.0.into();
+        assert_eq!(times.last(), Some(&last_parental_time));
+        for pair in times.windows(2) {
+            assert!(pair[1].value() - pair[0].value() <= 3.0);
+        }
+        let mut backward_times = stepped.backward_time_iterator().collect::<Vec<_>>();
+        backward_times.reverse();
+        assert_eq!(times, backward_times);
+
+        for pair in times.windows(2) {
+            stepped.update_state(pair[0]).unwrap();
+            graph.update_state(pair[0]).unwrap();
+            assert_eq!(graph.parental_deme_sizes(), stepped.parental_deme_sizes());
+            let mut inverse_size_sums = [0.0; 2];
+            let mut generations = [0.0; 2];
+            for time in pair[0].value() as usize..pair[1].value() as usize {
+                graph.update_state(time as f64).unwrap();
+                for (deme, &size) in graph.offspring_deme_sizes().unwrap().iter().enumerate() {
+                    if size > 0.0 {
+                        inverse_size_sums[deme] += 1.0 / f64::from(size);
+                        generations[deme] += 1.0;
+                    }
+                }
+            }
+            // Deme B exists for part of a step
+            let sizes = stepped.offspring_deme_sizes().unwrap();
+            for deme in 0..2 {
+                if graph.offspring_deme_sizes().unwrap()[deme] > 0.0 {
+                    let harmonic_mean = (generations[deme] / inverse_size_sums[deme]).round();
+                    assert_eq!(sizes[deme], harmonic_mean);
+                } else {
+                    assert_eq!(sizes[deme], 0.0);
+                }
+            }
+            assert_eq!(graph.selfing_rates(), stepped.selfing_rates());
+        }
+    }
+
+    #[test]
+    fn test_size_change_within_step() {
+        // With a burn-in of 2 generations, the size and selfing rate
+        // change during the first step, after which the size
+        // grows exponentially.
+        // The step aggregates the sizes and rates of all 4 generations.
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 12
+    - start_size: 200
+      end_size: 400
+      selfing_rate: 0.5
+";
+        let demes_graph = demes::loads(yaml).unwrap();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph.clone(), 2).unwrap();
+        let mut stepped =
+            ForwardGraph::new_discrete_time_with_time_step(demes_graph, 2, 4.0).unwrap();
+        stepped.update_state(0).unwrap();
+        assert_eq!(stepped.parental_deme_sizes().unwrap()[0], 100.0);
+
+        let mut sizes = vec![];
+        let mut selfing_rates = vec![];
+        for time in 0..4 {
+            graph.update_state(time).unwrap();
+            sizes.push(f64::from(graph.offspring_deme_sizes().unwrap()[0]));
+            selfing_rates.push(f64::from(graph.selfing_rates().unwrap()[0]));
+        }
+        assert_eq!(sizes[0], 100.0);
+        assert!(sizes[2] > 200.0 && sizes[2] < sizes[3]);
+        assert_eq!(selfing_rates, [0.0, 0.0, 0.5, 0.5]);
+        // The harmonic mean size and the mean rate over the step
+        let harmonic_mean = 4.0 / sizes.iter().map(|size| 1.0 / size).sum::<f64>();
+        assert_eq!(
+            stepped.offspring_deme_sizes().unwrap()[0],
+            harmonic_mean.round()
+        );
+        assert!(stepped.offspring_deme_sizes().unwrap()[0] < sizes[3]);
+        assert_eq!(stepped.selfing_rates().unwrap()[0], 0.25);
+    }
+
+    #[test]
+    fn test_accumulated_migration() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 0.1
+";
+        let demes_graph = demes::loads(yaml).unwrap();
+        let mut graph =
+            ForwardGraph::new_discrete_time_with_time_step(demes_graph, 10, 2.0).unwrap();
+        graph.update_state(0).unwrap();
+        assert_eq!(graph.ancestry_proportions(0), Some([1.0, 0.0].as_slice()));
+        let b = graph.ancestry_proportions(1).unwrap();
+        assert!((b[0] - 0.19).abs() < 1e-12, "{b:?}");
+        assert!((b[1] - 0.81).abs() < 1e-12, "{b:?}");
+        assert_eq!(graph.migration_matrix().unwrap().row(1), b);
+    }
+
+    #[test]
+    fn test_pulses_within_step() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+pulses:
+ - sources: [A]
+   dest: B
+   time: 10
+   proportions: [0.5]
+ - sources: [A]
+   dest: B
+   time: 9
+   proportions: [0.5]
+";
+        let demes_graph = demes::loads(yaml).unwrap();
+        let mut graph =
+            ForwardGraph::new_discrete_time_with_time_step(demes_graph, 10, 4.0).unwrap();
+        let mut num_pulses = 0;
+        let mut expected = graph.pulses_at_next_time().cloned().collect::<Vec<_>>();
+        for time in graph.time_iterator() {
+            graph.update_state(time).unwrap();
+            assert_eq!(
+                graph.pulses_at_current_time().cloned().collect::<Vec<_>>(),
+                expected
+            );
+            if graph.pulses_at_current_time().len() > 0 {
+                // Both pulses happen during the same step
+                num_pulses += graph.pulses_at_current_time().len();
+                assert_eq!(graph.pulses_at_current_time().len(), 2);
+                assert_eq!(graph.ancestry_proportions(1), Some([0.75, 0.25].as_slice()));
+            }
+            expected = graph.pulses_at_next_time().cloned().collect::<Vec<_>>();
+        }
+        assert_eq!(num_pulses, 2);
+    }
+}
+
+#[cfg(test)]
+mod test_deme_subset {
+    use super::*;
+
+    #[test]
+    fn test_subset_of_four_deme_model() {
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let mut full = ForwardGraph::new_discrete_time(demes_graph.clone(), 10).unwrap();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 10)
+            .unwrap()
+            .with_deme_subset(["A", "B", "D"])
+            .unwrap();
+        let mut founded_d = false;
+        for time in graph.time_iterator() {
+            graph.update_state(time).unwrap();
+            full.update_state(time).unwrap();
+            let sizes = graph.parental_deme_sizes().unwrap();
+            let full_sizes = full.parental_deme_sizes().unwrap();
+            assert_eq!(sizes[2], 0.0);
+            for deme in [0, 1, 3] {
+                assert_eq!(sizes[deme], full_sizes[deme]);
+            }
+            if let Some(sizes) = graph.offspring_deme_sizes() {
+                assert_eq!(sizes[2], 0.0);
+                assert_eq!(graph.ancestry_proportions(2), Some([0.0; 4].as_slice()));
+                if sizes[3] > 0.0 && graph.parental_deme_sizes().unwrap()[3] == 0.0 {
+                    // D is founded with all of its ancestry from B
+                    assert_eq!(
+                        graph.ancestry_proportions(3),
+                        Some([0.0, 1.0, 0.0, 0.0].as_slice())
+                    );
+                    founded_d = true;
+                }
+            }
+            assert!(graph.num_extant_parental_demes() <= 2);
+        }
+        assert!(founded_d);
+        assert!(graph.size_at("C", 49.0).unwrap().is_none());
+        assert!(graph.size_at("B", 49.0).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_absorbed_migration_and_pulses() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+ - name: C
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 0.25
+ - source: C
+   dest: B
+   rate: 0.25
+pulses:
+ - sources: [B]
+   dest: A
+   time: 5
+   proportions: [0.5]
+";
+        let demes_graph = demes::loads(yaml).unwrap();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 10)
+            .unwrap()
+            .with_deme_subset([1, 2])
+            .unwrap();
+        for time in graph.time_iterator() {
+            graph.update_state(time).unwrap();
+            // The pulse into A is not reported
+            assert_eq!(graph.pulses_at_current_time().len(), 0);
+            assert_eq!(graph.pulses_at_next_time().count(), 0);
+            if graph.offspring_deme_sizes().is_some() {
+                assert_eq!(
+                    graph.ancestry_proportions(1),
+                    Some([0.0, 2.0 / 3.0, 1.0 / 3.0].as_slice())
+                );
+                let matrix = graph.migration_matrix().unwrap();
+                assert_eq!(matrix.row(0), [0.0; 3]);
+                assert_eq!(matrix.row(1), [0.0, 0.75, 0.25]);
+                assert_eq!(graph.selfing_rates().unwrap()[0], 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_subset_after_update() {
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+        graph.update_state(5).unwrap();
+        assert_eq!(graph.num_extant_parental_demes(), 1);
+        let graph = graph.with_deme_subset(["B"]);
+        assert!(matches!(graph, Err(DemesForwardError::DemeSubsetError(_))));
+
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+        graph.update_state(15).unwrap();
+        // B and D
+        assert_eq!(graph.num_extant_parental_demes(), 2);
+        let graph = graph.with_deme_subset(["A", "B"]).unwrap();
+        assert_eq!(graph.last_time_updated(), Some(15.into()));
+        assert_eq!(graph.num_extant_parental_demes(), 1);
+        assert_eq!(graph.parental_deme_sizes().unwrap()[1], 100.0);
+        assert_eq!(graph.parental_deme_sizes().unwrap()[3], 0.0);
+    }
+
+    #[test]
+    fn test_invalid_subsets() {
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let graph = ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+        assert!(matches!(
+            graph.clone().with_deme_subset(Vec::<&str>::new()),
+            Err(DemesForwardError::DemeSubsetError(_))
+        ));
+        assert!(matches!(
+            graph.clone().with_deme_subset(["A", "E"]),
+            Err(DemesForwardError::DemesError(_))
+        ));
+        assert!(matches!(
+            graph.clone().with_deme_subset([0, 4]),
+            Err(DemesForwardError::DemesError(_))
+        ));
+        assert!(graph.with_deme_subset(["A", "C", "D"]).is_ok());
+    }
+
+    #[test]
+    fn test_all_ancestry_from_external_demes() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+pulses:
+ - sources: [A]
+   dest: B
+   time: 5
+   proportions: [1.0]
+";
+        let demes_graph = demes::loads(yaml).unwrap();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 10)
+            .unwrap()
+            .with_deme_subset(["B"])
+            .unwrap();
+        let result = graph
+            .time_iterator()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .try_for_each(|time| graph.update_state(time).map(|_| ()));
+        assert!(matches!(result, Err(DemesForwardError::DemeSubsetError(_))));
+    }
+
+    #[test]
+    fn test_subset_with_time_step() {
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let mut graph = ForwardGraph::new_discrete_time_with_time_step(demes_graph, 10, 3.0)
+            .unwrap()
+            .with_deme_subset(["A", "B", "D"])
+            .unwrap();
+        for time in graph.time_iterator() {
+            graph.update_state(time).unwrap();
+            assert_eq!(graph.parental_deme_sizes().unwrap()[2], 0.0);
+            if graph.offspring_deme_sizes().is_some() {
+                for deme in 0..graph.num_demes_in_model() {
+                    let sum: f64 = graph.ancestry_proportions(deme).unwrap().iter().sum();
+                    if graph.offspring_deme_sizes().unwrap()[deme] > 0.0 {
+                        assert!((sum - 1.0).abs() < 1e-12, "{sum}");
+                    } else {
+                        assert_eq!(sum, 0.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_events {
+    use super::*;
+
+    // (time, event) for each deme event
+    fn deme_events(graph: &mut ForwardGraph) -> Vec<(f64, DemographicEvent<'static>)> {
+        let mut events = vec![];
+        for time in graph.time_iterator().collect::<Vec<_>>() {
+            graph.update_state(time).unwrap();
+            for event in graph.events() {
+                match event {
+                    DemographicEvent::Appears { deme } => {
+                        events.push((time.value(), DemographicEvent::Appears { deme }))
+                    }
+                    DemographicEvent::Extinct { deme } => {
+                        events.push((time.value(), DemographicEvent::Extinct { deme }))
+                    }
+                    DemographicEvent::EpochChange {
+                        deme,
+                        parental_epoch,
+                        offspring_epoch,
+                    } => events.push((
+                        time.value(),
+                        DemographicEvent::EpochChange {
+                            deme,
+                            parental_epoch,
+                            offspring_epoch,
+                        },
+                    )),
+                    DemographicEvent::Pulse(_) => (),
+                }
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_four_deme_model() {
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+        let events = deme_events(&mut graph);
+        assert_eq!(
+            events,
+            [
+                (10.0, DemographicEvent::Extinct { deme: 0 }),
+                (10.0, DemographicEvent::Appears { deme: 1 }),
+                (10.0, DemographicEvent::Appears { deme: 2 }),
+                (11.0, DemographicEvent::Extinct { deme: 2 }),
+                (11.0, DemographicEvent::Appears { deme: 3 }),
+            ]
+        );
+
+        // Demes outside of a subset have no events
+        let demes_graph = graphs_for_testing::four_deme_model();
+        let mut graph = ForwardGraph::new_discrete_time(demes_graph, 10)
+            .unwrap()
+            .with_deme_subset(["A", "B", "D"])
+            .unwrap();
+        let events = deme_events(&mut graph);
+        assert_eq!(
+            events,
+            [
+                (10.0, DemographicEvent::Extinct { deme: 0 }),
+                (10.0, DemographicEvent::Appears { deme: 1 }),
+                (11.0, DemographicEvent::Appears { deme: 3 }),
+            ]
+        );
+    }
+
+    fn epochs_and_pulses_model() -> demes::Graph {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 10
+    - start_size: 200
+      end_time: 5
+    - start_size: 300
+ - name: B
+   epochs:
+    - start_size: 100
+pulses:
+ - sources: [A]
+   dest: B
+   time: 7
+   proportions: [0.5]
+";
+        demes::loads(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_epoch_changes_and_pulses() {
+        let mut graph = ForwardGraph::new_discrete_time(epochs_and_pulses_model(), 10).unwrap();
+        assert_eq!(graph.events().count(), 0);
+        let events = deme_events(&mut graph);
+        assert_eq!(
+            events,
+            [
+                (
+                    10.0,
+                    DemographicEvent::EpochChange {
+                        deme: 0,
+                        parental_epoch: 0,
+                        offspring_epoch: 1
+                    }
+                ),
+                (
+                    15.0,
+                    DemographicEvent::EpochChange {
+                        deme: 0,
+                        parental_epoch: 1,
+                        offspring_epoch: 2
+                    }
+                ),
+            ]
+        );
+
+        let mut graph = ForwardGraph::new_discrete_time(epochs_and_pulses_model(), 10).unwrap();
+        let mut num_pulses = 0;
+        for time in graph.time_iterator().collect::<Vec<_>>() {
+            graph.update_state(time).unwrap();
+            for event in graph.events() {
+                if let DemographicEvent::Pulse(pulse) = event {
+                    assert_eq!(pulse.time(), 7.0);
+                    assert_eq!(graph.pulses_at_current_time().next(), Some(pulse));
+                    num_pulses += 1;
+                }
+            }
+        }
+        assert_eq!(num_pulses, 1);
+    }
+
+    #[test]
+    fn test_epoch_changes_with_time_step() {
+        let mut graph =
+            ForwardGraph::new_discrete_time_with_time_step(epochs_and_pulses_model(), 9, 8.0)
+                .unwrap();
+        let events = deme_events(&mut graph);
+        // Both changes happen in the step starting at time 8
+        assert_eq!(
+            events,
+            [(
+                8.0,
+                DemographicEvent::EpochChange {
+                    deme: 0,
+                    parental_epoch: 0,
+                    offspring_epoch: 2
+                }
+            )]
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_cumulative_ancestry {
+    use super::*;
+
+    fn model() -> demes::Graph {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+      end_time: 20
+ - name: C
+   ancestors: [A, B]
+   proportions: [0.25, 0.75]
+   start_time: 40
+   epochs:
+    - start_size: 100
+ - name: D
+   ancestors: [C]
+   start_time: 30
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: C
+   rate: 0.01
+   start_time: 35
+   end_time: 10
+pulses:
+ - sources: [B]
+   dest: D
+   time: 25
+   proportions: [0.5]
+";
+        demes::loads(yaml).unwrap()
+    }
+
+    // The cumulative ancestry of the offspring at each time,
+    // found by multiplying the ancestry proportions
+    // of each generation.
+    fn naive(graph: &ForwardGraph) -> Vec<(ForwardTime, Vec<Vec<f64>>)> {
+        let mut graph = graph.clone();
+        let n = graph.num_demes_in_model();
+        let mut parents = (0..n)
+            .map(|i| {
+                let mut row = vec![0.0; n];
+                if graph.demes_graph().demes()[i].ancestor_names().is_empty() {
+                    row[i] = 1.0;
+                }
+                row
+            })
+            .collect::<Vec<_>>();
+        let mut rv = vec![];
+        for time in graph.time_iterator().collect::<Vec<_>>() {
+            graph.update_state(time).unwrap();
+            if graph.offspring_deme_sizes().is_none() {
+                break;
+            }
+            let offspring = (0..n)
+                .map(|i| {
+                    let proportions = graph.ancestry_proportions(i).unwrap();
+                    (0..n)
+                        .map(|j| (0..n).map(|k| proportions[k] * parents[k][j]).sum())
+                        .collect::<Vec<f64>>()
+                })
+                .collect::<Vec<_>>();
+            rv.push((time, offspring.clone()));
+            parents = offspring;
+        }
+        rv
+    }
+
+    fn assert_close(graph: &ForwardGraph, expected: &[Vec<f64>]) {
+        for (deme, row) in expected.iter().enumerate() {
+            let values = graph.cumulative_ancestry_proportions(deme).unwrap();
+            for (a, b) in values.iter().zip(row.iter()) {
+                assert!((a - b).abs() < 1e-12, "{deme} {values:?} {row:?}");
+            }
+            let sum: f64 = values.iter().sum();
+            if graph.offspring_deme_sizes().unwrap()[deme] > 0.0 {
+                assert!((sum - 1.0).abs() < 1e-12);
+                // Only the root demes contribute
+                assert_eq!(values[2], 0.0);
+                assert_eq!(values[3], 0.0);
+            } else {
+                assert_eq!(sum, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_not_tracked() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        graph.update_state(0).unwrap();
+        assert!(graph.cumulative_ancestry_proportions(0).is_none());
+    }
+
+    #[test]
+    fn test_forwards() {
+        for time_step in [1.0, 3.0] {
+            let graph = ForwardGraph::new_discrete_time_with_time_step(model(), 10, time_step)
+                .unwrap()
+                .with_cumulative_ancestry()
+                .unwrap();
+            let expected = naive(&graph);
+            let mut graph = graph;
+            assert!(graph.cumulative_ancestry_proportions(0).is_none());
+            for (time, expected) in expected {
+                graph.update_state(time).unwrap();
+                assert_close(&graph, &expected);
+                // Updating to the same time changes nothing
+                graph.update_state(time).unwrap();
+                assert_close(&graph, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_backwards_and_jumps() {
+        let graph = ForwardGraph::new_discrete_time(model(), 10)
+            .unwrap()
+            .with_cumulative_ancestry()
+            .unwrap();
+        let expected = naive(&graph);
+        let mut graph = graph;
+        for (time, expected) in expected.iter().rev() {
+            graph.update_state(*time).unwrap();
+            assert_close(&graph, expected);
+        }
+        for index in [30, 5, 45, 46, 0, 20] {
+            graph.update_state(expected[index].0).unwrap();
+            assert_close(&graph, &expected[index].1);
+        }
+    }
+
+    #[test]
+    fn test_enable_after_update() {
+        let graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        let expected = naive(&graph);
+        let mut graph = graph;
+        graph.update_state(expected[40].0).unwrap();
+        let graph = graph.with_cumulative_ancestry().unwrap();
+        assert_close(&graph, &expected[40].1);
+
+        let graph = graph.with_deme_subset(["A", "C", "D"]).unwrap();
+        for deme in [2, 3] {
+            let values = graph.cumulative_ancestry_proportions(deme).unwrap();
+            assert_eq!(values, [1.0, 0.0, 0.0, 0.0]);
+        }
+    }
+}
@@ -6,16 +6,11 @@ use crate::ForwardGraph;
 pub struct DemeSizeHistory {
     graph: ForwardGraph,
     deme_index: usize,
-    forward_model_start_time: f64,
     time_iterator: Box<dyn Iterator<Item = ForwardTime>>,
 }
 
 impl DemeSizeHistory {
-    pub fn new(
-        graph: ForwardGraph,
-        deme_index: usize,
-        forward_model_start_time: f64,
-    ) -> Result<Self, DemesForwardError> {
+    pub fn new(graph: ForwardGraph, deme_index: usize) -> Result<Self, DemesForwardError> {
         let mut graph = graph;
         // NOTE: we need to maually update
         // the internal state to the first generation
@@ -26,7 +21,6 @@ impl DemeSizeHistory {
         Ok(Self {
             graph,
             deme_index,
-            forward_model_start_time,
             time_iterator,
         })
     }
@@ -40,10 +34,9 @@ impl Iterator for DemeSizeHistory {
             Some(forward_time) => {
                 self.graph.update_state(forward_time).unwrap();
                 let size = self.graph.parental_deme_sizes().unwrap()[self.deme_index];
-                let time = self.forward_model_start_time;
-                self.forward_model_start_time -= 1.0;
+                let time = self.graph.time_to_backward(forward_time).unwrap().unwrap();
                 let item = DemeSizeAt {
-                    time: time.try_into().unwrap(),
+                    time,
                     forward_time,
                     size,
                 };
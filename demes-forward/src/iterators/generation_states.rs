@@ -0,0 +1,144 @@
+use crate::demography_state::OffspringDemography;
+use crate::square_matrix::SquareMatrix;
+use crate::time::ForwardTime;
+use crate::CurrentSize;
+use crate::DemographyState;
+use crate::ForwardGraph;
+
+/// The state of a [`ForwardGraph`] at one parental generation.
+///
+/// The state is a copy of the values of the graph.
+/// It is `Send` and `Sync`, so it may be shared with other
+/// threads while the graph is updated.
+///
+/// See [`ForwardGraph::iter_states`] and [`ForwardGraph::snapshot`].
+#[derive(Debug, Clone)]
+pub struct GenerationState {
+    time: ForwardTime,
+    backwards_time: demes::Time,
+    parental_deme_sizes: Vec<CurrentSize>,
+    offspring: Option<OffspringState>,
+}
+
+#[derive(Debug, Clone)]
+struct OffspringState {
+    deme_sizes: Vec<CurrentSize>,
+    ancestry_proportions: SquareMatrix,
+    selfing_rates: Vec<demes::SelfingRate>,
+    cloning_rates: Vec<demes::CloningRate>,
+}
+
+impl GenerationState {
+    pub(crate) fn new(graph: &ForwardGraph, time: ForwardTime) -> Self {
+        // NOTE: the unwraps are fine because the time
+        // comes from the graph's own time iterator.
+        let backwards_time = graph.time_to_backward(time).unwrap().unwrap();
+        let parental_deme_sizes = graph.parental_deme_sizes().unwrap().to_vec();
+        let offspring = graph.offspring_deme_sizes().map(|sizes| OffspringState {
+            deme_sizes: sizes.to_vec(),
+            ancestry_proportions: graph.ancestry_proportions_matrix().clone(),
+            selfing_rates: graph.selfing_rates().unwrap().to_vec(),
+            cloning_rates: graph.cloning_rates().unwrap().to_vec(),
+        });
+        Self {
+            time,
+            backwards_time,
+            parental_deme_sizes,
+            offspring,
+        }
+    }
+
+    /// The parental generation time.
+    pub fn time(&self) -> ForwardTime {
+        self.time
+    }
+
+    /// The parental generation time, measured backwards in time.
+    pub fn backwards_time(&self) -> demes::Time {
+        self.backwards_time
+    }
+
+    /// The sizes of each parental deme.
+    ///
+    /// See [`ForwardGraph::parental_deme_sizes`].
+    pub fn parental_deme_sizes(&self) -> &[CurrentSize] {
+        &self.parental_deme_sizes
+    }
+
+    /// The sizes of each offspring deme.
+    ///
+    /// Returns `None` if there are no offspring demes,
+    /// which is the case for the last parental generation.
+    ///
+    /// See [`ForwardGraph::offspring_deme_sizes`].
+    pub fn offspring_deme_sizes(&self) -> Option<&[CurrentSize]> {
+        self.offspring.as_ref().map(|o| o.deme_sizes.as_slice())
+    }
+
+    /// The ancestry proportions of an offspring deme.
+    ///
+    /// See [`ForwardGraph::ancestry_proportions`].
+    pub fn ancestry_proportions(&self, offspring_deme: usize) -> Option<&[f64]> {
+        match &self.offspring {
+            Some(o) if offspring_deme < o.ancestry_proportions.nrows() => {
+                Some(o.ancestry_proportions.row(offspring_deme))
+            }
+            _ => None,
+        }
+    }
+
+    /// The selfing rates of each offspring deme.
+    ///
+    /// See [`ForwardGraph::selfing_rates`].
+    pub fn selfing_rates(&self) -> Option<&[demes::SelfingRate]> {
+        self.offspring.as_ref().map(|o| o.selfing_rates.as_slice())
+    }
+
+    /// The cloning rates of each offspring deme.
+    ///
+    /// See [`ForwardGraph::cloning_rates`].
+    pub fn cloning_rates(&self) -> Option<&[demes::CloningRate]> {
+        self.offspring.as_ref().map(|o| o.cloning_rates.as_slice())
+    }
+
+    /// View the state as a [`DemographyState`].
+    pub fn as_demography_state(&self) -> DemographyState<'_> {
+        let offspring = self.offspring.as_ref().map(|o| OffspringDemography {
+            deme_sizes: &o.deme_sizes,
+            ancestry_proportions: &o.ancestry_proportions,
+            selfing_rates: &o.selfing_rates,
+            cloning_rates: &o.cloning_rates,
+        });
+        // NOTE: the unwrap is fine because the arrays
+        // were copied from a ForwardGraph.
+        DemographyState::new(self.time, &self.parental_deme_sizes, offspring).unwrap()
+    }
+}
+
+pub struct GenerationStates {
+    graph: ForwardGraph,
+    time_iterator: Box<dyn Iterator<Item = ForwardTime>>,
+}
+
+impl GenerationStates {
+    pub fn new(graph: ForwardGraph) -> Self {
+        let mut graph = graph;
+        // NOTE: see DemeSizeHistory::new
+        graph.update_state(0.0).unwrap();
+        let time_iterator = Box::new(graph.time_iterator());
+        Self {
+            graph,
+            time_iterator,
+        }
+    }
+}
+
+impl Iterator for GenerationStates {
+    type Item = GenerationState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let time = self.time_iterator.next()?;
+        self.graph.update_state(time).unwrap();
+        Some(GenerationState::new(&self.graph, time))
+    }
+}
@@ -1,3 +1,6 @@
 mod deme_size_history;
+mod generation_states;
 
 pub use deme_size_history::DemeSizeHistory;
+pub use generation_states::GenerationState;
+pub use generation_states::GenerationStates;
@@ -19,25 +19,64 @@
 //! ";
 //! assert!(demes::loads(yaml).is_ok());
 //! ```
+//!
+//! ## Optional features
+//!
+//! * `serde`: implements `Serialize` and `Deserialize` for
+//!   [`ForwardGraph`] to checkpoint simulations.
+//! * `rand`: adds functions to [`ForwardGraph`] that sample
+//!   deme sizes and parental demes.
+//!   This crate re-exports `rand`.
+//! * `arrow`: adds functions to [`TrajectoryTables`] that export
+//!   deme sizes and ancestry proportions as Arrow record batches.
+//!   This crate re-exports `arrow_array` and `arrow_schema`.
+//! * `parquet`: enables `arrow` and adds functions to
+//!   [`TrajectoryTables`] that write Parquet files.
+//!   This crate re-exports `parquet`.
 
 #![warn(missing_docs)]
 #![warn(rustdoc::broken_intra_doc_links)]
 
+#[cfg(feature = "arrow")]
+mod arrow_export;
+#[cfg(feature = "serde")]
+mod checkpoint;
+mod continuous_graph;
 mod current_size;
+mod demography_state;
 mod error;
+mod event;
 mod graph;
 mod iterators;
+#[cfg(feature = "rand")]
+mod sampling;
 mod square_matrix;
 mod time;
+mod trajectory_tables;
 
 #[cfg(test)]
 mod test_functions;
 
+#[cfg(feature = "arrow")]
+pub use arrow_array;
+#[cfg(feature = "arrow")]
+pub use arrow_schema;
+pub use continuous_graph::ContinuousGraph;
 pub use current_size::CurrentSize;
 pub use demes;
+pub use demography_state::DemographyState;
 pub use error::DemesForwardError;
+pub use event::DemographicEvent;
 pub use graph::ForwardGraph;
+pub use iterators::GenerationState;
+#[cfg(feature = "parquet")]
+pub use parquet;
+#[cfg(feature = "rand")]
+pub use rand;
+pub use square_matrix::SquareMatrix;
 pub use time::ForwardTime;
+pub use time::TimeRounding;
+pub use trajectory_tables::TrajectoryTables;
 
 /// The size of a deme at a given time.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
@@ -0,0 +1,258 @@
+// Random sampling of deme sizes and parental demes.
+//
+// These helpers give individual-based simulations a common
+// way to add demographic noise around the deterministic
+// values of a ForwardGraph.
+
+use rand::Rng;
+use rand_distr::Binomial;
+use rand_distr::Distribution;
+use rand_distr::Poisson;
+
+use crate::ForwardGraph;
+
+impl ForwardGraph {
+    /// Sample the sizes of the offspring demes.
+    ///
+    /// Each size is a Poisson random variable whose mean is the
+    /// corresponding value of [`ForwardGraph::offspring_deme_sizes`].
+    /// Demes with size zero always have sampled size zero.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// # Returns
+    ///
+    /// * `None` if there are no offspring demes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes_forward::rand::SeedableRng;
+    ///
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+    /// graph.update_state(0).unwrap();
+    /// let mut rng = demes_forward::rand::rngs::StdRng::seed_from_u64(42);
+    /// let sizes = graph.sample_offspring_deme_sizes(&mut rng).unwrap();
+    /// assert_eq!(sizes.len(), 1);
+    /// ```
+    pub fn sample_offspring_deme_sizes<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<u64>> {
+        let sizes = self.offspring_deme_sizes()?;
+        Some(
+            sizes
+                .iter()
+                .map(|&size| {
+                    let mean = f64::from(size);
+                    if mean > 0.0 {
+                        // NOTE: the unwrap is fine because a CurrentSize
+                        // is finite.
+                        Poisson::new(mean).unwrap().sample(rng) as u64
+                    } else {
+                        0
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Sample the parental demes of the offspring of a deme.
+    ///
+    /// The counts are a multinomial random variable with
+    /// probabilities given by [`ForwardGraph::ancestry_proportions`].
+    /// Element `j` of the output is the number of offspring whose
+    /// parents are from deme `j`.
+    /// Thus, element `offspring_deme` is the number of non-migrants
+    /// and the other elements are the numbers of migrants.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// # Parameters
+    ///
+    /// * `offspring_deme`: the index of an offspring deme.
+    /// * `num_offspring`: the number of offspring.
+    ///
+    /// # Returns
+    ///
+    /// * `None` if `offspring_deme` is not a valid index,
+    ///   if there are no offspring demes, or if
+    ///   `offspring_deme` is not extant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes_forward::rand::SeedableRng;
+    ///
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - demes: [A, B]
+    ///    rate: 0.1
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+    /// graph.update_state(0).unwrap();
+    /// let mut rng = demes_forward::rand::rngs::StdRng::seed_from_u64(42);
+    /// let counts = graph.sample_parental_demes(1, 100, &mut rng).unwrap();
+    /// assert_eq!(counts.iter().sum::<u64>(), 100);
+    /// ```
+    pub fn sample_parental_demes<R: Rng + ?Sized>(
+        &self,
+        offspring_deme: usize,
+        num_offspring: u64,
+        rng: &mut R,
+    ) -> Option<Vec<u64>> {
+        let proportions = self.ancestry_proportions(offspring_deme)?;
+        let mut remaining_mass: f64 = proportions.iter().sum();
+        if remaining_mass <= 0.0 {
+            return None;
+        }
+        let mut remaining = num_offspring;
+        let mut counts = vec![0; proportions.len()];
+        // The multinomial is sampled as a sequence of binomials.
+        for (count, &proportion) in counts.iter_mut().zip(proportions.iter()) {
+            if remaining == 0 || remaining_mass <= 0.0 {
+                break;
+            }
+            if proportion > 0.0 {
+                let p = (proportion / remaining_mass).min(1.0);
+                // NOTE: the unwrap is fine because p is in (0, 1]
+                *count = Binomial::new(remaining, p).unwrap().sample(rng);
+                remaining -= *count;
+                remaining_mass -= proportion;
+            }
+        }
+        // Rounding error may leave a few offspring unassigned,
+        // which go to the last deme with nonzero ancestry.
+        if remaining > 0 {
+            if let Some(index) = proportions.iter().rposition(|&p| p > 0.0) {
+                counts[index] += remaining;
+            }
+        }
+        Some(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn model() -> demes::Graph {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 1000
+ - name: B
+   epochs:
+    - start_size: 200
+      end_time: 10
+ - name: C
+   epochs:
+    - start_size: 500
+migrations:
+ - source: A
+   dest: C
+   rate: 0.2
+ - source: B
+   dest: C
+   rate: 0.1
+   start_time: 30
+";
+        demes::loads(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_sample_offspring_deme_sizes() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        let mut rng = StdRng::seed_from_u64(101);
+        assert!(graph.sample_offspring_deme_sizes(&mut rng).is_none());
+        graph.update_state(0).unwrap();
+        let num_samples = 1000;
+        let mut sums = [0.0; 3];
+        for _ in 0..num_samples {
+            let sizes = graph.sample_offspring_deme_sizes(&mut rng).unwrap();
+            sums.iter_mut()
+                .zip(sizes.iter())
+                .for_each(|(sum, &size)| *sum += size as f64);
+        }
+        for (sum, expected) in sums.iter().zip([1000.0, 200.0, 500.0]) {
+            let mean = sum / num_samples as f64;
+            // The standard error of the mean is sqrt(expected / num_samples)
+            assert!((mean - expected).abs() < 5.0 * (expected / num_samples as f64).sqrt());
+        }
+    }
+
+    #[test]
+    fn test_sample_parental_demes() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        let mut rng = StdRng::seed_from_u64(202);
+        assert!(graph.sample_parental_demes(0, 10, &mut rng).is_none());
+        graph.update_state(0).unwrap();
+        assert!(graph.sample_parental_demes(3, 10, &mut rng).is_none());
+        assert_eq!(
+            graph.sample_parental_demes(0, 10, &mut rng),
+            Some(vec![10, 0, 0])
+        );
+        assert_eq!(
+            graph.sample_parental_demes(2, 0, &mut rng),
+            Some(vec![0, 0, 0])
+        );
+
+        let num_offspring = 10000;
+        let counts = graph
+            .sample_parental_demes(2, num_offspring, &mut rng)
+            .unwrap();
+        assert_eq!(counts.iter().sum::<u64>(), num_offspring);
+        let proportions = graph.ancestry_proportions(2).unwrap();
+        for (&count, &p) in counts.iter().zip(proportions.iter()) {
+            let expected = p * num_offspring as f64;
+            let sd = (num_offspring as f64 * p * (1.0 - p)).sqrt();
+            assert!(
+                (count as f64 - expected).abs() <= 5.0 * sd,
+                "{count} {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sample_parental_demes_of_extinct_deme() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        let mut rng = StdRng::seed_from_u64(303);
+        let last = graph.time_iterator().last().unwrap();
+        graph.update_state(last.value() - 1.0).unwrap();
+        assert_eq!(graph.offspring_deme_sizes().unwrap()[1], 0.0);
+        assert!(graph.sample_parental_demes(1, 10, &mut rng).is_none());
+        assert_eq!(graph.sample_offspring_deme_sizes(&mut rng).unwrap()[1], 0);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        graph.update_state(5).unwrap();
+        let sample = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (
+                graph.sample_offspring_deme_sizes(&mut rng).unwrap(),
+                graph.sample_parental_demes(2, 100, &mut rng),
+            )
+        };
+        assert_eq!(sample(1), sample(1));
+    }
+}
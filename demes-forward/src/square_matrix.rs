@@ -1,3 +1,6 @@
+/// A square matrix stored in row-major order.
+///
+/// Rows and columns are indexed by deme.
 #[derive(Debug, Clone)]
 pub struct SquareMatrix {
     data: Vec<f64>,
@@ -5,14 +8,14 @@ pub struct SquareMatrix {
 }
 
 impl SquareMatrix {
-    pub fn zeros(nrows: usize) -> Self {
+    pub(crate) fn zeros(nrows: usize) -> Self {
         Self {
             data: vec![0.0; nrows * nrows],
             nrows,
         }
     }
 
-    pub fn fill(&mut self, value: f64) {
+    pub(crate) fn fill(&mut self, value: f64) {
         self.data.fill(value)
     }
 
@@ -20,23 +23,67 @@ impl SquareMatrix {
         &mut self.data[row * self.nrows + column]
     }
 
-    pub fn set(&mut self, row: usize, column: usize, value: f64) {
+    pub(crate) fn set(&mut self, row: usize, column: usize, value: f64) {
         *self.get_element_mut(row, column) = value;
     }
 
+    /// Get the value at a given row and column.
+    ///
+    /// Returns `None` if either index is out of range.
+    pub fn get(&self, row: usize, column: usize) -> Option<f64> {
+        if row < self.nrows && column < self.nrows {
+            Some(self.data[row * self.nrows + column])
+        } else {
+            None
+        }
+    }
+
+    /// Get a row of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// If `row` is out of range.
     pub fn row(&self, row: usize) -> &[f64] {
         let start = row * self.nrows;
         let end = start + self.nrows;
         &self.data[start..end]
     }
 
-    pub fn row_mut(&mut self, row: usize) -> &mut [f64] {
+    pub(crate) fn row_mut(&mut self, row: usize) -> &mut [f64] {
         let start = row * self.nrows;
         let end = start + self.nrows;
         &mut self.data[start..end]
     }
 
+    pub(crate) fn copy_from(&mut self, other: &SquareMatrix) {
+        self.data.copy_from_slice(&other.data);
+    }
+
+    // Replace self with lhs * self,
+    // using scratch to avoid allocating.
+    pub(crate) fn left_multiply(&mut self, lhs: &SquareMatrix, scratch: &mut SquareMatrix) {
+        let n = self.nrows;
+        scratch.fill(0.0);
+        for i in 0..n {
+            for k in 0..n {
+                let a = lhs.data[i * n + k];
+                if a != 0.0 {
+                    for j in 0..n {
+                        scratch.data[i * n + j] += a * self.data[k * n + j];
+                    }
+                }
+            }
+        }
+        std::mem::swap(&mut self.data, &mut scratch.data);
+    }
+
+    /// The number of rows (and columns).
     pub fn nrows(&self) -> usize {
         self.nrows
     }
+
+    /// The matrix as a slice in row-major order.
+    pub fn as_slice(&self) -> &[f64] {
+        &self.data
+    }
 }
@@ -35,7 +35,7 @@ pub fn ancestry_proportions_from_graph(
             .iter()
             .zip(deme.proportions().iter())
         {
-            rv[*a] = f64::from(*p);
+            rv[usize::from(*a)] = f64::from(*p);
         }
     } else {
         rv[child_deme] = 1.0;
@@ -74,7 +74,7 @@ pub fn ancestry_proportions_from_graph(
                     .demes_graph()
                     .demes()
                     .iter()
-                    .position(|d| d.name() == s)
+                    .position(|d| d.name() == &**s)
                     .unwrap();
                 sources.push(source);
                 source_proportions.push(f64::from(*d));
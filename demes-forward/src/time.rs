@@ -52,6 +52,49 @@ impl Add for ForwardTime {
     }
 }
 
+/// How times are rounded when converting a model to integer generations.
+///
+/// See [`ForwardGraph::new_discrete_time_with_rounding`](crate::ForwardGraph::new_discrete_time_with_rounding).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimeRounding {
+    /// Round to the nearest generation.
+    ///
+    /// This is the policy of [`demes::Graph::into_integer_generations`].
+    #[default]
+    Nearest,
+    /// Round down to the previous generation.
+    Down,
+    /// Round up to the next generation.
+    Up,
+    /// Round to the nearest generation, merging epochs
+    /// that would otherwise have length zero and removing
+    /// migrations that would otherwise have length zero.
+    ///
+    /// This is the policy of [`demes::Graph::into_integer_generations_merged`].
+    NearestMergingZeroLength,
+}
+
+impl TimeRounding {
+    pub(crate) fn apply(self, graph: demes::Graph) -> Result<demes::Graph, demes::DemesError> {
+        let with = |round: fn(f64) -> f64| {
+            move |time: demes::Time, generation_time: demes::GenerationTime| {
+                // NOTE: the unwrap is fine because only
+                // finite, non-negative times are converted.
+                demes::Time::try_from(round(f64::from(time) / f64::from(generation_time))).unwrap()
+            }
+        };
+        match self {
+            Self::Nearest => graph.into_integer_generations(),
+            Self::Down => graph.into_generations_with(with(f64::floor)),
+            Self::Up => graph.into_generations_with(with(f64::ceil)),
+            Self::NearestMergingZeroLength => graph
+                .into_integer_generations_merged()
+                .map(|(graph, _)| graph),
+        }
+    }
+}
+
 pub(crate) struct TimeIterator {
     current_time: ForwardTime,
     final_time: ForwardTime,
@@ -70,6 +113,24 @@ impl Iterator for TimeIterator {
     }
 }
 
+pub(crate) struct BackwardTimeIterator {
+    current_time: ForwardTime,
+}
+
+impl Iterator for BackwardTimeIterator {
+    type Item = ForwardTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current_time.0 >= 0.0 {
+            let time = self.current_time;
+            self.current_time = self.current_time - 1.0.into();
+            Some(time)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ModelTime {
     backwards_burn_in_time: demes::Time,
@@ -185,6 +246,27 @@ impl ModelTime {
         self.model_duration
     }
 
+    // The forward times of the offspring generations
+    // during which deme exists.
+    // The first offspring generation is at time 1.
+    pub(crate) fn offspring_lifetime(&self, deme: &demes::Deme) -> std::ops::Range<ForwardTime> {
+        // The forward time of the generation at backwards time zero,
+        // inverting convert.
+        let origin =
+            self.burnin_generation + self.model_duration - 1.0 + self.minimum_epoch_end_time;
+        let final_time = self.burnin_generation + self.model_duration;
+        let start = f64::from(deme.start_time());
+        let first = if start.is_finite() {
+            (origin - start + 1.0).max(1.0)
+        } else {
+            1.0
+        };
+        let end = (origin - f64::from(deme.end_time()) + 1.0)
+            .min(final_time)
+            .max(first);
+        first.into()..end.into()
+    }
+
     pub(crate) fn time_iterator(&self, start: Option<ForwardTime>) -> TimeIterator {
         let current_time = match start {
             Some(value) => (value.0 - 1.0).into(),
@@ -195,6 +277,12 @@ impl ModelTime {
             final_time: (self.burnin_generation() + self.model_duration()).into(),
         }
     }
+
+    pub(crate) fn backward_time_iterator(&self) -> BackwardTimeIterator {
+        BackwardTimeIterator {
+            current_time: (self.burnin_generation() + self.model_duration() - 1.0).into(),
+        }
+    }
 }
 
 pub enum BackwardTimeWrapper {
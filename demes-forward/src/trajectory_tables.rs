@@ -0,0 +1,403 @@
+// Dense tables of the values of a ForwardGraph
+// at every generation of the model.
+
+use crate::CurrentSize;
+use crate::DemesForwardError;
+use crate::ForwardGraph;
+use crate::ForwardTime;
+
+/// The demography of a [`ForwardGraph`] at every generation
+/// of the model.
+///
+/// Rows are indexed by the parental generation time,
+/// starting from zero, and columns by deme.
+/// The offspring values are not defined for the last
+/// parental generation.
+///
+/// The tables are stored in row-major order.
+/// The functions ending in `_table` give access to
+/// all rows at once.
+///
+/// See [`ForwardGraph::precompute`].
+#[derive(Debug, Clone)]
+pub struct TrajectoryTables {
+    deme_names: Vec<String>,
+    num_demes: usize,
+    num_generations: usize,
+    parental_deme_sizes: Vec<CurrentSize>,
+    offspring_deme_sizes: Vec<CurrentSize>,
+    ancestry_proportions: Vec<f64>,
+    selfing_rates: Vec<demes::SelfingRate>,
+    cloning_rates: Vec<demes::CloningRate>,
+}
+
+impl TrajectoryTables {
+    fn row_index<F: Into<ForwardTime>>(&self, time: F, num_rows: usize) -> Option<usize> {
+        let time = time.into();
+        if time.valid() && time.value().fract() == 0.0 && time.value() < num_rows as f64 {
+            Some(time.value() as usize)
+        } else {
+            None
+        }
+    }
+
+    fn num_offspring_generations(&self) -> usize {
+        self.num_generations - 1
+    }
+
+    fn row<'a, T, F: Into<ForwardTime>>(
+        &self,
+        table: &'a [T],
+        time: F,
+        num_rows: usize,
+    ) -> Option<&'a [T]> {
+        let row = self.row_index(time, num_rows)?;
+        let start = row * self.num_demes;
+        Some(&table[start..start + self.num_demes])
+    }
+
+    /// The names of the demes, which label the columns.
+    pub fn deme_names(&self) -> &[String] {
+        &self.deme_names
+    }
+
+    /// The number of demes in the model.
+    pub fn num_demes(&self) -> usize {
+        self.num_demes
+    }
+
+    /// The number of parental generations.
+    ///
+    /// This equals [`ForwardGraph::end_time`].
+    pub fn num_generations(&self) -> usize {
+        self.num_generations
+    }
+
+    /// The sizes of each parental deme at a given time.
+    ///
+    /// Returns `None` if `time` is not a parental generation
+    /// of the model.
+    pub fn parental_deme_sizes<F: Into<ForwardTime>>(&self, time: F) -> Option<&[CurrentSize]> {
+        self.row(&self.parental_deme_sizes, time, self.num_generations)
+    }
+
+    /// The sizes of each offspring deme of the parents at a given time.
+    ///
+    /// Returns `None` if `time` is not a parental generation
+    /// of the model or is the last parental generation.
+    pub fn offspring_deme_sizes<F: Into<ForwardTime>>(&self, time: F) -> Option<&[CurrentSize]> {
+        self.row(
+            &self.offspring_deme_sizes,
+            time,
+            self.num_offspring_generations(),
+        )
+    }
+
+    /// The ancestry proportions of an offspring deme
+    /// of the parents at a given time.
+    ///
+    /// Returns `None` if `time` is not a parental generation
+    /// of the model, is the last parental generation, or
+    /// if `offspring_deme` is out of range.
+    pub fn ancestry_proportions<F: Into<ForwardTime>>(
+        &self,
+        time: F,
+        offspring_deme: usize,
+    ) -> Option<&[f64]> {
+        if offspring_deme >= self.num_demes {
+            return None;
+        }
+        let row = self.row_index(time, self.num_offspring_generations())?;
+        let start = (row * self.num_demes + offspring_deme) * self.num_demes;
+        Some(&self.ancestry_proportions[start..start + self.num_demes])
+    }
+
+    /// The selfing rates of each offspring deme
+    /// of the parents at a given time.
+    ///
+    /// Returns `None` under the same conditions as
+    /// [`TrajectoryTables::offspring_deme_sizes`].
+    pub fn selfing_rates<F: Into<ForwardTime>>(&self, time: F) -> Option<&[demes::SelfingRate]> {
+        self.row(&self.selfing_rates, time, self.num_offspring_generations())
+    }
+
+    /// The cloning rates of each offspring deme
+    /// of the parents at a given time.
+    ///
+    /// Returns `None` under the same conditions as
+    /// [`TrajectoryTables::offspring_deme_sizes`].
+    pub fn cloning_rates<F: Into<ForwardTime>>(&self, time: F) -> Option<&[demes::CloningRate]> {
+        self.row(&self.cloning_rates, time, self.num_offspring_generations())
+    }
+
+    /// All parental deme sizes.
+    ///
+    /// The table has [`TrajectoryTables::num_generations`] rows.
+    pub fn parental_deme_sizes_table(&self) -> &[CurrentSize] {
+        &self.parental_deme_sizes
+    }
+
+    /// All offspring deme sizes.
+    ///
+    /// The table has one row fewer than
+    /// [`TrajectoryTables::parental_deme_sizes_table`].
+    pub fn offspring_deme_sizes_table(&self) -> &[CurrentSize] {
+        &self.offspring_deme_sizes
+    }
+
+    /// All ancestry proportions.
+    ///
+    /// Each generation is a square matrix in row-major order,
+    /// with one row per offspring deme, as in
+    /// [`ForwardGraph::ancestry_proportions`].
+    pub fn ancestry_proportions_table(&self) -> &[f64] {
+        &self.ancestry_proportions
+    }
+
+    /// All selfing rates.
+    ///
+    /// The table has the same shape as
+    /// [`TrajectoryTables::offspring_deme_sizes_table`].
+    pub fn selfing_rates_table(&self) -> &[demes::SelfingRate] {
+        &self.selfing_rates
+    }
+
+    /// All cloning rates.
+    ///
+    /// The table has the same shape as
+    /// [`TrajectoryTables::offspring_deme_sizes_table`].
+    pub fn cloning_rates_table(&self) -> &[demes::CloningRate] {
+        &self.cloning_rates
+    }
+}
+
+impl ForwardGraph {
+    /// Evaluate the model at every generation.
+    ///
+    /// The values are stored in dense tables, giving
+    /// constant-time lookups by generation and deme.
+    /// The memory required is proportional to
+    /// [`ForwardGraph::end_time`] times the square of the number
+    /// of demes in the model, which may be large for long models
+    /// with many demes.
+    ///
+    /// The tables have one row per generation, even if the
+    /// graph has a time step longer than one generation.
+    /// The subset of demes set by [`ForwardGraph::with_deme_subset`]
+    /// is respected.
+    /// The state of `self` is not changed.
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`ForwardGraph::update_state`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 10
+    ///     - start_size: 200
+    /// ";
+    /// let demes_graph = demes_forward::demes::loads(yaml).unwrap();
+    /// let graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 5).unwrap();
+    /// let tables = graph.precompute().unwrap();
+    /// assert_eq!(tables.num_generations(), 16);
+    /// assert_eq!(tables.parental_deme_sizes(5).unwrap(), [100.0]);
+    /// assert_eq!(tables.offspring_deme_sizes(5).unwrap(), [200.0]);
+    /// assert_eq!(tables.parental_deme_sizes(15).unwrap(), [200.0]);
+    /// assert!(tables.offspring_deme_sizes(15).is_none());
+    /// assert!(tables.parental_deme_sizes(16).is_none());
+    /// ```
+    pub fn precompute(&self) -> Result<TrajectoryTables, DemesForwardError> {
+        let mut graph = self.clone_with_single_generation_steps();
+        let num_demes = graph.num_demes_in_model();
+        let num_generations = graph.end_time().value() as usize;
+        let num_offspring_generations = num_generations - 1;
+        let mut tables = TrajectoryTables {
+            deme_names: graph
+                .deme_names()
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            num_demes,
+            num_generations,
+            parental_deme_sizes: Vec::with_capacity(num_generations * num_demes),
+            offspring_deme_sizes: Vec::with_capacity(num_offspring_generations * num_demes),
+            ancestry_proportions: Vec::with_capacity(
+                num_offspring_generations * num_demes * num_demes,
+            ),
+            selfing_rates: Vec::with_capacity(num_offspring_generations * num_demes),
+            cloning_rates: Vec::with_capacity(num_offspring_generations * num_demes),
+        };
+        for generation in 0..num_generations {
+            let state = graph.update_state(generation as f64)?.ok_or_else(|| {
+                DemesForwardError::InternalError(format!(
+                    "no parental demes at generation {generation}"
+                ))
+            })?;
+            tables
+                .parental_deme_sizes
+                .extend_from_slice(state.parental_deme_sizes());
+            match (
+                state.offspring_deme_sizes(),
+                state.ancestry_proportions_matrix(),
+                state.selfing_rates(),
+                state.cloning_rates(),
+            ) {
+                (
+                    Some(sizes),
+                    Some(ancestry_proportions),
+                    Some(selfing_rates),
+                    Some(cloning_rates),
+                ) => {
+                    tables.offspring_deme_sizes.extend_from_slice(sizes);
+                    tables
+                        .ancestry_proportions
+                        .extend_from_slice(ancestry_proportions.as_slice());
+                    tables.selfing_rates.extend_from_slice(selfing_rates);
+                    tables.cloning_rates.extend_from_slice(cloning_rates);
+                }
+                _ => {
+                    if generation != num_offspring_generations {
+                        return Err(DemesForwardError::InternalError(format!(
+                            "no offspring demes at generation {generation}"
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> demes::Graph {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 50
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      end_size: 200
+ - name: C
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      selfing_rate: 0.5
+migrations:
+ - demes: [B, C]
+   rate: 1e-3
+pulses:
+ - sources: [B]
+   dest: C
+   time: 20
+   proportions: [0.5]
+";
+        demes::loads(yaml).unwrap()
+    }
+
+    fn assert_tables_match(tables: &TrajectoryTables, graph: &ForwardGraph) {
+        let mut graph = graph.clone();
+        assert_eq!(tables.num_demes(), graph.num_demes_in_model());
+        assert_eq!(tables.deme_names(), ["A", "B", "C"]);
+        assert_eq!(tables.num_generations() as f64, graph.end_time().value());
+        for time in graph.time_iterator().collect::<Vec<_>>() {
+            graph.update_state(time).unwrap();
+            assert_eq!(
+                tables.parental_deme_sizes(time),
+                graph.parental_deme_sizes()
+            );
+            assert_eq!(
+                tables.offspring_deme_sizes(time),
+                graph.offspring_deme_sizes()
+            );
+            assert_eq!(tables.selfing_rates(time), graph.selfing_rates());
+            assert_eq!(tables.cloning_rates(time), graph.cloning_rates());
+            for deme in 0..=graph.num_demes_in_model() {
+                assert_eq!(
+                    tables.ancestry_proportions(time, deme),
+                    graph.ancestry_proportions(deme)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tables() {
+        let mut graph = ForwardGraph::new_discrete_time(model(), 10).unwrap();
+        graph.update_state(30).unwrap();
+        let tables = graph.precompute().unwrap();
+        // The state is unchanged
+        assert_eq!(graph.last_time_updated(), Some(30.0.into()));
+        assert_tables_match(
+            &tables,
+            &ForwardGraph::new_discrete_time(model(), 10).unwrap(),
+        );
+
+        let num_generations = tables.num_generations();
+        let num_demes = tables.num_demes();
+        assert_eq!(
+            tables.parental_deme_sizes_table().len(),
+            num_generations * num_demes
+        );
+        assert_eq!(
+            tables.offspring_deme_sizes_table().len(),
+            (num_generations - 1) * num_demes
+        );
+        assert_eq!(
+            tables.ancestry_proportions_table().len(),
+            (num_generations - 1) * num_demes * num_demes
+        );
+        assert_eq!(
+            tables.selfing_rates_table().len(),
+            (num_generations - 1) * num_demes
+        );
+        assert_eq!(
+            tables.cloning_rates_table().len(),
+            (num_generations - 1) * num_demes
+        );
+
+        for time in [-1.0, 0.5, num_generations as f64, f64::NAN] {
+            assert!(tables.parental_deme_sizes(time).is_none());
+            assert!(tables.offspring_deme_sizes(time).is_none());
+            assert!(tables.ancestry_proportions(time, 0).is_none());
+        }
+    }
+
+    #[test]
+    fn test_tables_with_time_step() {
+        let graph = ForwardGraph::new_discrete_time_with_time_step(model(), 10, 7.0).unwrap();
+        let tables = graph.precompute().unwrap();
+        assert_tables_match(
+            &tables,
+            &ForwardGraph::new_discrete_time(model(), 10).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_tables_with_deme_subset() {
+        let graph = ForwardGraph::new_discrete_time(model(), 10)
+            .unwrap()
+            .with_deme_subset(["A", "C"])
+            .unwrap();
+        let tables = graph.precompute().unwrap();
+        assert_tables_match(&tables, &graph);
+        assert!(tables
+            .parental_deme_sizes_table()
+            .chunks(tables.num_demes())
+            .all(|sizes| sizes[1] == 0.0));
+    }
+}
@@ -141,6 +141,96 @@ fn test_four_deme_model_pub_api_only_start_after_zero() {
     );
 }
 
+fn collect_state(
+    graph: &mut demes_forward::ForwardGraph,
+    time: demes_forward::ForwardTime,
+) -> (Option<Vec<CurrentSize>>, Option<Vec<CurrentSize>>, Vec<f64>) {
+    graph.update_state(time).unwrap();
+    let ancestry_proportions = (0..graph.num_demes_in_model())
+        .filter_map(|deme| graph.ancestry_proportions(deme))
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    (
+        graph.parental_deme_sizes().map(|s| s.to_vec()),
+        graph.offspring_deme_sizes().map(|s| s.to_vec()),
+        ancestry_proportions,
+    )
+}
+
+#[test]
+fn test_backward_time_iterator() {
+    let demes_graph = four_deme_model();
+    let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+    let forward_times = graph.time_iterator().collect::<Vec<_>>();
+    let mut backward_times = graph.backward_time_iterator().collect::<Vec<_>>();
+    backward_times.reverse();
+    assert_eq!(forward_times, backward_times);
+
+    let forward = forward_times
+        .iter()
+        .map(|&time| collect_state(&mut graph, time))
+        .collect::<Vec<_>>();
+    let mut backward = graph
+        .backward_time_iterator()
+        .map(|time| collect_state(&mut graph, time))
+        .collect::<Vec<_>>();
+    backward.reverse();
+    assert_eq!(forward, backward);
+}
+
+#[test]
+fn test_deme_names_and_indexes() {
+    let demes_graph = four_deme_model();
+    let graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 100).unwrap();
+    let names = graph.deme_names();
+    assert_eq!(&*names, &["A", "B", "C", "D"]);
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(graph.deme_index(name), Some(i));
+    }
+    assert!(graph.deme_index("E").is_none());
+}
+
+#[test]
+fn test_iter_states() {
+    let demes_graph = four_deme_model();
+    let mut graph = demes_forward::ForwardGraph::new_discrete_time(demes_graph, 10).unwrap();
+    graph.update_state(20).unwrap();
+    let states = graph.iter_states().collect::<Vec<_>>();
+    // The state of the graph is unchanged
+    assert_eq!(
+        graph.last_time_updated(),
+        Some(demes_forward::ForwardTime::from(20.0))
+    );
+
+    let times = graph.backward_time_iterator().count();
+    assert_eq!(states.len(), times);
+    for state in &states {
+        let (parental_sizes, offspring_sizes, ancestry_proportions) =
+            collect_state(&mut graph, state.time());
+        assert_eq!(
+            state.backwards_time(),
+            graph.time_to_backward(state.time()).unwrap().unwrap()
+        );
+        assert_eq!(Some(state.parental_deme_sizes().to_vec()), parental_sizes);
+        assert_eq!(
+            state.offspring_deme_sizes().map(|s| s.to_vec()),
+            offspring_sizes
+        );
+        assert_eq!(
+            (0..graph.num_demes_in_model())
+                .filter_map(|deme| state.ancestry_proportions(deme))
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>(),
+            ancestry_proportions
+        );
+        assert_eq!(state.selfing_rates(), graph.selfing_rates());
+        assert_eq!(state.cloning_rates(), graph.cloning_rates());
+    }
+    assert!(states.last().unwrap().offspring_deme_sizes().is_none());
+}
+
 #[test]
 fn gutenkunst2009() {
     let yaml = "
@@ -666,3 +756,296 @@ demes:
     assert!(graph.time_to_forward(101.0).unwrap().is_none());
     assert_eq!(graph.backwards_burn_in_time(), 91.0);
 }
+
+#[test]
+fn test_burn_in() {
+    for burnin in [0.0, 1.0, 10.0] {
+        let mut graph =
+            demes_forward::ForwardGraph::new_discrete_time(four_deme_model(), burnin).unwrap();
+        assert_eq!(graph.burn_in_duration(), burnin);
+        assert_eq!(graph.burn_in_end_time(), burnin.into());
+        assert!(!graph.in_burn_in());
+        let mut num_burn_in_generations = 0;
+        for time in graph.time_iterator().collect::<Vec<_>>() {
+            graph.update_state(time).unwrap();
+            if graph.in_burn_in() {
+                num_burn_in_generations += 1;
+                // Nothing changes during the burn-in
+                assert_eq!(graph.parental_deme_sizes().unwrap()[0], 100.0);
+                assert_eq!(graph.offspring_deme_sizes().unwrap()[0], 100.0);
+            }
+        }
+        assert_eq!(num_burn_in_generations as f64, burnin);
+        if burnin > 0.0 {
+            // The last generation of burn-in
+            let last = graph.burn_in_end_time().value() - 1.0;
+            assert_eq!(
+                graph.time_to_backward(last).unwrap().unwrap(),
+                graph.backwards_burn_in_time()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_with_start_time() {
+    let graph = demes_forward::ForwardGraph::new_discrete_time(four_deme_model(), 10).unwrap();
+    let end_time = graph.end_time().value();
+    for start_time in [0.0, 10.0, 11.0, end_time - 1.0] {
+        let mut expected = graph.clone();
+        expected.update_state(start_time).unwrap();
+        let mut started = graph.clone().with_start_time(start_time).unwrap();
+        assert_eq!(started.last_time_updated(), Some(start_time.into()));
+        let times = started.time_iterator().collect::<Vec<_>>();
+        assert_eq!(times[0], start_time.into());
+        assert_eq!(times, expected.time_iterator().collect::<Vec<_>>());
+        for time in times {
+            started.update_state(time).unwrap();
+            expected.update_state(time).unwrap();
+            assert_eq!(
+                started.parental_deme_sizes(),
+                expected.parental_deme_sizes()
+            );
+            assert_eq!(
+                started.offspring_deme_sizes(),
+                expected.offspring_deme_sizes()
+            );
+        }
+    }
+    for start_time in [-1.0, 1.5, end_time, f64::INFINITY, f64::NAN] {
+        assert!(matches!(
+            graph.clone().with_start_time(start_time),
+            Err(demes_forward::DemesForwardError::TimeError(_))
+        ));
+    }
+}
+
+#[test]
+fn test_deme_lifetimes() {
+    // The most recent deme ends before time zero
+    let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 30
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      end_time: 10
+ - name: C
+   ancestors: [B]
+   start_time: 20
+   epochs:
+    - start_size: 100
+      end_time: 5
+";
+    let models = [four_deme_model(), demes::loads(yaml).unwrap()];
+    for (model, burnin) in models.into_iter().zip([10.0, 0.0]) {
+        let full = demes_forward::ForwardGraph::new_discrete_time(model, burnin).unwrap();
+        let subset = full.clone().with_deme_subset([0, 1]).unwrap();
+        for mut graph in [full, subset] {
+            let lifetimes = graph.deme_lifetimes().to_vec();
+            assert_eq!(lifetimes.len(), graph.num_demes_in_model());
+            for time in graph.time_iterator().collect::<Vec<_>>() {
+                graph.update_state(time).unwrap();
+                let offspring_time = time + 1.0.into();
+                let sizes = match graph.offspring_deme_sizes() {
+                    Some(sizes) => sizes.to_vec(),
+                    None => vec![CurrentSize::try_from(0.0).unwrap(); lifetimes.len()],
+                };
+                for (lifetime, size) in lifetimes.iter().zip(sizes) {
+                    assert_eq!(
+                        lifetime.contains(&offspring_time),
+                        size > 0.0,
+                        "{lifetime:?} {offspring_time:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_size_at_does_not_change_state() {
+    let mut graph = demes_forward::ForwardGraph::new_discrete_time(four_deme_model(), 10).unwrap();
+    let times = graph.time_iterator().collect::<Vec<_>>();
+    for &time in &times {
+        graph.update_state(time).unwrap();
+        let parental_deme_sizes = graph.parental_deme_sizes().unwrap().to_vec();
+        let offspring_deme_sizes = graph.offspring_deme_sizes().map(|sizes| sizes.to_vec());
+        // Query the sizes at all times, including
+        // times that have not been visited yet.
+        for &other in &times {
+            let backward_time = graph.time_to_backward(other).unwrap().unwrap();
+            for deme in 0..graph.num_demes_in_model() {
+                let _ = graph.size_at(deme, backward_time).unwrap();
+            }
+        }
+        assert_eq!(graph.last_time_updated(), Some(time));
+        assert_eq!(graph.parental_deme_sizes().unwrap(), parental_deme_sizes);
+        assert_eq!(
+            graph.offspring_deme_sizes().map(|sizes| sizes.to_vec()),
+            offspring_deme_sizes
+        );
+        // The sizes agree with the parental generation
+        let backward_time = graph.time_to_backward(time).unwrap().unwrap();
+        for (deme, size) in parental_deme_sizes.iter().enumerate() {
+            match graph.size_at(deme, backward_time).unwrap() {
+                Some(expected) => assert_eq!(*size, f64::from(expected)),
+                None => assert_eq!(*size, 0.0),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_snapshot() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<demes_forward::GenerationState>();
+
+    let mut graph = demes_forward::ForwardGraph::new_discrete_time(four_deme_model(), 10).unwrap();
+    assert!(graph.snapshot().is_none());
+    let mut snapshots = vec![];
+    for (time, state) in graph.time_iterator().zip(graph.iter_states()) {
+        graph.update_state(time).unwrap();
+        let snapshot = graph.snapshot().unwrap();
+        assert_eq!(snapshot.time(), time);
+        assert_eq!(snapshot.time(), state.time());
+        assert_eq!(snapshot.backwards_time(), state.backwards_time());
+        assert_eq!(snapshot.parental_deme_sizes(), state.parental_deme_sizes());
+        assert_eq!(
+            snapshot.offspring_deme_sizes(),
+            state.offspring_deme_sizes()
+        );
+        assert_eq!(snapshot.selfing_rates(), state.selfing_rates());
+        assert_eq!(snapshot.cloning_rates(), state.cloning_rates());
+        for deme in 0..graph.num_demes_in_model() {
+            assert_eq!(
+                snapshot.ancestry_proportions(deme),
+                state.ancestry_proportions(deme)
+            );
+        }
+        snapshots.push(std::sync::Arc::new(snapshot));
+    }
+
+    // The snapshots are unaffected by later updates.
+    let handles = snapshots
+        .iter()
+        .cloned()
+        .map(|snapshot| std::thread::spawn(move || snapshot.parental_deme_sizes().to_vec()))
+        .collect::<Vec<_>>();
+    graph.update_state(0).unwrap();
+    for (handle, state) in handles.into_iter().zip(graph.iter_states()) {
+        assert_eq!(handle.join().unwrap(), state.parental_deme_sizes());
+    }
+
+    graph.update_state(graph.end_time()).unwrap();
+    assert!(graph.snapshot().is_none());
+}
+
+#[test]
+fn test_demography_state() {
+    let mut graph = demes_forward::ForwardGraph::new_discrete_time(four_deme_model(), 10).unwrap();
+    assert!(graph.state().unwrap().is_none());
+    let states = graph.iter_states().collect::<Vec<_>>();
+    for (time, generation_state) in graph
+        .time_iterator()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .zip(&states)
+    {
+        let state = graph.update_state(time).unwrap().unwrap();
+        let from_iterator = generation_state.as_demography_state();
+        for s in [state, from_iterator] {
+            assert_eq!(s.time(), time);
+            assert_eq!(s.num_demes(), 4);
+        }
+        assert_eq!(
+            state.parental_deme_sizes(),
+            from_iterator.parental_deme_sizes()
+        );
+        assert_eq!(
+            state.offspring_deme_sizes(),
+            from_iterator.offspring_deme_sizes()
+        );
+        assert_eq!(state.selfing_rates(), from_iterator.selfing_rates());
+        assert_eq!(state.cloning_rates(), from_iterator.cloning_rates());
+        assert_eq!(
+            state.ancestry_proportions_matrix().map(|m| m.as_slice()),
+            from_iterator
+                .ancestry_proportions_matrix()
+                .map(|m| m.as_slice())
+        );
+        for deme in 0..5 {
+            assert_eq!(
+                state.ancestry_proportions(deme),
+                from_iterator.ancestry_proportions(deme)
+            );
+        }
+
+        // The state agrees with the individual getters
+        let state = graph.state().unwrap().unwrap();
+        assert_eq!(
+            Some(state.parental_deme_sizes()),
+            graph.parental_deme_sizes()
+        );
+        assert_eq!(state.offspring_deme_sizes(), graph.offspring_deme_sizes());
+        assert_eq!(state.selfing_rates(), graph.selfing_rates());
+        assert_eq!(state.cloning_rates(), graph.cloning_rates());
+        for deme in 0..5 {
+            assert_eq!(
+                state.ancestry_proportions(deme),
+                graph.ancestry_proportions(deme)
+            );
+        }
+    }
+    assert!(graph.update_state(graph.end_time()).unwrap().is_none());
+}
+
+#[test]
+fn test_reset() {
+    let mut graph = demes_forward::ForwardGraph::new_discrete_time(four_deme_model(), 10)
+        .unwrap()
+        .with_cumulative_ancestry()
+        .unwrap();
+    let fresh = graph.clone();
+    let times = graph.time_iterator().collect::<Vec<_>>();
+    for &time in &times[..times.len() / 2] {
+        graph.update_state(time).unwrap();
+    }
+    graph.reset();
+    assert!(graph.last_time_updated().is_none());
+    assert!(graph.parental_deme_sizes().is_none());
+    assert!(graph.offspring_deme_sizes().is_none());
+    assert!(graph.migration_matrix().is_none());
+    assert_eq!(graph.pulses_at_current_time().len(), 0);
+    assert_eq!(
+        graph.time_iterator().collect::<Vec<_>>(),
+        fresh.time_iterator().collect::<Vec<_>>()
+    );
+
+    // Iterating after a reset gives the same states as a new graph
+    let mut fresh = fresh;
+    for time in times {
+        graph.update_state(time).unwrap();
+        fresh.update_state(time).unwrap();
+        assert_eq!(graph.parental_deme_sizes(), fresh.parental_deme_sizes());
+        assert_eq!(graph.offspring_deme_sizes(), fresh.offspring_deme_sizes());
+        assert!(graph
+            .pulses_at_current_time()
+            .eq(fresh.pulses_at_current_time()));
+        for deme in 0..graph.num_demes_in_model() {
+            assert_eq!(
+                graph.ancestry_proportions(deme),
+                fresh.ancestry_proportions(deme)
+            );
+            assert_eq!(
+                graph.cumulative_ancestry_proportions(deme),
+                fresh.cumulative_ancestry_proportions(deme)
+            );
+        }
+    }
+}
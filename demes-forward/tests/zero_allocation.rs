@@ -0,0 +1,134 @@
+// Checks that ForwardGraph::update_state does not allocate
+// once its buffers are initialized.
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::cell::Cell;
+
+use demes_forward::demes;
+use demes_forward::ForwardGraph;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|a| a.set(a.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|a| a.get())
+}
+
+fn model() -> demes::Graph {
+    let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 50
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      end_size: 200
+ - name: C
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      end_time: 20
+    - start_size: 50
+ - name: D
+   ancestors: [B, C]
+   proportions: [0.5, 0.5]
+   start_time: 10
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [B, C]
+   rate: 1e-3
+   start_time: 40
+ - source: D
+   dest: B
+   rate: 1e-2
+pulses:
+ - sources: [B]
+   dest: C
+   time: 30
+   proportions: [0.5]
+ - sources: [C]
+   dest: B
+   time: 15
+   proportions: [0.25]
+";
+    demes::loads(yaml).unwrap()
+}
+
+// Updates to every time except the last parental generation
+fn update_all(graph: &mut ForwardGraph, times: &[demes_forward::ForwardTime]) -> usize {
+    let before = allocations();
+    for &time in times {
+        graph.update_state(time).unwrap();
+    }
+    allocations() - before
+}
+
+fn run(graph: ForwardGraph) {
+    let mut graph = graph;
+    let mut times = graph.time_iterator().collect::<Vec<_>>();
+    times.pop();
+
+    // Warm up the buffers
+    update_all(&mut graph, &times);
+
+    assert_eq!(update_all(&mut graph, &times), 0);
+    times.reverse();
+    assert_eq!(update_all(&mut graph, &times), 0);
+}
+
+#[test]
+fn test_update_state_does_not_allocate() {
+    run(ForwardGraph::new_discrete_time(model(), 20).unwrap());
+}
+
+#[test]
+fn test_update_state_with_time_step_does_not_allocate() {
+    run(ForwardGraph::new_discrete_time_with_time_step(model(), 20, 3.0).unwrap());
+}
+
+#[test]
+fn test_update_state_with_deme_subset_does_not_allocate() {
+    let graph = ForwardGraph::new_discrete_time_with_time_step(model(), 20, 3.0)
+        .unwrap()
+        .with_deme_subset(["A", "B", "D"])
+        .unwrap();
+    run(graph);
+}
+
+#[test]
+fn test_update_state_with_cumulative_ancestry_does_not_allocate() {
+    let graph = ForwardGraph::new_discrete_time(model(), 20)
+        .unwrap()
+        .with_cumulative_ancestry()
+        .unwrap();
+    run(graph);
+}
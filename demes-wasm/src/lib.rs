@@ -0,0 +1,182 @@
+//! WebAssembly bindings to [demes].
+//!
+//! The functions in this crate are intended to be called from
+//! JavaScript.
+//! Errors are reported as JavaScript `Error` objects.
+
+use wasm_bindgen::prelude::*;
+
+/// A resolved demes graph.
+#[wasm_bindgen]
+pub struct Graph {
+    graph: demes::Graph,
+}
+
+#[wasm_bindgen]
+impl Graph {
+    /// The graph in YAML format.
+    #[wasm_bindgen(js_name = asString)]
+    pub fn as_string(&self) -> Result<String, JsError> {
+        Ok(self.graph.as_string()?)
+    }
+
+    /// The graph in JSON format.
+    #[wasm_bindgen(js_name = asJsonString)]
+    pub fn as_json_string(&self) -> Result<String, JsError> {
+        Ok(self.graph.as_json_string()?)
+    }
+
+    /// The number of demes in the graph.
+    #[wasm_bindgen(getter, js_name = numDemes)]
+    pub fn num_demes(&self) -> usize {
+        self.graph.num_demes()
+    }
+
+    /// The names of the demes in the graph.
+    #[wasm_bindgen(getter, js_name = demeNames)]
+    pub fn deme_names(&self) -> Vec<String> {
+        self.graph
+            .demes()
+            .iter()
+            .map(|deme| deme.name().to_string())
+            .collect()
+    }
+}
+
+/// An error found while validating a model.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    message: String,
+    location: Option<demes::Location>,
+}
+
+#[wasm_bindgen]
+impl ValidationError {
+    /// The error message.
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// The line of the input causing the error, if known.
+    ///
+    /// Lines are numbered from one.
+    #[wasm_bindgen(getter)]
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|l| l.line())
+    }
+
+    /// The column of the input causing the error, if known.
+    ///
+    /// Columns are numbered from one.
+    #[wasm_bindgen(getter)]
+    pub fn column(&self) -> Option<usize> {
+        self.location.map(|l| l.column())
+    }
+}
+
+fn load_graph(yaml: &str) -> Result<demes::Graph, demes::DemesError> {
+    let options = demes::LoadOptions::default().set_locate_errors(true);
+    demes::loads_with_options(yaml, &options)
+}
+
+fn validation_error(error: demes::DemesError) -> ValidationError {
+    let location = error.location();
+    let message = match error {
+        demes::DemesError::Located { error, .. } => error.to_string(),
+        error => error.to_string(),
+    };
+    ValidationError { message, location }
+}
+
+/// Load a graph from a YAML string.
+///
+/// # Errors
+///
+/// Throws an `Error` if the model is not valid.
+#[wasm_bindgen]
+pub fn loads(yaml: &str) -> Result<Graph, JsError> {
+    Ok(Graph {
+        graph: load_graph(yaml)?,
+    })
+}
+
+/// Validate a model in YAML format.
+///
+/// Returns `undefined` if the model is valid.
+#[wasm_bindgen]
+pub fn validate(yaml: &str) -> Option<ValidationError> {
+    load_graph(yaml).err().map(validation_error)
+}
+
+/// Convert a model from YAML to JSON format.
+///
+/// The output is fully resolved.
+///
+/// # Errors
+///
+/// Throws an `Error` if the model is not valid.
+#[wasm_bindgen(js_name = asJsonString)]
+pub fn as_json_string(yaml: &str) -> Result<String, JsError> {
+    Ok(load_graph(yaml)?.as_json_string()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+";
+        assert!(validate(yaml).is_none());
+
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: -1
+";
+        let error = validate(yaml).unwrap();
+        assert_eq!(error.line(), Some(7));
+        assert_eq!(error.column(), Some(4));
+        assert!(!error.message().contains("line"));
+
+        let error = validate("time_units: generations\ndemes: [").unwrap();
+        assert!(error.line().is_some());
+
+        let error = validate("time_units: generations\ndemes: []").unwrap();
+        assert_eq!(error.line(), None);
+    }
+
+    #[test]
+    fn test_graph() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+";
+        let graph = Graph {
+            graph: load_graph(yaml).unwrap(),
+        };
+        assert_eq!(graph.num_demes(), 2);
+        assert_eq!(graph.deme_names(), ["A", "B"]);
+        let json = graph.graph.as_json_string().unwrap();
+        assert_eq!(demes::loads_json(&json).unwrap(), graph.graph);
+    }
+}
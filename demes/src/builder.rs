@@ -1,5 +1,8 @@
+use std::sync::Arc;
+
 use thiserror::Error;
 
+use crate::specification::DemeDefaults;
 use crate::specification::Graph;
 use crate::specification::GraphDefaults;
 use crate::specification::UnresolvedDeme;
@@ -7,11 +10,14 @@ use crate::specification::UnresolvedDemeHistory;
 use crate::specification::UnresolvedEpoch;
 use crate::specification::UnresolvedGraph;
 use crate::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
 use crate::InputGenerationTime;
 use crate::InputProportion;
 use crate::InputTime;
 use crate::TimeUnits;
 use crate::UnresolvedMigration;
+use crate::UnresolvedPulse;
 
 /// Error type raised by [`GraphBuilder`]
 #[derive(Error, Debug)]
@@ -26,6 +32,83 @@ pub enum BuilderError {
     DemesError(#[from] crate::DemesError),
 }
 
+/// Describes a deme added by [`GraphBuilder::add_deme_with`].
+///
+/// Epochs are added in order, from most ancient to most recent.
+/// All error checks are delayed until resolution.
+#[derive(Clone, Debug, Default)]
+pub struct DemeBuilder {
+    epochs: Vec<UnresolvedEpoch>,
+    history: UnresolvedDemeHistory,
+    description: Option<String>,
+}
+
+impl DemeBuilder {
+    /// Add an epoch.
+    pub fn epoch(self, epoch: UnresolvedEpoch) -> Self {
+        let mut epochs = self.epochs;
+        epochs.push(epoch);
+        Self { epochs, ..self }
+    }
+
+    /// Set the ancestors.
+    pub fn ancestors<I, A>(self, ancestors: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<str>,
+    {
+        let history = UnresolvedDemeHistory {
+            ancestors: Some(
+                ancestors
+                    .into_iter()
+                    .map(|a| a.as_ref().to_owned())
+                    .collect(),
+            ),
+            ..self.history
+        };
+        Self { history, ..self }
+    }
+
+    /// Set the ancestry proportions.
+    pub fn proportions<I, P>(self, proportions: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<InputProportion>,
+    {
+        let history = UnresolvedDemeHistory {
+            proportions: Some(proportions.into_iter().map(|p| p.into()).collect()),
+            ..self.history
+        };
+        Self { history, ..self }
+    }
+
+    /// Set the start time.
+    pub fn start_time<T: Into<InputTime>>(self, start_time: T) -> Self {
+        let history = UnresolvedDemeHistory {
+            start_time: Some(start_time.into()),
+            ..self.history
+        };
+        Self { history, ..self }
+    }
+
+    /// Set the epoch defaults of the deme.
+    pub fn epoch_defaults(self, epoch: UnresolvedEpoch) -> Self {
+        let history = UnresolvedDemeHistory {
+            defaults: DemeDefaults { epoch },
+            ..self.history
+        };
+        Self { history, ..self }
+    }
+
+    /// Set the description.
+    pub fn description<D: Into<String>>(self, description: D) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+}
+
 /// This type allows building a [`Graph`](crate::Graph) using code
 /// rather then using text input.
 ///
@@ -36,8 +119,35 @@ pub enum BuilderError {
 ///   The lack of a rust REPL and the strong type checking
 ///   are the primary reasons.
 /// * All error checks are delayed until resolution.
+/// * Cloning a builder is cheap.
+///   The clone shares its data with the original until
+///   either of them is modified, making it efficient to
+///   derive many models from a common base model.
+///
+/// # Examples
+///
+/// ```
+/// use demes::UnresolvedEpoch;
+///
+/// let mut base = demes::GraphBuilder::new_generations(None);
+/// base.add_deme_with("A", |deme| {
+///     deme.epoch(UnresolvedEpoch::default().set_start_size(100.))
+/// });
+/// for size in [10., 20., 30.] {
+///     let mut b = base.clone();
+///     b.add_deme_with("B", |deme| {
+///         deme.start_time(50.)
+///             .ancestors(["A"])
+///             .epoch(UnresolvedEpoch::default().set_start_size(size))
+///     });
+///     let graph = b.resolve().unwrap();
+///     assert_eq!(graph.deme("B").start_size(), size);
+/// }
+/// assert_eq!(base.resolve().unwrap().num_demes(), 1);
+/// ```
+#[derive(Clone)]
 pub struct GraphBuilder {
-    graph: UnresolvedGraph,
+    graph: Arc<UnresolvedGraph>,
     metadata: Option<crate::Metadata>,
 }
 
@@ -54,7 +164,7 @@ impl GraphBuilder {
         defaults: Option<GraphDefaults>,
     ) -> Self {
         Self {
-            graph: UnresolvedGraph::new(time_units, generation_time, defaults),
+            graph: Arc::new(UnresolvedGraph::new(time_units, generation_time, defaults)),
             metadata: None,
         }
     }
@@ -64,11 +174,51 @@ impl GraphBuilder {
     /// This function works by calling [`GraphBuilder::new`](crate::GraphBuilder::new).
     pub fn new_generations(defaults: Option<GraphDefaults>) -> Self {
         Self {
-            graph: UnresolvedGraph::new(TimeUnits::Generations, None, defaults),
+            graph: Arc::new(UnresolvedGraph::new(TimeUnits::Generations, None, defaults)),
+            metadata: None,
+        }
+    }
+
+    /// Construct a builder with time units in years.
+    ///
+    /// All times are given in years.
+    /// To obtain a graph with time units in generations,
+    /// see [`GraphBuilder::resolve_into_generations`].
+    ///
+    /// This function works by calling [`GraphBuilder::new`](crate::GraphBuilder::new).
+    pub fn new_years<G: Into<InputGenerationTime>>(
+        generation_time: G,
+        defaults: Option<GraphDefaults>,
+    ) -> Self {
+        Self::new(TimeUnits::Years, Some(generation_time.into()), defaults)
+    }
+
+    pub(crate) fn new_from_unresolved(graph: UnresolvedGraph) -> Self {
+        Self {
+            graph: Arc::new(graph),
             metadata: None,
         }
     }
 
+    // The graph is only copied if it is shared with a clone
+    // of this builder.
+    fn graph_mut(&mut self) -> &mut UnresolvedGraph {
+        Arc::make_mut(&mut self.graph)
+    }
+
+    fn into_unresolved(self) -> UnresolvedGraph {
+        let mut graph = Arc::try_unwrap(self.graph).unwrap_or_else(|graph| (*graph).clone());
+        if let Some(metadata) = self.metadata {
+            graph.set_metadata(metadata);
+        }
+        graph
+    }
+
+    // Copy description, doi, and metadata from a graph
+    pub(crate) fn liftover_toplevel_data(&mut self, graph: &Graph) {
+        self.graph_mut().liftover_toplevel_data(graph)
+    }
+
     /// Add a [`Deme`](crate::Deme) to the graph.
     ///
     /// # Examples
@@ -92,7 +242,269 @@ impl GraphBuilder {
     ) {
         let epochs = epochs.into_iter().collect::<Vec<_>>();
         let ptr = UnresolvedDeme::new_via_builder(name, epochs, history, description);
-        self.graph.add_deme(ptr);
+        self.graph_mut().add_deme(ptr);
+    }
+
+    /// Add a [`Deme`](crate::Deme) to the graph, described by a
+    /// [`DemeBuilder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme_with("A", |deme| {
+    ///     deme.description("ancestral")
+    ///         .epoch(UnresolvedEpoch::default().set_start_size(100.).set_end_time(50.))
+    /// });
+    /// b.add_deme_with("B", |deme| {
+    ///     deme.ancestors(["A"])
+    ///         .epoch(UnresolvedEpoch::default().set_start_size(50.).set_end_size(500.))
+    /// });
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.deme("B").start_time(), 50.0);
+    /// ```
+    pub fn add_deme_with<F>(&mut self, name: &str, f: F)
+    where
+        F: FnOnce(DemeBuilder) -> DemeBuilder,
+    {
+        let deme = f(DemeBuilder::default());
+        self.add_deme(name, deme.epochs, deme.history, deme.description.as_deref());
+    }
+
+    fn deme_index(&self, name: &str) -> Result<usize, BuilderError> {
+        self.graph
+            .demes()
+            .iter()
+            .position(|deme| deme.name() == name)
+            .ok_or_else(|| {
+                DemesError::DemeError(
+                    ErrorContext::new(
+                        format!("deme {name} does not exist"),
+                        ErrorKind::UnknownDeme,
+                    )
+                    .with_deme(name),
+                )
+                .into()
+            })
+    }
+
+    fn deme_mut(&mut self, name: &str) -> Result<&mut UnresolvedDeme, BuilderError> {
+        let index = self.deme_index(name)?;
+        Ok(&mut self.graph_mut().demes_mut()[index])
+    }
+
+    /// Add an epoch to the deme that was most recently added
+    /// to the graph.
+    ///
+    /// # Errors
+    ///
+    /// [`BuilderError`] if no demes have been added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme("A", [], demes::UnresolvedDemeHistory::default(), None);
+    /// b.add_epoch(UnresolvedEpoch::default().set_start_size(100.).set_end_time(50.))
+    ///     .unwrap();
+    /// b.add_epoch(UnresolvedEpoch::default().set_start_size(200.))
+    ///     .unwrap();
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.deme(0).num_epochs(), 2);
+    /// ```
+    pub fn add_epoch(&mut self, epoch: UnresolvedEpoch) -> Result<(), BuilderError> {
+        let deme = self.graph_mut().demes_mut().last_mut().ok_or_else(|| {
+            DemesError::DemeError(ErrorContext::new(
+                "no demes have been added",
+                ErrorKind::Missing,
+            ))
+        })?;
+        deme.epochs_mut().push(epoch);
+        Ok(())
+    }
+
+    /// Add an epoch to a deme that was previously added to the graph.
+    ///
+    /// # Errors
+    ///
+    /// [`BuilderError`] if the deme does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme_with("A", |deme| {
+    ///     deme.epoch(UnresolvedEpoch::default().set_start_size(100.).set_end_time(50.))
+    /// });
+    /// b.add_deme_with("B", |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(100.)));
+    /// b.add_epoch_to("A", UnresolvedEpoch::default().set_start_size(200.))
+    ///     .unwrap();
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.deme("A").num_epochs(), 2);
+    /// ```
+    pub fn add_epoch_to(&mut self, name: &str, epoch: UnresolvedEpoch) -> Result<(), BuilderError> {
+        self.deme_mut(name)?.epochs_mut().push(epoch);
+        Ok(())
+    }
+
+    /// Remove a deme that was previously added to the graph.
+    ///
+    /// Ancestors, migrations, and pulses that refer to the
+    /// deme are not changed.
+    ///
+    /// # Returns
+    ///
+    /// The removed deme.
+    ///
+    /// # Errors
+    ///
+    /// [`BuilderError`] if no deme is named `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// for name in ["A", "B"] {
+    ///     b.add_deme_with(name, |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(100.)));
+    /// }
+    /// let removed = b.remove_deme("A").unwrap();
+    /// assert_eq!(removed.name(), "A");
+    /// assert!(b.remove_deme("A").is_err());
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.num_demes(), 1);
+    /// ```
+    pub fn remove_deme(&mut self, name: &str) -> Result<UnresolvedDeme, BuilderError> {
+        let index = self.deme_index(name)?;
+        Ok(self.graph_mut().demes_mut().remove(index))
+    }
+
+    /// Remove the migration at `index`, in the order that
+    /// migrations were added.
+    ///
+    /// # Returns
+    ///
+    /// The removed migration or `None` if `index` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// for name in ["A", "B"] {
+    ///     b.add_deme_with(name, |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(100.)));
+    /// }
+    /// b.add_symmetric_migration(["A", "B"], 1e-3, None::<f64>, None);
+    /// assert!(b.remove_migration(0).is_some());
+    /// assert!(b.remove_migration(0).is_none());
+    /// let graph = b.resolve().unwrap();
+    /// assert!(graph.migrations().is_empty());
+    /// ```
+    pub fn remove_migration(&mut self, index: usize) -> Option<UnresolvedMigration> {
+        let migrations = self.graph_mut().migrations_mut();
+        (index < migrations.len()).then(|| migrations.remove(index))
+    }
+
+    /// Remove the pulse at `index`, in the order that
+    /// pulses were added.
+    ///
+    /// # Returns
+    ///
+    /// The removed pulse or `None` if `index` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// for name in ["A", "B"] {
+    ///     b.add_deme_with(name, |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(100.)));
+    /// }
+    /// b.add_pulse(Some(&["A"]), Some("B"), Some(10.), Some([0.5]));
+    /// let removed = b.remove_pulse(0).unwrap();
+    /// assert_eq!(removed.dest.as_deref(), Some("B"));
+    /// assert!(b.remove_pulse(0).is_none());
+    /// let graph = b.resolve().unwrap();
+    /// assert!(graph.pulses().is_empty());
+    /// ```
+    pub fn remove_pulse(&mut self, index: usize) -> Option<UnresolvedPulse> {
+        let pulses = self.graph_mut().pulses_mut();
+        (index < pulses.len()).then(|| pulses.remove(index))
+    }
+
+    /// Replace a deme that was previously added to the graph.
+    ///
+    /// The new deme is described by a [`DemeBuilder`], as in
+    /// [`GraphBuilder::add_deme_with`], and keeps the position
+    /// of the deme that it replaces.
+    ///
+    /// # Errors
+    ///
+    /// [`BuilderError`] if no deme is named `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme_with("A", |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(100.)));
+    /// b.replace_deme("A", |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(200.)))
+    ///     .unwrap();
+    /// assert!(b.replace_deme("B", |deme| deme).is_err());
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.deme(0).start_size(), 200.);
+    /// ```
+    pub fn replace_deme<F>(&mut self, name: &str, f: F) -> Result<(), BuilderError>
+    where
+        F: FnOnce(DemeBuilder) -> DemeBuilder,
+    {
+        let deme = self.deme_mut(name)?;
+        let builder = f(DemeBuilder::default());
+        *deme = UnresolvedDeme::new_via_builder(
+            name,
+            builder.epochs,
+            builder.history,
+            builder.description.as_deref(),
+        );
+        Ok(())
+    }
+
+    /// Modify a deme that was previously added to the graph.
+    ///
+    /// # Errors
+    ///
+    /// [`BuilderError`] if no deme is named `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme_with("A", |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(100.)));
+    /// for size in [200., 300.] {
+    ///     b.modify_deme("A", |deme| deme.epochs_mut()[0].start_size = Some(size.into()))
+    ///         .unwrap();
+    /// }
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.deme(0).start_size(), 300.);
+    /// ```
+    pub fn modify_deme<F>(&mut self, name: &str, f: F) -> Result<(), BuilderError>
+    where
+        F: FnOnce(&mut UnresolvedDeme),
+    {
+        f(self.deme_mut(name)?);
+        Ok(())
     }
 
     /// Add a migration to the graph.
@@ -145,7 +557,48 @@ impl GraphBuilder {
     /// # b.resolve().unwrap();
     /// ```
     pub fn add_migration(&mut self, migration: UnresolvedMigration) {
-        self.graph.add_migration(migration);
+        self.graph_mut().add_migration(migration);
+    }
+
+    /// Add a symmetric migration among `demes` to the graph.
+    ///
+    /// This is a convenience wrapper around [`GraphBuilder::add_migration`].
+    /// Times that are `None` are resolved from the times
+    /// during which the demes exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// for name in ["A", "B", "C"] {
+    ///     b.add_deme_with(name, |deme| {
+    ///         deme.epoch(demes::UnresolvedEpoch::default().set_start_size(100.))
+    ///     });
+    /// }
+    /// b.add_symmetric_migration(["A", "B", "C"], 1e-4, None, Some(10.0));
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.migrations().len(), 6);
+    /// ```
+    pub fn add_symmetric_migration<I, A, R, T>(
+        &mut self,
+        demes: I,
+        rate: R,
+        start_time: Option<T>,
+        end_time: Option<T>,
+    ) where
+        I: IntoIterator<Item = A>,
+        A: AsRef<str>,
+        R: Into<crate::InputMigrationRate>,
+        T: Into<InputTime>,
+    {
+        let migration = UnresolvedMigration {
+            start_time: start_time.map(|t| t.into()),
+            end_time: end_time.map(|t| t.into()),
+            ..Default::default()
+        }
+        .set_demes(demes)
+        .set_rate(rate);
+        self.add_migration(migration);
     }
 
     /// Add an [`UnresolvedPulse`](crate::UnresolvedPulse) to the graph.
@@ -180,73 +633,413 @@ impl GraphBuilder {
                 .map(|p| p.into())
                 .collect::<Vec<InputProportion>>()
         });
-        self.graph.add_pulse(sources, dest, time, proportions);
+        self.graph_mut().add_pulse(sources, dest, time, proportions);
+    }
+
+    /// Add an [`UnresolvedPulse`](crate::UnresolvedPulse) to the graph.
+    ///
+    /// Unlike [`GraphBuilder::add_pulse`], the pulse is
+    /// described using the setters of [`UnresolvedPulse`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// for name in ["A", "B"] {
+    ///     b.add_deme_with(name, |deme| {
+    ///         deme.epoch(demes::UnresolvedEpoch::default().set_start_size(100.))
+    ///     });
+    /// }
+    /// let pulse = demes::UnresolvedPulse::default()
+    ///     .set_sources(["A"])
+    ///     .set_dest("B")
+    ///     .set_time(50.)
+    ///     .set_proportions([0.5]);
+    /// b.add_pulse_builder(pulse);
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.pulses().len(), 1);
+    /// ```
+    pub fn add_pulse_builder(&mut self, pulse: UnresolvedPulse) {
+        let UnresolvedPulse {
+            sources,
+            dest,
+            time,
+            proportions,
+        } = pulse;
+        self.graph_mut().add_pulse(sources, dest, time, proportions);
+    }
+
+    /// Check the structure of the graph without resolving it.
+    ///
+    /// The checks are for:
+    ///
+    /// * duplicate deme names.
+    /// * demes with no epochs and no default epoch size.
+    /// * ancestor names that do not refer to a deme.
+    ///
+    /// Passing these checks does not imply that resolution
+    /// will succeed.
+    ///
+    /// # Errors
+    ///
+    /// All problems that are found, in the order that demes were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme_with("A", |deme| {
+    ///     deme.epoch(demes::UnresolvedEpoch::default().set_start_size(100.))
+    /// });
+    /// assert!(b.check().is_ok());
+    /// b.add_deme_with("B", |deme| deme.ancestors(["X"]));
+    /// let errors = b.check().unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// ```
+    pub fn check(&self) -> Result<(), Vec<BuilderError>> {
+        let errors = self.graph.check_structure();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.into_iter().map(BuilderError::from).collect())
+        }
     }
 
     /// Generate and return a resolved [`Graph`](crate::Graph).
     ///
     /// # Errors
     ///
-    /// Returns [`DemesError'](crate::DemesError) if any
+    /// Returns [`DemesError`](crate::DemesError) if any
     /// of the data are invalid.
     pub fn resolve(self) -> Result<Graph, DemesError> {
-        let mut builder = self;
-        match builder.metadata {
-            None => (),
-            Some(m) => builder.graph.set_metadata(m),
-        }
-        builder.graph.resolve()?.try_into()
+        self.into_unresolved().resolve()
     }
 
-    /// Set top-level metadata
+    /// Generate and return a resolved [`Graph`](crate::Graph)
+    /// with time units converted to generations.
     ///
-    /// # Parameters
+    /// This is equivalent to calling [`GraphBuilder::resolve`]
+    /// followed by [`Graph::into_generations`](crate::Graph::into_generations).
     ///
-    /// * `metadata`: the metadata type
+    /// # Errors
     ///
-    /// # Note
+    /// Returns [`DemesError`](crate::DemesError) if any
+    /// of the data are invalid or if conversion fails.
     ///
-    /// Repeated calls will overwrite existing metadata.
+    /// # Examples
+    ///
+    /// ```
+    /// let mut b = demes::GraphBuilder::new_years(25.0, None);
+    /// b.add_deme_with("A", |deme| {
+    ///     deme.epoch(demes::UnresolvedEpoch::default().set_start_size(100.).set_end_time(1000.))
+    ///         .epoch(demes::UnresolvedEpoch::default().set_start_size(200.))
+    /// });
+    /// let graph = b.resolve_into_generations().unwrap();
+    /// assert_eq!(graph.time_units(), demes::TimeUnits::Generations);
+    /// assert_eq!(graph.deme(0).epochs()[0].end_time(), 40.0);
+    /// ```
+    pub fn resolve_into_generations(self) -> Result<Graph, DemesError> {
+        self.resolve()?.into_generations()
+    }
+
+    /// Generate and return a resolved [`Graph`](crate::Graph)
+    /// along with any non-fatal issues found during resolution.
     ///
     /// # Errors
     ///
-    /// * [`BuilderError`] if serialization to YAML fails.
+    /// Returns [`DemesError`](crate::DemesError) if any
+    /// of the data are invalid.
     ///
-    /// # Example
+    /// # Examples
     ///
     /// ```
-    /// #[derive(serde::Serialize, serde::Deserialize)]
-    /// struct MyMetaData {
-    ///    foo: i32,
-    ///    bar: String
-    /// }
-    /// # let mut builder = demes::GraphBuilder::new_generations(None);
-    /// builder.set_toplevel_metadata(&MyMetaData{foo: 3, bar: "string".to_owned()}).unwrap();
+    /// let start_size = demes::InputDemeSize::from(100.);
+    /// let epoch = demes::UnresolvedEpoch{start_size: Some(start_size), ..Default::default()};
+    /// let history = demes::UnresolvedDemeHistory::default();
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme("A", vec![epoch], history.clone(), None);
+    /// b.add_deme("B", vec![epoch], history, None);
+    /// let migration = demes::UnresolvedMigration::default().set_source("A").set_dest("B").set_rate(0.999);
+    /// b.add_migration(migration);
+    /// let (graph, warnings) = b.resolve_with_warnings().unwrap();
+    /// assert_eq!(graph.num_demes(), 2);
+    /// assert_eq!(warnings.len(), 1);
+    /// assert!(matches!(warnings[0], demes::Warning::MigrationRateNearOne{..}));
     /// ```
-    pub fn set_toplevel_metadata<T: serde::Serialize>(
-        &mut self,
-        metadata: &T,
-    ) -> Result<(), BuilderError> {
-        let yaml = serde_yaml::to_string(metadata)?;
-        let md: std::collections::BTreeMap<String, serde_yaml::Value> =
-            serde_yaml::from_str(&yaml)?;
-        let metadata = crate::Metadata::try_from(md)?;
-        self.metadata = Some(metadata);
-        Ok(())
+    pub fn resolve_with_warnings(self) -> Result<(Graph, Vec<crate::Warning>), DemesError> {
+        let mut warnings = vec![];
+        let graph = self
+            .into_unresolved()
+            .resolve_with_warnings(&mut warnings)?;
+        Ok((graph, warnings))
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::specification::DemeDefaults;
-    use crate::InputDemeSize;
 
-    #[test]
-    #[should_panic]
-    fn new_builder() {
-        let b = GraphBuilder::new(TimeUnits::Generations, None, None);
-        b.resolve().unwrap();
+    /// Generate and return a resolved [`Graph`](crate::Graph),
+    /// reporting every error found rather than only the first.
+    ///
+    /// Resolution continues past a deme, migration, or pulse
+    /// that fails to resolve.
+    /// Objects that refer to a deme that fails to resolve
+    /// are not checked further, as their errors would only
+    /// repeat that of the deme.
+    /// Errors in the graph as a whole, such as duplicate deme
+    /// names, end resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns all [`DemesError`](crate::DemesError) values
+    /// found, in the order in which they were found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::{UnresolvedEpoch, UnresolvedMigration};
+    ///
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme_with("A", |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(-1.)));
+    /// b.add_deme_with("B", |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(1.)));
+    /// b.add_deme_with("C", |deme| deme.epoch(UnresolvedEpoch::default().set_start_size(1.)));
+    /// b.add_migration(UnresolvedMigration::default().set_demes(["B", "C"]).set_rate(2.));
+    /// let errors = b.resolve_all_errors().unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].context().unwrap().deme(), Some("A"));
+    /// ```
+    pub fn resolve_all_errors(self) -> Result<Graph, Vec<DemesError>> {
+        self.into_unresolved().resolve_all_errors()
+    }
+
+    /// Set the top-level defaults, replacing any existing defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let defaults = demes::GraphDefaults {
+    ///     epoch: demes::UnresolvedEpoch::default().set_start_size(100.),
+    ///     ..Default::default()
+    /// };
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.set_defaults(defaults);
+    /// builder.add_deme_with("A", |deme| deme);
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.deme(0).start_size(), 100.);
+    /// ```
+    pub fn set_defaults(&mut self, defaults: GraphDefaults) {
+        *self.graph_mut().defaults_mut() = defaults;
+    }
+
+    /// Set the top-level epoch defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.set_epoch_defaults(demes::UnresolvedEpoch::default().set_start_size(100.));
+    /// builder.add_deme_with("A", |deme| deme);
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.deme(0).start_size(), 100.);
+    /// ```
+    pub fn set_epoch_defaults(&mut self, epoch: UnresolvedEpoch) {
+        self.graph_mut().defaults_mut().epoch = epoch;
+    }
+
+    /// Set the epoch defaults of a deme that was previously
+    /// added to the graph.
+    ///
+    /// These defaults take precedence over the
+    /// top-level epoch defaults.
+    ///
+    /// # Errors
+    ///
+    /// [`BuilderError`] if the deme does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use demes::UnresolvedEpoch;
+    ///
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.add_deme_with("A", |deme| {
+    ///     deme.epoch(UnresolvedEpoch::default().set_start_size(100.).set_end_time(50.))
+    ///         .epoch(UnresolvedEpoch::default().set_end_time(10.))
+    ///         .epoch(UnresolvedEpoch::default())
+    /// });
+    /// builder
+    ///     .set_deme_epoch_defaults("A", UnresolvedEpoch::default().set_start_size(200.))
+    ///     .unwrap();
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.deme(0).epochs()[0].start_size(), 100.);
+    /// assert_eq!(graph.deme(0).epochs()[2].start_size(), 200.);
+    /// ```
+    pub fn set_deme_epoch_defaults(
+        &mut self,
+        name: &str,
+        epoch: UnresolvedEpoch,
+    ) -> Result<(), BuilderError> {
+        self.deme_mut(name)?.defaults_mut().epoch = epoch;
+        Ok(())
+    }
+
+    /// Set the top-level deme defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.set_epoch_defaults(demes::UnresolvedEpoch::default().set_start_size(100.));
+    /// builder.set_deme_defaults(demes::TopLevelDemeDefaults {
+    ///     ancestors: Some(vec!["A".to_string()]),
+    ///     ..Default::default()
+    /// });
+    /// builder.add_deme_with("A", |deme| deme.ancestors(Vec::<String>::new()));
+    /// builder.add_deme_with("B", |deme| deme.start_time(50.));
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.deme(1).ancestor_names()[0].as_ref(), "A");
+    /// ```
+    pub fn set_deme_defaults(&mut self, deme: crate::TopLevelDemeDefaults) {
+        self.graph_mut().defaults_mut().deme = deme;
+    }
+
+    /// Set the top-level migration defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.set_epoch_defaults(demes::UnresolvedEpoch::default().set_start_size(100.));
+    /// builder.set_migration_defaults(demes::UnresolvedMigration::default().set_rate(1e-3));
+    /// builder.add_deme_with("A", |deme| deme);
+    /// builder.add_deme_with("B", |deme| deme);
+    /// builder.add_migration(demes::UnresolvedMigration::default().set_demes(["A", "B"]));
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.migrations()[0].rate(), 1e-3);
+    /// ```
+    pub fn set_migration_defaults(&mut self, migration: UnresolvedMigration) {
+        self.graph_mut().defaults_mut().migration = migration;
+    }
+
+    /// Set the top-level pulse defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.set_epoch_defaults(demes::UnresolvedEpoch::default().set_start_size(100.));
+    /// builder.set_pulse_defaults(demes::UnresolvedPulse::default().set_dest("B").set_proportions([0.1]));
+    /// builder.add_deme_with("A", |deme| deme);
+    /// builder.add_deme_with("B", |deme| deme);
+    /// builder.add_pulse_builder(demes::UnresolvedPulse::default().set_sources(["A"]).set_time(10.));
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.pulses()[0].dest(), "B");
+    /// ```
+    pub fn set_pulse_defaults(&mut self, pulse: UnresolvedPulse) {
+        self.graph_mut().defaults_mut().pulse = pulse;
+    }
+
+    /// Set the description of the graph.
+    ///
+    /// Repeated calls will overwrite an existing description.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.set_description(Some("a model"));
+    /// # builder.add_deme_with("A", |deme| {
+    /// #     deme.epoch(demes::UnresolvedEpoch::default().set_start_size(100.))
+    /// # });
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.description(), Some("a model"));
+    /// ```
+    pub fn set_description<D: Into<String>>(&mut self, description: Option<D>) {
+        self.graph_mut().set_description(description);
+    }
+
+    /// Add a DOI to the graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.add_doi("https://doi.org/10.1093/genetics/iyac131");
+    /// # builder.add_deme_with("A", |deme| {
+    /// #     deme.epoch(demes::UnresolvedEpoch::default().set_start_size(100.))
+    /// # });
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.doi().count(), 1);
+    /// ```
+    pub fn add_doi<D: Into<String>>(&mut self, doi: D) {
+        self.graph_mut()
+            .doi_mut()
+            .get_or_insert_with(Vec::new)
+            .push(doi.into());
+    }
+
+    /// Set top-level metadata
+    ///
+    /// # Parameters
+    ///
+    /// * `metadata`: any type that serializes to a mapping,
+    ///   including a [`BTreeMap`](std::collections::BTreeMap).
+    ///
+    /// # Note
+    ///
+    /// Repeated calls will overwrite existing metadata.
+    ///
+    /// # Errors
+    ///
+    /// * [`BuilderError`] if serialization to YAML fails or if
+    ///   `metadata` is not a non-empty mapping.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(serde::Serialize, serde::Deserialize)]
+    /// struct MyMetaData {
+    ///    foo: i32,
+    ///    bar: String
+    /// }
+    /// # let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.set_toplevel_metadata(&MyMetaData{foo: 3, bar: "string".to_owned()}).unwrap();
+    /// ```
+    ///
+    /// A map is also accepted:
+    ///
+    /// ```
+    /// let mut metadata = std::collections::BTreeMap::new();
+    /// metadata.insert("model", "two-deme split");
+    /// let mut builder = demes::GraphBuilder::new_generations(None);
+    /// builder.add_deme_with("A", |deme| {
+    ///     deme.epoch(demes::UnresolvedEpoch::default().set_start_size(100.))
+    /// });
+    /// builder.set_toplevel_metadata(&metadata).unwrap();
+    /// let graph = builder.resolve().unwrap();
+    /// let yaml = graph.metadata().unwrap().as_yaml_string().unwrap();
+    /// assert_eq!(yaml, "model: two-deme split\n");
+    /// ```
+    #[doc(alias = "set_metadata")]
+    pub fn set_toplevel_metadata<T: serde::Serialize>(
+        &mut self,
+        metadata: &T,
+    ) -> Result<(), BuilderError> {
+        let value = serde_yaml::to_value(metadata)?;
+        let md: std::collections::BTreeMap<String, serde_yaml::Value> =
+            serde_yaml::from_value(value)?;
+        let metadata = crate::Metadata::try_from(md)?;
+        self.metadata = Some(metadata);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InputDemeSize;
+
+    #[test]
+    #[should_panic]
+    fn new_builder() {
+        let b = GraphBuilder::new(TimeUnits::Generations, None, None);
+        b.resolve().unwrap();
     }
 
     #[test]
@@ -330,6 +1123,690 @@ mod tests {
         );
     }
 
+    #[test]
+    fn builder_set_toplevel_metadata() {
+        let mut b = GraphBuilder::new_generations(None);
+        b.add_deme_with("A", |deme| {
+            deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+        });
+        assert!(b
+            .set_toplevel_metadata(&std::collections::BTreeMap::<String, i32>::new())
+            .is_err());
+        assert!(b.set_toplevel_metadata(&1).is_err());
+        let mut metadata = std::collections::BTreeMap::new();
+        metadata.insert("foo".to_string(), vec![1, 2]);
+        b.set_toplevel_metadata(&metadata).unwrap();
+        let graph = b.resolve().unwrap();
+        let yaml = "
+ time_units: generations
+ metadata:
+  foo: [1, 2]
+ demes:
+  - name: A
+    epochs:
+     - start_size: 50
+    ";
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+    }
+
+    #[test]
+    fn builder_description_and_doi() {
+        let mut b = GraphBuilder::new_generations(None);
+        b.add_deme_with("A", |deme| {
+            deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+        });
+        b.set_description(Some("first"));
+        b.set_description(Some("a model".to_string()));
+        b.add_doi("doi 1");
+        b.add_doi("doi 2".to_string());
+        let graph = b.resolve().unwrap();
+        let yaml = "
+ time_units: generations
+ description: a model
+ doi: [doi 1, doi 2]
+ demes:
+  - name: A
+    epochs:
+     - start_size: 50
+    ";
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+        assert_eq!(
+            graph.as_string().unwrap(),
+            crate::loads(yaml).unwrap().as_string().unwrap()
+        );
+
+        let mut b = GraphBuilder::new_generations(None);
+        b.add_deme_with("A", |deme| {
+            deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+        });
+        b.set_description(Some("a model"));
+        b.set_description(None::<String>);
+        let graph = b.resolve().unwrap();
+        assert!(graph.description().is_none());
+        assert_eq!(graph.doi().count(), 0);
+    }
+
+    #[test]
+    fn builder_symmetric_migration() {
+        let yaml = "
+ time_units: generations
+ demes:
+  - name: A
+    epochs:
+     - start_size: 50
+  - name: B
+    epochs:
+     - start_size: 50
+ migrations:
+  - demes: [A, B]
+    rate: 1e-3
+    start_time: 100
+  - demes: [A, B]
+    rate: 1e-4
+    end_time: 100
+    ";
+        let mut b = GraphBuilder::new_generations(None);
+        for name in ["A", "B"] {
+            b.add_deme_with(name, |deme| {
+                deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+            });
+        }
+        b.add_symmetric_migration(["A", "B"], 1e-3, Some(100.), None);
+        b.add_symmetric_migration(
+            vec!["A".to_string(), "B".to_string()],
+            1e-4,
+            None,
+            Some(100.),
+        );
+        let graph = b.resolve().unwrap();
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+
+        let mut b = GraphBuilder::new_generations(None);
+        b.add_deme_with("A", |deme| {
+            deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+        });
+        b.add_symmetric_migration(["A"], 1e-3, None::<f64>, None);
+        assert!(b.resolve().is_err());
+    }
+
+    #[test]
+    fn builder_from_graph() {
+        let yaml = "
+ time_units: generations
+ metadata:
+  foo: 1
+ demes:
+  - name: ancestor1
+    epochs:
+     - start_size: 50
+       end_time: 20
+    ";
+        let graph = crate::loads(yaml).unwrap();
+        let b = graph.clone().into_builder();
+        let round_trip = b.resolve().unwrap();
+        assert_eq!(graph, round_trip);
+        assert!(round_trip.input_string().is_none());
+
+        let mut b = graph.into_builder();
+        b.add_deme(
+            "derived",
+            vec![UnresolvedEpoch {
+                start_size: Some(50.0.into()),
+                ..Default::default()
+            }],
+            UnresolvedDemeHistory {
+                ancestors: Some(vec!["ancestor1".to_string()]),
+                ..Default::default()
+            },
+            None,
+        );
+        let graph = b.resolve().unwrap();
+        assert_eq!(graph.num_demes(), 2);
+        assert_eq!(graph.deme("derived").start_time(), 20.0);
+        assert!(graph.metadata().is_some());
+    }
+
+    #[test]
+    fn builder_add_deme_with() {
+        let yaml = "
+ time_units: generations
+ demes:
+  - name: A
+    epochs:
+     - start_size: 50
+       end_time: 20
+  - name: B
+    description: a deme
+    ancestors: [A]
+    start_time: 30
+    defaults:
+      epoch:
+        selfing_rate: 0.5
+    epochs:
+     - start_size: 50
+       end_size: 100
+       size_function: linear
+       end_time: 10
+     - start_size: 10
+  - name: C
+    ancestors: [A, B]
+    proportions: [0.25, 0.75]
+    start_time: 25
+    epochs:
+     - start_size: 10
+       cloning_rate: 0.1
+    ";
+        let mut b = GraphBuilder::new_generations(None);
+        b.add_deme_with("A", |deme| {
+            deme.epoch(
+                UnresolvedEpoch::default()
+                    .set_start_size(50.)
+                    .set_end_time(20.),
+            )
+        });
+        b.add_deme_with("B", |deme| {
+            deme.description("a deme")
+                .ancestors(["A"])
+                .start_time(30.)
+                .epoch_defaults(UnresolvedEpoch::default().set_selfing_rate(0.5))
+                .epoch(
+                    UnresolvedEpoch::default()
+                        .set_start_size(50.)
+                        .set_end_size(100.)
+                        .set_size_function(crate::SizeFunction::Linear)
+                        .set_end_time(10.),
+                )
+                .epoch(UnresolvedEpoch::default().set_start_size(10.))
+        });
+        b.add_deme_with("C", |deme| {
+            deme.ancestors(vec!["A".to_string(), "B".to_string()])
+                .proportions([0.25, 0.75])
+                .start_time(25.)
+                .epoch(
+                    UnresolvedEpoch::default()
+                        .set_start_size(10.)
+                        .set_cloning_rate(0.1),
+                )
+        });
+        let graph = b.resolve().unwrap();
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+        assert_eq!(graph.deme("B").description(), "a deme");
+        assert_eq!(graph.deme("B").epochs()[1].selfing_rate(), 0.5);
+    }
+
+    #[test]
+    fn builder_pulse_builder() {
+        let yaml = "
+ time_units: generations
+ demes:
+  - name: A
+    epochs:
+     - start_size: 50
+  - name: B
+    epochs:
+     - start_size: 50
+  - name: C
+    epochs:
+     - start_size: 50
+ pulses:
+  - sources: [A, B]
+    dest: C
+    time: 10
+    proportions: [0.25, 0.5]
+    ";
+        let mut b = GraphBuilder::new_generations(None);
+        for name in ["A", "B", "C"] {
+            b.add_deme_with(name, |deme| {
+                deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+            });
+        }
+        b.add_pulse_builder(
+            UnresolvedPulse::default()
+                .set_sources(vec!["A".to_string(), "B".to_string()])
+                .set_dest("C")
+                .set_time(10.)
+                .set_proportions(vec![0.25, 0.5]),
+        );
+        let graph = b.resolve().unwrap();
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+    }
+
+    #[test]
+    fn builder_check() {
+        let context = |e: &BuilderError| match e {
+            BuilderError::DemesError(e) => e.context().unwrap().clone(),
+            _ => panic!("expected a DemesError"),
+        };
+        let mut b = GraphBuilder::new_generations(None);
+        assert!(b.check().is_ok());
+        b.add_deme_with("A", |deme| {
+            deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+        });
+        b.add_deme_with("B", |deme| {
+            deme.epoch_defaults(UnresolvedEpoch::default().set_start_size(50.))
+        });
+        assert!(b.check().is_ok());
+        b.add_deme_with("A", |deme| {
+            deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+        });
+        b.add_deme_with("C", |deme| deme.ancestors(["A", "X", "Y"]));
+        let errors = b.check().unwrap_err();
+        assert_eq!(errors.len(), 4);
+        let expected = [
+            (crate::ErrorKind::DuplicateDeme, "A"),
+            (crate::ErrorKind::Missing, "C"),
+            (crate::ErrorKind::UnknownDeme, "X"),
+            (crate::ErrorKind::UnknownDeme, "Y"),
+        ];
+        for (error, (kind, deme)) in errors.iter().zip(expected) {
+            assert_eq!(context(error).kind(), kind);
+            assert_eq!(context(error).deme(), Some(deme));
+        }
+        // The builder is still usable
+        assert!(b.resolve().is_err());
+
+        // Top-level defaults
+        let defaults = GraphDefaults {
+            epoch: UnresolvedEpoch::default().set_end_size(10.),
+            deme: crate::TopLevelDemeDefaults {
+                ancestors: Some(vec!["X".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut b = GraphBuilder::new_generations(Some(defaults));
+        b.add_deme_with("A", |deme| deme.ancestors(Vec::<String>::new()));
+        b.add_deme_with("B", |deme| deme);
+        let errors = b.check().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(context(&errors[0]).deme(), Some("X"));
+    }
+
+    #[test]
+    fn builder_replace_and_modify_deme() {
+        let mut b = GraphBuilder::new_generations(None);
+        for name in ["A", "B"] {
+            b.add_deme_with(name, |deme| {
+                deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+            });
+        }
+        b.replace_deme("A", |deme| {
+            deme.description("replaced")
+                .epoch(
+                    UnresolvedEpoch::default()
+                        .set_start_size(10.)
+                        .set_end_time(100.),
+                )
+                .epoch(UnresolvedEpoch::default().set_start_size(20.))
+        })
+        .unwrap();
+        b.modify_deme("B", |deme| {
+            deme.set_description("modified");
+            deme.ancestors = Some(vec!["A".to_string()]);
+            deme.start_time = Some(150.0.into());
+            deme.epochs_mut()[0].end_size = Some(100.0.into());
+        })
+        .unwrap();
+        assert!(matches!(
+            b.modify_deme("C", |_| ()),
+            Err(BuilderError::DemesError(DemesError::DemeError(_)))
+        ));
+        let graph = b.resolve().unwrap();
+        let yaml = "
+ time_units: generations
+ demes:
+  - name: A
+    description: replaced
+    epochs:
+     - start_size: 10
+       end_time: 100
+     - start_size: 20
+  - name: B
+    description: modified
+    ancestors: [A]
+    start_time: 150
+    epochs:
+     - start_size: 50
+       end_size: 100
+    ";
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+    }
+
+    #[test]
+    fn builder_remove() {
+        let yaml = "
+ time_units: generations
+ demes:
+  - name: A
+    epochs:
+     - start_size: 50
+  - name: C
+    epochs:
+     - start_size: 50
+ migrations:
+  - source: C
+    dest: A
+    rate: 1e-4
+ pulses:
+  - sources: [A]
+    dest: C
+    time: 20
+    proportions: [0.2]
+    ";
+        let mut b = GraphBuilder::new_generations(None);
+        for name in ["A", "B", "C"] {
+            b.add_deme_with(name, |deme| {
+                deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+            });
+        }
+        b.add_symmetric_migration(["A", "B"], 1e-3, None::<f64>, None);
+        b.add_migration(
+            UnresolvedMigration::default()
+                .set_source("C")
+                .set_dest("A")
+                .set_rate(1e-4),
+        );
+        for (source, time) in [("A", 10.), ("B", 15.), ("A", 20.)] {
+            b.add_pulse_builder(
+                UnresolvedPulse::default()
+                    .set_sources([source])
+                    .set_dest("C")
+                    .set_time(time)
+                    .set_proportions([0.2]),
+            );
+        }
+        // Removing B leaves references to it
+        let removed = b.remove_deme("B").unwrap();
+        assert_eq!(removed.name(), "B");
+        assert!(b.remove_deme("B").is_err());
+        let removed = b.remove_migration(0).unwrap();
+        assert_eq!(removed.demes.unwrap(), ["A", "B"]);
+        assert!(b.remove_migration(1).is_none());
+        assert_eq!(b.remove_pulse(1).unwrap().time, Some(15.0.into()));
+        assert_eq!(b.remove_pulse(0).unwrap().time, Some(10.0.into()));
+        assert!(b.remove_pulse(1).is_none());
+        let graph = b.resolve().unwrap();
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+    }
+
+    #[test]
+    fn builder_defaults() {
+        let yaml = "
+ time_units: generations
+ defaults:
+   epoch:
+     start_size: 50
+   migration:
+     rate: 1e-3
+   pulse:
+     proportions: [0.5]
+     time: 10
+ demes:
+  - name: A
+  - name: B
+ migrations:
+  - demes: [A, B]
+ pulses:
+  - sources: [A]
+    dest: B
+    ";
+        let mut b = GraphBuilder::new_generations(None);
+        b.set_epoch_defaults(UnresolvedEpoch::default().set_start_size(50.));
+        b.set_migration_defaults(UnresolvedMigration::default().set_rate(1e-3));
+        b.set_pulse_defaults(
+            UnresolvedPulse::default()
+                .set_proportions([0.5])
+                .set_time(10.),
+        );
+        for name in ["A", "B"] {
+            b.add_deme_with(name, |deme| deme);
+        }
+        b.add_symmetric_migration(["A", "B"], 1e-3, None::<f64>, None);
+        b.add_pulse_builder(UnresolvedPulse::default().set_sources(["A"]).set_dest("B"));
+        let graph = b.resolve().unwrap();
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+
+        // Replacing all defaults
+        let mut b = GraphBuilder::new_generations(None);
+        b.set_epoch_defaults(UnresolvedEpoch::default().set_start_size(50.));
+        b.set_defaults(GraphDefaults::default());
+        b.add_deme_with("A", |deme| deme);
+        assert!(b.resolve().is_err());
+    }
+
+    #[test]
+    fn builder_years() {
+        let yaml = "
+ time_units: years
+ generation_time: 25
+ demes:
+  - name: A
+    epochs:
+     - start_size: 50
+       end_time: 500
+     - start_size: 100
+  - name: B
+    epochs:
+     - start_size: 50
+ migrations:
+  - demes: [A, B]
+    rate: 1e-3
+    start_time: 400
+    end_time: 100
+    ";
+        let build = || {
+            let mut b = GraphBuilder::new_years(25.0, None);
+            b.add_deme_with("A", |deme| {
+                deme.epoch(
+                    UnresolvedEpoch::default()
+                        .set_start_size(50.)
+                        .set_end_time(500.),
+                )
+                .epoch(UnresolvedEpoch::default().set_start_size(100.))
+            });
+            b.add_deme_with("B", |deme| {
+                deme.epoch(UnresolvedEpoch::default().set_start_size(50.))
+            });
+            b.add_symmetric_migration(["A", "B"], 1e-3, Some(400.), Some(100.));
+            b
+        };
+        let graph = crate::loads(yaml).unwrap();
+        assert_eq!(build().resolve().unwrap(), graph);
+        let converted = build().resolve_into_generations().unwrap();
+        assert_eq!(converted, graph.into_generations().unwrap());
+        assert_eq!(converted.time_units(), TimeUnits::Generations);
+        assert_eq!(converted.migrations()[0].end_time(), 4.0);
+    }
+
+    #[test]
+    fn builder_clone() {
+        let mut base = GraphBuilder::new_generations(None);
+        base.add_deme_with("A", |deme| {
+            deme.epoch(UnresolvedEpoch::default().set_start_size(100.))
+        });
+        base.set_toplevel_metadata(&std::collections::BTreeMap::from([("x", 1)]))
+            .unwrap();
+        let unmodified = base.clone();
+        assert!(Arc::ptr_eq(&base.graph, &unmodified.graph));
+
+        let mut fork = base.clone();
+        fork.modify_deme("A", |deme| deme.set_description("modified"))
+            .unwrap();
+        assert!(!Arc::ptr_eq(&base.graph, &fork.graph));
+        assert!(Arc::ptr_eq(&base.graph, &unmodified.graph));
+
+        let fork = fork.resolve().unwrap();
+        let base = base.resolve().unwrap();
+        assert_eq!(fork.deme(0).description(), "modified");
+        assert_eq!(base.deme(0).description(), "");
+        assert_eq!(unmodified.resolve().unwrap(), base);
+        assert!(base.metadata().is_some());
+        assert_eq!(fork.metadata(), base.metadata());
+    }
+
+    #[test]
+    fn builder_resolve_all_errors() {
+        let epoch = |size: f64| UnresolvedEpoch::default().set_start_size(size);
+        let mut b = GraphBuilder::new_generations(None);
+        b.add_deme_with("A", |deme| deme.epoch(epoch(-1.)));
+        b.add_deme_with("B", |deme| deme.epoch(epoch(100.)));
+        // Not checked: the ancestor fails to resolve
+        b.add_deme_with("C", |deme| {
+            deme.ancestors(["A"]).start_time(50.).epoch(epoch(-1.))
+        });
+        b.add_deme_with("D", |deme| deme.epoch(epoch(100.)));
+        b.add_deme_with("E", |deme| deme.epoch(epoch(f64::NAN)));
+        b.add_migration(
+            UnresolvedMigration::default()
+                .set_demes(["B", "D"])
+                .set_rate(2.),
+        );
+        // Not checked: the source fails to resolve
+        b.add_migration(
+            UnresolvedMigration::default()
+                .set_source("A")
+                .set_dest("B")
+                .set_rate(2.),
+        );
+        b.add_pulse_builder(
+            UnresolvedPulse::default()
+                .set_sources(["B"])
+                .set_dest("D")
+                .set_time(10.)
+                .set_proportions([1.5]),
+        );
+        b.add_pulse_builder(
+            UnresolvedPulse::default()
+                .set_sources(["D"])
+                .set_dest("B")
+                .set_time(10.)
+                .set_proportions([0.5]),
+        );
+        let errors = b.clone().resolve_all_errors().unwrap_err();
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        // Epoch sizes are validated after all demes are resolved
+        assert!(errors[0].to_string().contains("deme E"));
+        assert_eq!(errors[1].context().unwrap().deme(), Some("A"));
+        assert!(matches!(errors[2], DemesError::MigrationError(_)));
+        assert!(matches!(errors[3], DemesError::PulseError(_)));
+        // The first error is the same as that of resolve
+        assert_eq!(
+            b.clone().resolve().unwrap_err().to_string(),
+            errors[0].to_string()
+        );
+
+        // A graph with no errors
+        b.modify_deme("A", |deme| deme.epochs_mut()[0] = epoch(100.))
+            .unwrap();
+        b.modify_deme("C", |deme| deme.epochs_mut()[0] = epoch(100.))
+            .unwrap();
+        b.modify_deme("E", |deme| deme.epochs_mut()[0] = epoch(100.))
+            .unwrap();
+        b.remove_migration(0);
+        b.remove_migration(0);
+        b.remove_pulse(0);
+        assert_eq!(
+            b.clone().resolve_all_errors().unwrap(),
+            b.resolve().unwrap()
+        );
+
+        // Errors in the graph as a whole end resolution
+        let mut b = GraphBuilder::new_generations(None);
+        b.add_deme_with("A", |deme| deme.epoch(epoch(-1.)));
+        assert_eq!(b.clone().resolve_all_errors().unwrap_err().len(), 1);
+        b.add_deme_with("A", |deme| deme.epoch(epoch(1.)));
+        let errors = b.resolve_all_errors().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].context().unwrap().kind(),
+            ErrorKind::DuplicateDeme
+        );
+    }
+
+    #[test]
+    fn builder_set_deme_epoch_defaults() {
+        let yaml = "
+time_units: generations
+defaults:
+  epoch: {start_size: 1}
+demes:
+ - name: A
+   defaults:
+     epoch: {start_size: 100, selfing_rate: 0.5}
+   epochs:
+    - end_time: 50
+    - start_size: 200
+ - name: B
+   defaults:
+     epoch: {cloning_rate: 0.25}
+   epochs:
+    - {}
+";
+        let expected = crate::loads(yaml).unwrap();
+        let mut b = GraphBuilder::new_generations(None);
+        b.set_epoch_defaults(UnresolvedEpoch::default().set_start_size(1.));
+        b.add_deme(
+            "A",
+            [
+                UnresolvedEpoch::default().set_end_time(50.),
+                UnresolvedEpoch::default().set_start_size(200.),
+            ],
+            UnresolvedDemeHistory::default().set_epoch_defaults(
+                UnresolvedEpoch::default()
+                    .set_start_size(100.)
+                    .set_selfing_rate(0.5),
+            ),
+            None,
+        );
+        b.add_deme_with("B", |deme| deme.epoch(UnresolvedEpoch::default()));
+        b.set_deme_epoch_defaults("B", UnresolvedEpoch::default().set_cloning_rate(0.25))
+            .unwrap();
+        assert_eq!(
+            b.graph.demes()[1].defaults().epoch,
+            UnresolvedEpoch::default().set_cloning_rate(0.25)
+        );
+        assert!(b
+            .set_deme_epoch_defaults("C", UnresolvedEpoch::default())
+            .is_err());
+        assert_eq!(b.resolve().unwrap(), expected);
+    }
+
+    #[test]
+    fn builder_add_epoch() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - {start_size: 100, end_time: 50}
+    - {start_size: 200, end_time: 10}
+    - {start_size: 300}
+ - name: B
+   ancestors: [A]
+   start_time: 20
+   epochs:
+    - {start_size: 10, end_time: 5}
+    - {start_size: 20}
+";
+        let epoch = |size: f64, end_time: f64| {
+            UnresolvedEpoch::default()
+                .set_start_size(size)
+                .set_end_time(end_time)
+        };
+        let mut b = GraphBuilder::new_generations(None);
+        assert!(b.add_epoch(epoch(1., 0.)).is_err());
+        b.add_deme("A", [], UnresolvedDemeHistory::default(), None);
+        b.add_epoch(epoch(100., 50.)).unwrap();
+        b.add_epoch(epoch(200., 10.)).unwrap();
+        b.add_deme_with("B", |deme| {
+            deme.ancestors(["A"]).start_time(20.).epoch(epoch(10., 5.))
+        });
+        b.add_epoch(epoch(20., 0.)).unwrap();
+        b.add_epoch_to("A", epoch(300., 0.)).unwrap();
+        assert!(b.add_epoch_to("C", epoch(300., 0.)).is_err());
+        assert_eq!(b.resolve().unwrap(), crate::loads(yaml).unwrap());
+    }
+
     #[test]
     fn test_invalid_pulse() {
         let mut b = GraphBuilder::new(TimeUnits::Generations, None, None);
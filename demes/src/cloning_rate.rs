@@ -1,4 +1,6 @@
 use crate::error::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
 use serde::{Deserialize, Serialize};
 
 /// The cloning rate of an [`Epoch`](crate::Epoch).
@@ -10,11 +12,13 @@ pub struct CloningRate(f64);
 impl CloningRate {
     fn validate<F>(&self, f: F) -> Result<(), DemesError>
     where
-        F: std::ops::FnOnce(String) -> DemesError,
+        F: std::ops::FnOnce(ErrorContext) -> DemesError,
     {
         if !self.0.is_finite() || self.0.is_sign_negative() || self.0 > 1.0 {
             let msg = format!("cloning rate must be 0.0 <= C <= 1.0, got: {}", self.0);
-            Err(f(msg))
+            Err(f(
+                ErrorContext::new(msg, ErrorKind::InvalidValue).with_value(self.0)
+            ))
         } else {
             Ok(())
         }
@@ -0,0 +1,284 @@
+use std::io::Write;
+
+use crate::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
+use crate::Graph;
+use crate::Time;
+
+/// Generate evenly-spaced times for use with
+/// [`Graph::write_size_trajectories_csv`].
+///
+/// # Parameters
+///
+/// * `start`: the first time
+/// * `end`: the last time
+/// * `num_points`: the number of times
+///
+/// `start` may be more ancient or more recent than `end`.
+///
+/// # Errors
+///
+/// [`DemesError::ValueError`] if `start` or `end` are not finite
+/// and non-negative, or if `num_points` is less than two.
+///
+/// Requires the `csv` feature.
+///
+/// # Examples
+///
+/// ```
+/// let times = demes::uniform_time_grid(100.0, 0.0, 5).unwrap();
+/// assert_eq!(times, [100.0, 75.0, 50.0, 25.0, 0.0]);
+/// ```
+pub fn uniform_time_grid(start: f64, end: f64, num_points: usize) -> Result<Vec<Time>, DemesError> {
+    for value in [start, end] {
+        if !value.is_finite() || value < 0.0 {
+            return Err(DemesError::ValueError(
+                ErrorContext::new(
+                    format!("grid times must be finite and non-negative, got: {value}"),
+                    ErrorKind::InvalidValue,
+                )
+                .with_value(value),
+            ));
+        }
+    }
+    if num_points < 2 {
+        return Err(DemesError::ValueError(
+            format!("a grid requires at least two points, got: {num_points}").into(),
+        ));
+    }
+    let step = (end - start) / (num_points - 1) as f64;
+    (0..num_points)
+        .map(|i| {
+            if i + 1 == num_points {
+                Time::try_from(end)
+            } else {
+                // The max guards against rounding below zero
+                Time::try_from((start + step * i as f64).max(0.0))
+            }
+        })
+        .collect()
+}
+
+impl Graph {
+    /// Write the size of each deme at each of a set of times as CSV.
+    ///
+    /// The output has a header line followed by one line per time and
+    /// deme, with the columns `time`, `deme`, and `size`.
+    /// Deme names are valid identifiers and so never need quoting.
+    /// Lines are written in the order of `times` and then in the order
+    /// of the demes in the graph.
+    /// A deme is omitted at times when it does not exist.
+    ///
+    /// Requires the `csv` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::IOerror`] if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///  - name: B
+    ///    ancestors: [A]
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_size: 400
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let times = demes::uniform_time_grid(100.0, 0.0, 3).unwrap();
+    /// let mut output = vec![];
+    /// graph.write_size_trajectories_csv(&mut output, &times).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "time,deme,size\n100,A,100\n50,A,100\n0,B,400\n"
+    /// );
+    /// ```
+    pub fn write_size_trajectories_csv<W: Write>(
+        &self,
+        writer: W,
+        times: &[Time],
+    ) -> Result<(), DemesError> {
+        let mut writer = writer;
+        writeln!(writer, "time,deme,size")?;
+        for &time in times {
+            for deme in self.demes() {
+                if let Some(size) = deme.size_at(time)? {
+                    writeln!(writer, "{},{},{}", time, deme.name(), size)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the migrations of the graph as CSV.
+    ///
+    /// The output has a header line followed by one line per
+    /// migration, with the columns `source`, `dest`, `start_time`,
+    /// `end_time`, and `rate`.
+    ///
+    /// Requires the `csv` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::IOerror`] if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - demes: [A, B]
+    ///    rate: 1e-3
+    ///    start_time: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let mut output = vec![];
+    /// graph.write_migrations_csv(&mut output).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "source,dest,start_time,end_time,rate\nA,B,100,0,0.001\nB,A,100,0,0.001\n"
+    /// );
+    /// ```
+    pub fn write_migrations_csv<W: Write>(&self, writer: W) -> Result<(), DemesError> {
+        let mut writer = writer;
+        writeln!(writer, "source,dest,start_time,end_time,rate")?;
+        for m in self.migrations() {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                m.source(),
+                m.dest(),
+                m.start_time(),
+                m.end_time(),
+                m.rate()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the pulses of the graph as CSV.
+    ///
+    /// The output has a header line followed by one line per
+    /// source of each pulse, with the columns `time`, `source`,
+    /// `dest`, and `proportion`.
+    /// Pulses are written in the order that they happen.
+    ///
+    /// Requires the `csv` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::IOerror`] if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: C
+    ///    epochs:
+    ///     - start_size: 100
+    /// pulses:
+    ///  - sources: [A, B]
+    ///    dest: C
+    ///    time: 10
+    ///    proportions: [0.25, 0.5]
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let mut output = vec![];
+    /// graph.write_pulses_csv(&mut output).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "time,source,dest,proportion\n10,A,C,0.25\n10,B,C,0.5\n"
+    /// );
+    /// ```
+    pub fn write_pulses_csv<W: Write>(&self, writer: W) -> Result<(), DemesError> {
+        let mut writer = writer;
+        writeln!(writer, "time,source,dest,proportion")?;
+        for pulse in self.pulses() {
+            for (source, proportion) in pulse.sources().iter().zip(pulse.proportions()) {
+                writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    pulse.time(),
+                    source,
+                    pulse.dest(),
+                    proportion
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_time_grid() {
+        assert_eq!(uniform_time_grid(0.0, 1.0, 3).unwrap(), [0.0, 0.5, 1.0]);
+        assert_eq!(uniform_time_grid(0.3, 0.0, 4).unwrap()[3], 0.0);
+        assert!(uniform_time_grid(0.0, 1.0, 1).is_err());
+        assert!(uniform_time_grid(-1.0, 1.0, 2).is_err());
+        assert!(uniform_time_grid(f64::INFINITY, 1.0, 2).is_err());
+        assert!(uniform_time_grid(0.0, f64::NAN, 2).is_err());
+    }
+
+    #[test]
+    fn test_size_trajectories() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 100
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      end_size: 200
+      size_function: linear
+";
+        let graph = crate::loads(yaml).unwrap();
+        let times = [f64::INFINITY, 150.0, 100.0, 50.0, 0.0]
+            .into_iter()
+            .map(|t| Time::try_from(t).unwrap())
+            .collect::<Vec<_>>();
+        let mut output = vec![];
+        graph
+            .write_size_trajectories_csv(&mut output, &times)
+            .unwrap();
+        let expected = "time,deme,size
+inf,A,100
+150,A,100
+100,A,100
+50,B,150
+0,B,200
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
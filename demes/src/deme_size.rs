@@ -1,4 +1,6 @@
 use crate::error::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
 use serde::{Deserialize, Serialize};
 
 /// The size of a [`Deme`](crate::Deme) at a given [`Time`](crate::Time).
@@ -45,11 +47,13 @@ pub struct DemeSize(f64);
 impl DemeSize {
     fn validate<F>(&self, f: F) -> Result<(), DemesError>
     where
-        F: std::ops::FnOnce(String) -> DemesError,
+        F: std::ops::FnOnce(ErrorContext) -> DemesError,
     {
         if self.0.is_nan() || self.0.is_infinite() || self.0 <= 0.0 {
             let msg = format!("deme sizes must be 0 <= d < Infinity, got: {}", self.0);
-            Err(f(msg))
+            Err(f(
+                ErrorContext::new(msg, ErrorKind::InvalidValue).with_value(self.0)
+            ))
         } else {
             Ok(())
         }
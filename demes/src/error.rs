@@ -1,5 +1,131 @@
 use thiserror::Error;
 
+/// The kind of problem described by an [`ErrorContext`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A value is out of range, such as a negative deme size.
+    InvalidValue,
+    /// A required value is missing or could not be resolved.
+    Missing,
+    /// A deme name does not refer to a deme in the graph.
+    UnknownDeme,
+    /// The same deme name is used more than once.
+    DuplicateDeme,
+    /// Values that are valid on their own conflict with each other,
+    /// such as overlapping time intervals or a mismatch between
+    /// the numbers of ancestors and proportions.
+    Inconsistent,
+    /// Any other problem.
+    Other,
+}
+
+/// Structured data associated with the variants of
+/// [`DemesError`] that describe invalid models.
+///
+/// Tools that handle errors programmatically
+/// should use the accessors of this type rather than
+/// parsing error messages.
+///
+/// # Examples
+///
+/// ```
+/// let yaml = "
+/// time_units: generations
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+///       end_time: 10
+///     - start_size: -1
+/// ";
+/// let error = demes::loads(yaml).unwrap_err();
+/// let context = error.context().unwrap();
+/// assert_eq!(context.kind(), demes::ErrorKind::InvalidValue);
+/// assert_eq!(context.value(), Some(-1.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorContext {
+    message: String,
+    kind: ErrorKind,
+    deme: Option<String>,
+    epoch: Option<usize>,
+    value: Option<f64>,
+}
+
+impl ErrorContext {
+    pub(crate) fn new<S: Into<String>>(message: S, kind: ErrorKind) -> Self {
+        Self {
+            message: message.into(),
+            kind,
+            deme: None,
+            epoch: None,
+            value: None,
+        }
+    }
+
+    pub(crate) fn with_deme<S: Into<String>>(self, deme: S) -> Self {
+        Self {
+            deme: Some(deme.into()),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_value(self, value: f64) -> Self {
+        Self {
+            value: Some(value),
+            ..self
+        }
+    }
+
+    /// The error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The name of the deme associated with the error, if known.
+    pub fn deme(&self) -> Option<&str> {
+        self.deme.as_deref()
+    }
+
+    /// The index of the epoch associated with the error, if known.
+    ///
+    /// The index is with respect to the epochs of [`ErrorContext::deme`].
+    pub fn epoch(&self) -> Option<usize> {
+        self.epoch
+    }
+
+    /// The offending value, if known.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+impl From<String> for ErrorContext {
+    fn from(message: String) -> Self {
+        Self::new(message, ErrorKind::Other)
+    }
+}
+
+impl From<&str> for ErrorContext {
+    fn from(message: &str) -> Self {
+        Self::new(message, ErrorKind::Other)
+    }
+}
+
+// The message is quoted, matching the output of
+// earlier versions in which the variants held a String.
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.message)
+    }
+}
+
 /// Error type for this crate.
 ///
 /// The enum fields correspond to
@@ -29,20 +155,20 @@ use thiserror::Error;
 #[non_exhaustive]
 pub enum DemesError {
     /// Errors related to demes
-    #[error("{0:?}")]
-    DemeError(String),
-    #[error("{0:?}")]
+    #[error("{0}")]
+    DemeError(ErrorContext),
+    #[error("{0}")]
     /// Errors related to epochs
-    EpochError(String),
-    #[error("{0:?}")]
+    EpochError(ErrorContext),
+    #[error("{0}")]
     /// Top-level errors.
-    GraphError(String),
-    #[error("{0:?}")]
+    GraphError(ErrorContext),
+    #[error("{0}")]
     /// Errors related to migrations
-    MigrationError(String),
-    #[error("{0:?}")]
+    MigrationError(ErrorContext),
+    #[error("{0}")]
     /// Errors related to pulses
-    PulseError(String),
+    PulseError(ErrorContext),
     #[error(transparent)]
     /// Errors coming from `serde_yaml`.
     YamlError(#[from] serde_yaml::Error),
@@ -56,10 +182,128 @@ pub enum DemesError {
     #[error(transparent)]
     /// Errors coming from `toml` during deserialization.
     TomlDeError(#[from] toml::de::Error),
+    #[cfg(feature = "toml")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "toml")))]
+    #[error(transparent)]
+    /// Errors coming from `toml` during serialization.
+    TomlSerError(#[from] toml::ser::Error),
     /// Errors related to low-level types
-    #[error("{0:?}")]
-    ValueError(String),
+    #[error("{0}")]
+    ValueError(ErrorContext),
+    /// Errors related to top-level metadata
+    #[error("{0}")]
+    MetadataError(ErrorContext),
+    /// An error associated with a location in YAML input.
+    ///
+    /// See [`LoadOptions::set_locate_errors`](crate::LoadOptions::set_locate_errors).
+    #[error("{error} (at {location})")]
+    Located {
+        /// The location of the object causing the error
+        location: crate::Location,
+        /// The error
+        error: Box<DemesError>,
+    },
+    /// A limit set by [`LoadOptions`](crate::LoadOptions) was exceeded.
+    #[error("{limit} exceeds the maximum of {max}: {found}")]
+    ResourceLimitExceeded {
+        /// The limit that was exceeded
+        limit: crate::ResourceLimit,
+        /// The maximum value allowed
+        max: usize,
+        /// The value found in the input
+        found: usize,
+    },
     /// IO errors from the rust standard library
     #[error(transparent)]
     IOerror(#[from] std::io::Error),
 }
+
+impl DemesError {
+    /// Structured data about the error, if available.
+    ///
+    /// Available for the variants that describe invalid models,
+    /// including those wrapped in [`DemesError::Located`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    ancestors: [X]
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let error = demes::loads(yaml).unwrap_err();
+    /// let context = error.context().unwrap();
+    /// assert_eq!(context.kind(), demes::ErrorKind::UnknownDeme);
+    /// assert_eq!(context.deme(), Some("X"));
+    /// ```
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::DemeError(context)
+            | Self::EpochError(context)
+            | Self::GraphError(context)
+            | Self::MigrationError(context)
+            | Self::PulseError(context)
+            | Self::ValueError(context)
+            | Self::MetadataError(context) => Some(context),
+            Self::Located { error, .. } => error.context(),
+            _ => None,
+        }
+    }
+
+    // Attach a deme and epoch to the context
+    // unless they are already known.
+    pub(crate) fn in_epoch(self, deme: &str, epoch: usize) -> Self {
+        let update = |context: ErrorContext| ErrorContext {
+            deme: context.deme.or_else(|| Some(deme.to_string())),
+            epoch: context.epoch.or(Some(epoch)),
+            ..context
+        };
+        match self {
+            Self::DemeError(context) => Self::DemeError(update(context)),
+            Self::EpochError(context) => Self::EpochError(update(context)),
+            Self::ValueError(context) => Self::ValueError(update(context)),
+            _ => self,
+        }
+    }
+
+    /// The location in the YAML input associated with the error, if known.
+    ///
+    /// Locations are known for:
+    ///
+    /// * errors raised while parsing YAML input, such as syntax errors,
+    ///   unknown fields, or values of the wrong type.
+    /// * errors raised while resolving a specific deme, migration, or pulse
+    ///   if locations are requested via [`LoadOptions`](crate::LoadOptions).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    start_time: 100
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let options = demes::LoadOptions::default().set_locate_errors(true);
+    /// let error = demes::loads_with_options(yaml, &options).unwrap_err();
+    /// let location = error.location().unwrap();
+    /// assert_eq!(location.line(), 7);
+    /// assert_eq!(location.column(), 4);
+    /// ```
+    pub fn location(&self) -> Option<crate::Location> {
+        match self {
+            Self::YamlError(e) => e.location().map(crate::Location::from),
+            Self::Located { location, .. } => Some(*location),
+            _ => None,
+        }
+    }
+}
@@ -778,7 +778,8 @@ pub extern "C" fn demes_deme_num_ancestors(deme: &Deme) -> usize {
 #[no_mangle]
 pub extern "C" fn demes_deme_ancestor_indexes(deme: &Deme) -> *const usize {
     if !deme.ancestor_indexes().is_empty() {
-        deme.ancestor_indexes().as_ptr()
+        // DemeIndex is repr(transparent) over usize
+        deme.ancestor_indexes().as_ptr().cast::<usize>()
     } else {
         std::ptr::null()
     }
@@ -1689,7 +1690,7 @@ fn test_ancestors_and_proportions() {
             let ancestor = demes_graph_deme(&graph, *ancestors.add(i));
             let name = demes_deme_name(ancestor.as_ref().unwrap());
             let cname = CStr::from_ptr(name).to_str().unwrap();
-            assert_eq!(deme.ancestor_names()[i], cname);
+            assert_eq!(&*deme.ancestor_names()[i], cname);
             assert_eq!(*proportions.add(i), deme.proportions()[i]);
             demes_c_char_deallocate(name);
         }
@@ -1714,7 +1715,7 @@ fn test_pulses() {
         for j in 0..num_proportions {
             let source = demes_pulse_source(pref, j);
             let source_ref = unsafe { CStr::from_ptr(source) }.to_str().unwrap();
-            assert_eq!(graph.pulses()[i].sources()[j], source_ref);
+            assert_eq!(&*graph.pulses()[i].sources()[j], source_ref);
             unsafe { demes_c_char_deallocate(source) };
             let dest = demes_pulse_dest(pref);
             let dest_ref = unsafe { CStr::from_ptr(dest) }.to_str().unwrap();
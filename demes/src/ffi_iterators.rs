@@ -229,7 +229,7 @@ impl DemeAncestorIterator {
 
         if c < self.num_ancestors {
             let deme = &self.parent_graph.as_ref().demes()
-                [self.offspring_deme.as_ref().ancestor_indexes()[c]];
+                [usize::from(self.offspring_deme.as_ref().ancestor_indexes()[c])];
             let proportion: f64 = self.offspring_deme.as_ref().proportions()[c].into();
             self.output.deme = deme;
             self.output.proportion = proportion;
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+/// The format of a file containing a [`Graph`](crate::Graph).
+///
+/// See [`load_path`](crate::load_path) and
+/// [`Graph::save_as`](crate::Graph::save_as).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FileFormat {
+    /// YAML
+    Yaml,
+    /// JSON. Requires the `json` feature.
+    Json,
+    /// TOML. Requires the `toml` feature.
+    Toml,
+}
+
+impl FileFormat {
+    /// The format implied by the extension of a file name.
+    ///
+    /// Extensions are not case-sensitive.
+    /// Returns `None` if the extension is missing or not recognized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use demes::FileFormat;
+    /// assert_eq!(FileFormat::from_path("model.yml"), Some(FileFormat::Yaml));
+    /// assert_eq!(FileFormat::from_path("model.JSON"), Some(FileFormat::Json));
+    /// assert_eq!(FileFormat::from_path("model.txt"), None);
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        for (path, format) in [
+            ("model.yaml", Some(FileFormat::Yaml)),
+            ("model.YML", Some(FileFormat::Yaml)),
+            ("dir.json/model.json", Some(FileFormat::Json)),
+            ("model.toml", Some(FileFormat::Toml)),
+            ("model.yaml.gz", None),
+            ("model.txt", None),
+            ("model", None),
+        ] {
+            assert_eq!(FileFormat::from_path(path), format, "{path}");
+        }
+    }
+}
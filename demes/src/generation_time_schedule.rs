@@ -0,0 +1,258 @@
+// NOTE: this module is an extension to the demes specification.
+
+use crate::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
+use crate::GenerationTime;
+use crate::Graph;
+use crate::Time;
+
+/// A generation time that is piecewise-constant over time.
+///
+/// **This is an extension to the demes specification.**
+/// A [`Graph`] has a single generation time.
+/// A schedule is only used to convert the times of a graph
+/// into generations, see [`Graph::into_generations_with_schedule`].
+///
+/// Each piece of the schedule has a start time, in the time units
+/// of the graph, and the generation time that applies from that
+/// time until the start of the next (more ancient) piece.
+/// The last piece extends to infinity.
+///
+/// Requires the `generation_time_schedule` feature.
+///
+/// # Examples
+///
+/// ```
+/// // 25 years per generation for the last 10,000 years
+/// // and 20 years per generation before that.
+/// let schedule = demes::GenerationTimeSchedule::new(&[(0.0, 25.0), (1e4, 20.0)]).unwrap();
+/// assert_eq!(schedule.to_generations(5e3.try_into().unwrap()), 200.0);
+/// assert_eq!(schedule.to_generations(2e4.try_into().unwrap()), 900.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "generation_time_schedule")))]
+pub struct GenerationTimeSchedule {
+    start_times: Vec<Time>,
+    generation_times: Vec<GenerationTime>,
+}
+
+fn schedule_error<S: Into<String>>(message: S) -> DemesError {
+    DemesError::ValueError(ErrorContext::new(message, ErrorKind::InvalidValue))
+}
+
+impl GenerationTimeSchedule {
+    /// Create a new schedule.
+    ///
+    /// # Parameters
+    ///
+    /// * `pieces`: the `(start_time, generation_time)` of each piece,
+    ///   ordered from the most recent to the most ancient.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError::ValueError`] if:
+    ///
+    /// * `pieces` is empty or the first start time is not zero.
+    /// * Start times are not finite or do not strictly increase.
+    /// * A generation time is not finite and greater than zero.
+    pub fn new(pieces: &[(f64, f64)]) -> Result<Self, DemesError> {
+        match pieces.first() {
+            Some((start_time, _)) if *start_time == 0.0 => (),
+            Some(_) => return Err(schedule_error("the first piece must start at time 0")),
+            None => return Err(schedule_error("a schedule requires at least one piece")),
+        }
+        let mut start_times = Vec::with_capacity(pieces.len());
+        let mut generation_times = Vec::with_capacity(pieces.len());
+        for (start_time, generation_time) in pieces.iter().copied() {
+            if !start_time.is_finite() {
+                return Err(schedule_error(format!(
+                    "start times must be finite, got: {start_time}"
+                )));
+            }
+            if start_times.last().is_some_and(|&last| start_time <= last) {
+                return Err(schedule_error(format!(
+                    "start times must be strictly increasing, got: {start_time}"
+                )));
+            }
+            if !generation_time.is_finite() || generation_time <= 0.0 {
+                return Err(schedule_error(format!(
+                    "generation time must be > 0.0, got: {generation_time}"
+                )));
+            }
+            start_times.push(Time::try_from(start_time)?);
+            generation_times.push(GenerationTime::try_from(generation_time)?);
+        }
+        Ok(Self {
+            start_times,
+            generation_times,
+        })
+    }
+
+    /// The generation time that applies at a given time.
+    pub fn generation_time_at(&self, time: Time) -> GenerationTime {
+        let index = self.start_times.partition_point(|&start| start <= time);
+        // index > 0 because the first piece starts at time 0
+        self.generation_times[index - 1]
+    }
+
+    /// Convert a time into generations.
+    ///
+    /// The number of generations is the integral of one over
+    /// the generation time from the present to `time`.
+    pub fn to_generations(&self, time: Time) -> Time {
+        let time = f64::from(time);
+        let mut generations = 0.0;
+        for (i, generation_time) in self.generation_times.iter().enumerate() {
+            let start = f64::from(self.start_times[i]);
+            if start >= time {
+                break;
+            }
+            let end = self
+                .start_times
+                .get(i + 1)
+                .map_or(time, |&next| f64::from(next).min(time));
+            generations += (end - start) / f64::from(*generation_time);
+        }
+        // Unwrap is fine: the sum of non-negative values is a valid time
+        Time::try_from(generations).unwrap()
+    }
+}
+
+impl Graph {
+    /// Convert the time units to generations using a
+    /// [`GenerationTimeSchedule`] in place of the generation time
+    /// of the graph.
+    ///
+    /// **This is an extension to the demes specification.**
+    ///
+    /// Requires the `generation_time_schedule` feature.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Graph::into_generations`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: years
+    /// generation_time: 25
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 20000
+    ///     - start_size: 200
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let schedule = demes::GenerationTimeSchedule::new(&[(0.0, 25.0), (1e4, 20.0)]).unwrap();
+    /// let graph = graph.into_generations_with_schedule(&schedule).unwrap();
+    /// assert_eq!(graph.time_units(), demes::TimeUnits::Generations);
+    /// assert_eq!(graph.deme(0).epochs()[0].end_time(), 900.0);
+    /// ```
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "generation_time_schedule")))]
+    pub fn into_generations_with_schedule(
+        self,
+        schedule: &GenerationTimeSchedule,
+    ) -> Result<Graph, DemesError> {
+        self.convert_to_generations_details(&mut |time, _| schedule.to_generations(time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(t: f64) -> Time {
+        Time::try_from(t).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        for pieces in [
+            vec![],
+            vec![(1.0, 25.0)],
+            vec![(0.0, 25.0), (10.0, 0.0)],
+            vec![(0.0, 25.0), (10.0, f64::INFINITY)],
+            vec![(0.0, 25.0), (10.0, 20.0), (10.0, 30.0)],
+            vec![(0.0, 25.0), (f64::INFINITY, 20.0)],
+            vec![(0.0, 25.0), (f64::NAN, 20.0)],
+        ] {
+            assert!(
+                matches!(
+                    GenerationTimeSchedule::new(&pieces),
+                    Err(DemesError::ValueError(_))
+                ),
+                "{pieces:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_generations() {
+        let schedule =
+            GenerationTimeSchedule::new(&[(0.0, 2.0), (10.0, 5.0), (20.0, 1.0)]).unwrap();
+        assert_eq!(schedule.generation_time_at(time(0.0)), 2.0);
+        assert_eq!(schedule.generation_time_at(time(10.0)), 5.0);
+        assert_eq!(schedule.generation_time_at(time(1e6)), 1.0);
+        assert_eq!(schedule.to_generations(time(0.0)), 0.0);
+        assert_eq!(schedule.to_generations(time(4.0)), 2.0);
+        assert_eq!(schedule.to_generations(time(15.0)), 6.0);
+        assert_eq!(schedule.to_generations(time(30.0)), 17.0);
+        assert_eq!(schedule.to_generations(time(f64::INFINITY)), f64::INFINITY);
+
+        // A constant schedule is the same as the generation time
+        let schedule = GenerationTimeSchedule::new(&[(0.0, 25.0)]).unwrap();
+        let t = time(1234.0);
+        assert_eq!(
+            schedule.to_generations(t),
+            crate::time::to_generations(t, GenerationTime::try_from(25.0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_into_generations_with_schedule() {
+        let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 200
+ - name: B
+   ancestors: [A]
+   start_time: 500
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B]
+   rate: 1e-3
+   start_time: 200
+pulses:
+ - sources: [A]
+   dest: B
+   time: 300
+   proportions: [0.5]
+";
+        let graph = crate::loads(yaml).unwrap();
+        let schedule = GenerationTimeSchedule::new(&[(0.0, 25.0)]).unwrap();
+        assert_eq!(
+            graph
+                .clone()
+                .into_generations_with_schedule(&schedule)
+                .unwrap(),
+            graph.clone().into_generations().unwrap()
+        );
+
+        let schedule = GenerationTimeSchedule::new(&[(0.0, 20.0), (400.0, 50.0)]).unwrap();
+        let converted = graph.into_generations_with_schedule(&schedule).unwrap();
+        assert_eq!(converted.generation_time(), 1.0);
+        assert_eq!(converted.deme(0).epochs()[0].end_time(), 32.0);
+        assert_eq!(converted.deme(1).start_time(), 22.0);
+        assert_eq!(converted.migrations()[0].start_time(), 10.0);
+        assert_eq!(converted.pulses()[0].time(), 15.0);
+    }
+}
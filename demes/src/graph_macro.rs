@@ -0,0 +1,308 @@
+/// Build a [`Graph`](crate::Graph) using a syntax close to that of `YAML`.
+///
+/// The macro expands to calls to [`GraphBuilder`](crate::GraphBuilder)
+/// and returns `Result<Graph, DemesError>`.
+///
+/// The input is a comma-separated list of `key: value` pairs.
+/// `time_units` must come first, followed by `generation_time`
+/// if it is given.
+/// The remaining keys may be given in any order:
+///
+/// * `description: "..."`
+/// * `doi: ["...", ...]`
+/// * `defaults: { epoch: {...}, migration: {...}, pulse: {...} }`
+/// * `demes: [name { ... }, ...]`
+/// * `migrations: [{ ... }, ...]`
+/// * `pulses: [{ ... }, ...]`
+///
+/// Deme names are identifiers.
+/// Time units are `generations`, `years`, or a string literal.
+/// Size functions are `constant`, `linear`, or `exponential`.
+///
+/// Misspelled keys and values of the wrong type are compile-time errors.
+/// Errors in the model itself, such as a reference to an undefined deme,
+/// are reported when the graph is resolved.
+///
+/// Each value must be a single token tree.
+/// Wrap expressions, including negative numbers, in parentheses.
+///
+/// Requires the `macros` feature.
+///
+/// # Examples
+///
+/// ```
+/// let n = 1000.0;
+/// let graph = demes::graph! {
+///     time_units: generations,
+///     demes: [
+///         A { epochs: [{ start_size: (2.0 * n), end_time: 100.0 }] },
+///         B { ancestors: [A], epochs: [{ start_size: n }] },
+///         C { ancestors: [A], epochs: [{ start_size: n, end_size: (10.0 * n) }] },
+///     ],
+///     migrations: [{ demes: [B, C], rate: 1e-3 }],
+///     pulses: [{ sources: [B], dest: C, time: 50.0, proportions: [0.1] }],
+/// }
+/// .unwrap();
+/// assert_eq!(graph.num_demes(), 3);
+/// assert_eq!(graph.deme(0).start_size(), 2000.0);
+/// assert_eq!(graph.migrations().len(), 2);
+/// ```
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "macros")))]
+macro_rules! graph {
+    (@time_units generations) => {
+        $crate::TimeUnits::Generations
+    };
+    (@time_units years) => {
+        $crate::TimeUnits::Years
+    };
+    (@time_units $units:literal) => {
+        $crate::TimeUnits::Custom(::std::string::String::from($units))
+    };
+    (@size_function constant) => {
+        $crate::SizeFunction::Constant
+    };
+    (@size_function linear) => {
+        $crate::SizeFunction::Linear
+    };
+    (@size_function exponential) => {
+        $crate::SizeFunction::Exponential
+    };
+    (@names [$($name:ident),* $(,)?]) => {
+        ::std::vec::Vec::<&str>::from([$(stringify!($name)),*])
+    };
+
+    (@epoch_field $e:ident, start_size, $v:expr) => {
+        $e.set_start_size($v)
+    };
+    (@epoch_field $e:ident, end_size, $v:expr) => {
+        $e.set_end_size($v)
+    };
+    (@epoch_field $e:ident, end_time, $v:expr) => {
+        $e.set_end_time($v)
+    };
+    (@epoch_field $e:ident, size_function, $v:ident) => {
+        $e.set_size_function($crate::graph!(@size_function $v))
+    };
+    (@epoch_field $e:ident, cloning_rate, $v:expr) => {
+        $e.set_cloning_rate($v)
+    };
+    (@epoch_field $e:ident, selfing_rate, $v:expr) => {
+        $e.set_selfing_rate($v)
+    };
+    (@epoch { $($f:ident : $v:tt),* $(,)? }) => {{
+        let epoch = $crate::UnresolvedEpoch::default();
+        $(let epoch = $crate::graph!(@epoch_field epoch, $f, $v);)*
+        epoch
+    }};
+
+    (@migration_field $m:ident, demes, $v:tt) => {
+        $m.set_demes($crate::graph!(@names $v))
+    };
+    (@migration_field $m:ident, source, $v:ident) => {
+        $m.set_source(stringify!($v))
+    };
+    (@migration_field $m:ident, dest, $v:ident) => {
+        $m.set_dest(stringify!($v))
+    };
+    (@migration_field $m:ident, rate, $v:expr) => {
+        $m.set_rate($v)
+    };
+    (@migration_field $m:ident, start_time, $v:expr) => {
+        $m.set_start_time($v)
+    };
+    (@migration_field $m:ident, end_time, $v:expr) => {
+        $m.set_end_time($v)
+    };
+    (@migration { $($f:ident : $v:tt),* $(,)? }) => {{
+        let migration = $crate::UnresolvedMigration::default();
+        $(let migration = $crate::graph!(@migration_field migration, $f, $v);)*
+        migration
+    }};
+
+    (@pulse_field $p:ident, sources, $v:tt) => {
+        $p.set_sources($crate::graph!(@names $v))
+    };
+    (@pulse_field $p:ident, dest, $v:ident) => {
+        $p.set_dest(stringify!($v))
+    };
+    (@pulse_field $p:ident, time, $v:expr) => {
+        $p.set_time($v)
+    };
+    (@pulse_field $p:ident, proportions, [$($v:expr),* $(,)?]) => {
+        $p.set_proportions(::std::vec::Vec::<f64>::from([$($v),*]))
+    };
+    (@pulse { $($f:ident : $v:tt),* $(,)? }) => {{
+        let pulse = $crate::UnresolvedPulse::default();
+        $(let pulse = $crate::graph!(@pulse_field pulse, $f, $v);)*
+        pulse
+    }};
+
+    (@deme_field $d:ident, description, $v:expr) => {
+        $d.description($v)
+    };
+    (@deme_field $d:ident, ancestors, $v:tt) => {
+        $d.ancestors($crate::graph!(@names $v))
+    };
+    (@deme_field $d:ident, proportions, [$($v:expr),* $(,)?]) => {
+        $d.proportions(::std::vec::Vec::<f64>::from([$($v),*]))
+    };
+    (@deme_field $d:ident, start_time, $v:expr) => {
+        $d.start_time($v)
+    };
+    (@deme_field $d:ident, defaults, { epoch: $epoch:tt $(,)? }) => {
+        $d.epoch_defaults($crate::graph!(@epoch $epoch))
+    };
+    (@deme_field $d:ident, epochs, [$($epoch:tt),* $(,)?]) => {
+        $d$(.epoch($crate::graph!(@epoch $epoch)))*
+    };
+
+    (@defaults $b:ident, epoch, $v:tt) => {
+        $b.set_epoch_defaults($crate::graph!(@epoch $v))
+    };
+    (@defaults $b:ident, migration, $v:tt) => {
+        $b.set_migration_defaults($crate::graph!(@migration $v))
+    };
+    (@defaults $b:ident, pulse, $v:tt) => {
+        $b.set_pulse_defaults($crate::graph!(@pulse $v))
+    };
+
+    (@graph $b:ident, description, $v:expr) => {
+        $b.set_description(Some($v))
+    };
+    (@graph $b:ident, doi, [$($doi:expr),* $(,)?]) => {
+        $($b.add_doi($doi);)*
+    };
+    (@graph $b:ident, defaults, { $($kind:ident : $v:tt),* $(,)? }) => {
+        $($crate::graph!(@defaults $b, $kind, $v);)*
+    };
+    (@graph $b:ident, demes, [$($name:ident { $($f:ident : $v:tt),* $(,)? }),* $(,)?]) => {
+        $($b.add_deme_with(stringify!($name), |deme| {
+            $(let deme = $crate::graph!(@deme_field deme, $f, $v);)*
+            deme
+        });)*
+    };
+    (@graph $b:ident, migrations, [$($migration:tt),* $(,)?]) => {
+        $($b.add_migration($crate::graph!(@migration $migration));)*
+    };
+    (@graph $b:ident, pulses, [$($pulse:tt),* $(,)?]) => {
+        $($b.add_pulse_builder($crate::graph!(@pulse $pulse));)*
+    };
+
+    (@build $units:tt, $generation_time:expr, $($key:ident : $v:tt),*) => {{
+        #[allow(unused_mut)]
+        let mut builder = $crate::GraphBuilder::new(
+            $crate::graph!(@time_units $units),
+            $generation_time,
+            None,
+        );
+        $($crate::graph!(@graph builder, $key, $v);)*
+        builder.resolve()
+    }};
+
+    (time_units: $units:tt, generation_time: $g:expr $(, $key:ident : $v:tt)* $(,)?) => {
+        $crate::graph!(
+            @build $units,
+            Some($crate::InputGenerationTime::from($g)),
+            $($key : $v),*
+        )
+    };
+    (time_units: $units:tt $(, $key:ident : $v:tt)* $(,)?) => {
+        $crate::graph!(@build $units, None, $($key : $v),*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DemesError;
+    use crate::SizeFunction;
+    use crate::TimeUnits;
+
+    #[test]
+    fn test_graph_macro() {
+        let graph = graph! {
+            time_units: years,
+            generation_time: 25.0,
+            description: "a model",
+            doi: ["https://doi.org/10.1000/xyz"],
+            defaults: { epoch: { start_size: 100.0 }, migration: { rate: 1e-3 } },
+            demes: [
+                A { description: "ancestral", epochs: [{}] },
+                B {
+                    ancestors: [A],
+                    start_time: 1000.0,
+                    epochs: [{ start_size: 50.0, end_time: 500.0 }, {}],
+                },
+                C {
+                    ancestors: [A, B],
+                    proportions: [0.25, 0.75],
+                    start_time: 500.0,
+                    defaults: { epoch: { selfing_rate: 0.5 } },
+                    epochs: [{ end_size: 200.0, size_function: linear }],
+                },
+            ],
+            migrations: [{ source: B, dest: C, end_time: 100.0 }],
+            pulses: [{ sources: [C], dest: B, time: 250.0, proportions: [0.1] }],
+        }
+        .unwrap();
+
+        let yaml = "
+time_units: years
+generation_time: 25
+description: a model
+doi: [https://doi.org/10.1000/xyz]
+defaults:
+  epoch: {start_size: 100}
+  migration: {rate: 1e-3}
+demes:
+ - name: A
+   description: ancestral
+   epochs:
+    - {}
+ - name: B
+   ancestors: [A]
+   start_time: 1000
+   epochs:
+    - {start_size: 50, end_time: 500}
+    - {}
+ - name: C
+   ancestors: [A, B]
+   proportions: [0.25, 0.75]
+   start_time: 500
+   defaults:
+     epoch: {selfing_rate: 0.5}
+   epochs:
+    - {end_size: 200, size_function: linear}
+migrations:
+ - {source: B, dest: C, end_time: 100}
+pulses:
+ - {sources: [C], dest: B, time: 250, proportions: [0.1]}
+";
+        assert_eq!(graph, crate::loads(yaml).unwrap());
+        assert_eq!(
+            graph.deme(2).epochs()[0].size_function(),
+            SizeFunction::Linear
+        );
+        assert_eq!(graph.deme(2).epochs()[0].selfing_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_graph_macro_time_units() {
+        let graph = graph! {
+            time_units: "months",
+            generation_time: 12.0,
+            demes: [A { epochs: [{ start_size: 1.0 }] }],
+        }
+        .unwrap();
+        assert_eq!(graph.time_units(), TimeUnits::Custom("months".to_string()));
+        assert_eq!(graph.generation_time(), 12.0);
+
+        let graph = graph! { time_units: generations, demes: [] };
+        assert!(matches!(graph, Err(DemesError::DemeError(_))));
+        let graph = graph! {
+            time_units: generations,
+            demes: [A { ancestors: [B], epochs: [{ start_size: 1.0 }] }],
+        };
+        assert!(graph.is_err());
+    }
+}
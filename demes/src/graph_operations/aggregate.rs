@@ -0,0 +1,597 @@
+use std::collections::HashSet;
+
+use crate::AsymmetricMigration;
+use crate::Deme;
+use crate::DemesError;
+use crate::Epoch;
+use crate::Graph;
+use crate::GraphBuilder;
+use crate::InputGenerationTime;
+use crate::InputTime;
+use crate::SizeFunction;
+use crate::Time;
+use crate::UnresolvedDemeHistory;
+use crate::UnresolvedEpoch;
+use crate::UnresolvedMigration;
+
+/// How deme sizes are combined by
+/// [`Graph::aggregate_demes`](crate::Graph::aggregate_demes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AggregationPolicy {
+    /// The size of the aggregate is the sum of the deme sizes.
+    Sum,
+    /// The size of the aggregate is the harmonic mean of the deme sizes.
+    HarmonicMean,
+}
+
+impl AggregationPolicy {
+    fn apply(&self, sizes: &[f64]) -> f64 {
+        match self {
+            Self::Sum => sizes.iter().sum(),
+            Self::HarmonicMean => sizes.len() as f64 / sizes.iter().map(|s| 1. / s).sum::<f64>(),
+        }
+    }
+}
+
+struct LiftedDeme {
+    name: String,
+    description: String,
+    epochs: Vec<UnresolvedEpoch>,
+    ancestors: Vec<String>,
+    proportions: Vec<f64>,
+    start_time: Time,
+}
+
+// The epoch of `deme` containing the interval [end, start]
+fn covering_epoch(deme: &Deme, start: f64, end: f64) -> &Epoch {
+    deme.epochs()
+        .iter()
+        .find(|e| start <= e.start_time() && end >= e.end_time())
+        // Only called for intervals where the deme exists
+        .unwrap()
+}
+
+fn epoch_size_at(epoch: &Epoch, time: f64) -> f64 {
+    if time == epoch.start_time() {
+        epoch.start_size().into()
+    } else if time == epoch.end_time() {
+        epoch.end_size().into()
+    } else {
+        // time is within the epoch, so neither unwrap can fail
+        epoch.size_at(time).unwrap().unwrap().into()
+    }
+}
+
+fn normalize(values: Vec<f64>) -> Vec<f64> {
+    let sum = values.iter().sum::<f64>();
+    values.into_iter().map(|v| v / sum).collect::<Vec<_>>()
+}
+
+// Relative sizes of each deme at a given time
+fn size_weights(members: &[&Deme], time: f64) -> Vec<f64> {
+    normalize(
+        members
+            .iter()
+            .map(|d| epoch_size_at(covering_epoch(d, time, time), time))
+            .collect::<Vec<_>>(),
+    )
+}
+
+// Sum values with the same name, retaining the order
+// of first appearance.
+fn merge_by_name<I: Iterator<Item = (String, f64)>>(pairs: I) -> (Vec<String>, Vec<f64>) {
+    let mut names: Vec<String> = vec![];
+    let mut values: Vec<f64> = vec![];
+    for (name, value) in pairs {
+        match names.iter().position(|n| n == &name) {
+            Some(i) => values[i] += value,
+            None => {
+                names.push(name);
+                values.push(value);
+            }
+        }
+    }
+    (names, values)
+}
+
+fn sorted_unique_times<I: Iterator<Item = Time>>(times: I) -> Vec<f64> {
+    let mut times = times.collect::<Vec<_>>();
+    times.sort_by(|a, b| b.cmp(a));
+    times.dedup();
+    times.into_iter().map(f64::from).collect::<Vec<_>>()
+}
+
+fn aggregate_epochs(members: &[&Deme], policy: AggregationPolicy) -> Vec<UnresolvedEpoch> {
+    let boundaries = sorted_unique_times(members.iter().flat_map(|d| d.end_times()));
+    let mut epochs = vec![];
+    let mut start = f64::from(members[0].start_time());
+    for end in boundaries {
+        let member_epochs = members
+            .iter()
+            .map(|d| covering_epoch(d, start, end))
+            .collect::<Vec<_>>();
+        let start_sizes = member_epochs
+            .iter()
+            .map(|e| epoch_size_at(e, start))
+            .collect::<Vec<_>>();
+        let end_sizes = member_epochs
+            .iter()
+            .map(|e| epoch_size_at(e, end))
+            .collect::<Vec<_>>();
+        let start_size = policy.apply(&start_sizes);
+        let end_size = policy.apply(&end_sizes);
+        let size_function = if start_size == end_size {
+            SizeFunction::Constant
+        } else {
+            let mut non_constant = member_epochs
+                .iter()
+                .map(|e| e.size_function())
+                .filter(|f| !matches!(f, SizeFunction::Constant));
+            let first = non_constant.next().unwrap_or(SizeFunction::Linear);
+            if non_constant.all(|f| f == first) {
+                first
+            } else {
+                SizeFunction::Linear
+            }
+        };
+        let weights = normalize(end_sizes);
+        let weighted = |rate: fn(&Epoch) -> f64| {
+            member_epochs
+                .iter()
+                .zip(weights.iter())
+                .map(|(e, w)| rate(e) * w)
+                .sum::<f64>()
+        };
+        epochs.push(UnresolvedEpoch {
+            end_time: Some(end.into()),
+            start_size: Some(start_size.into()),
+            end_size: Some(end_size.into()),
+            size_function: Some(size_function),
+            cloning_rate: Some(weighted(|e| e.cloning_rate().into()).into()),
+            selfing_rate: Some(weighted(|e| e.selfing_rate().into()).into()),
+        });
+        start = end;
+    }
+    epochs
+}
+
+fn lift_demes(
+    graph: &Graph,
+    members: &[&Deme],
+    name: &str,
+    policy: AggregationPolicy,
+) -> Result<Vec<LiftedDeme>, DemesError> {
+    let is_member = |n: &str| members.iter().any(|d| d.name() == n);
+    let start_weights = size_weights(members, members[0].start_time().into());
+    let (ancestors, proportions) = merge_by_name(members.iter().zip(start_weights).flat_map(
+        |(deme, weight)| {
+            deme.ancestor_names().iter().map(|a| a.to_string()).zip(
+                deme.proportions()
+                    .iter()
+                    .map(move |p| f64::from(*p) * weight),
+            )
+        },
+    ));
+    let aggregate = LiftedDeme {
+        name: name.to_string(),
+        description: format!(
+            "aggregate of demes: {}",
+            members
+                .iter()
+                .map(|d| d.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        epochs: aggregate_epochs(members, policy),
+        ancestors,
+        proportions,
+        start_time: members[0].start_time(),
+    };
+
+    let mut lifted = vec![];
+    let mut aggregate = Some(aggregate);
+    for deme in graph.demes() {
+        if is_member(deme.name()) {
+            if let Some(aggregate) = aggregate.take() {
+                lifted.push(aggregate);
+            }
+            continue;
+        }
+        let (ancestors, proportions) = merge_by_name(
+            deme.ancestor_names()
+                .iter()
+                .zip(deme.proportions().iter())
+                .map(|(a, p)| {
+                    let a = if is_member(a) {
+                        name.to_string()
+                    } else {
+                        a.to_string()
+                    };
+                    (a, f64::from(*p))
+                }),
+        );
+        lifted.push(LiftedDeme {
+            name: deme.name().to_string(),
+            description: deme.description().to_string(),
+            epochs: deme
+                .epochs()
+                .iter()
+                .cloned()
+                .map(UnresolvedEpoch::from)
+                .collect::<Vec<_>>(),
+            ancestors,
+            proportions,
+            start_time: deme.start_time(),
+        });
+    }
+
+    // Ancestors must precede descendants.
+    // Retain the input order as much as possible.
+    let mut placed = HashSet::<String>::default();
+    let mut ordered = vec![];
+    while !lifted.is_empty() {
+        let next = lifted
+            .iter()
+            .position(|d| d.ancestors.iter().all(|a| placed.contains(a)))
+            .ok_or_else(|| {
+                DemesError::GraphError("aggregation resulted in cyclic ancestry".into())
+            })?;
+        let deme = lifted.remove(next);
+        placed.insert(deme.name.clone());
+        ordered.push(deme);
+    }
+    Ok(ordered)
+}
+
+fn lift_migration_group(
+    group: &[&AsymmetricMigration],
+    members: &[&Deme],
+    into_aggregate: bool,
+) -> Vec<(f64, f64, f64)> {
+    let mut times = group
+        .iter()
+        .flat_map(|m| [m.start_time(), m.end_time()])
+        .collect::<Vec<_>>();
+    if into_aggregate {
+        times.extend(members.iter().flat_map(|d| d.end_times()));
+    }
+    let times = sorted_unique_times(times.into_iter());
+    let mut lifted: Vec<(f64, f64, f64)> = vec![];
+    for window in times.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let midpoint = if start.is_finite() {
+            end + (start - end) / 2.
+        } else {
+            start
+        };
+        let weights = if into_aggregate {
+            size_weights(members, midpoint)
+        } else {
+            vec![]
+        };
+        let rate = group
+            .iter()
+            .filter(|m| start <= m.start_time() && end >= m.end_time())
+            .map(|m| {
+                let rate = f64::from(m.rate());
+                if into_aggregate {
+                    let i = members.iter().position(|d| d.name() == m.dest()).unwrap();
+                    rate * weights[i]
+                } else {
+                    rate
+                }
+            })
+            .sum::<f64>();
+        if rate > 0.0 {
+            match lifted.last_mut() {
+                Some(last) if last.1 == start && last.2 == rate => last.1 = end,
+                _ => lifted.push((start, end, rate)),
+            }
+        }
+    }
+    lifted
+}
+
+fn lift_migrations(graph: &Graph, members: &[&Deme], name: &str, new_graph: &mut GraphBuilder) {
+    let is_member = |n: &str| members.iter().any(|d| d.name() == n);
+    let mut groups: Vec<((String, String), Vec<&AsymmetricMigration>)> = vec![];
+    for m in graph.migrations() {
+        let key = match (is_member(m.source()), is_member(m.dest())) {
+            (true, true) => continue,
+            (false, false) => {
+                new_graph.add_migration(UnresolvedMigration::from(m.clone()));
+                continue;
+            }
+            (true, false) => (name.to_string(), m.dest().to_string()),
+            (false, true) => (m.source().to_string(), name.to_string()),
+        };
+        match groups.iter_mut().find(|(k, _)| k == &key) {
+            Some((_, group)) => group.push(m),
+            None => groups.push((key, vec![m])),
+        }
+    }
+    for ((source, dest), group) in groups {
+        let into_aggregate = dest == name;
+        for (start, end, rate) in lift_migration_group(&group, members, into_aggregate) {
+            new_graph.add_migration(
+                UnresolvedMigration::default()
+                    .set_source(source.as_str())
+                    .set_dest(dest.as_str())
+                    .set_start_time(start)
+                    .set_end_time(end)
+                    .set_rate(rate),
+            );
+        }
+    }
+}
+
+fn lift_pulses(graph: &Graph, members: &[&Deme], name: &str, new_graph: &mut GraphBuilder) {
+    let is_member = |n: &str| members.iter().any(|d| d.name() == n);
+    for pulse in graph.pulses() {
+        let time = f64::from(pulse.time());
+        let pairs = pulse
+            .sources()
+            .iter()
+            .zip(pulse.proportions().iter().map(|p| f64::from(*p)));
+        let (dest, (sources, proportions)) = if is_member(pulse.dest()) {
+            let weights = size_weights(members, time);
+            let i = members
+                .iter()
+                .position(|d| d.name() == pulse.dest())
+                .unwrap();
+            (
+                name,
+                merge_by_name(
+                    pairs
+                        .filter(|(s, _)| !is_member(s))
+                        .map(|(s, p)| (s.to_string(), p * weights[i])),
+                ),
+            )
+        } else {
+            (
+                pulse.dest(),
+                merge_by_name(pairs.map(|(s, p)| {
+                    if is_member(s) {
+                        (name.to_string(), p)
+                    } else {
+                        (s.to_string(), p)
+                    }
+                })),
+            )
+        };
+        if sources.is_empty() {
+            continue;
+        }
+        let sources = sources.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        new_graph.add_pulse(
+            Some(&sources),
+            Some(dest),
+            Some(InputTime::from(time)),
+            Some(proportions),
+        );
+    }
+}
+
+pub(crate) fn aggregate_demes<S: AsRef<str>>(
+    graph: Graph,
+    demes: &[S],
+    name: &str,
+    policy: AggregationPolicy,
+) -> Result<Graph, DemesError> {
+    if demes.is_empty() {
+        return Err(DemesError::ValueError(
+            "no demes given for aggregation".into(),
+        ));
+    }
+    let mut members: Vec<&Deme> = vec![];
+    for deme in demes {
+        let deme = graph.get_deme(deme.as_ref()).ok_or_else(|| {
+            DemesError::DemeError(format!("deme {} does not exist", deme.as_ref()).into())
+        })?;
+        if members.iter().any(|d| d.name() == deme.name()) {
+            return Err(DemesError::ValueError(
+                format!("deme {} given more than once for aggregation", deme.name()).into(),
+            ));
+        }
+        members.push(deme);
+    }
+    if graph.get_deme(name).is_some() && !members.iter().any(|d| d.name() == name) {
+        return Err(DemesError::DemeError(
+            format!("deme {name} already exists").into(),
+        ));
+    }
+    if members
+        .iter()
+        .any(|d| d.start_time() != members[0].start_time() || d.end_time() != members[0].end_time())
+    {
+        return Err(DemesError::DemeError(
+            "aggregated demes must have identical start and end times".into(),
+        ));
+    }
+
+    let generation_time = InputGenerationTime::from(f64::from(graph.generation_time()));
+    let mut new_graph = GraphBuilder::new(graph.time_units(), Some(generation_time), None);
+    new_graph.liftover_toplevel_data(&graph);
+    for deme in lift_demes(&graph, &members, name, policy)? {
+        let history = if deme.ancestors.is_empty() {
+            UnresolvedDemeHistory::default()
+        } else {
+            UnresolvedDemeHistory {
+                ancestors: Some(deme.ancestors),
+                proportions: Some(deme.proportions.into_iter().map(|p| p.into()).collect()),
+                start_time: Some(deme.start_time.into()),
+                ..Default::default()
+            }
+        };
+        new_graph.add_deme(
+            &deme.name,
+            deme.epochs,
+            history,
+            if deme.description.is_empty() {
+                None
+            } else {
+                Some(&deme.description)
+            },
+        );
+    }
+    lift_migrations(&graph, &members, name, &mut new_graph);
+    lift_pulses(&graph, &members, name, &mut new_graph);
+    new_graph.resolve()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    static ISLANDS: &str = "
+time_units: generations
+demes:
+ - name: ancestor
+   epochs:
+    - start_size: 1000
+      end_time: 100
+ - name: A
+   ancestors: [ancestor]
+   epochs:
+    - start_size: 100
+      end_time: 50
+    - start_size: 200
+ - name: B
+   ancestors: [ancestor]
+   epochs:
+    - start_size: 300
+ - name: C
+   ancestors: [ancestor]
+   epochs:
+    - start_size: 100
+ - name: D
+   ancestors: [A, C]
+   proportions: [0.5, 0.5]
+   start_time: 20
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B]
+   rate: 1e-2
+ - source: C
+   dest: A
+   rate: 1e-3
+ - source: B
+   dest: C
+   rate: 2e-3
+pulses:
+ - sources: [C]
+   dest: B
+   time: 10
+   proportions: [0.1]
+ - sources: [A, B]
+   dest: C
+   time: 10
+   proportions: [0.1, 0.2]
+";
+
+    #[test]
+    fn test_sum() {
+        let graph = crate::loads(ISLANDS).unwrap();
+        let aggregated = graph
+            .aggregate_demes(&["A", "B"], "AB", AggregationPolicy::Sum)
+            .unwrap();
+        assert_eq!(
+            aggregated.deme_names().to_vec(),
+            ["ancestor", "AB", "C", "D"]
+        );
+        let ab = aggregated.deme("AB");
+        assert_eq!(ab.ancestor_names(), &[Arc::from("ancestor")]);
+        assert_eq!(ab.num_epochs(), 2);
+        assert_eq!(ab.start_time(), 100.0);
+        assert_eq!(ab.end_times().collect::<Vec<_>>(), [50.0, 0.0]);
+        assert_eq!(ab.epochs()[0].start_size(), 400.0);
+        assert_eq!(ab.epochs()[1].start_size(), 500.0);
+
+        let d = aggregated.deme("D");
+        assert_eq!(d.ancestor_names(), &[Arc::from("AB"), Arc::from("C")]);
+
+        // C -> A is weighted by the relative size of A
+        let into_ab = aggregated
+            .migrations()
+            .iter()
+            .filter(|m| m.dest() == "AB")
+            .collect::<Vec<_>>();
+        assert_eq!(into_ab.len(), 2);
+        assert_eq!(into_ab[0].source(), "C");
+        assert_eq!(into_ab[0].start_time(), 100.0);
+        assert_eq!(into_ab[0].end_time(), 50.0);
+        assert_eq!(into_ab[0].rate(), 1e-3 * 100. / 400.);
+        assert_eq!(into_ab[1].start_time(), 50.0);
+        assert_eq!(into_ab[1].end_time(), 0.0);
+        assert_eq!(into_ab[1].rate(), 1e-3 * 200. / 500.);
+
+        let from_ab = aggregated
+            .migrations()
+            .iter()
+            .filter(|m| m.source() == "AB")
+            .collect::<Vec<_>>();
+        assert_eq!(from_ab.len(), 1);
+        assert_eq!(from_ab[0].dest(), "C");
+        assert_eq!(from_ab[0].rate(), 2e-3);
+
+        assert_eq!(aggregated.pulses().len(), 2);
+        let p = &aggregated.pulses()[0];
+        assert_eq!(p.dest(), "AB");
+        assert_eq!(p.sources(), &[Arc::from("C")]);
+        assert_eq!(p.proportions()[0], 0.1 * 300. / 500.);
+        let p = &aggregated.pulses()[1];
+        assert_eq!(p.dest(), "C");
+        assert_eq!(p.sources(), &[Arc::from("AB")]);
+        assert!((f64::from(p.proportions()[0]) - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_harmonic_mean() {
+        let graph = crate::loads(ISLANDS).unwrap();
+        let aggregated = graph
+            .aggregate_demes(&["B", "C"], "B", AggregationPolicy::HarmonicMean)
+            .unwrap();
+        assert_eq!(
+            aggregated.deme_names().to_vec(),
+            ["ancestor", "A", "B", "D"]
+        );
+        assert_eq!(
+            aggregated.deme("B").start_size(),
+            2. / (1. / 300. + 1. / 100.)
+        );
+        assert_eq!(
+            aggregated.deme("D").ancestor_names(),
+            &[Arc::from("A"), Arc::from("B")]
+        );
+        // B -> C and the pulse from C into B are removed.
+        assert!(aggregated
+            .migrations()
+            .iter()
+            .all(|m| m.source() != m.dest() && (m.source() == "A" || m.dest() == "A")));
+        assert_eq!(aggregated.pulses().len(), 1);
+        assert_eq!(aggregated.pulses()[0].sources(), &[Arc::from("A")]);
+        assert_eq!(aggregated.pulses()[0].dest(), "B");
+    }
+
+    #[test]
+    fn test_errors() {
+        let graph = crate::loads(ISLANDS).unwrap();
+        let empty: &[&str] = &[];
+        for (demes, name) in [
+            (empty, "X"),
+            (&["A", "A"], "X"),
+            (&["A", "X"], "X"),
+            (&["A", "B"], "C"),
+            (&["A", "D"], "X"),
+            (&["A", "ancestor"], "X"),
+        ] {
+            assert!(graph
+                .clone()
+                .aggregate_demes(demes, name, AggregationPolicy::Sum)
+                .is_err());
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::AsymmetricMigration;
+use crate::Deme;
+use crate::Epoch;
+use crate::Graph;
+use crate::Pulse;
+
+/// Absolute and relative tolerances for comparing floating-point values.
+///
+/// Two values `a` and `b` are considered equal if
+/// `a == b` or if `|a - b| <= max(relative * max(|a|, |b|), absolute)`.
+/// The exact comparison means that infinite times only compare equal
+/// to one another.
+///
+/// The default uses a relative tolerance of `1e-9` and
+/// an absolute tolerance of `0.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    /// The absolute tolerance
+    pub absolute: f64,
+    /// The relative tolerance
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            absolute: 0.0,
+            relative: 1e-9,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Create a new instance.
+    pub fn new(absolute: f64, relative: f64) -> Self {
+        Self { absolute, relative }
+    }
+
+    fn is_close<A: Into<f64>, B: Into<f64>>(&self, a: A, b: B) -> bool {
+        let a = a.into();
+        let b = b.into();
+        if a == b {
+            return true;
+        }
+        if !a.is_finite() || !b.is_finite() {
+            return false;
+        }
+        let diff = (a - b).abs();
+        diff <= (self.relative * a.abs().max(b.abs())).max(self.absolute)
+    }
+
+    fn all_close<A, B>(&self, a: A, b: B) -> bool
+    where
+        A: ExactSizeIterator,
+        B: ExactSizeIterator,
+        A::Item: Into<f64>,
+        B::Item: Into<f64>,
+    {
+        a.len() == b.len() && a.zip(b).all(|(a, b)| self.is_close(a, b))
+    }
+}
+
+/// Tolerances used by [`Graph::approx_eq`](crate::Graph::approx_eq).
+///
+/// Separate tolerances apply to:
+///
+/// * times, including the generation time
+/// * deme sizes
+/// * rates, including migration rates, ancestry/pulse proportions,
+///   and selfing/cloning rates.
+///
+/// # Examples
+///
+/// ```
+/// let options = demes::ToleranceOptions::default()
+///     .set_time(demes::Tolerance::new(1e-6, 0.0))
+///     .set_size(demes::Tolerance::new(0.0, 1e-6));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ToleranceOptions {
+    time: Tolerance,
+    size: Tolerance,
+    rate: Tolerance,
+}
+
+impl ToleranceOptions {
+    /// Use the same tolerance for all comparisons.
+    pub fn new_uniform(tolerance: Tolerance) -> Self {
+        Self {
+            time: tolerance,
+            size: tolerance,
+            rate: tolerance,
+        }
+    }
+
+    /// Set the tolerance for times.
+    pub fn set_time(self, tolerance: Tolerance) -> Self {
+        Self {
+            time: tolerance,
+            ..self
+        }
+    }
+
+    /// Set the tolerance for deme sizes.
+    pub fn set_size(self, tolerance: Tolerance) -> Self {
+        Self {
+            size: tolerance,
+            ..self
+        }
+    }
+
+    /// Set the tolerance for rates and proportions.
+    pub fn set_rate(self, tolerance: Tolerance) -> Self {
+        Self {
+            rate: tolerance,
+            ..self
+        }
+    }
+
+    /// The tolerance for times.
+    pub fn time(&self) -> Tolerance {
+        self.time
+    }
+
+    /// The tolerance for deme sizes.
+    pub fn size(&self) -> Tolerance {
+        self.size
+    }
+
+    /// The tolerance for rates and proportions.
+    pub fn rate(&self) -> Tolerance {
+        self.rate
+    }
+}
+
+fn epochs_approx_eq(a: &Epoch, b: &Epoch, options: &ToleranceOptions) -> bool {
+    a.size_function() == b.size_function()
+        && options.time.is_close(a.start_time(), b.start_time())
+        && options.time.is_close(a.end_time(), b.end_time())
+        && options.size.is_close(a.start_size(), b.start_size())
+        && options.size.is_close(a.end_size(), b.end_size())
+        && options.rate.is_close(a.selfing_rate(), b.selfing_rate())
+        && options.rate.is_close(a.cloning_rate(), b.cloning_rate())
+}
+
+fn demes_approx_eq(a: &Deme, b: &Deme, options: &ToleranceOptions) -> bool {
+    a.name() == b.name()
+        && a.description() == b.description()
+        && a.ancestor_names() == b.ancestor_names()
+        && options.time.is_close(a.start_time(), b.start_time())
+        && options.rate.all_close(
+            a.proportions().iter().cloned(),
+            b.proportions().iter().cloned(),
+        )
+        && a.num_epochs() == b.num_epochs()
+        && a.epochs()
+            .iter()
+            .zip(b.epochs().iter())
+            .all(|(a, b)| epochs_approx_eq(a, b, options))
+}
+
+fn migrations_approx_eq(
+    a: &AsymmetricMigration,
+    b: &AsymmetricMigration,
+    options: &ToleranceOptions,
+) -> bool {
+    a.source() == b.source()
+        && a.dest() == b.dest()
+        && options.time.is_close(a.start_time(), b.start_time())
+        && options.time.is_close(a.end_time(), b.end_time())
+        && options.rate.is_close(a.rate(), b.rate())
+}
+
+fn pulses_approx_eq(a: &Pulse, b: &Pulse, options: &ToleranceOptions) -> bool {
+    a.sources() == b.sources()
+        && a.dest() == b.dest()
+        && options.time.is_close(a.time(), b.time())
+        && options.rate.all_close(
+            a.proportions().iter().cloned(),
+            b.proportions().iter().cloned(),
+        )
+}
+
+fn all_approx_eq<T, F>(a: &[T], b: &[T], f: F) -> bool
+where
+    F: Fn(&T, &T) -> bool,
+{
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| f(a, b))
+}
+
+pub(crate) fn approx_eq(a: &Graph, b: &Graph, options: ToleranceOptions) -> bool {
+    a.time_units() == b.time_units()
+        && options
+            .time
+            .is_close(a.generation_time(), b.generation_time())
+        && a.description() == b.description()
+        && a.doi().eq(b.doi())
+        && a.metadata() == b.metadata()
+        && all_approx_eq(a.demes(), b.demes(), |a, b| demes_approx_eq(a, b, &options))
+        && all_approx_eq(a.migrations(), b.migrations(), |a, b| {
+            migrations_approx_eq(a, b, &options)
+        })
+        && all_approx_eq(a.pulses(), b.pulses(), |a, b| {
+            pulses_approx_eq(a, b, &options)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tolerance() {
+        let t = Tolerance::default();
+        assert!(t.is_close(1.0, 1.0 + 1e-12));
+        assert!(!t.is_close(1.0, 1.0 + 1e-6));
+        assert!(t.is_close(f64::INFINITY, f64::INFINITY));
+        assert!(!t.is_close(f64::INFINITY, 1e300));
+        assert!(!t.is_close(0.0, 1e-12));
+        let t = Tolerance::new(1e-10, 0.0);
+        assert!(t.is_close(0.0, 1e-12));
+    }
+
+    #[test]
+    fn test_graphs() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 50
+    - start_size: 200
+ - name: B
+   ancestors: [A]
+   start_time: 10
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B]
+   rate: 1e-3
+";
+        let perturbed = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100.00001
+      end_time: 50
+    - start_size: 200
+ - name: B
+   ancestors: [A]
+   start_time: 10.0000001
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B]
+   rate: 1.0000001e-3
+";
+        let a = crate::loads(yaml).unwrap();
+        let b = crate::loads(perturbed).unwrap();
+        assert!(a.approx_eq(&a, ToleranceOptions::default()));
+        assert!(!a.approx_eq(&b, ToleranceOptions::default()));
+        let loose = ToleranceOptions::new_uniform(Tolerance::new(0.0, 1e-6));
+        assert!(a.approx_eq(&b, loose));
+        assert!(b.approx_eq(&a, loose));
+        assert!(!a.approx_eq(&b, loose.set_time(Tolerance::default())));
+        assert!(!a.approx_eq(&b, loose.set_size(Tolerance::default())));
+        assert!(!a.approx_eq(&b, loose.set_rate(Tolerance::default())));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::Graph;
+
+// Order demes so that ancestors always precede
+// their descendants (as required by the specification).
+// Ties are broken by deme name, making the order independent
+// of the input order.
+pub(crate) fn canonical_deme_order(graph: &Graph) -> Vec<usize> {
+    let mut placed = vec![false; graph.num_demes()];
+    let mut order = Vec::with_capacity(graph.num_demes());
+    while order.len() < graph.num_demes() {
+        let next = graph
+            .demes()
+            .iter()
+            .enumerate()
+            .filter(|(i, deme)| {
+                !placed[*i]
+                    && deme
+                        .ancestor_indexes()
+                        .iter()
+                        .all(|&a| placed[usize::from(a)])
+            })
+            .min_by(|a, b| a.1.name().cmp(b.1.name()))
+            .map(|(i, _)| i)
+            // A resolved graph cannot contain cycles.
+            .unwrap();
+        placed[next] = true;
+        order.push(next);
+    }
+    order
+}
+
+// 64-bit FNV-1a.
+// We use this rather than std::hash::DefaultHasher
+// because the output of the latter is not guaranteed
+// to be stable across rust versions.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a() {
+        // Reference values for the 64-bit FNV-1a hash
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_deme_order() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: Z
+   epochs:
+    - start_size: 100
+      end_time: 50
+ - name: Y
+   ancestors: [Z]
+   start_time: 50
+   epochs:
+    - start_size: 100
+ - name: A
+   ancestors: [Z]
+   start_time: 50
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+";
+        let graph = crate::loads(yaml).unwrap();
+        assert_eq!(canonical_deme_order(&graph), vec![3, 0, 2, 1]);
+    }
+
+    #[test]
+    fn test_canonical_migrations() {
+        let a = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - source: B
+   dest: A
+   rate: 1e-3
+ - source: A
+   dest: B
+   rate: 1e-3
+   end_time: 10
+ - source: A
+   dest: B
+   rate: 1e-4
+   start_time: 10
+";
+        let b = "
+time_units: generations
+demes:
+ - name: B
+   epochs:
+    - start_size: 100
+ - name: A
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 1e-4
+   start_time: 10
+ - source: A
+   dest: B
+   rate: 1e-3
+   end_time: 10
+ - source: B
+   dest: A
+   rate: 1e-3
+";
+        let a = crate::loads(a).unwrap();
+        let b = crate::loads(b).unwrap();
+        assert_ne!(a, b);
+        let ca = a.clone().canonicalize().unwrap();
+        assert_eq!(ca, b.clone().canonicalize().unwrap());
+        assert_eq!(a.content_hash().unwrap(), b.content_hash().unwrap());
+        assert_eq!(ca.migrations()[0].source(), "A");
+        assert_eq!(ca.migrations()[0].end_time(), 10.0);
+        assert_eq!(ca.migrations()[1].source(), "A");
+        assert_eq!(ca.migrations()[1].end_time(), 0.0);
+        assert_eq!(ca.migrations()[2].source(), "B");
+        assert_ne!(
+            a.content_hash().unwrap(),
+            a.clone().rescale(2.0).unwrap().content_hash().unwrap()
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::AsymmetricMigration;
+use crate::Deme;
+use crate::DemesError;
+use crate::Graph;
+use crate::SizeFunction;
+use crate::TimeUnits;
+
+// Mapping::remove does not preserve the order of the remaining keys
+pub(crate) fn shift_remove(map: &mut Mapping, key: &str) -> Option<Value> {
+    let value = map.get(key).cloned();
+    map.retain(|k, _| k != key);
+    value
+}
+
+fn remove_if(map: &mut Mapping, key: &str, remove: bool) {
+    if remove {
+        shift_remove(map, key);
+    }
+}
+
+fn as_mapping(value: &mut Value) -> Result<&mut Mapping, DemesError> {
+    value
+        .as_mapping_mut()
+        .ok_or_else(|| DemesError::GraphError("expected a YAML mapping".into()))
+}
+
+fn as_sequence(value: Option<&mut Value>) -> Result<&mut Vec<Value>, DemesError> {
+    value
+        .and_then(|v| v.as_sequence_mut())
+        .ok_or_else(|| DemesError::GraphError("expected a YAML sequence".into()))
+}
+
+fn compact_deme(graph: &Graph, deme: &Deme, value: &mut Value) -> Result<(), DemesError> {
+    let map = as_mapping(value)?;
+
+    let num_epochs = deme.num_epochs();
+    for (i, (epoch, value)) in deme
+        .epochs()
+        .iter()
+        .zip(as_sequence(map.get_mut("epochs"))?.iter_mut())
+        .enumerate()
+    {
+        let epoch_map = as_mapping(value)?;
+        let constant = epoch.start_size() == epoch.end_size();
+        let default_size_function = if constant {
+            SizeFunction::Constant
+        } else {
+            SizeFunction::Exponential
+        };
+        remove_if(epoch_map, "end_size", constant);
+        remove_if(
+            epoch_map,
+            "size_function",
+            epoch.size_function() == default_size_function,
+        );
+        remove_if(epoch_map, "cloning_rate", epoch.cloning_rate() == 0.0);
+        remove_if(epoch_map, "selfing_rate", epoch.selfing_rate() == 0.0);
+        remove_if(
+            epoch_map,
+            "end_time",
+            i + 1 == num_epochs && epoch.end_time() == 0.0,
+        );
+    }
+
+    remove_if(map, "description", deme.description().is_empty());
+    let default_start_time = match deme.ancestor_indexes() {
+        [] => Some(f64::INFINITY),
+        [ancestor] => Some(f64::from(graph.deme(*ancestor).end_time())),
+        _ => None,
+    };
+    remove_if(
+        map,
+        "start_time",
+        default_start_time.is_some_and(|t| deme.start_time() == t),
+    );
+    remove_if(map, "proportions", deme.proportions().len() < 2);
+    remove_if(map, "ancestors", deme.ancestor_names().is_empty());
+
+    // Write the keys in the order that they are typically given in the HDM
+    let mut ordered = Mapping::default();
+    for key in [
+        "name",
+        "description",
+        "ancestors",
+        "proportions",
+        "start_time",
+        "epochs",
+    ] {
+        if let Some(v) = shift_remove(map, key) {
+            ordered.insert(key.into(), v);
+        }
+    }
+    ordered.extend(std::mem::take(map));
+    *map = ordered;
+    Ok(())
+}
+
+// The times at which a migration between demes would start
+// and end if the times are not given in the input.
+fn default_migration_times(graph: &Graph, demes: &[&str]) -> Option<(f64, f64)> {
+    let demes = demes
+        .iter()
+        .map(|&name| graph.get_deme(name))
+        .collect::<Option<Vec<_>>>()?;
+    let start_time = demes
+        .iter()
+        .map(|d| f64::from(d.start_time()))
+        .fold(f64::INFINITY, f64::min);
+    let end_time = demes
+        .iter()
+        .map(|d| f64::from(d.end_time()))
+        .fold(0.0, f64::max);
+    Some((start_time, end_time))
+}
+
+// Two migrations are reciprocal if they could be
+// written as one symmetric migration.
+fn reciprocal(a: &AsymmetricMigration, b: &AsymmetricMigration) -> bool {
+    a.source() == b.dest()
+        && a.dest() == b.source()
+        && a.rate() == b.rate()
+        && a.start_time() == b.start_time()
+        && a.end_time() == b.end_time()
+}
+
+fn compact_migration(
+    graph: &Graph,
+    migration: &AsymmetricMigration,
+    symmetric: bool,
+    value: Value,
+) -> Result<Value, DemesError> {
+    let mut value = value;
+    let map = as_mapping(&mut value)?;
+    let demes = [migration.source(), migration.dest()];
+    if symmetric {
+        let source = shift_remove(map, "source");
+        let dest = shift_remove(map, "dest");
+        let mut ordered = Mapping::default();
+        ordered.insert(
+            "demes".into(),
+            Value::Sequence(source.into_iter().chain(dest).collect()),
+        );
+        ordered.extend(std::mem::take(map));
+        *map = ordered;
+    }
+    if let Some((start_time, end_time)) = default_migration_times(graph, &demes) {
+        remove_if(map, "start_time", migration.start_time() == start_time);
+        remove_if(map, "end_time", migration.end_time() == end_time);
+    }
+    Ok(value)
+}
+
+// Pairs of asymmetric migrations are only combined into a
+// symmetric migration if they are adjacent.
+// Doing so for non-adjacent pairs would change the order
+// of the resolved migrations.
+fn compact_migrations(graph: &Graph, values: Vec<Value>) -> Result<Vec<Value>, DemesError> {
+    let migrations = graph.migrations();
+    let mut values = values.into_iter();
+    let mut compacted = vec![];
+    let mut i = 0;
+    while let Some(value) = values.next() {
+        let symmetric = i + 1 < migrations.len() && reciprocal(&migrations[i], &migrations[i + 1]);
+        compacted.push(compact_migration(graph, &migrations[i], symmetric, value)?);
+        if symmetric {
+            let _ = values.next();
+            i += 1;
+        }
+        i += 1;
+    }
+    Ok(compacted)
+}
+
+pub(crate) fn compact_value(graph: &Graph) -> Result<Value, DemesError> {
+    let mut value = serde_yaml::to_value(graph)?;
+    let map = as_mapping(&mut value)?;
+
+    remove_if(
+        map,
+        "generation_time",
+        matches!(graph.time_units(), TimeUnits::Generations) && graph.generation_time() == 1.0,
+    );
+
+    for (deme, value) in graph
+        .demes()
+        .iter()
+        .zip(as_sequence(map.get_mut("demes"))?.iter_mut())
+    {
+        compact_deme(graph, deme, value)?;
+    }
+
+    if let Some(migrations) = map.get_mut("migrations") {
+        let values = std::mem::take(as_sequence(Some(migrations))?);
+        *migrations = Value::Sequence(compact_migrations(graph, values)?);
+    }
+    remove_if(map, "migrations", graph.migrations().is_empty());
+    remove_if(map, "pulses", graph.pulses().is_empty());
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(yaml: &str) -> String {
+        let graph = crate::loads(yaml).unwrap();
+        let compact = serde_yaml::to_string(&compact_value(&graph).unwrap()).unwrap();
+        assert_eq!(crate::loads(&compact).unwrap(), graph, "{compact}");
+        compact
+    }
+
+    #[test]
+    fn test_elide_defaults() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 100
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+      end_size: 200
+ - name: C
+   description: a deme
+   ancestors: [A]
+   start_time: 150
+   epochs:
+    - start_size: 100
+      end_size: 200
+      size_function: linear
+      selfing_rate: 0.5
+      end_time: 10
+";
+        let compact = round_trip(yaml);
+        let expected = "time_units: generations
+demes:
+- name: A
+  epochs:
+  - end_time: 100.0
+    start_size: 100.0
+- name: B
+  ancestors:
+  - A
+  epochs:
+  - start_size: 100.0
+    end_size: 200.0
+- name: C
+  description: a deme
+  ancestors:
+  - A
+  start_time: 150.0
+  epochs:
+  - end_time: 10.0
+    start_size: 100.0
+    end_size: 200.0
+    size_function: linear
+    selfing_rate: 0.5
+";
+        assert_eq!(compact, expected);
+    }
+
+    #[test]
+    fn test_symmetric_migrations() {
+        let yaml = "
+time_units: years
+generation_time: 1
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+ - name: C
+   start_time: 100
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B, C]
+   rate: 1e-3
+   end_time: 10
+ - source: A
+   dest: B
+   rate: 1e-2
+   start_time: 10
+ - source: B
+   dest: A
+   rate: 1e-2
+   start_time: 5
+";
+        let compact = round_trip(yaml);
+        let value: Value = serde_yaml::from_str(&compact).unwrap();
+        assert_eq!(value["generation_time"], Value::from(1.0));
+        let migrations = value["migrations"].as_sequence().unwrap();
+        // Of the 6 migrations from the symmetric block, the pairs
+        // A/B, A/C, and B/C are adjacent.
+        assert_eq!(migrations.len(), 5);
+        assert_eq!(
+            migrations[0]["demes"],
+            serde_yaml::from_str::<Value>("[A, B]").unwrap()
+        );
+        assert!(migrations[0].get("start_time").is_none());
+        assert_eq!(
+            migrations[1]["demes"],
+            serde_yaml::from_str::<Value>("[A, C]").unwrap()
+        );
+        assert_eq!(migrations[3]["source"], Value::from("A"));
+        assert!(migrations[3].get("end_time").is_none());
+        assert_eq!(migrations[4]["start_time"], Value::from(5.0));
+    }
+}
@@ -0,0 +1,504 @@
+use crate::AsymmetricMigration;
+use crate::Deme;
+use crate::Epoch;
+use crate::GenerationTime;
+use crate::Graph;
+use crate::Metadata;
+use crate::Proportion;
+use crate::Pulse;
+use crate::Time;
+use crate::TimeUnits;
+
+/// A difference in top-level [`Graph`] data.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum GraphChange {
+    /// The time units differ
+    TimeUnits {
+        #[allow(missing_docs)]
+        left: TimeUnits,
+        #[allow(missing_docs)]
+        right: TimeUnits,
+    },
+    /// The generation times differ
+    GenerationTime {
+        #[allow(missing_docs)]
+        left: GenerationTime,
+        #[allow(missing_docs)]
+        right: GenerationTime,
+    },
+    /// The descriptions differ
+    Description {
+        #[allow(missing_docs)]
+        left: Option<String>,
+        #[allow(missing_docs)]
+        right: Option<String>,
+    },
+    /// The DOI lists differ
+    Doi {
+        #[allow(missing_docs)]
+        left: Vec<String>,
+        #[allow(missing_docs)]
+        right: Vec<String>,
+    },
+    /// The top-level metadata differ
+    Metadata {
+        #[allow(missing_docs)]
+        left: Option<Metadata>,
+        #[allow(missing_docs)]
+        right: Option<Metadata>,
+    },
+}
+
+/// A difference between two epochs of a [`Deme`].
+///
+/// Epochs are compared by their index within the deme.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum EpochChange {
+    /// An epoch is present in the right graph only
+    Added {
+        #[allow(missing_docs)]
+        index: usize,
+        #[allow(missing_docs)]
+        epoch: Epoch,
+    },
+    /// An epoch is present in the left graph only
+    Removed {
+        #[allow(missing_docs)]
+        index: usize,
+        #[allow(missing_docs)]
+        epoch: Epoch,
+    },
+    /// The epoch at `index` differs
+    Modified {
+        #[allow(missing_docs)]
+        index: usize,
+        #[allow(missing_docs)]
+        left: Epoch,
+        #[allow(missing_docs)]
+        right: Epoch,
+    },
+}
+
+/// A difference between two demes with the same name.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DemeChange {
+    /// The start times differ
+    StartTime {
+        #[allow(missing_docs)]
+        left: Time,
+        #[allow(missing_docs)]
+        right: Time,
+    },
+    /// The descriptions differ
+    Description {
+        #[allow(missing_docs)]
+        left: String,
+        #[allow(missing_docs)]
+        right: String,
+    },
+    /// The ancestor names differ
+    Ancestors {
+        #[allow(missing_docs)]
+        left: Vec<String>,
+        #[allow(missing_docs)]
+        right: Vec<String>,
+    },
+    /// The ancestry proportions differ
+    Proportions {
+        #[allow(missing_docs)]
+        left: Vec<Proportion>,
+        #[allow(missing_docs)]
+        right: Vec<Proportion>,
+    },
+    /// An epoch differs
+    Epoch(EpochChange),
+}
+
+/// All differences found for a deme present in both graphs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DemeDifference {
+    name: String,
+    changes: Vec<DemeChange>,
+}
+
+impl DemeDifference {
+    /// The deme name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The changes found for this deme
+    pub fn changes(&self) -> &[DemeChange] {
+        &self.changes
+    }
+}
+
+/// A structured report of the differences between two [`Graph`] instances.
+///
+/// Generated by [`diff`](crate::diff).
+/// "Added" refers to items only present in the right graph and
+/// "removed" refers to items only present in the left graph.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphDiff {
+    graph_changes: Vec<GraphChange>,
+    demes_added: Vec<String>,
+    demes_removed: Vec<String>,
+    demes_modified: Vec<DemeDifference>,
+    migrations_added: Vec<AsymmetricMigration>,
+    migrations_removed: Vec<AsymmetricMigration>,
+    pulses_added: Vec<Pulse>,
+    pulses_removed: Vec<Pulse>,
+}
+
+impl GraphDiff {
+    /// `true` if the graphs are identical, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.graph_changes.is_empty()
+            && self.demes_added.is_empty()
+            && self.demes_removed.is_empty()
+            && self.demes_modified.is_empty()
+            && self.migrations_added.is_empty()
+            && self.migrations_removed.is_empty()
+            && self.pulses_added.is_empty()
+            && self.pulses_removed.is_empty()
+    }
+
+    /// Differences in top-level data
+    pub fn graph_changes(&self) -> &[GraphChange] {
+        &self.graph_changes
+    }
+
+    /// Names of demes only present in the right graph
+    pub fn demes_added(&self) -> &[String] {
+        &self.demes_added
+    }
+
+    /// Names of demes only present in the left graph
+    pub fn demes_removed(&self) -> &[String] {
+        &self.demes_removed
+    }
+
+    /// Demes present in both graphs that differ
+    pub fn demes_modified(&self) -> &[DemeDifference] {
+        &self.demes_modified
+    }
+
+    /// Migrations only present in the right graph
+    pub fn migrations_added(&self) -> &[AsymmetricMigration] {
+        &self.migrations_added
+    }
+
+    /// Migrations only present in the left graph
+    pub fn migrations_removed(&self) -> &[AsymmetricMigration] {
+        &self.migrations_removed
+    }
+
+    /// Pulses only present in the right graph
+    pub fn pulses_added(&self) -> &[Pulse] {
+        &self.pulses_added
+    }
+
+    /// Pulses only present in the left graph
+    pub fn pulses_removed(&self) -> &[Pulse] {
+        &self.pulses_removed
+    }
+}
+
+fn diff_toplevel(left: &Graph, right: &Graph) -> Vec<GraphChange> {
+    let mut changes = vec![];
+    if left.time_units() != right.time_units() {
+        changes.push(GraphChange::TimeUnits {
+            left: left.time_units(),
+            right: right.time_units(),
+        });
+    }
+    if left.generation_time() != right.generation_time() {
+        changes.push(GraphChange::GenerationTime {
+            left: left.generation_time(),
+            right: right.generation_time(),
+        });
+    }
+    if left.description() != right.description() {
+        changes.push(GraphChange::Description {
+            left: left.description().map(|d| d.to_string()),
+            right: right.description().map(|d| d.to_string()),
+        });
+    }
+    let left_doi = left.doi().map(|d| d.to_string()).collect::<Vec<_>>();
+    let right_doi = right.doi().map(|d| d.to_string()).collect::<Vec<_>>();
+    if left_doi != right_doi {
+        changes.push(GraphChange::Doi {
+            left: left_doi,
+            right: right_doi,
+        });
+    }
+    if left.metadata() != right.metadata() {
+        changes.push(GraphChange::Metadata {
+            left: left.metadata(),
+            right: right.metadata(),
+        });
+    }
+    changes
+}
+
+fn diff_epochs(left: &[Epoch], right: &[Epoch]) -> Vec<DemeChange> {
+    let mut changes = vec![];
+    for index in 0..left.len().max(right.len()) {
+        let change = match (left.get(index), right.get(index)) {
+            (Some(l), Some(r)) if l != r => Some(EpochChange::Modified {
+                index,
+                left: *l,
+                right: *r,
+            }),
+            (Some(l), None) => Some(EpochChange::Removed { index, epoch: *l }),
+            (None, Some(r)) => Some(EpochChange::Added { index, epoch: *r }),
+            _ => None,
+        };
+        if let Some(change) = change {
+            changes.push(DemeChange::Epoch(change))
+        }
+    }
+    changes
+}
+
+fn diff_deme(left: &Deme, right: &Deme) -> Vec<DemeChange> {
+    let mut changes = vec![];
+    if left.start_time() != right.start_time() {
+        changes.push(DemeChange::StartTime {
+            left: left.start_time(),
+            right: right.start_time(),
+        });
+    }
+    if left.description() != right.description() {
+        changes.push(DemeChange::Description {
+            left: left.description().to_string(),
+            right: right.description().to_string(),
+        });
+    }
+    if left.ancestor_names() != right.ancestor_names() {
+        changes.push(DemeChange::Ancestors {
+            left: left
+                .ancestor_names()
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            right: right
+                .ancestor_names()
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+        });
+    }
+    if left.proportions() != right.proportions() {
+        changes.push(DemeChange::Proportions {
+            left: left.proportions().to_vec(),
+            right: right.proportions().to_vec(),
+        });
+    }
+    changes.extend(diff_epochs(left.epochs(), right.epochs()));
+    changes
+}
+
+// Returns (only in left, only in right),
+// treating the inputs as multisets.
+fn unmatched<T: Clone + PartialEq>(left: &[T], right: &[T]) -> (Vec<T>, Vec<T>) {
+    let mut matched = vec![false; right.len()];
+    let mut only_left = vec![];
+    for l in left {
+        match right
+            .iter()
+            .enumerate()
+            .position(|(i, r)| !matched[i] && r == l)
+        {
+            Some(i) => matched[i] = true,
+            None => only_left.push(l.clone()),
+        }
+    }
+    let only_right = right
+        .iter()
+        .zip(matched.iter())
+        .filter(|(_, &m)| !m)
+        .map(|(r, _)| r.clone())
+        .collect::<Vec<_>>();
+    (only_left, only_right)
+}
+
+/// Compare two graphs, returning a structured report
+/// of their differences.
+///
+/// Demes are matched by name and epochs are matched by index.
+/// Migrations and pulses are compared as resolved (MDM) values.
+///
+/// # Examples
+///
+/// ```
+/// let left = demes::loads(
+///     "
+/// time_units: generations
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+/// ",
+/// )
+/// .unwrap();
+/// let right = demes::loads(
+///     "
+/// time_units: generations
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 200
+///  - name: B
+///    epochs:
+///     - start_size: 100
+/// ",
+/// )
+/// .unwrap();
+/// let report = demes::diff(&left, &right);
+/// assert!(!report.is_empty());
+/// assert_eq!(report.demes_added(), &["B".to_string()]);
+/// assert_eq!(report.demes_modified().len(), 1);
+/// assert_eq!(report.demes_modified()[0].name(), "A");
+/// assert!(demes::diff(&left, &left).is_empty());
+/// ```
+pub fn diff(left: &Graph, right: &Graph) -> GraphDiff {
+    let mut report = GraphDiff {
+        graph_changes: diff_toplevel(left, right),
+        ..Default::default()
+    };
+    for deme in left.demes() {
+        match right.get_deme(deme.name()) {
+            Some(other) => {
+                let changes = diff_deme(deme, other);
+                if !changes.is_empty() {
+                    report.demes_modified.push(DemeDifference {
+                        name: deme.name().to_string(),
+                        changes,
+                    })
+                }
+            }
+            None => report.demes_removed.push(deme.name().to_string()),
+        }
+    }
+    report.demes_added = right
+        .demes()
+        .iter()
+        .filter(|d| left.get_deme(d.name()).is_none())
+        .map(|d| d.name().to_string())
+        .collect::<Vec<_>>();
+    (report.migrations_removed, report.migrations_added) =
+        unmatched(left.migrations(), right.migrations());
+    (report.pulses_removed, report.pulses_added) = unmatched(left.pulses(), right.pulses());
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static BASE: &str = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 50
+    - start_size: 200
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B]
+   rate: 1e-3
+";
+
+    #[test]
+    fn test_identical_graphs() {
+        let g = crate::loads(BASE).unwrap();
+        assert!(diff(&g, &g).is_empty());
+    }
+
+    #[test]
+    fn test_modified_epochs_and_migrations() {
+        let left = crate::loads(BASE).unwrap();
+        let right = crate::loads(
+            "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 50
+ - name: B
+   epochs:
+    - start_size: 100
+ - name: C
+   ancestors: [B]
+   start_time: 10
+   epochs:
+    - start_size: 10
+pulses:
+ - sources: [B]
+   dest: C
+   time: 5
+   proportions: [0.5]
+",
+        )
+        .unwrap();
+        let report = diff(&left, &right);
+        assert_eq!(report.demes_added(), &["C".to_string()]);
+        assert!(report.demes_removed().is_empty());
+        assert_eq!(report.demes_modified().len(), 1);
+        let changes = report.demes_modified()[0].changes();
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(
+            changes[0],
+            DemeChange::Epoch(EpochChange::Removed { index: 1, .. })
+        ));
+        assert_eq!(report.migrations_removed().len(), 2);
+        assert!(report.migrations_added().is_empty());
+        assert_eq!(report.pulses_added().len(), 1);
+        assert!(report.pulses_removed().is_empty());
+
+        let reverse = diff(&right, &left);
+        assert_eq!(reverse.demes_removed(), &["C".to_string()]);
+        assert_eq!(reverse.migrations_added().len(), 2);
+        assert_eq!(reverse.pulses_removed().len(), 1);
+    }
+
+    #[test]
+    fn test_toplevel_changes() {
+        let left = crate::loads(BASE).unwrap();
+        let right = left.clone().into_generations().unwrap();
+        assert!(diff(&left, &right).is_empty());
+        let right = left.clone().rescale(2.0).unwrap();
+        let report = diff(&left, &right);
+        assert!(report.graph_changes().is_empty());
+        assert_eq!(report.demes_modified().len(), 2);
+
+        let right = crate::loads(&BASE.replace(
+            "time_units: generations",
+            "time_units: years\ngeneration_time: 1\ndescription: a model\ndoi: [\"https://doi.org/10.1000/xyz\"]",
+        ))
+        .unwrap();
+        let report = diff(&left, &right);
+        assert!(!report.is_empty());
+        let changes = report.graph_changes();
+        assert!(!changes.is_empty());
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(&changes[0], GraphChange::TimeUnits { .. }));
+        assert!(changes.contains(&GraphChange::Description {
+            left: None,
+            right: Some("a model".to_string()),
+        }));
+        assert!(changes.contains(&GraphChange::Doi {
+            left: vec![],
+            right: vec!["https://doi.org/10.1000/xyz".to_string()],
+        }));
+        assert!(report.demes_modified().is_empty());
+    }
+}
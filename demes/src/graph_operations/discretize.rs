@@ -0,0 +1,92 @@
+use crate::Epoch;
+use crate::SizeFunction;
+use crate::UnresolvedEpoch;
+
+// Replace an epoch with a sequence of constant-size epochs
+// of length `step`, the most recent of which may be shorter.
+// The size of each piece is the size of the input epoch at
+// the midpoint of the piece.
+// Constant-size epochs are returned unchanged.
+pub(crate) fn discretize_epoch(epoch: &Epoch, step: f64) -> Vec<UnresolvedEpoch> {
+    let constant = |end_time: f64, size: f64| UnresolvedEpoch {
+        end_time: Some(end_time.into()),
+        start_size: Some(size.into()),
+        end_size: Some(size.into()),
+        size_function: Some(SizeFunction::Constant),
+        cloning_rate: Some(f64::from(epoch.cloning_rate()).into()),
+        selfing_rate: Some(f64::from(epoch.selfing_rate()).into()),
+    };
+    let start_time = f64::from(epoch.start_time());
+    let end_time = f64::from(epoch.end_time());
+    if matches!(epoch.size_function(), SizeFunction::Constant) || !start_time.is_finite() {
+        return vec![constant(end_time, f64::from(epoch.end_size()))];
+    }
+    let mut epochs = vec![];
+    let mut piece_start = start_time;
+    let mut k = 1.0;
+    while piece_start > end_time {
+        let piece_end = (start_time - k * step).max(end_time);
+        let midpoint = piece_end + (piece_start - piece_end) / 2.0;
+        // The midpoint is within the epoch, so neither unwrap can fail
+        let size = epoch.size_at(midpoint).unwrap().unwrap();
+        epochs.push(constant(piece_end, f64::from(size)));
+        piece_start = piece_end;
+        k += 1.0;
+    }
+    epochs
+}
+
+#[cfg(test)]
+mod tests {
+    static GROWTH: &str = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 100
+    - start_size: 100
+      end_size: 1000
+      end_time: 10
+ - name: B
+   ancestors: [A]
+   start_time: 50
+   epochs:
+    - start_size: 50
+migrations:
+ - demes: [A, B]
+   rate: 1e-3
+";
+
+    #[test]
+    fn test_exponential_growth() {
+        let graph = crate::loads(GROWTH).unwrap();
+        let discretized = graph.clone().discretize_sizes(40.0).unwrap();
+        let deme = discretized.deme("A");
+        assert_eq!(deme.num_epochs(), 4);
+        assert_eq!(
+            deme.end_times().collect::<Vec<_>>(),
+            [100.0, 60.0, 20.0, 10.0]
+        );
+        for (epoch, midpoint) in deme.epochs().iter().skip(1).zip([80.0, 40.0, 15.0]) {
+            assert_eq!(
+                epoch.start_size(),
+                graph.deme("A").size_at(midpoint).unwrap().unwrap()
+            );
+            assert_eq!(epoch.start_size(), epoch.end_size());
+        }
+        assert_eq!(discretized.deme("B"), graph.deme("B"));
+        assert_eq!(discretized.migrations(), graph.migrations());
+    }
+
+    #[test]
+    fn test_invalid_step() {
+        let graph = crate::loads(GROWTH).unwrap();
+        for bad in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                graph.clone().discretize_sizes(bad),
+                Err(crate::DemesError::ValueError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use crate::time::round_time_to_integer_generations;
+use crate::Graph;
+use crate::Time;
+
+/// A change to a model that would result from
+/// converting its times to integer generations.
+///
+/// See [`Graph::check_integer_generations`](crate::Graph::check_integer_generations).
+/// Times are in the units of the graph being checked.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum IntegerGenerationsIssue {
+    /// An epoch would have length zero.
+    ZeroLengthEpoch {
+        /// The deme name
+        deme: String,
+        /// The index of the epoch in the deme
+        epoch: usize,
+        /// The start time of the epoch
+        start_time: Time,
+        /// The end time of the epoch
+        end_time: Time,
+    },
+    /// A migration would have length zero.
+    ZeroLengthMigration {
+        /// The index of the migration in the graph
+        migration: usize,
+        /// The source deme
+        source: String,
+        /// The destination deme
+        dest: String,
+        /// The start time of the migration
+        start_time: Time,
+        /// The end time of the migration
+        end_time: Time,
+    },
+    /// Two pulses at different times would happen in the same generation.
+    ///
+    /// The pulses would be applied in the order that they
+    /// appear in the graph.
+    SimultaneousPulses {
+        /// The index of the more ancient pulse in the graph
+        first: usize,
+        /// The index of the more recent pulse in the graph
+        second: usize,
+    },
+    /// A pulse would happen when one of its demes does not exist.
+    PulseOutsideDeme {
+        /// The index of the pulse in the graph
+        pulse: usize,
+        /// The name of the source or destination deme
+        deme: String,
+    },
+}
+
+impl std::fmt::Display for IntegerGenerationsIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroLengthEpoch {
+                deme,
+                epoch,
+                start_time,
+                end_time,
+            } => write!(
+                f,
+                "epoch {epoch} of deme {deme} from {start_time} to {end_time} would have length zero"
+            ),
+            Self::ZeroLengthMigration {
+                migration,
+                source,
+                dest,
+                start_time,
+                end_time,
+            } => write!(
+                f,
+                "migration {migration} from {source} to {dest} from {start_time} to {end_time} would have length zero"
+            ),
+            Self::SimultaneousPulses { first, second } => write!(
+                f,
+                "pulses {first} and {second} would happen in the same generation"
+            ),
+            Self::PulseOutsideDeme { pulse, deme } => write!(
+                f,
+                "pulse {pulse} would happen when deme {deme} does not exist"
+            ),
+        }
+    }
+}
+
+pub(crate) fn check_integer_generations(graph: &Graph) -> Vec<IntegerGenerationsIssue> {
+    let round = |time: Time| round_time_to_integer_generations(time, graph.generation_time());
+    let collapses = |start: Time, end: Time| round(start) == round(end);
+    let mut issues = vec![];
+
+    for deme in graph.demes() {
+        for (i, epoch) in deme.epochs().iter().enumerate() {
+            if collapses(epoch.start_time(), epoch.end_time()) {
+                issues.push(IntegerGenerationsIssue::ZeroLengthEpoch {
+                    deme: deme.name().to_string(),
+                    epoch: i,
+                    start_time: epoch.start_time(),
+                    end_time: epoch.end_time(),
+                });
+            }
+        }
+    }
+
+    for (i, migration) in graph.migrations().iter().enumerate() {
+        if collapses(migration.start_time(), migration.end_time()) {
+            issues.push(IntegerGenerationsIssue::ZeroLengthMigration {
+                migration: i,
+                source: migration.source().to_string(),
+                dest: migration.dest().to_string(),
+                start_time: migration.start_time(),
+                end_time: migration.end_time(),
+            });
+        }
+    }
+
+    // Pulses are sorted from the most ancient to the most recent
+    let pulses = graph.pulses();
+    for (i, pair) in pulses.windows(2).enumerate() {
+        if pair[0].time() != pair[1].time() && collapses(pair[0].time(), pair[1].time()) {
+            issues.push(IntegerGenerationsIssue::SimultaneousPulses {
+                first: i,
+                second: i + 1,
+            });
+        }
+    }
+
+    for (i, pulse) in pulses.iter().enumerate() {
+        let time = round(pulse.time());
+        // Sources must exist just before the pulse and
+        // the destination must exist just after it.
+        let sources = pulse.sources().iter().filter(|source| {
+            graph.get_deme(source.as_ref()).is_some_and(|deme| {
+                time >= round(deme.start_time()) || time < round(deme.end_time())
+            })
+        });
+        let dest = graph
+            .get_deme(pulse.dest())
+            .filter(|deme| time > round(deme.start_time()) || time <= round(deme.end_time()));
+        for name in sources
+            .map(|s| s.as_ref())
+            .chain(dest.map(|deme| deme.name()))
+        {
+            issues.push(IntegerGenerationsIssue::PulseOutsideDeme {
+                pulse: i,
+                deme: name.to_string(),
+            });
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_issues() {
+        let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - {start_size: 100, end_time: 1000}
+    - {start_size: 200}
+ - name: B
+   ancestors: [A]
+   start_time: 500
+   epochs:
+    - {start_size: 100}
+migrations:
+ - {source: A, dest: B, rate: 1e-3, start_time: 400}
+pulses:
+ - {sources: [A], dest: B, time: 300, proportions: [0.1]}
+ - {sources: [A], dest: B, time: 200, proportions: [0.1]}
+";
+        let graph = crate::loads(yaml).unwrap();
+        assert!(check_integer_generations(&graph).is_empty());
+        assert!(graph.into_integer_generations().is_ok());
+    }
+
+    #[test]
+    fn test_issues() {
+        let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - {start_size: 100, end_time: 1000}
+    - {start_size: 200, end_time: 990}
+    - {start_size: 300}
+ - name: B
+   ancestors: [A]
+   start_time: 500
+   epochs:
+    - {start_size: 100}
+migrations:
+ - {source: A, dest: B, rate: 1e-3, start_time: 405, end_time: 400}
+ - {source: B, dest: A, rate: 1e-3, start_time: 400}
+pulses:
+ - {sources: [B], dest: A, time: 498, proportions: [0.1]}
+ - {sources: [A], dest: B, time: 305, proportions: [0.1]}
+ - {sources: [A], dest: B, time: 300, proportions: [0.1]}
+";
+        let graph = crate::loads(yaml).unwrap();
+        let issues = check_integer_generations(&graph);
+        let t = |x: f64| Time::try_from(x).unwrap();
+        assert_eq!(
+            issues,
+            [
+                IntegerGenerationsIssue::ZeroLengthEpoch {
+                    deme: "A".to_string(),
+                    epoch: 1,
+                    start_time: t(1000.0),
+                    end_time: t(990.0),
+                },
+                IntegerGenerationsIssue::ZeroLengthMigration {
+                    migration: 0,
+                    source: "A".to_string(),
+                    dest: "B".to_string(),
+                    start_time: t(405.0),
+                    end_time: t(400.0),
+                },
+                IntegerGenerationsIssue::SimultaneousPulses {
+                    first: 1,
+                    second: 2
+                },
+                IntegerGenerationsIssue::PulseOutsideDeme {
+                    pulse: 0,
+                    deme: "B".to_string()
+                },
+            ]
+        );
+        assert!(issues[3].to_string().contains("deme B does not exist"));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::DemesError;
+use crate::Graph;
+use crate::Time;
+
+// The maximum expected number of migration events per
+// step of the uniformization.  Keeping this small avoids
+// underflow of the Poisson weights.
+const MAX_EVENTS_PER_STEP: f64 = 10.0;
+
+// Relative error at which we stop summing Poisson terms.
+const TRUNCATION_TOLERANCE: f64 = 1e-15;
+
+// A lineage in dest moves, backwards in time,
+// to source at a rate per generation.
+struct MigrationRate {
+    source: usize,
+    dest: usize,
+    rate: f64,
+}
+
+// Evolve the probability distribution over demes backwards in time
+// for a number of generations using continuous-time migration.
+//
+// We use uniformization: if lambda is the maximum total
+// rate of leaving any deme, then exp(Qt) is a Poisson(lambda * t)
+// mixture of powers of P = I + Q/lambda.
+// Every term is non-negative, so there is no loss of precision
+// from cancellation.
+fn migrate(distribution: &mut [f64], migrations: &[MigrationRate], generations: f64) {
+    let mut leaving = vec![0.0; distribution.len()];
+    for m in migrations {
+        leaving[m.dest] += m.rate;
+    }
+    let lambda = leaving.iter().cloned().fold(0.0, f64::max);
+    if lambda == 0.0 || generations == 0.0 {
+        return;
+    }
+    let num_steps = (lambda * generations / MAX_EVENTS_PER_STEP).ceil().max(1.0);
+    let expected_events = lambda * generations / num_steps;
+    let mut term = vec![0.0; distribution.len()];
+    let mut next = vec![0.0; distribution.len()];
+    for _ in 0..(num_steps as usize) {
+        term.copy_from_slice(distribution);
+        let mut weight = (-expected_events).exp();
+        let mut total_weight = weight;
+        distribution.iter_mut().for_each(|p| *p *= weight);
+        let mut k = 0.0;
+        while 1.0 - total_weight > TRUNCATION_TOLERANCE && weight > 0.0 {
+            k += 1.0;
+            for (n, (t, l)) in next.iter_mut().zip(term.iter().zip(leaving.iter())) {
+                *n = t * (1.0 - l / lambda);
+            }
+            for m in migrations {
+                next[m.source] += term[m.dest] * m.rate / lambda;
+            }
+            std::mem::swap(&mut term, &mut next);
+            weight *= expected_events / k;
+            total_weight += weight;
+            for (p, t) in distribution.iter_mut().zip(term.iter()) {
+                *p += weight * t;
+            }
+        }
+        // Account for the truncated terms
+        distribution.iter_mut().for_each(|p| *p /= total_weight);
+    }
+}
+
+pub(crate) fn lineage_distribution(
+    graph: &Graph,
+    deme: usize,
+    from: Time,
+    to: Time,
+) -> Result<Vec<f64>, DemesError> {
+    if from > to {
+        return Err(DemesError::ValueError(
+            format!("query time {to} is more recent than the starting time {from}").into(),
+        ));
+    }
+    if f64::from(to).is_infinite() {
+        return Err(DemesError::ValueError("query time must be finite".into()));
+    }
+    if !graph.deme(deme).exists_at(from) {
+        return Err(DemesError::DemeError(
+            format!(
+                "deme {} does not exist at time {from}",
+                graph.deme(deme).name()
+            )
+            .into(),
+        ));
+    }
+
+    // Unwrapping is fine: names in a resolved graph are valid
+    let index = |name: &str| graph.deme_index(name).unwrap();
+
+    // All times at which the process changes
+    let mut event_times = vec![from, to];
+    for d in graph.demes() {
+        event_times.push(d.start_time());
+    }
+    for m in graph.migrations() {
+        event_times.push(m.start_time());
+        event_times.push(m.end_time());
+    }
+    for p in graph.pulses() {
+        event_times.push(p.time());
+    }
+    event_times.retain(|&t| t >= from && t <= to);
+    event_times.sort();
+    event_times.dedup();
+
+    let generation_time = f64::from(graph.generation_time());
+    let mut distribution = vec![0.0; graph.num_demes()];
+    distribution[deme] = 1.0;
+
+    for window in event_times.windows(2) {
+        let (recent, ancient) = (window[0], window[1]);
+
+        let migrations = graph
+            .migrations()
+            .iter()
+            .filter(|m| m.end_time() <= recent && m.start_time() >= ancient)
+            .map(|m| MigrationRate {
+                source: index(m.source()),
+                dest: index(m.dest()),
+                rate: f64::from(m.rate()),
+            })
+            .collect::<Vec<_>>();
+        let generations = (f64::from(ancient) - f64::from(recent)) / generation_time;
+        migrate(&mut distribution, &migrations, generations);
+
+        // Pulses are stored in the order they happen forwards
+        // in time, so we undo them in reverse order.
+        for pulse in graph.pulses().iter().rev().filter(|p| p.time() == ancient) {
+            let dest = index(pulse.dest());
+            let moving = distribution[dest];
+            for (source, proportion) in pulse.sources().iter().zip(pulse.proportions()) {
+                let proportion = f64::from(*proportion);
+                distribution[index(source)] += moving * proportion;
+                distribution[dest] -= moving * proportion;
+            }
+        }
+
+        for (i, d) in graph.demes().iter().enumerate() {
+            if d.start_time() == ancient && !d.ancestor_indexes().is_empty() {
+                let moving = std::mem::replace(&mut distribution[i], 0.0);
+                for (&a, proportion) in d.ancestor_indexes().iter().zip(d.proportions()) {
+                    distribution[usize::from(a)] += moving * f64::from(*proportion);
+                }
+            }
+        }
+    }
+
+    Ok(distribution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distribution(yaml: &str, deme: usize, from: f64, to: f64) -> Vec<f64> {
+        let graph = crate::loads(yaml).unwrap();
+        lineage_distribution(
+            &graph,
+            deme,
+            from.try_into().unwrap(),
+            to.try_into().unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_split_and_pulse() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 100
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+ - name: C
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+pulses:
+ - sources: [B]
+   dest: C
+   proportions: [0.25]
+   time: 50
+";
+        assert_eq!(distribution(yaml, 2, 0.0, 49.0), [0.0, 0.0, 1.0]);
+        assert_eq!(distribution(yaml, 2, 0.0, 50.0), [0.0, 0.25, 0.75]);
+        // The pulse has already happened
+        assert_eq!(distribution(yaml, 2, 50.0, 60.0), [0.0, 0.0, 1.0]);
+        assert_eq!(distribution(yaml, 2, 0.0, 99.0), [0.0, 0.25, 0.75]);
+        assert_eq!(distribution(yaml, 2, 0.0, 100.0), [1.0, 0.0, 0.0]);
+        assert_eq!(distribution(yaml, 0, 100.0, 1000.0), [1.0, 0.0, 0.0]);
+        let graph = crate::loads(yaml).unwrap();
+        let t = |x: f64| Time::try_from(x).unwrap();
+        assert!(lineage_distribution(&graph, 0, t(0.0), t(10.0)).is_err());
+        assert!(lineage_distribution(&graph, 1, t(10.0), t(0.0)).is_err());
+        assert!(lineage_distribution(&graph, 1, t(10.0), t(f64::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_migration() {
+        let yaml = "
+time_units: years
+generation_time: 2
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 1e-2
+   start_time: 200
+";
+        // 100 generations with migration
+        let d = distribution(yaml, 1, 0.0, 1000.0);
+        let expected = (-1.0_f64).exp();
+        assert!((d[1] - expected).abs() < 1e-12);
+        assert!((d[0] + d[1] - 1.0).abs() < 1e-12);
+        // No lineage movement from A into B
+        assert_eq!(distribution(yaml, 0, 0.0, 1000.0), [1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_symmetric_migration_reaches_equilibrium() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B]
+   rate: 0.1
+";
+        let d = distribution(yaml, 0, 0.0, 1e4);
+        assert!((d[0] - 0.5).abs() < 1e-12);
+        assert!((d[1] - 0.5).abs() < 1e-12);
+    }
+}
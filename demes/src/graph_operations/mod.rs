@@ -1 +1,9 @@
+pub mod aggregate;
+pub mod approx_eq;
+pub mod canonical;
+pub mod compact;
+pub mod diff;
+pub mod discretize;
+pub mod integer_generations;
+pub mod lineage;
 pub mod slice;
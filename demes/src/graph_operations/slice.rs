@@ -61,7 +61,7 @@ fn liftover_demes<EL>(
             .iter()
             .zip(graph.deme(i).proportions().iter())
         {
-            if retained_deme_names.contains(name) {
+            if retained_deme_names.iter().any(|n| **n == **name) {
                 ancestors.push(name.to_string());
                 proportions.push(f64::from(*proportion).into());
             }
@@ -137,7 +137,7 @@ fn liftover_pulses<F>(
             && retained_deme_names.iter().any(|n| n == p.dest())
             && p.sources()
                 .iter()
-                .all(|s| retained_deme_names.iter().any(|n| n == s))
+                .all(|s| retained_deme_names.iter().any(|n| **n == **s))
     }) {
         let sources = pulse
             .sources()
@@ -156,9 +156,9 @@ fn liftover_pulses<F>(
 fn liftover_metadata(graph: &Graph, new_graph: &mut GraphBuilder) -> Result<(), DemesError> {
     if let Some(metadata) = graph.metadata() {
         if let Err(e) = new_graph.set_toplevel_metadata(metadata.as_raw_ref()) {
-            return Err(DemesError::GraphError(format!(
-                "failed to set toplevel metadata: {e:?}"
-            )));
+            return Err(DemesError::GraphError(
+                format!("failed to set toplevel metadata: {e:?}").into(),
+            ));
         }
     }
     Ok(())
@@ -324,6 +324,90 @@ pub fn slice_until(graph: Graph, when: Time) -> Result<Graph, DemesError> {
     slice_history(graph, callbacks)
 }
 
+// Clip an epoch to the interval [start, end).
+// If the clipped epoch starts at or beyond end, the deme will
+// become a root deme.  For non-constant epochs, we then add
+// a constant-size epoch extending to infinity in the past.
+fn clip_epoch(epoch: &Epoch, start: Time, end: Time) -> Vec<UnresolvedEpoch> {
+    let mut epochs = vec![];
+    let (end_time, end_size) = if epoch.end_time() < start {
+        (start, epoch.size_at(start).unwrap().unwrap())
+    } else {
+        (epoch.end_time(), epoch.end_size())
+    };
+    let start_size = if epoch.start_time() > end {
+        epoch.size_at(end).unwrap().unwrap()
+    } else {
+        epoch.start_size()
+    };
+    if epoch.start_time() >= end && epoch.size_function() != crate::SizeFunction::Constant {
+        epochs.push(UnresolvedEpoch {
+            end_time: Some(end.into()),
+            start_size: Some(f64::from(start_size).into()),
+            end_size: Some(f64::from(start_size).into()),
+            size_function: None,
+            cloning_rate: Some(f64::from(epoch.cloning_rate()).into()),
+            selfing_rate: Some(f64::from(epoch.selfing_rate()).into()),
+        });
+    }
+    epochs.push(UnresolvedEpoch {
+        end_time: Some(end_time.into()),
+        start_size: Some(f64::from(start_size).into()),
+        end_size: Some(f64::from(end_size).into()),
+        size_function: Some(epoch.size_function()),
+        cloning_rate: Some(f64::from(epoch.cloning_rate()).into()),
+        selfing_rate: Some(f64::from(epoch.selfing_rate()).into()),
+    });
+    epochs
+}
+
+// Clip the epochs of a deme to the interval [start, end)
+pub(crate) fn clip_deme_epochs(deme: &Deme, start: Time, end: Time) -> Vec<UnresolvedEpoch> {
+    deme.epochs()
+        .iter()
+        .filter(|e| e.start_time() > start && e.end_time() < end)
+        .flat_map(|e| clip_epoch(e, start, end))
+        .collect::<Vec<_>>()
+}
+
+// Retain only history from [start, end)
+pub fn slice_between(graph: Graph, start: Time, end: Time) -> Result<Graph, DemesError> {
+    if start >= end {
+        return Err(DemesError::ValueError(
+            format!("slice start time ({start:?}) must be less than slice end time ({end:?})")
+                .into(),
+        ));
+    }
+    let callbacks = Callbacks {
+        keep_deme: |d: &Deme| d.start_time() > start && d.end_time() < end,
+        keep_migration: |m: &AsymmetricMigration| m.start_time() > start && m.end_time() < end,
+        keep_pulse: |p: &Pulse| p.time() > start && p.time() < end,
+        epoch_liftover: |e: &Epoch| -> Option<Box<dyn Iterator<Item = UnresolvedEpoch>>> {
+            if e.start_time() > start && e.end_time() < end {
+                Some(Box::new(clip_epoch(e, start, end).into_iter()))
+            } else {
+                None
+            }
+        },
+        migration_start_time: |t: Time| {
+            if t > end {
+                Some(end.into())
+            } else {
+                Some(t.into())
+            }
+        },
+        migration_end_time: |t: Time| {
+            if t < start {
+                Some(start.into())
+            } else {
+                Some(t.into())
+            }
+        },
+    };
+
+    slice_history(graph, callbacks)
+}
+
 #[cfg(test)]
 static SIMPLE_TWO_DEME_GRAPH: &str = "
  time_units: generations
@@ -846,3 +930,140 @@ mod test_slice_until {
         assert_eq!(clipped.demes()[1].end_time(), when);
     }
 }
+
+#[cfg(test)]
+mod test_slice_between {
+    use super::slice_between;
+
+    #[test]
+    fn test_invalid_interval() {
+        let graph = crate::loads(super::SIMPLE_TWO_DEME_GRAPH).unwrap();
+        for (start, end) in [(10.0, 10.0), (20.0, 10.0)] {
+            assert!(matches!(
+                slice_between(
+                    graph.clone(),
+                    start.try_into().unwrap(),
+                    end.try_into().unwrap()
+                ),
+                Err(crate::DemesError::ValueError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_simple_two_deme_graph() {
+        let expected = "
+ time_units: generations
+ demes:
+  - name: derived
+    epochs:
+     - start_size: 50
+       end_time: 5
+";
+        let graph = crate::loads(super::SIMPLE_TWO_DEME_GRAPH).unwrap();
+        let expected_graph = crate::loads(expected).unwrap();
+        let clipped =
+            slice_between(graph, 5.0.try_into().unwrap(), 20.0.try_into().unwrap()).unwrap();
+        assert_eq!(clipped, expected_graph);
+    }
+
+    #[test]
+    fn test_simple_two_deme_graph_with_migration_0() {
+        let expected = "
+ time_units: generations
+ demes:
+  - name: ancestor1
+    epochs:
+     - start_size: 50
+       end_time: 50
+  - name: ancestor2
+    epochs:
+     - start_size: 50
+       end_time: 50
+ migrations:
+  - demes: [ancestor1, ancestor2]
+    rate: 0.25
+    start_time: 75
+    end_time: 50
+";
+        let graph = crate::loads(super::SIMPLE_TWO_DEME_GRAPH_WITH_MIGRATION_0).unwrap();
+        let expected_graph = crate::loads(expected).unwrap();
+        let clipped =
+            slice_between(graph, 50.0.try_into().unwrap(), 75.0.try_into().unwrap()).unwrap();
+        assert_eq!(clipped, expected_graph);
+    }
+
+    #[test]
+    fn test_pulses_at_boundaries() {
+        let graph = crate::loads(super::SIMPLE_TWO_DEME_GRAPH_WITH_PULSE_0).unwrap();
+        // A pulse at the recent edge is removed because
+        // the retained demes end at that time.
+        let clipped = slice_between(
+            graph.clone(),
+            40.0.try_into().unwrap(),
+            50.0.try_into().unwrap(),
+        )
+        .unwrap();
+        assert!(clipped.pulses().is_empty());
+        let clipped = slice_between(
+            graph.clone(),
+            30.0.try_into().unwrap(),
+            40.0.try_into().unwrap(),
+        )
+        .unwrap();
+        assert!(clipped.pulses().is_empty());
+        let clipped =
+            slice_between(graph, 30.0.try_into().unwrap(), 41.0.try_into().unwrap()).unwrap();
+        assert_eq!(clipped.pulses().len(), 1);
+    }
+
+    #[test]
+    fn test_correct_epoch_sizes() {
+        let graph = crate::loads(super::SIMPLE_MODEL_WITH_GROWTH).unwrap();
+        let start: crate::Time = 25.0.try_into().unwrap();
+        let end: crate::Time = 75.0.try_into().unwrap();
+        let clipped = slice_between(graph.clone(), start, end).unwrap();
+        assert_eq!(clipped.num_demes(), 1);
+        let deme = clipped.deme(0);
+        assert_eq!(deme.name(), "derived");
+        assert_eq!(deme.num_epochs(), 2);
+        let e = deme.epochs()[0];
+        assert_eq!(e.start_time(), f64::INFINITY);
+        assert_eq!(e.end_time(), end);
+        assert_eq!(e.start_size(), e.end_size());
+        assert_eq!(e.start_size(), graph.deme(1).size_at(end).unwrap().unwrap());
+        let e = deme.epochs()[1];
+        assert_eq!(e.start_time(), end);
+        assert_eq!(e.end_time(), start);
+        assert_eq!(e.start_size(), graph.deme(1).size_at(end).unwrap().unwrap());
+        assert_eq!(e.end_size(), graph.deme(1).size_at(start).unwrap().unwrap());
+    }
+
+    #[test]
+    fn test_epoch_boundary_at_end() {
+        let graph = crate::loads(super::SIMPLE_MODEL_WITH_GROWTH).unwrap();
+        let start: crate::Time = 25.0.try_into().unwrap();
+        let end: crate::Time = 100.0.try_into().unwrap();
+        let clipped = slice_between(graph.clone(), start, end).unwrap();
+        assert_eq!(clipped.num_demes(), 1);
+        let deme = clipped.deme(0);
+        assert_eq!(deme.num_epochs(), 2);
+        assert_eq!(deme.epochs()[0].end_time(), end);
+        assert_eq!(deme.epochs()[0].end_size(), 100.0);
+        assert_eq!(deme.epochs()[1].start_size(), 100.0);
+    }
+
+    #[test]
+    fn test_compare_to_composition() {
+        let graph = crate::loads(super::SIMPLE_TWO_EPOCH_MODEL).unwrap();
+        let start: crate::Time = 50.0.try_into().unwrap();
+        let end: crate::Time = 150.0.try_into().unwrap();
+        let clipped = slice_between(graph.clone(), start, end).unwrap();
+        let composed = super::slice_after(super::slice_until(graph, start).unwrap(), end).unwrap();
+        assert_eq!(clipped.num_demes(), 1);
+        assert_eq!(clipped.deme(0).end_time(), start);
+        assert_eq!(clipped.deme(0).num_epochs(), 2);
+        assert_eq!(clipped.deme(0).start_size(), composed.deme(0).start_size());
+        assert_eq!(clipped.deme(0).end_size(), composed.deme(0).end_size());
+    }
+}
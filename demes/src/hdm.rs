@@ -0,0 +1,151 @@
+//! The human data model (HDM).
+//!
+//! The
+//! [specification](https://popsim-consortium.github.io/demes-spec-docs/main/specification.html)
+//! describes two data models.
+//! The HDM is the model that humans write, where fields
+//! may be omitted and filled in from defaults.
+//! The machine data model (MDM) is fully resolved
+//! and is represented by [`Graph`].
+//!
+//! The types in this module allow inspecting and modifying
+//! a model before resolution, which preserves
+//! information such as the `defaults` that are lost
+//! when converting to the MDM.
+//!
+//! # Examples
+//!
+//! ```
+//! let yaml = "
+//! time_units: generations
+//! defaults:
+//!   epoch:
+//!     start_size: 100
+//! demes:
+//!  - name: A
+//!  - name: B
+//! ";
+//! let mut hdm = demes::hdm::loads(yaml).unwrap();
+//! assert_eq!(hdm.demes().len(), 2);
+//! assert!(hdm.demes()[0].epochs().is_empty());
+//! hdm.defaults_mut().epoch.start_size = Some(250.0.into());
+//! let graph = hdm.resolve().unwrap();
+//! assert_eq!(graph.deme(1).start_size(), 250.0);
+//! ```
+
+use std::io::Read;
+
+use crate::DemesError;
+#[cfg(doc)]
+use crate::Graph;
+
+pub use crate::specification::{
+    DemeDefaults, GraphDefaults, TopLevelDemeDefaults, UnresolvedDeme, UnresolvedEpoch,
+    UnresolvedGraph, UnresolvedMigration, UnresolvedPulse,
+};
+
+/// Parse an [`UnresolvedGraph`] from an in-memory YAML [`str`].
+///
+/// # Errors
+///
+/// [`DemesError`] if the input is not a valid HDM.
+/// Errors that are only detected during resolution
+/// are returned by [`UnresolvedGraph::resolve`].
+pub fn loads(yaml: &str) -> Result<UnresolvedGraph, DemesError> {
+    Ok(serde_yaml::from_str(yaml)?)
+}
+
+/// Parse an [`UnresolvedGraph`] from a type implementing
+/// [`std::io::Read`].
+///
+/// # Errors
+///
+/// See [`loads`].
+pub fn load<T: Read>(reader: T) -> Result<UnresolvedGraph, DemesError> {
+    Ok(serde_yaml::from_reader(reader)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modify_and_resolve() {
+        let yaml = "
+time_units: years
+generation_time: 25
+description: the input
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 200
+ - name: B
+   ancestors: [A]
+   start_time: 500
+   epochs:
+    - start_size: 50
+migrations:
+ - demes: [A, B]
+   rate: 1e-3
+";
+        let mut hdm = loads(yaml).unwrap();
+        assert_eq!(hdm.description(), Some("the input"));
+        assert_eq!(hdm.demes()[1].name(), "B");
+        assert_eq!(hdm.migrations().len(), 1);
+        assert!(hdm.pulses().is_empty());
+        assert!(hdm.generation_time().unwrap().equals(25.0));
+        hdm.set_description(None::<String>);
+        hdm.demes_mut()[1].set_description("derived");
+        hdm.demes_mut()[1].epochs_mut()[0].start_size = Some(75.0.into());
+        hdm.migrations_mut().clear();
+        let graph = hdm.resolve().unwrap();
+        assert_eq!(graph.description(), None);
+        assert_eq!(graph.deme(1).description(), "derived");
+        assert_eq!(graph.deme(1).start_size(), 75.0);
+        assert!(graph.migrations().is_empty());
+    }
+
+    #[test]
+    fn test_modify_deme_history() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+ - name: C
+   ancestors: [A]
+   start_time: 50
+   epochs:
+    - start_size: 50
+";
+        let mut hdm = loads(yaml).unwrap();
+        let deme = &hdm.demes()[2];
+        assert_eq!(deme.ancestors(), Some(&["A".to_string()][..]));
+        assert!(deme.proportions().is_none());
+        assert_eq!(deme.start_time(), Some(50.0.into()));
+        assert!(hdm.demes()[0].ancestors().is_none());
+
+        let deme = &mut hdm.demes_mut()[2];
+        deme.set_ancestors(Some(vec!["A".to_string(), "B".to_string()]));
+        deme.set_proportions(Some(vec![0.25.into(), 0.75.into()]));
+        deme.set_start_time(Some(25.0));
+        let graph = hdm.resolve().unwrap();
+        let deme = graph.get_deme("C").unwrap();
+        assert_eq!(deme.ancestor_indexes(), [0.into(), 1.into()]);
+        assert_eq!(deme.proportions()[1], 0.75);
+        assert_eq!(deme.start_time(), 25.0);
+    }
+
+    #[test]
+    fn test_load_and_resolution_errors() {
+        assert!(loads("time_units: generations\ndemes: [{name: A, bad: 1}]").is_err());
+        let hdm = load("time_units: generations\ndemes: []".as_bytes()).unwrap();
+        assert!(hdm.resolve().is_err());
+    }
+}
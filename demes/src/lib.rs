@@ -7,6 +7,7 @@
 //!
 //! * Support for reading `YAML` descriptions of `demes` models.
 //!   See [`loads`] and [`load`].
+//!   Files in any supported format can be read using [`load_path`].
 //! * Support for building a demes model using `rust` code.
 //!   See [`GraphBuilder`].
 //!
@@ -34,6 +35,16 @@
 //! are available:
 //!
 //! * `json`: enables reading/writing a [`Graph`] in JSON format.
+//! * `toml`: enables reading/writing a [`Graph`] in TOML format.
+//! * `gzip`: enables reading gzip-compressed input.
+//! * `tokio`: enables loading a [`Graph`] from an async reader.
+//! * `macros`: enables the [`graph!`] macro for building a [`Graph`]
+//!   using a syntax close to that of `YAML`.
+//! * `generation_time_schedule`: enables converting a [`Graph`] to
+//!   generations using a generation time that changes over time.
+//!   This is an extension to the demes specification.
+//! * `csv`: enables writing deme size trajectories, migrations,
+//!   and pulses as CSV.
 
 #![warn(missing_docs)]
 #![warn(rustdoc::broken_intra_doc_links)]
@@ -45,16 +56,38 @@ mod builder;
 mod cloning_rate;
 mod deme_size;
 mod error;
+mod file_format;
 mod graph_operations;
+pub mod hdm;
+mod load_options;
+mod load_path;
+mod location;
 mod migration_rate;
+mod params;
 mod proportion;
+mod save;
 mod selfing_rate;
+mod serialize;
+mod serialize_options;
 mod specification;
 mod time;
+mod warning;
 
 #[cfg(feature = "json")]
 mod process_json;
 
+#[cfg(feature = "tokio")]
+mod load_async;
+
+#[cfg(feature = "macros")]
+mod graph_macro;
+
+#[cfg(feature = "generation_time_schedule")]
+mod generation_time_schedule;
+
+#[cfg(feature = "csv")]
+mod csv_export;
+
 #[cfg(feature = "ffi")]
 pub mod ffi;
 #[cfg(feature = "ffi")]
@@ -62,15 +95,37 @@ mod ffi_iterators;
 
 use std::io::Read;
 
-pub use builder::{BuilderError, GraphBuilder};
+pub use builder::{BuilderError, DemeBuilder, GraphBuilder};
 pub use cloning_rate::{CloningRate, InputCloningRate};
+#[cfg(feature = "csv")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "csv")))]
+pub use csv_export::uniform_time_grid;
 pub use deme_size::{DemeSize, InputDemeSize};
-pub use error::DemesError;
+pub use error::{DemesError, ErrorContext, ErrorKind};
+pub use file_format::FileFormat;
+#[cfg(feature = "generation_time_schedule")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "generation_time_schedule")))]
+pub use generation_time_schedule::GenerationTimeSchedule;
+pub use graph_operations::aggregate::AggregationPolicy;
+pub use graph_operations::approx_eq::{Tolerance, ToleranceOptions};
+pub use graph_operations::diff::{
+    diff, DemeChange, DemeDifference, EpochChange, GraphChange, GraphDiff,
+};
+pub use graph_operations::integer_generations::IntegerGenerationsIssue;
+#[cfg(feature = "tokio")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "tokio")))]
+pub use load_async::*;
+pub use load_options::{LoadOptions, ResourceLimit};
+pub use load_path::load_path;
+pub use location::Location;
 pub use migration_rate::{InputMigrationRate, MigrationRate};
+pub use params::loads_with_params;
 pub use proportion::{InputProportion, Proportion};
 pub use selfing_rate::{InputSelfingRate, SelfingRate};
+pub use serialize_options::{KeyOrder, SerializeOptions, YamlStyle};
 pub use specification::*;
 pub use time::*;
+pub use warning::Warning;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -102,6 +157,51 @@ pub fn loads(yaml: &str) -> Result<specification::Graph, DemesError> {
     specification::Graph::new_resolved_from_str(yaml)
 }
 
+/// Build a [`Graph`] from an in-memory [`str`], returning
+/// any non-fatal issues found during resolution.
+///
+/// See [`Warning`] for the issues that are reported.
+///
+/// # Errors
+///
+/// Returns [`DemesError`] in the event of invalid input.
+///
+/// # Examples
+///
+/// ```
+/// let yaml = "
+/// time_units: generations
+/// defaults:
+///   epoch:
+///     start_size: 1000
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+///  - name: B
+///    epochs:
+///     - start_size: 100
+/// migrations:
+///  - source: A
+///    dest: B
+///    rate: 0.995
+/// ";
+///
+/// let (graph, warnings) = demes::loads_with_warnings(yaml).unwrap();
+/// assert_eq!(graph.num_demes(), 2);
+/// assert_eq!(warnings.len(), 2);
+/// assert_eq!(
+///     warnings[0],
+///     demes::Warning::UnusedDefault("defaults.epoch.start_size".to_string())
+/// );
+/// assert!(matches!(warnings[1], demes::Warning::MigrationRateNearOne { .. }));
+/// ```
+pub fn loads_with_warnings(yaml: &str) -> Result<(specification::Graph, Vec<Warning>), DemesError> {
+    let mut warnings = vec![];
+    let graph = specification::Graph::new_from_str_with_warnings(yaml, &mut warnings)?;
+    Ok((graph, warnings))
+}
+
 /// Generate a [`Graph`] from a JSON string.
 #[cfg(feature = "json")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
@@ -177,6 +277,212 @@ pub fn load_toml<T: Read>(reader: T) -> Result<specification::Graph, DemesError>
     specification::Graph::new_resolved_from_toml_reader(reader)
 }
 
+#[cfg(feature = "gzip")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "gzip")))]
+/// Load a [`Graph`] from a reader of gzip-compressed YAML.
+///
+/// # Errors
+///
+/// * [`DemesError::IOerror`] if the input is not valid gzip data.
+/// * [`DemesError`] in the event of invalid input.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+///
+/// let yaml = "
+/// time_units: generations
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+/// ";
+/// let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+/// encoder.write_all(yaml.as_bytes()).unwrap();
+/// let compressed = encoder.finish().unwrap();
+/// let graph = demes::load_gz(compressed.as_slice()).unwrap();
+/// assert_eq!(graph, demes::loads(yaml).unwrap());
+/// ```
+pub fn load_gz<T: Read>(reader: T) -> Result<specification::Graph, DemesError> {
+    load(flate2::read::MultiGzDecoder::new(reader))
+}
+
+/// Build a [`Graph`] from an in-memory [`str`], applying
+/// the limits in [`LoadOptions`].
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::ResourceLimitExceeded`] if the input exceeds
+///   any limits.
+///
+/// # Examples
+///
+/// See [`LoadOptions`].
+pub fn loads_with_options(
+    yaml: &str,
+    options: &LoadOptions,
+) -> Result<specification::Graph, DemesError> {
+    specification::Graph::new_from_str_with_options(yaml, options)
+}
+
+/// Build a [`Graph`] from a type implementing
+/// [`std::io::Read`], applying
+/// the limits in [`LoadOptions`].
+///
+/// If a maximum input size is set, no more than
+/// that many bytes (plus one) are read from `reader`.
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::ResourceLimitExceeded`] if the input exceeds
+///   any limits.
+pub fn load_with_options<T: Read>(
+    reader: T,
+    options: &LoadOptions,
+) -> Result<specification::Graph, DemesError> {
+    specification::Graph::new_from_reader_with_options(reader, options)
+}
+
+/// Generate a [`Graph`] from a JSON string, applying
+/// the limits in [`LoadOptions`].
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::ResourceLimitExceeded`] if the input exceeds
+///   any limits.
+#[cfg(feature = "json")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+pub fn loads_json_with_options(
+    json: &str,
+    options: &LoadOptions,
+) -> Result<specification::Graph, DemesError> {
+    specification::Graph::new_from_json_str_with_options(json, options)
+}
+
+/// Load a [`Graph`] from a JSON reader, applying
+/// the limits in [`LoadOptions`].
+///
+/// If a maximum input size is set, no more than
+/// that many bytes (plus one) are read from `reader`.
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::ResourceLimitExceeded`] if the input exceeds
+///   any limits.
+#[cfg(feature = "json")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+pub fn load_json_with_options<T: Read>(
+    reader: T,
+    options: &LoadOptions,
+) -> Result<specification::Graph, DemesError> {
+    specification::Graph::new_from_json_reader_with_options(reader, options)
+}
+
+/// Build a [`Graph`] from an in-memory [`str`], requiring that
+/// the top-level metadata satisfies a validator.
+///
+/// # Parameters
+///
+/// * `yaml`: the input model
+/// * `validator`: a function that receives the top-level metadata,
+///   or `None` if the input has no metadata.
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::MetadataError`] if `validator` returns an error.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(serde::Deserialize)]
+/// struct MyMetaData {
+///     foo: i32,
+/// }
+///
+/// fn validator(metadata: Option<&demes::Metadata>) -> Result<(), String> {
+///     let metadata = metadata.ok_or_else(|| "metadata is required".to_string())?;
+///     let yaml = metadata.as_yaml_string().map_err(|e| e.to_string())?;
+///     let _: MyMetaData = serde_yaml::from_str(&yaml).map_err(|e| e.to_string())?;
+///     Ok(())
+/// }
+///
+/// let yaml = "
+/// time_units: generations
+/// metadata:
+///  foo: 1
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+/// ";
+/// assert!(demes::loads_with_metadata_validator(yaml, validator).is_ok());
+///
+/// let yaml = "
+/// time_units: generations
+/// metadata:
+///  bar: 1
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+/// ";
+/// assert!(matches!(
+///     demes::loads_with_metadata_validator(yaml, validator),
+///     Err(demes::DemesError::MetadataError(_))
+/// ));
+/// ```
+pub fn loads_with_metadata_validator<F, E>(
+    yaml: &str,
+    validator: F,
+) -> Result<specification::Graph, DemesError>
+where
+    F: FnOnce(Option<&Metadata>) -> Result<(), E>,
+    E: std::fmt::Display,
+{
+    validate_metadata(loads(yaml)?, validator)
+}
+
+/// Build a [`Graph`] from a type implementing
+/// [`std::io::Read`], requiring that
+/// the top-level metadata satisfies a validator.
+///
+/// See [`loads_with_metadata_validator`] for details.
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::MetadataError`] if `validator` returns an error.
+pub fn load_with_metadata_validator<T, F, E>(
+    reader: T,
+    validator: F,
+) -> Result<specification::Graph, DemesError>
+where
+    T: Read,
+    F: FnOnce(Option<&Metadata>) -> Result<(), E>,
+    E: std::fmt::Display,
+{
+    validate_metadata(load(reader)?, validator)
+}
+
+fn validate_metadata<F, E>(
+    graph: specification::Graph,
+    validator: F,
+) -> Result<specification::Graph, DemesError>
+where
+    F: FnOnce(Option<&Metadata>) -> Result<(), E>,
+    E: std::fmt::Display,
+{
+    validator(graph.metadata().as_ref())
+        .map_err(|e| DemesError::MetadataError(e.to_string().into()))?;
+    Ok(graph)
+}
+
 /// Return the package version given in the
 /// `Cargo.toml` file of this crate.
 ///
@@ -0,0 +1,211 @@
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::DemesError;
+use crate::Graph;
+use crate::LoadOptions;
+
+// Only the IO is async: parsing and resolution
+// happen once all input is read.
+async fn read_string<T: AsyncRead + Unpin>(
+    reader: T,
+    options: &LoadOptions,
+) -> Result<String, DemesError> {
+    let mut buf = vec![];
+    match options.max_input_size() {
+        // Read at most one byte more than the limit
+        // so that we never buffer arbitrarily large inputs.
+        Some(max) => {
+            let _ = reader
+                .take((max as u64).saturating_add(1))
+                .read_to_end(&mut buf)
+                .await?;
+            options.check(crate::ResourceLimit::InputSize, buf.len())?;
+        }
+        None => {
+            let mut reader = reader;
+            let _ = reader.read_to_end(&mut buf).await?;
+        }
+    }
+    String::from_utf8(buf)
+        .map_err(|e| DemesError::IOerror(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+}
+
+/// Build a [`Graph`] from a type implementing
+/// [`tokio::io::AsyncRead`].
+///
+/// This is the async equivalent of [`load`](crate::load).
+///
+/// # Errors
+///
+/// Returns [`DemesError`] in the event of invalid input.
+///
+/// # Examples
+///
+/// ```
+/// // A slice of raw bytes implements AsyncRead.
+/// // More typically, the reader would be a file or a socket
+/// // and the future would be run by the application's runtime.
+/// async fn load_model(yaml: &str) -> Result<demes::Graph, demes::DemesError> {
+///     demes::load_async(yaml.as_bytes()).await
+/// }
+/// ```
+pub async fn load_async<T: AsyncRead + Unpin>(reader: T) -> Result<Graph, DemesError> {
+    load_with_options_async(reader, &LoadOptions::default()).await
+}
+
+/// Build a [`Graph`] from a type implementing
+/// [`tokio::io::AsyncRead`], applying
+/// the limits in [`LoadOptions`].
+///
+/// This is the async equivalent of [`load_with_options`](crate::load_with_options).
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::ResourceLimitExceeded`] if the input exceeds
+///   any limits.
+pub async fn load_with_options_async<T: AsyncRead + Unpin>(
+    reader: T,
+    options: &LoadOptions,
+) -> Result<Graph, DemesError> {
+    let yaml = read_string(reader, options).await?;
+    Graph::new_from_str_with_options(&yaml, options)
+}
+
+/// Load a [`Graph`] from a JSON reader implementing
+/// [`tokio::io::AsyncRead`].
+///
+/// This is the async equivalent of [`load_json`](crate::load_json).
+#[cfg(feature = "json")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+pub async fn load_json_async<T: AsyncRead + Unpin>(reader: T) -> Result<Graph, DemesError> {
+    load_json_with_options_async(reader, &LoadOptions::default()).await
+}
+
+/// Load a [`Graph`] from a JSON reader implementing
+/// [`tokio::io::AsyncRead`], applying
+/// the limits in [`LoadOptions`].
+///
+/// This is the async equivalent of [`load_json_with_options`](crate::load_json_with_options).
+///
+/// # Errors
+///
+/// * [`DemesError`] in the event of invalid input.
+/// * [`DemesError::ResourceLimitExceeded`] if the input exceeds
+///   any limits.
+#[cfg(feature = "json")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+pub async fn load_json_with_options_async<T: AsyncRead + Unpin>(
+    reader: T,
+    options: &LoadOptions,
+) -> Result<Graph, DemesError> {
+    let json = read_string(reader, options).await?;
+    crate::loads_json_with_options(&json, options)
+}
+
+/// Load a [`Graph`] from a TOML reader implementing
+/// [`tokio::io::AsyncRead`].
+///
+/// This is the async equivalent of [`load_toml`](crate::load_toml).
+#[cfg(feature = "toml")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "toml")))]
+pub async fn load_toml_async<T: AsyncRead + Unpin>(reader: T) -> Result<Graph, DemesError> {
+    let toml = read_string(reader, &LoadOptions::default()).await?;
+    Graph::new_resolved_from_toml_str(&toml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A minimal executor so that testing does not
+    // require the tokio runtime.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        struct ThreadWaker(std::thread::Thread);
+
+        impl std::task::Wake for ThreadWaker {
+            fn wake(self: std::sync::Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = std::sync::Arc::new(ThreadWaker(std::thread::current())).into();
+        let mut context = std::task::Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut context) {
+                std::task::Poll::Ready(output) => return output,
+                std::task::Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    static YAML: &str = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+";
+
+    #[test]
+    fn test_load_async() {
+        block_on(async {
+            let graph = load_async(YAML.as_bytes()).await.unwrap();
+            assert_eq!(graph, crate::loads(YAML).unwrap());
+
+            let options = LoadOptions::default().set_max_input_size(10);
+            assert!(matches!(
+                load_with_options_async(YAML.as_bytes(), &options).await,
+                Err(DemesError::ResourceLimitExceeded { .. })
+            ));
+            let options = LoadOptions::default().set_max_demes(1);
+            assert!(matches!(
+                load_with_options_async(YAML.as_bytes(), &options).await,
+                Err(DemesError::ResourceLimitExceeded { .. })
+            ));
+            assert!(matches!(
+                load_async(&[0xff_u8, 0xfe][..]).await,
+                Err(DemesError::IOerror(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_load_async_from_file() {
+        let bytes = std::fs::read("examples/jouganous.yaml").unwrap();
+        let graph = block_on(load_async(std::io::Cursor::new(bytes))).unwrap();
+        let expected = crate::load(std::fs::File::open("examples/jouganous.yaml").unwrap());
+        assert_eq!(graph, expected.unwrap());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_json_async() {
+        let graph = crate::loads(YAML).unwrap();
+        let json = graph.as_json_string().unwrap();
+        assert_eq!(block_on(load_json_async(json.as_bytes())).unwrap(), graph);
+
+        let options = LoadOptions::default().set_max_input_size(10);
+        assert!(matches!(
+            block_on(load_json_with_options_async(json.as_bytes(), &options)),
+            Err(DemesError::ResourceLimitExceeded { .. })
+        ));
+        let options = LoadOptions::default().set_max_demes(1);
+        assert!(matches!(
+            block_on(load_json_with_options_async(json.as_bytes(), &options)),
+            Err(DemesError::ResourceLimitExceeded { .. })
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_load_toml_async() {
+        let graph = crate::loads(YAML).unwrap();
+        let toml = graph.as_toml_string().unwrap();
+        assert_eq!(block_on(load_toml_async(toml.as_bytes())).unwrap(), graph);
+    }
+}
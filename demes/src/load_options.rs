@@ -0,0 +1,170 @@
+/// The kinds of limits that can be set by [`LoadOptions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResourceLimit {
+    /// The size of the input, in bytes.
+    InputSize,
+    /// The number of demes.
+    Demes,
+    /// The total number of epochs over all demes.
+    Epochs,
+    /// The number of asymmetric migrations.
+    Migrations,
+}
+
+impl std::fmt::Display for ResourceLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InputSize => write!(f, "input size"),
+            Self::Demes => write!(f, "number of demes"),
+            Self::Epochs => write!(f, "number of epochs"),
+            Self::Migrations => write!(f, "number of migrations"),
+        }
+    }
+}
+
+/// Options controlling how models are loaded.
+///
+/// The limits are intended for handling untrusted inputs.
+/// See also [`LoadOptions::set_locate_errors`].
+/// Limits are checked prior to resolution, and
+/// exceeding any of them results in
+/// [`DemesError::ResourceLimitExceeded`](crate::DemesError::ResourceLimitExceeded).
+///
+/// By default, no limits are applied.
+///
+/// # Notes
+///
+/// * Symmetric migrations involving `n` demes count as
+///   `n*(n-1)` asymmetric migrations.
+/// * A deme with no epochs counts as having one epoch.
+///
+/// # Examples
+///
+/// ```
+/// let options = demes::LoadOptions::default()
+///     .set_max_input_size(1 << 20)
+///     .set_max_demes(2);
+/// let yaml = "
+/// time_units: generations
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+///  - name: B
+///    epochs:
+///     - start_size: 100
+///  - name: C
+///    epochs:
+///     - start_size: 100
+/// ";
+/// match demes::loads_with_options(yaml, &options) {
+///     Err(demes::DemesError::ResourceLimitExceeded { limit, max, found }) => {
+///         assert_eq!(limit, demes::ResourceLimit::Demes);
+///         assert_eq!(max, 2);
+///         assert_eq!(found, 3);
+///     }
+///     _ => panic!("expected an error"),
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    max_input_size: Option<usize>,
+    max_demes: Option<usize>,
+    max_epochs: Option<usize>,
+    max_migrations: Option<usize>,
+    locate_errors: bool,
+}
+
+impl LoadOptions {
+    /// Set the maximum input size, in bytes.
+    pub fn set_max_input_size(self, max: usize) -> Self {
+        Self {
+            max_input_size: Some(max),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of demes.
+    pub fn set_max_demes(self, max: usize) -> Self {
+        Self {
+            max_demes: Some(max),
+            ..self
+        }
+    }
+
+    /// Set the maximum total number of epochs.
+    pub fn set_max_epochs(self, max: usize) -> Self {
+        Self {
+            max_epochs: Some(max),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of asymmetric migrations.
+    pub fn set_max_migrations(self, max: usize) -> Self {
+        Self {
+            max_migrations: Some(max),
+            ..self
+        }
+    }
+
+    /// Report the location of the deme, migration, or pulse
+    /// causing a resolution error.
+    ///
+    /// When `true`, such errors are returned as
+    /// [`DemesError::Located`](crate::DemesError::Located).
+    /// The default is `false`.
+    ///
+    /// See [`DemesError::location`](crate::DemesError::location).
+    pub fn set_locate_errors(self, locate_errors: bool) -> Self {
+        Self {
+            locate_errors,
+            ..self
+        }
+    }
+
+    /// The maximum input size, in bytes.
+    pub fn max_input_size(&self) -> Option<usize> {
+        self.max_input_size
+    }
+
+    /// The maximum number of demes.
+    pub fn max_demes(&self) -> Option<usize> {
+        self.max_demes
+    }
+
+    /// The maximum total number of epochs.
+    pub fn max_epochs(&self) -> Option<usize> {
+        self.max_epochs
+    }
+
+    /// The maximum number of asymmetric migrations.
+    pub fn max_migrations(&self) -> Option<usize> {
+        self.max_migrations
+    }
+
+    /// Whether to report the location of resolution errors.
+    pub fn locate_errors(&self) -> bool {
+        self.locate_errors
+    }
+
+    pub(crate) fn check(
+        &self,
+        limit: ResourceLimit,
+        found: usize,
+    ) -> Result<(), crate::DemesError> {
+        let max = match limit {
+            ResourceLimit::InputSize => self.max_input_size,
+            ResourceLimit::Demes => self.max_demes,
+            ResourceLimit::Epochs => self.max_epochs,
+            ResourceLimit::Migrations => self.max_migrations,
+        };
+        match max {
+            Some(max) if found > max => {
+                Err(crate::DemesError::ResourceLimitExceeded { limit, max, found })
+            }
+            _ => Ok(()),
+        }
+    }
+}
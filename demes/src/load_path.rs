@@ -0,0 +1,209 @@
+use std::path::Path;
+
+use crate::DemesError;
+use crate::FileFormat;
+use crate::Graph;
+
+fn is_gzip(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("gz"))
+}
+
+#[cfg(feature = "gzip")]
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>, DemesError> {
+    use std::io::Read;
+    let mut decompressed = vec![];
+    let _ = flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decompress(_bytes: &[u8]) -> Result<Vec<u8>, DemesError> {
+    Err(DemesError::ValueError(
+        "loading compressed input requires the gzip feature".into(),
+    ))
+}
+
+// Guess the format from the first line that is neither
+// blank nor a comment.
+// JSON objects start with "{" and TOML documents
+// start with a table header or a "key = value" pair.
+// Everything else is treated as YAML.
+fn sniff_format(input: &str) -> FileFormat {
+    let line = input
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'));
+    match line {
+        Some(line) if line.starts_with('{') => FileFormat::Json,
+        Some(line) if line.starts_with('[') && line.ends_with(']') && !line.contains(',') => {
+            FileFormat::Toml
+        }
+        Some(line) => match (line.find('='), line.find(':')) {
+            (Some(equals), Some(colon)) if equals < colon => FileFormat::Toml,
+            (Some(_), None) => FileFormat::Toml,
+            _ => FileFormat::Yaml,
+        },
+        None => FileFormat::Yaml,
+    }
+}
+
+fn load_str(input: &str, format: FileFormat) -> Result<Graph, DemesError> {
+    match format {
+        FileFormat::Yaml => crate::loads(input),
+        #[cfg(feature = "json")]
+        FileFormat::Json => crate::loads_json(input),
+        #[cfg(not(feature = "json"))]
+        FileFormat::Json => Err(DemesError::ValueError(
+            "loading JSON input requires the json feature".into(),
+        )),
+        #[cfg(feature = "toml")]
+        FileFormat::Toml => crate::loads_toml(input),
+        #[cfg(not(feature = "toml"))]
+        FileFormat::Toml => Err(DemesError::ValueError(
+            "loading TOML input requires the toml feature".into(),
+        )),
+    }
+}
+
+/// Load a [`Graph`] from a file, choosing the input
+/// format from the file extension.
+///
+/// * `.yaml` and `.yml` files are read as YAML.
+/// * `.json` files are read as JSON.
+/// * `.toml` files are read as TOML.
+///
+/// Extensions are not case-sensitive.
+/// For any other extension, or no extension, the format is
+/// guessed from the file contents.
+///
+/// Files ending in `.gz`, such as `model.yaml.gz`, are
+/// decompressed and the format is chosen from the preceding
+/// extension.
+/// Reading compressed files requires the `gzip` feature.
+///
+/// # Errors
+///
+/// * [`DemesError::IOerror`] if the file cannot be read.
+/// * [`DemesError::ValueError`] if the input is JSON, TOML,
+///   or compressed and the corresponding feature is not enabled.
+/// * [`DemesError`] in the event of invalid input.
+///
+/// # Examples
+///
+/// ```
+/// let graph = demes::load_path("examples/jouganous.yaml").unwrap();
+/// # assert_eq!(graph.num_demes(), 6);
+/// ```
+pub fn load_path<P: AsRef<Path>>(path: P) -> Result<Graph, DemesError> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    let (bytes, path) = if is_gzip(path) {
+        // The format is given by the extension that precedes ".gz"
+        let stem = Path::new(path.file_stem().unwrap_or_default());
+        (decompress(&bytes)?, stem)
+    } else {
+        (bytes, path)
+    };
+    let input = String::from_utf8(bytes).map_err(|e| {
+        DemesError::IOerror(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    })?;
+    let format = FileFormat::from_path(path).unwrap_or_else(|| sniff_format(&input));
+    load_str(&input, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static YAML: &str = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+";
+
+    #[test]
+    fn test_is_gzip() {
+        assert!(is_gzip(Path::new("model.yaml.gz")));
+        assert!(is_gzip(Path::new("model.GZ")));
+        assert!(!is_gzip(Path::new("model.yaml")));
+        assert!(!is_gzip(Path::new("gz")));
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        assert_eq!(sniff_format(YAML), FileFormat::Yaml);
+        assert_eq!(sniff_format("# comment\n{\"a\": 1}"), FileFormat::Json);
+        assert_eq!(sniff_format("time_units = \"years\""), FileFormat::Toml);
+        assert_eq!(sniff_format("\n[[demes]]\nname = \"A\""), FileFormat::Toml);
+        assert_eq!(sniff_format("description: a = b"), FileFormat::Yaml);
+        assert_eq!(sniff_format(""), FileFormat::Yaml);
+    }
+
+    #[test]
+    fn test_load_path() {
+        let graph = load_path("examples/jouganous.yaml").unwrap();
+        let expected = crate::load(std::fs::File::open("examples/jouganous.yaml").unwrap());
+        assert_eq!(graph, expected.unwrap());
+        assert!(matches!(
+            load_path("examples/no_such_file.yaml"),
+            Err(DemesError::IOerror(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_path_formats() {
+        let graph = crate::loads(YAML).unwrap();
+        let mut inputs = vec![("yaml", graph.as_string().unwrap())];
+        #[cfg(feature = "json")]
+        inputs.push(("json", graph.as_json_string().unwrap()));
+        #[cfg(feature = "toml")]
+        inputs.push(("toml", graph.as_toml_string().unwrap()));
+
+        let dir = std::env::temp_dir().join(format!("demes_load_path_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (extension, input) in inputs {
+            let path = dir.join(format!("model.{extension}"));
+            std::fs::write(&path, &input).unwrap();
+            assert_eq!(load_path(&path).unwrap(), graph, "{extension}");
+            // Without an informative extension, we sniff the contents
+            let path = dir.join(format!("{extension}_model.txt"));
+            std::fs::write(&path, &input).unwrap();
+            assert_eq!(load_path(&path).unwrap(), graph, "{extension}");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_load_path_gzip() {
+        use std::io::Write;
+
+        let graph = crate::loads(YAML).unwrap();
+        let mut inputs = vec![("model.yaml.gz", graph.as_string().unwrap())];
+        #[cfg(feature = "json")]
+        inputs.push(("model.json.gz", graph.as_json_string().unwrap()));
+        inputs.push(("model.gz", graph.as_string().unwrap()));
+
+        let dir = std::env::temp_dir().join(format!("demes_load_path_gz_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, input) in inputs {
+            let path = dir.join(name);
+            let mut encoder = flate2::write::GzEncoder::new(
+                std::fs::File::create(&path).unwrap(),
+                flate2::Compression::default(),
+            );
+            encoder.write_all(input.as_bytes()).unwrap();
+            let _ = encoder.finish().unwrap();
+            assert_eq!(load_path(&path).unwrap(), graph, "{name}");
+        }
+        // Not compressed
+        let path = dir.join("plain.yaml.gz");
+        std::fs::write(&path, YAML).unwrap();
+        assert!(matches!(load_path(&path), Err(DemesError::IOerror(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
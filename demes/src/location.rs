@@ -0,0 +1,174 @@
+use serde::de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+/// A position in a YAML input.
+///
+/// Lines and columns start at 1.
+///
+/// See [`DemesError::location`](crate::DemesError::location).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    /// The line number
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column number
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl From<serde_yaml::Location> for Location {
+    fn from(value: serde_yaml::Location) -> Self {
+        Self {
+            line: value.line(),
+            column: value.column(),
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+// We find the location of an element of a top-level sequence
+// by deserializing the input and raising an error when we reach
+// that element.  The YAML parser then tells us where the error is.
+const MARKER: &str = "demes-location-probe";
+
+// The error must be raised after the parser reaches the
+// element in order for the location to be that of the element.
+struct Fail;
+
+impl<'de> DeserializeSeed<'de> for Fail {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Fail {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a mapping")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, _: A) -> Result<Self::Value, A::Error> {
+        Err(A::Error::custom(MARKER))
+    }
+}
+
+struct SequenceProbe {
+    index: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for SequenceProbe {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SequenceProbe {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        for _ in 0..self.index {
+            seq.next_element::<IgnoredAny>()?;
+        }
+        seq.next_element_seed(Fail)?;
+        Ok(())
+    }
+}
+
+struct GraphProbe<'a> {
+    key: &'a str,
+    index: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for GraphProbe<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for GraphProbe<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a mapping")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.key {
+                map.next_value_seed(SequenceProbe { index: self.index })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The location of element `index` of the top-level sequence `key`.
+pub(crate) fn locate_sequence_element(yaml: &str, key: &str, index: usize) -> Option<Location> {
+    let deserializer = serde_yaml::Deserializer::from_str(yaml);
+    match (GraphProbe { key, index }).deserialize(deserializer) {
+        Err(e) if e.to_string().contains(MARKER) => e.location().map(Location::from),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_demes() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - {name: B, epochs: [{start_size: 100}]}
+ -   name: C
+     epochs:
+      - start_size: 100
+";
+        let location = locate_sequence_element(yaml, "demes", 0).unwrap();
+        assert_eq!((location.line(), location.column()), (4, 4));
+        let location = locate_sequence_element(yaml, "demes", 1).unwrap();
+        assert_eq!((location.line(), location.column()), (7, 4));
+        let location = locate_sequence_element(yaml, "demes", 2).unwrap();
+        assert_eq!((location.line(), location.column()), (8, 6));
+        assert!(locate_sequence_element(yaml, "demes", 3).is_none());
+        assert!(locate_sequence_element(yaml, "migrations", 0).is_none());
+    }
+}
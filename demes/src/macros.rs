@@ -51,11 +51,14 @@ macro_rules! impl_newtype_traits {
             }
         }
 
+        // NOTE: NaN is rejected during construction,
+        // so partial_cmp always succeeds.
+        // We fall back to total_cmp rather than panicking.
         impl Ord for $type {
             fn cmp(&self, other: &Self) -> std::cmp::Ordering {
                 match self.0.partial_cmp(&other.0) {
                     Some(ordering) => ordering,
-                    None => panic!("invalid f64 snuck in somehow"),
+                    None => self.0.total_cmp(&other.0),
                 }
             }
         }
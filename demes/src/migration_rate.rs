@@ -1,4 +1,6 @@
 use crate::error::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
 use serde::{Deserialize, Serialize};
 
 /// A migration rate.
@@ -51,11 +53,13 @@ impl TryFrom<InputMigrationRate> for MigrationRate {
 impl MigrationRate {
     fn validate<F>(&self, f: F) -> Result<(), DemesError>
     where
-        F: std::ops::FnOnce(String) -> DemesError,
+        F: std::ops::FnOnce(ErrorContext) -> DemesError,
     {
         if !self.0.is_finite() || self.0.is_sign_negative() || self.0 > 1.0 {
             let msg = format!("migration rate must be 0.0 <= m <= 1.0, got: {}", self.0);
-            Err(f(msg))
+            Err(f(
+                ErrorContext::new(msg, ErrorKind::InvalidValue).with_value(self.0)
+            ))
         } else {
             Ok(())
         }
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
+use crate::Graph;
+
+fn is_placeholder(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Format a value so that YAML reads it back as the same float.
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        ".nan".to_string()
+    } else if value == f64::INFINITY {
+        ".inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-.inf".to_string()
+    } else {
+        format!("{value:?}")
+    }
+}
+
+fn substitute(yaml: &str, params: &HashMap<String, f64>) -> Result<String, DemesError> {
+    let mut output = String::with_capacity(yaml.len());
+    let mut used = HashSet::new();
+    let mut rest = yaml;
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let name = rest[1..].find('}').map(|close| &rest[1..close + 1]);
+        match name {
+            Some(name) if is_placeholder(name) => {
+                let value = params.get(name).ok_or_else(|| {
+                    DemesError::ValueError(ErrorContext::new(
+                        format!("no value given for parameter {name}"),
+                        ErrorKind::Missing,
+                    ))
+                })?;
+                output.push_str(&format_value(*value));
+                let _ = used.insert(name);
+                rest = &rest[name.len() + 2..];
+            }
+            // Not a placeholder, e.g. a flow mapping
+            _ => {
+                output.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+
+    let mut unused = params
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !used.contains(name))
+        .collect::<Vec<_>>();
+    if !unused.is_empty() {
+        unused.sort();
+        return Err(DemesError::ValueError(
+            format!("parameters not used in the input: {}", unused.join(", ")).into(),
+        ));
+    }
+    Ok(output)
+}
+
+/// Build a [`Graph`] from an in-memory [`str`] containing
+/// named placeholders.
+///
+/// A placeholder is a name in braces, such as `{N_anc}`.
+/// Names start with a letter or an underscore, followed by
+/// letters, digits, or underscores.
+/// Each placeholder is replaced by its value in `params`
+/// before the input is resolved.
+/// Braces that do not enclose a valid name, such as those
+/// of YAML flow mappings, are left unchanged.
+///
+/// # Errors
+///
+/// * [`DemesError::ValueError`] if a placeholder has no value
+///   in `params` or if a parameter is not used.
+/// * [`DemesError`] in the event of invalid input after substitution.
+///
+/// # Examples
+///
+/// ```
+/// let yaml = "
+/// time_units: generations
+/// demes:
+///  - name: ancestor
+///    epochs:
+///     - {start_size: {N_anc}}
+///  - name: derived
+///    start_time: {T_split}
+///    ancestors: [ancestor]
+///    epochs:
+///     - start_size: 10
+/// ";
+///
+/// let params = std::collections::HashMap::from([
+///     ("N_anc".to_string(), 1000.0),
+///     ("T_split".to_string(), 50.0),
+/// ]);
+/// let graph = demes::loads_with_params(yaml, &params).unwrap();
+/// assert_eq!(graph.deme(0).start_size(), 1000.0);
+/// assert_eq!(graph.deme(1).start_time(), 50.0);
+/// ```
+pub fn loads_with_params(yaml: &str, params: &HashMap<String, f64>) -> Result<Graph, DemesError> {
+    crate::loads(&substitute(yaml, params)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(values: &[(&str, f64)]) -> HashMap<String, f64> {
+        values.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_substitute() {
+        let p = params(&[("a", 1.5), ("B_2", 1e-8), ("_c", f64::INFINITY)]);
+        let output = substitute("x: {a}\ny: [{B_2}, {_c}]\nz: {a: 1}\nw: {}", &p).unwrap();
+        assert_eq!(output, "x: 1.5\ny: [1e-8, .inf]\nz: {a: 1}\nw: {}");
+        assert_eq!(format_value(100.0), "100.0");
+        assert_eq!(format_value(f64::NEG_INFINITY), "-.inf");
+    }
+
+    #[test]
+    fn test_missing_and_unused_parameters() {
+        match substitute("x: {a}", &params(&[])) {
+            Err(DemesError::ValueError(context)) => {
+                assert_eq!(context.kind(), ErrorKind::Missing);
+                assert!(context.message().contains('a'));
+            }
+            _ => panic!("expected a ValueError"),
+        }
+        match substitute("x: {a}", &params(&[("a", 1.0), ("c", 2.0), ("b", 2.0)])) {
+            Err(DemesError::ValueError(context)) => {
+                assert_eq!(context.message(), "parameters not used in the input: b, c");
+            }
+            _ => panic!("expected a ValueError"),
+        }
+    }
+
+    #[test]
+    fn test_loads_with_params() {
+        let yaml = "
+time_units: years
+generation_time: {gen_time}
+demes:
+ - name: A
+   epochs:
+    - start_size: {N}
+      end_time: {T}
+    - {start_size: {N}, end_size: {N_end}}
+";
+        let p = params(&[
+            ("gen_time", 25.0),
+            ("N", 100.0),
+            ("T", 1000.0),
+            ("N_end", 1e4),
+        ]);
+        let graph = loads_with_params(yaml, &p).unwrap();
+        let expected = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 100
+      end_size: 1e4
+";
+        assert_eq!(graph, crate::loads(expected).unwrap());
+        let p = params(&[
+            ("gen_time", 25.0),
+            ("N", -1.0),
+            ("T", 1000.0),
+            ("N_end", 1e4),
+        ]);
+        assert!(loads_with_params(yaml, &p).is_err());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::error::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
 use serde::{Deserialize, Serialize};
 
 /// An ancestry proportion.
@@ -83,11 +85,13 @@ pub struct Proportion(f64);
 impl Proportion {
     fn validate<F>(&self, f: F) -> Result<(), DemesError>
     where
-        F: std::ops::FnOnce(String) -> DemesError,
+        F: std::ops::FnOnce(ErrorContext) -> DemesError,
     {
         if !self.0.is_finite() || self.0 <= 0.0 || self.0 > 1.0 {
             let msg = format!("proportions must be 0.0 < p <= 1.0, got: {}", self.0);
-            Err(f(msg))
+            Err(f(
+                ErrorContext::new(msg, ErrorKind::InvalidValue).with_value(self.0)
+            ))
         } else {
             Ok(())
         }
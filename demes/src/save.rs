@@ -0,0 +1,189 @@
+use std::ffi::{OsStr, OsString};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::DemesError;
+use crate::FileFormat;
+use crate::Graph;
+
+fn to_string(graph: &Graph, format: FileFormat) -> Result<String, DemesError> {
+    match format {
+        FileFormat::Yaml => graph.as_string(),
+        #[cfg(feature = "json")]
+        FileFormat::Json => graph.as_json_string(),
+        #[cfg(not(feature = "json"))]
+        FileFormat::Json => Err(DemesError::ValueError(
+            "writing JSON output requires the json feature".into(),
+        )),
+        #[cfg(feature = "toml")]
+        FileFormat::Toml => graph.as_toml_string(),
+        #[cfg(not(feature = "toml"))]
+        FileFormat::Toml => Err(DemesError::ValueError(
+            "writing TOML output requires the toml feature".into(),
+        )),
+    }
+}
+
+// Distinguishes temporary files created by different
+// threads of the same process.
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+// Give up after this many name collisions with existing files.
+const MAX_TEMP_FILE_ATTEMPTS: usize = 100;
+
+// Create a new temporary file in the same directory as path.
+// Using create_new means that we never truncate, or follow
+// a symlink to, a file that we did not create.
+fn create_temp_file(path: &Path, file_name: &OsStr) -> std::io::Result<(PathBuf, File)> {
+    for _ in 0..MAX_TEMP_FILE_ATTEMPTS {
+        let mut temp_name = OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = path.with_file_name(temp_name);
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        format!("could not create a temporary file for {}", path.display()),
+    ))
+}
+
+// Write to a temporary file in the same directory as the
+// destination and then rename it.
+// A rename within a file system is atomic, so readers of
+// path never see a partially-written file.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), DemesError> {
+    let file_name = path.file_name().ok_or_else(|| {
+        DemesError::ValueError(format!("invalid file name: {}", path.display()).into())
+    })?;
+    let (temp_path, mut file) = create_temp_file(path, file_name)?;
+
+    let mut write = || -> std::io::Result<()> {
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)
+    };
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        e.into()
+    })
+}
+
+pub(crate) fn save_as(graph: &Graph, path: &Path, format: FileFormat) -> Result<(), DemesError> {
+    let output = to_string(graph, format)?;
+    write_atomic(path, output.as_bytes())
+}
+
+pub(crate) fn save(graph: &Graph, path: &Path) -> Result<(), DemesError> {
+    let format = FileFormat::from_path(path).ok_or_else(|| {
+        DemesError::ValueError(
+            format!(
+                "cannot determine the file format from the extension of {}",
+                path.display()
+            )
+            .into(),
+        )
+    })?;
+    save_as(graph, path, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static YAML: &str = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+";
+
+    #[test]
+    fn test_save() {
+        let graph = crate::loads(YAML).unwrap();
+        let dir = std::env::temp_dir().join(format!("demes_save_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("model.yaml");
+        save(&graph, &path).unwrap();
+        assert_eq!(crate::load_path(&path).unwrap(), graph);
+        // Overwrite an existing file
+        let other = crate::loads(&YAML.replace("100", "200")).unwrap();
+        save(&other, &path).unwrap();
+        assert_eq!(crate::load_path(&path).unwrap(), other);
+
+        #[cfg(feature = "json")]
+        {
+            let path = dir.join("model.json");
+            save(&graph, &path).unwrap();
+            assert_eq!(
+                crate::loads_json(&std::fs::read_to_string(&path).unwrap()).unwrap(),
+                graph
+            );
+        }
+        #[cfg(feature = "toml")]
+        {
+            let path = dir.join("model.toml");
+            save(&graph, &path).unwrap();
+            assert_eq!(
+                crate::loads_toml(&std::fs::read_to_string(&path).unwrap()).unwrap(),
+                graph
+            );
+        }
+
+        let path = dir.join("model.txt");
+        assert!(matches!(
+            save(&graph, &path),
+            Err(DemesError::ValueError(_))
+        ));
+        save_as(&graph, &path, FileFormat::Yaml).unwrap();
+        assert_eq!(crate::load_path(&path).unwrap(), graph);
+
+        // No temporary files are left behind
+        let mut files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        let mut expected = vec!["model.txt", "model.yaml"];
+        #[cfg(feature = "json")]
+        expected.push("model.json");
+        #[cfg(feature = "toml")]
+        expected.push("model.toml");
+        expected.sort();
+        assert_eq!(files, expected);
+
+        // Concurrent saves to the same path do not
+        // share temporary files.
+        let path = dir.join("model.yaml");
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| save(&graph, &path).unwrap());
+            }
+        });
+        assert_eq!(crate::load_path(&path).unwrap(), graph);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), expected.len());
+
+        // The directory does not exist
+        assert!(matches!(
+            save(&graph, &dir.join("missing").join("model.yaml")),
+            Err(DemesError::IOerror(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
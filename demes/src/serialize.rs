@@ -0,0 +1,325 @@
+// A YAML writer that supports the formatting choices of SerializeOptions.
+// serde_yaml does not offer control over the style of its output,
+// so we write serde_yaml::Value ourselves.
+
+use serde_yaml::{Mapping, Value};
+
+use crate::graph_operations::compact::shift_remove;
+use crate::DemesError;
+use crate::Graph;
+use crate::KeyOrder;
+use crate::SerializeOptions;
+use crate::YamlStyle;
+
+// Where a mapping appears in the graph.
+// Used to find the deme epochs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Graph,
+    Deme,
+    Other,
+}
+
+struct Writer {
+    output: String,
+    flow_epochs: bool,
+}
+
+fn double_quoted(string: &str) -> String {
+    let mut quoted = String::with_capacity(string.len() + 2);
+    quoted.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Format a scalar.
+// Inside of flow collections, plain scalars cannot
+// contain the flow indicators.
+fn scalar(value: &Value, in_flow: bool) -> Result<String, DemesError> {
+    if let Value::String(string) = value {
+        if string.contains(['\n', '\r']) || (in_flow && string.contains([',', '[', ']', '{', '}']))
+        {
+            return Ok(double_quoted(string));
+        }
+    }
+    let formatted = serde_yaml::to_string(value)?;
+    Ok(formatted.trim_end_matches('\n').to_string())
+}
+
+fn flow(value: &Value) -> Result<String, DemesError> {
+    match value {
+        Value::Sequence(sequence) => Ok(format!(
+            "[{}]",
+            sequence
+                .iter()
+                .map(flow)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ")
+        )),
+        Value::Mapping(mapping) => Ok(format!(
+            "{{{}}}",
+            mapping
+                .iter()
+                .map(|(k, v)| Ok(format!("{}: {}", flow(k)?, flow(v)?)))
+                .collect::<Result<Vec<_>, DemesError>>()?
+                .join(", ")
+        )),
+        Value::Tagged(tagged) => Ok(format!("{} {}", tagged.tag, flow(&tagged.value)?)),
+        scalar_value => scalar(scalar_value, true),
+    }
+}
+
+// Format a value that is not inside a flow collection.
+fn block_value(value: &Value) -> Result<String, DemesError> {
+    match value {
+        Value::Sequence(_) | Value::Mapping(_) | Value::Tagged(_) => flow(value),
+        scalar_value => scalar(scalar_value, false),
+    }
+}
+
+impl Writer {
+    fn indent(&mut self, indent: usize) {
+        self.output.extend(std::iter::repeat(' ').take(indent));
+    }
+
+    // If `continued`, the first key is written on the current line
+    // (following the "- " of a sequence item).
+    fn mapping(
+        &mut self,
+        mapping: &Mapping,
+        indent: usize,
+        context: Context,
+        continued: bool,
+    ) -> Result<(), DemesError> {
+        for (i, (key, value)) in mapping.iter().enumerate() {
+            if i > 0 || !continued {
+                self.indent(indent);
+            }
+            self.output.push_str(&scalar(key, false)?);
+            self.output.push(':');
+            let item_context = match (context, key.as_str()) {
+                (Context::Graph, Some("demes")) => Context::Deme,
+                _ => Context::Other,
+            };
+            let flow_items =
+                self.flow_epochs && context == Context::Deme && key.as_str() == Some("epochs");
+            match value {
+                Value::Mapping(m) if !m.is_empty() => {
+                    self.output.push('\n');
+                    self.mapping(m, indent + 2, Context::Other, false)?;
+                }
+                Value::Sequence(s) if !s.is_empty() => {
+                    self.output.push('\n');
+                    self.sequence(s, indent, item_context, flow_items)?;
+                }
+                _ => {
+                    self.output.push(' ');
+                    self.output.push_str(&block_value(value)?);
+                    self.output.push('\n');
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn sequence(
+        &mut self,
+        sequence: &[Value],
+        indent: usize,
+        context: Context,
+        flow_items: bool,
+    ) -> Result<(), DemesError> {
+        for value in sequence {
+            self.indent(indent);
+            self.output.push_str("- ");
+            match value {
+                Value::Mapping(m) if !m.is_empty() && !flow_items => {
+                    self.mapping(m, indent + 2, context, true)?;
+                }
+                _ => {
+                    self.output.push_str(&block_value(value)?);
+                    self.output.push('\n');
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut entries = std::mem::take(mapping).into_iter().collect::<Vec<_>>();
+            entries.sort_by_cached_key(|(k, _)| flow(k).unwrap_or_default());
+            for (_, v) in entries.iter_mut() {
+                sort_keys(v);
+            }
+            *mapping = entries.into_iter().collect();
+        }
+        Value::Sequence(sequence) => sequence.iter_mut().for_each(sort_keys),
+        Value::Tagged(tagged) => sort_keys(&mut tagged.value),
+        _ => (),
+    }
+}
+
+fn round_floats(value: &mut Value, significant_digits: usize) {
+    match value {
+        Value::Number(number) => {
+            if let Some(x) = number.as_f64().filter(|x| x.is_finite() && number.is_f64()) {
+                let digits = significant_digits.max(1) - 1;
+                // Unwrap is fine: we are parsing the output of format!
+                let rounded = format!("{x:.digits$e}").parse::<f64>().unwrap();
+                *number = rounded.into();
+            }
+        }
+        Value::Mapping(mapping) => mapping
+            .iter_mut()
+            .for_each(|(_, v)| round_floats(v, significant_digits)),
+        Value::Sequence(sequence) => sequence
+            .iter_mut()
+            .for_each(|v| round_floats(v, significant_digits)),
+        _ => (),
+    }
+}
+
+pub(crate) fn serialize_with(
+    graph: &Graph,
+    options: SerializeOptions,
+) -> Result<String, DemesError> {
+    let mut value = if options.compact() {
+        crate::graph_operations::compact::compact_value(graph)?
+    } else {
+        serde_yaml::to_value(graph)?
+    };
+    let mapping = value
+        .as_mapping_mut()
+        .ok_or_else(|| DemesError::GraphError("expected a YAML mapping".into()))?;
+
+    if !options.include_metadata() {
+        shift_remove(mapping, "metadata");
+    }
+    if !options.include_description() {
+        shift_remove(mapping, "description");
+        if let Some(Value::Sequence(demes)) = mapping.get_mut("demes") {
+            for deme in demes.iter_mut().filter_map(|d| d.as_mapping_mut()) {
+                shift_remove(deme, "description");
+            }
+        }
+    }
+    if let Some(digits) = options.float_precision() {
+        mapping
+            .iter_mut()
+            .filter(|(k, _)| k.as_str() != Some("metadata"))
+            .for_each(|(_, v)| round_floats(v, digits));
+    }
+    if matches!(options.key_order(), KeyOrder::Sorted) {
+        sort_keys(&mut value);
+    }
+
+    let mut writer = Writer {
+        output: String::new(),
+        flow_epochs: matches!(options.epoch_style(), YamlStyle::Flow),
+    };
+    match &value {
+        Value::Mapping(mapping) => writer.mapping(mapping, 0, Context::Graph, false)?,
+        _ => unreachable!("a graph is written as a mapping"),
+    }
+    Ok(writer.output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static YAML: &str = "
+time_units: years
+generation_time: 25
+description: a model
+metadata:
+  key: [1, 2, {a: 'b, c'}]
+  nested:
+    text: |
+      two
+      lines
+demes:
+ - name: A
+   description: ancestral, really
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 123.456789
+ - name: B
+   ancestors: [A]
+   start_time: 500
+   epochs:
+    - start_size: 50
+migrations:
+ - demes: [A, B]
+   rate: 1e-3
+";
+
+    #[test]
+    fn test_default_options_round_trip() {
+        let graph = crate::loads(YAML).unwrap();
+        let output = serialize_with(&graph, SerializeOptions::default()).unwrap();
+        // Only the style of multi-line strings differs
+        let expected: Value = serde_yaml::from_str(&graph.as_string().unwrap()).unwrap();
+        assert_eq!(serde_yaml::from_str::<Value>(&output).unwrap(), expected);
+        assert!(output.contains("  - a: b, c\n"));
+        assert!(output.contains("    text: \"two\\nlines\\n\"\n"));
+        assert_eq!(crate::loads(&output).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_options_round_trip() {
+        let graph = crate::loads(YAML).unwrap();
+        for compact in [false, true] {
+            for epoch_style in [YamlStyle::Block, YamlStyle::Flow] {
+                for key_order in [KeyOrder::Default, KeyOrder::Sorted] {
+                    let options = SerializeOptions::default()
+                        .set_compact(compact)
+                        .set_epoch_style(epoch_style)
+                        .set_key_order(key_order);
+                    let output = serialize_with(&graph, options).unwrap();
+                    assert_eq!(crate::loads(&output).unwrap(), graph, "{output}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let graph = crate::loads(YAML).unwrap();
+        let options = SerializeOptions::default()
+            .set_epoch_style(YamlStyle::Flow)
+            .set_float_precision(Some(4))
+            .set_include_description(false)
+            .set_include_metadata(false)
+            .set_compact(true);
+        let output = serialize_with(&graph, options).unwrap();
+        assert!(!output.contains("description"));
+        assert!(!output.contains("metadata"));
+        assert!(output.contains("  - {start_size: 123.5}\n"));
+        let rounded = crate::loads(&output).unwrap();
+        assert_eq!(rounded.deme(0).epochs()[1].start_size(), 123.5);
+
+        let output = serialize_with(
+            &graph,
+            SerializeOptions::default().set_key_order(KeyOrder::Sorted),
+        )
+        .unwrap();
+        assert!(output.starts_with("demes:\n"));
+        assert!(output.find("generation_time").unwrap() < output.find("migrations").unwrap());
+    }
+}
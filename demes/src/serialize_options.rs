@@ -0,0 +1,147 @@
+/// The YAML style used for a collection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum YamlStyle {
+    /// One entry per line, using indentation to show structure.
+    #[default]
+    Block,
+    /// All entries on one line, using `{}` and `[]`.
+    Flow,
+}
+
+/// The order of the keys of YAML mappings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyOrder {
+    /// The order used by [`Graph::as_string`](crate::Graph::as_string).
+    #[default]
+    Default,
+    /// Keys are sorted alphabetically.
+    Sorted,
+}
+
+/// Options controlling the output of
+/// [`Graph::serialize_with`](crate::Graph::serialize_with).
+///
+/// The default options produce output equivalent to
+/// [`Graph::as_string`](crate::Graph::as_string).
+///
+/// # Examples
+///
+/// ```
+/// let options = demes::SerializeOptions::default()
+///     .set_epoch_style(demes::YamlStyle::Flow)
+///     .set_float_precision(Some(6))
+///     .set_include_metadata(false);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SerializeOptions {
+    compact: bool,
+    epoch_style: YamlStyle,
+    float_precision: Option<usize>,
+    include_description: bool,
+    include_metadata: bool,
+    key_order: KeyOrder,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            compact: false,
+            epoch_style: YamlStyle::default(),
+            float_precision: None,
+            include_description: true,
+            include_metadata: true,
+            key_order: KeyOrder::default(),
+        }
+    }
+}
+
+impl SerializeOptions {
+    /// Omit values that are equal to the defaults given by the
+    /// specification.
+    ///
+    /// See [`Graph::as_compact_string`](crate::Graph::as_compact_string).
+    /// The default is `false`.
+    pub fn set_compact(self, compact: bool) -> Self {
+        Self { compact, ..self }
+    }
+
+    /// Set the YAML style of deme epochs.
+    pub fn set_epoch_style(self, epoch_style: YamlStyle) -> Self {
+        Self {
+            epoch_style,
+            ..self
+        }
+    }
+
+    /// Set the maximum number of significant digits of
+    /// floating-point values.
+    ///
+    /// `None`, the default, writes values at full precision.
+    /// Values in the top-level metadata are not changed.
+    ///
+    /// # Note
+    ///
+    /// Loading the output will not, in general, give a graph
+    /// equal to the input when values are rounded.
+    pub fn set_float_precision(self, float_precision: Option<usize>) -> Self {
+        Self {
+            float_precision,
+            ..self
+        }
+    }
+
+    /// Include the `description` fields of the graph and of the demes.
+    /// The default is `true`.
+    pub fn set_include_description(self, include_description: bool) -> Self {
+        Self {
+            include_description,
+            ..self
+        }
+    }
+
+    /// Include the top-level metadata.
+    /// The default is `true`.
+    pub fn set_include_metadata(self, include_metadata: bool) -> Self {
+        Self {
+            include_metadata,
+            ..self
+        }
+    }
+
+    /// Set the order of keys.
+    pub fn set_key_order(self, key_order: KeyOrder) -> Self {
+        Self { key_order, ..self }
+    }
+
+    /// Whether values equal to the specification defaults are omitted.
+    pub fn compact(&self) -> bool {
+        self.compact
+    }
+
+    /// The YAML style of deme epochs.
+    pub fn epoch_style(&self) -> YamlStyle {
+        self.epoch_style
+    }
+
+    /// The maximum number of significant digits of floating-point values.
+    pub fn float_precision(&self) -> Option<usize> {
+        self.float_precision
+    }
+
+    /// Whether `description` fields are included.
+    pub fn include_description(&self) -> bool {
+        self.include_description
+    }
+
+    /// Whether the top-level metadata is included.
+    pub fn include_metadata(&self) -> bool {
+        self.include_metadata
+    }
+
+    /// The order of keys.
+    pub fn key_order(&self) -> KeyOrder {
+        self.key_order
+    }
+}
@@ -6,6 +6,8 @@ use crate::time::*;
 use crate::CloningRate;
 use crate::DemeSize;
 use crate::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
 use crate::InputCloningRate;
 use crate::InputDemeSize;
 use crate::InputMigrationRate;
@@ -19,10 +21,11 @@ use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::io::Read;
+use std::sync::Arc;
 
 macro_rules! get_deme {
     ($name: expr, $deme_map: expr, $demes: expr) => {
-        match $deme_map.get($name) {
+        match $deme_map.get(AsRef::<str>::as_ref($name)) {
             Some(index) => $demes.get(*index),
             None => None,
         }
@@ -39,6 +42,18 @@ fn rescale_input_size(input: Option<InputDemeSize>, scaling_factor: f64) -> Opti
     input.map(|size| (f64::from(size) / scaling_factor).into())
 }
 
+// Add an offset to all finite times
+fn shift_input_time(input: Option<InputTime>, offset: f64) -> Option<InputTime> {
+    input.map(|time| {
+        let time = f64::from(time);
+        if time.is_finite() {
+            (time + offset).into()
+        } else {
+            time.into()
+        }
+    })
+}
+
 // Multiply all migration rates by the scaling factor
 fn rescale_input_migration_rate(
     input: Option<InputMigrationRate>,
@@ -56,8 +71,9 @@ fn size_at_details<F: Into<f64>>(
     size_function: SizeFunction,
 ) -> Result<Option<f64>, DemesError> {
     let time: f64 = time.into();
-    Time::try_from(time)
-        .map_err(|_| DemesError::EpochError(format!("invalid time value: {time:?}")))?;
+    Time::try_from(time).map_err(|_| {
+        DemesError::EpochError(invalid(format!("invalid time value: {time:?}")).with_value(time))
+    })?;
 
     if time == f64::INFINITY && epoch_start_time == f64::INFINITY {
         return Ok(Some(epoch_start_size));
@@ -80,6 +96,89 @@ fn size_at_details<F: Into<f64>>(
     Ok(Some(size))
 }
 
+// The integral of size over a time span during which
+// the size changes from start_size to end_size.
+fn size_integral_details(
+    start_size: f64,
+    end_size: f64,
+    duration: f64,
+    size_function: SizeFunction,
+) -> f64 {
+    if start_size == end_size {
+        return start_size * duration;
+    }
+    match size_function {
+        SizeFunction::Constant => end_size * duration,
+        SizeFunction::Linear => 0.5 * (start_size + end_size) * duration,
+        SizeFunction::Exponential => {
+            duration * (end_size - start_size) / (end_size / start_size).ln()
+        }
+    }
+}
+
+// Evaluate sizes within an epoch at many times.
+// The growth rate is computed once and input times
+// must be validated by the caller.
+// The calculations are identical to size_at_details.
+struct EpochSizes {
+    start_time: f64,
+    end_time: f64,
+    start_size: f64,
+    end_size: f64,
+    size_function: SizeFunction,
+    growth_rate: f64,
+}
+
+impl EpochSizes {
+    fn new(epoch: &Epoch) -> Self {
+        let start_time = f64::from(epoch.start_time);
+        let end_time = f64::from(epoch.end_time);
+        let start_size = f64::from(epoch.start_size);
+        let end_size = f64::from(epoch.end_size);
+        let growth_rate = match epoch.size_function {
+            SizeFunction::Exponential => (end_size / start_size).ln() / (start_time - end_time),
+            _ => 0.0,
+        };
+        Self {
+            start_time,
+            end_time,
+            start_size,
+            end_size,
+            size_function: epoch.size_function,
+            growth_rate,
+        }
+    }
+
+    // The size at a time in [end_time, start_time].
+    fn size_within(&self, time: f64) -> f64 {
+        let dt = self.start_time - time;
+        match self.size_function {
+            SizeFunction::Constant => self.end_size,
+            SizeFunction::Linear => {
+                self.start_size
+                    + dt * (self.end_size - self.start_size) / (self.start_time - self.end_time)
+            }
+            SizeFunction::Exponential => self.start_size * (self.growth_rate * dt).exp(),
+        }
+    }
+
+    fn size_at(&self, time: f64) -> Result<Option<DemeSize>, DemesError> {
+        let size = if time == f64::INFINITY && self.start_time == f64::INFINITY {
+            self.start_size
+        } else if time < self.end_time || time >= self.start_time {
+            return Ok(None);
+        } else {
+            self.size_within(time)
+        };
+        match DemeSize::try_from(size) {
+            Ok(size) => Ok(Some(size)),
+            Err(_) => Err(DemesError::EpochError(
+                invalid(format!("size calculation led to invalid size: {size}")).with_value(size),
+            )),
+        }
+    }
+}
+
 /// Specify how deme sizes change during an [`Epoch`](crate::Epoch).
 ///
 /// # Examples
@@ -191,18 +290,74 @@ impl Display for SizeFunction {
     }
 }
 
+/// The index of a deme in a [`Graph`].
+///
+/// The index is the position of the deme in
+/// [`Graph::demes`](crate::Graph::demes).
+/// Having a separate type prevents deme indexes from being
+/// confused with other indexes, such as those of epochs.
+///
+/// # Examples
+///
+/// ```
+/// let yaml = "
+/// time_units: generations
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+///       end_time: 50
+///  - name: B
+///    ancestors: [A]
+///    epochs:
+///     - start_size: 100
+/// ";
+/// let graph = demes::loads(yaml).unwrap();
+/// let ancestor = graph.deme("B").ancestor_indexes()[0];
+/// assert_eq!(ancestor, demes::DemeIndex::from(0));
+/// assert_eq!(graph.deme(ancestor).name(), "A");
+/// assert_eq!(usize::from(ancestor), 0);
+/// ```
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DemeIndex(usize);
+
+impl From<usize> for DemeIndex {
+    fn from(value: usize) -> Self {
+        Self(value)
+    }
+}
+
+impl From<DemeIndex> for usize {
+    fn from(value: DemeIndex) -> Self {
+        value.0
+    }
+}
+
+impl Display for DemeIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// A deme can be identified as an index
 /// or as a name
 #[derive(Copy, Clone, Debug)]
 pub enum DemeId<'name> {
     /// The index of a deme
-    Index(usize),
+    Index(DemeIndex),
     /// The name of a deme
     Name(&'name str),
 }
 
 impl From<usize> for DemeId<'_> {
     fn from(value: usize) -> Self {
+        Self::Index(value.into())
+    }
+}
+
+impl From<DemeIndex> for DemeId<'_> {
+    fn from(value: DemeIndex) -> Self {
         Self::Index(value)
     }
 }
@@ -254,13 +409,12 @@ pub struct UnresolvedMigration {
 impl UnresolvedMigration {
     fn validate(&self) -> Result<(), DemesError> {
         if let Some(value) = self.start_time {
-            Time::try_from(value).map_err(|_| {
-                DemesError::MigrationError(format!("invalid start_time: {value:?}"))
-            })?;
+            Time::try_from(value)
+                .map_err(|_| DemesError::MigrationError(invalid_value("start_time", value)))?;
         }
         if let Some(value) = self.end_time {
             Time::try_from(value)
-                .map_err(|_| DemesError::MigrationError(format!("invalid end_time: {value:?}")))?;
+                .map_err(|_| DemesError::MigrationError(invalid_value("end_time", value)))?;
         }
         Ok(())
     }
@@ -269,17 +423,17 @@ impl UnresolvedMigration {
         let source = self
             .source
             .as_ref()
-            .ok_or_else(|| DemesError::MigrationError("source is none".to_string()))?;
+            .ok_or_else(|| DemesError::MigrationError(missing("source is none")))?;
 
         let dest = self
             .dest
             .as_ref()
-            .ok_or_else(|| DemesError::MigrationError("dest is none".to_string()))?;
+            .ok_or_else(|| DemesError::MigrationError(missing("dest is none")))?;
 
         self.rate.ok_or_else(|| {
-            DemesError::MigrationError(format!(
+            DemesError::MigrationError(missing(format!(
                 "rate frmm source: {source} to dest: {dest} is None",
-            ))
+            )))
         })?;
 
         Ok(())
@@ -289,33 +443,39 @@ impl UnresolvedMigration {
         let demes = self
             .demes
             .as_ref()
-            .ok_or_else(|| DemesError::MigrationError("demes is None".to_string()))?;
+            .ok_or_else(|| DemesError::MigrationError(missing("demes is None")))?;
         self.rate.ok_or_else(|| {
-            DemesError::MigrationError(format!("migration rate among {demes:?} is None",))
+            DemesError::MigrationError(missing(format!("migration rate among {demes:?} is None",)))
         })?;
         Ok(())
     }
 
     fn resolved_rate_or_err(&self) -> Result<InputMigrationRate, DemesError> {
-        self.rate
-            .ok_or_else(|| DemesError::MigrationError("migration rate not resolved".to_string()))
+        self.rate.ok_or_else(|| {
+            DemesError::MigrationError(ErrorContext::new(
+                "migration rate not resolved",
+                ErrorKind::Missing,
+            ))
+        })
     }
 
     fn resolved_dest_or_err(&self) -> Result<String, DemesError> {
         match &self.dest {
             Some(dest) => Ok(dest.to_string()),
-            None => Err(DemesError::MigrationError(
-                "migration dest not resolved".to_string(),
-            )),
+            None => Err(DemesError::MigrationError(ErrorContext::new(
+                "migration dest not resolved",
+                ErrorKind::Missing,
+            ))),
         }
     }
 
     fn resolved_source_or_err(&self) -> Result<String, DemesError> {
         match &self.source {
             Some(source) => Ok(source.to_string()),
-            None => Err(DemesError::MigrationError(
-                "migration source not resolved".to_string(),
-            )),
+            None => Err(DemesError::MigrationError(ErrorContext::new(
+                "migration source not resolved",
+                ErrorKind::Missing,
+            ))),
         }
     }
 
@@ -444,6 +604,11 @@ impl UnresolvedMigration {
         self.rate = rescale_input_migration_rate(self.rate, scaling_factor);
         Ok(())
     }
+
+    fn shift_times(&mut self, offset: f64) {
+        self.start_time = shift_input_time(self.start_time, offset);
+        self.end_time = shift_input_time(self.end_time, offset);
+    }
 }
 
 /// An asymmetric migration epoch.
@@ -451,8 +616,8 @@ impl UnresolvedMigration {
 /// All input migrations are resolved to asymmetric migration instances.
 #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
 pub struct AsymmetricMigration {
-    source: String,
-    dest: String,
+    source: Arc<str>,
+    dest: Arc<str>,
     rate: MigrationRate,
     start_time: Time,
     end_time: Time,
@@ -462,7 +627,7 @@ impl AsymmetricMigration {
     fn resolved_time_to_generations(
         &mut self,
         generation_time: GenerationTime,
-        rounding: fn(Time, GenerationTime) -> Time,
+        rounding: &mut dyn FnMut(Time, GenerationTime) -> Time,
     ) -> Result<(), DemesError> {
         self.start_time = convert_resolved_time_to_generations(
             generation_time,
@@ -480,10 +645,9 @@ impl AsymmetricMigration {
         )?;
 
         if self.end_time >= self.start_time {
-            Err(DemesError::MigrationError(
-                "conversion of migration times to generations resulted in a zero-length epoch"
-                    .to_string(),
-            ))
+            Err(DemesError::MigrationError(invalid(
+                "conversion of migration times to generations resulted in a zero-length epoch",
+            )))
         } else {
             Ok(())
         }
@@ -532,9 +696,9 @@ impl TryFrom<UnresolvedMigration> for Migration {
     fn try_from(value: UnresolvedMigration) -> Result<Self, Self::Error> {
         if value.demes.is_none() {
             if value.source.is_none() || value.dest.is_none() {
-                Err(DemesError::MigrationError(
-                    "a migration must specify either demes or source and dest".to_string(),
-                ))
+                Err(DemesError::MigrationError(missing(
+                    "a migration must specify either demes or source and dest",
+                )))
             } else {
                 value.valid_asymmetric_or_err()?;
                 Ok(Migration::Asymmetric(UnresolvedMigration {
@@ -547,10 +711,9 @@ impl TryFrom<UnresolvedMigration> for Migration {
                 }))
             }
         } else if value.source.is_some() || value.dest.is_some() {
-            Err(DemesError::MigrationError(
-                "a migration must specify either demes or source and dest, but not both"
-                    .to_string(),
-            ))
+            Err(DemesError::MigrationError(inconsistent(
+                "a migration must specify either demes or source and dest, but not both",
+            )))
         } else {
             value.valid_symmetric_or_err()?;
             Ok(Migration::Symmetric(value))
@@ -572,7 +735,7 @@ impl From<AsymmetricMigration> for UnresolvedMigration {
         Self {
             demes: None,
             source: Some(value.source().to_owned()),
-            dest: Some(value.dest.to_owned()),
+            dest: Some(value.dest().to_owned()),
             start_time: Some(value.start_time().into()),
             end_time: Some(value.end_time().into()),
             rate: Some(f64::from(value.rate()).into()),
@@ -584,8 +747,8 @@ impl From<AsymmetricMigration> for UnresolvedMigration {
 #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Pulse {
-    sources: Vec<String>,
-    dest: String,
+    sources: Vec<Arc<str>>,
+    dest: Arc<str>,
     time: Time,
     proportions: Vec<Proportion>,
 }
@@ -608,22 +771,44 @@ impl TryFrom<UnresolvedPulse> for Pulse {
     type Error = DemesError;
     fn try_from(value: UnresolvedPulse) -> Result<Self, Self::Error> {
         let input_proportions = value.proportions.ok_or_else(|| {
-            DemesError::PulseError("pulse proportions are unresolved".to_string())
+            DemesError::PulseError(ErrorContext::new(
+                "pulse proportions are unresolved",
+                ErrorKind::Missing,
+            ))
         })?;
         let mut proportions = vec![];
         for p in input_proportions {
             proportions.push(Proportion::try_from(p)?);
         }
         Ok(Self {
-            sources: value.sources.ok_or_else(|| {
-                DemesError::PulseError("pulse sources are unresolved".to_string())
-            })?,
+            sources: value
+                .sources
+                .ok_or_else(|| {
+                    DemesError::PulseError(ErrorContext::new(
+                        "pulse sources are unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
+                .into_iter()
+                .map(Arc::from)
+                .collect(),
             dest: value
                 .dest
-                .ok_or_else(|| DemesError::PulseError("pulse dest are unresolved".to_string()))?,
+                .ok_or_else(|| {
+                    DemesError::PulseError(ErrorContext::new(
+                        "pulse dest are unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
+                .into(),
             time: value
                 .time
-                .ok_or_else(|| DemesError::PulseError("pulse time are unresolved".to_string()))?
+                .ok_or_else(|| {
+                    DemesError::PulseError(ErrorContext::new(
+                        "pulse time are unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
                 .try_into()?,
             proportions,
         })
@@ -633,8 +818,8 @@ impl TryFrom<UnresolvedPulse> for Pulse {
 impl From<Pulse> for UnresolvedPulse {
     fn from(value: Pulse) -> Self {
         Self {
-            sources: Some(value.sources),
-            dest: Some(value.dest),
+            sources: Some(value.sources.iter().map(|s| s.to_string()).collect()),
+            dest: Some(value.dest.to_string()),
             time: Some(f64::from(value.time).into()),
             proportions: Some(
                 value
@@ -648,19 +833,107 @@ impl From<Pulse> for UnresolvedPulse {
 }
 
 impl UnresolvedPulse {
+    /// Set the source demes
+    ///
+    /// See [`GraphBuilder::add_pulse_builder`](crate::GraphBuilder::add_pulse_builder).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedPulse::default().set_sources(["A", "B"]);
+    /// ```
+    pub fn set_sources<I, A>(self, sources: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<str>,
+    {
+        Self {
+            sources: Some(
+                sources
+                    .into_iter()
+                    .map(|a| a.as_ref().to_owned())
+                    .collect::<Vec<_>>(),
+            ),
+            ..self
+        }
+    }
+
+    /// Set the destination deme
+    ///
+    /// See [`GraphBuilder::add_pulse_builder`](crate::GraphBuilder::add_pulse_builder).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedPulse::default().set_dest("A");
+    /// ```
+    pub fn set_dest<A>(self, dest: A) -> Self
+    where
+        A: AsRef<str>,
+    {
+        Self {
+            dest: Some(dest.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    /// Set the time
+    ///
+    /// See [`GraphBuilder::add_pulse_builder`](crate::GraphBuilder::add_pulse_builder).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedPulse::default().set_time(10.);
+    /// ```
+    pub fn set_time<T>(self, time: T) -> Self
+    where
+        T: Into<InputTime>,
+    {
+        Self {
+            time: Some(time.into()),
+            ..self
+        }
+    }
+
+    /// Set the proportions
+    ///
+    /// See [`GraphBuilder::add_pulse_builder`](crate::GraphBuilder::add_pulse_builder).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedPulse::default().set_proportions([0.25, 0.5]);
+    /// ```
+    pub fn set_proportions<I, P>(self, proportions: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<InputProportion>,
+    {
+        Self {
+            proportions: Some(
+                proportions
+                    .into_iter()
+                    .map(|p| p.into())
+                    .collect::<Vec<_>>(),
+            ),
+            ..self
+        }
+    }
+
     fn validate_as_default(&self) -> Result<(), DemesError> {
         if let Some(value) = self.time {
             Time::try_from(value)
-                .map_err(|_| DemesError::PulseError(format!("invalid time: {value:?}")))?;
+                .map_err(|_| DemesError::PulseError(invalid_value("time", value)))?;
         }
 
         if let Some(proportions) = &self.proportions {
             for v in proportions {
                 if Proportion::try_from(*v).is_err() {
-                    return Err(DemesError::PulseError(format!(
+                    return Err(DemesError::PulseError(invalid(format!(
                         "invalid proportion: {:?}",
                         *v
-                    )));
+                    ))));
                 }
             }
         }
@@ -672,12 +945,12 @@ impl UnresolvedPulse {
         Ok(self
             .proportions
             .as_ref()
-            .ok_or_else(|| DemesError::PulseError("proportions are None".to_string()))?)
+            .ok_or_else(|| DemesError::PulseError(missing("proportions are None")))?)
     }
 
     fn get_time(&self) -> Result<Time, DemesError> {
         self.time
-            .ok_or_else(|| DemesError::PulseError("time is None".to_string()))?
+            .ok_or_else(|| DemesError::PulseError(missing("time is None")))?
             .try_into()
     }
 
@@ -685,14 +958,14 @@ impl UnresolvedPulse {
         Ok(self
             .sources
             .as_ref()
-            .ok_or_else(|| DemesError::PulseError("sources are None".to_string()))?)
+            .ok_or_else(|| DemesError::PulseError(missing("sources are None")))?)
     }
 
     fn get_dest(&self) -> Result<&str, DemesError> {
         Ok(self
             .dest
             .as_ref()
-            .ok_or_else(|| DemesError::PulseError("pulse dest is None".to_string()))?)
+            .ok_or_else(|| DemesError::PulseError(missing("pulse dest is None")))?)
     }
 
     fn resolve(&mut self, defaults: &GraphDefaults) -> Result<(), DemesError> {
@@ -702,15 +975,16 @@ impl UnresolvedPulse {
 
     fn validate_proportions(&self, sources: &[String]) -> Result<(), DemesError> {
         if self.proportions.is_none() {
-            return Err(DemesError::PulseError("proportions is None".to_string()));
+            return Err(DemesError::PulseError(missing("proportions is None")));
         }
         let proportions = self.get_proportions()?;
         for p in proportions.iter() {
-            Proportion::try_from(*p)
-                .map_err(|_| DemesError::PulseError(format!("invalid proportion: {:?}", *p)))?;
+            Proportion::try_from(*p).map_err(|_| {
+                DemesError::PulseError(invalid(format!("invalid proportion: {:?}", *p)))
+            })?;
         }
         if proportions.len() != sources.len() {
-            return Err(DemesError::PulseError(format!("number of sources must equal number of proportions; got {} source and {} proportions", sources.len(), proportions.len())));
+            return Err(DemesError::PulseError(inconsistent(format!("number of sources must equal number of proportions; got {} source and {} proportions", sources.len(), proportions.len()))));
         }
 
         let sum_proportions = proportions
@@ -718,9 +992,12 @@ impl UnresolvedPulse {
             .fold(0.0, |sum, &proportion| sum + f64::from(proportion));
 
         if !(1e-9..1.0 + 1e-9).contains(&sum_proportions) {
-            return Err(DemesError::PulseError(format!(
-                "pulse proportions must sum to 0.0 < p < 1.0, got: {sum_proportions}",
-            )));
+            return Err(DemesError::PulseError(
+                invalid(format!(
+                    "pulse proportions must sum to 0.0 < p < 1.0, got: {sum_proportions}",
+                ))
+                .with_value(sum_proportions),
+            ));
         }
 
         Ok(())
@@ -735,34 +1012,44 @@ impl UnresolvedPulse {
         sources: &[String],
     ) -> Result<(), DemesError> {
         if !time.is_valid_pulse_time() {
-            return Err(DemesError::PulseError(format!(
+            return Err(DemesError::PulseError(invalid(format!(
                 "invalid pulse time: {}",
                 f64::from(time)
-            )));
+            ))));
         }
 
         for source_name in sources {
             let source = get_deme!(source_name, deme_map, demes).ok_or_else(|| {
-                DemesError::PulseError(format!("invalid pulse source: {source_name}"))
+                DemesError::PulseError(unknown_deme(
+                    format!("invalid pulse source: {source_name}"),
+                    source_name,
+                ))
             })?;
 
             let ti = source.get_time_interval()?;
 
             if !ti.contains_exclusive_start_inclusive_end(time) {
-                return Err(DemesError::PulseError(format!(
-                    "pulse at time: {time:?} does not overlap with source: {source_name}",
-                )));
+                return Err(DemesError::PulseError(
+                    inconsistent(format!(
+                        "pulse at time: {time:?} does not overlap with source: {source_name}",
+                    ))
+                    .with_deme(source_name.as_str()),
+                ));
             }
         }
 
-        let dest_deme = get_deme!(dest, deme_map, demes)
-            .ok_or_else(|| DemesError::PulseError(format!("invalid pulse dest: {dest}")))?;
+        let dest_deme = get_deme!(dest, deme_map, demes).ok_or_else(|| {
+            DemesError::PulseError(unknown_deme(format!("invalid pulse dest: {dest}"), dest))
+        })?;
         let ti = dest_deme.get_time_interval()?;
         if !ti.contains_inclusive_start_exclusive_end(time) {
-            return Err(DemesError::PulseError(format!(
-                "pulse at time: {:?} does not overlap with dest: {}",
-                time, dest_deme.name,
-            )));
+            return Err(DemesError::PulseError(
+                inconsistent(format!(
+                    "pulse at time: {:?} does not overlap with dest: {}",
+                    time, dest_deme.name,
+                ))
+                .with_deme(dest_deme.name.as_str()),
+            ));
         }
 
         Ok(())
@@ -779,23 +1066,27 @@ impl UnresolvedPulse {
             Some(d) => {
                 let t = d.get_time_interval()?;
                 if !t.contains_inclusive(time) {
-                    return Err(DemesError::PulseError(format!(
-                        "destination deme {dest} does not exist at time of pulse",
-                    )));
+                    return Err(DemesError::PulseError(
+                        inconsistent(format!(
+                            "destination deme {dest} does not exist at time of pulse",
+                        ))
+                        .with_deme(dest),
+                    ));
                 }
                 Ok(())
             }
-            None => Err(DemesError::PulseError(format!(
-                "pulse deme {dest} is invalid",
+            None => Err(DemesError::PulseError(unknown_deme(
+                format!("pulse deme {dest} is invalid"),
+                dest,
             ))),
         }
     }
 
     fn dest_is_not_source(&self, dest: &str, sources: &[String]) -> Result<(), DemesError> {
         if sources.iter().any(|s| s.as_str() == dest) {
-            Err(DemesError::PulseError(format!(
-                "dest: {dest} is also listed as a source",
-            )))
+            Err(DemesError::PulseError(
+                inconsistent(format!("dest: {dest} is also listed as a source",)).with_deme(dest),
+            ))
         } else {
             Ok(())
         }
@@ -805,9 +1096,13 @@ impl UnresolvedPulse {
         let mut unique_sources = HashSet::<String>::default();
         for source in sources {
             if unique_sources.contains(source) {
-                return Err(DemesError::PulseError(format!(
-                    "source: {source} listed multiple times",
-                )));
+                return Err(DemesError::PulseError(
+                    ErrorContext::new(
+                        format!("source: {source} listed multiple times",),
+                        ErrorKind::DuplicateDeme,
+                    )
+                    .with_deme(source.as_str()),
+                ));
             }
             unique_sources.insert(source.clone());
         }
@@ -834,13 +1129,17 @@ impl UnresolvedPulse {
         self.time = rescale_input_time(self.time, scaling_factor);
         Ok(())
     }
+
+    fn shift_times(&mut self, offset: f64) {
+        self.time = shift_input_time(self.time, offset);
+    }
 }
 
 impl Pulse {
     fn resolved_time_to_generations(
         &mut self,
         generation_time: GenerationTime,
-        rounding: fn(Time, GenerationTime) -> Time,
+        rounding: &mut dyn FnMut(Time, GenerationTime) -> Time,
     ) -> Result<(), DemesError> {
         self.time = convert_resolved_time_to_generations(
             generation_time,
@@ -858,7 +1157,7 @@ impl Pulse {
     }
 
     /// Resolved pulse source demes as slice
-    pub fn sources(&self) -> &[String] {
+    pub fn sources(&self) -> &[Arc<str>] {
         &self.sources
     }
 
@@ -934,37 +1233,156 @@ impl From<Epoch> for UnresolvedEpoch {
 }
 
 impl UnresolvedEpoch {
+    /// Set the end time
+    ///
+    /// See [`GraphBuilder::add_deme_with`](crate::GraphBuilder::add_deme_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedEpoch::default().set_end_time(10.);
+    /// ```
+    pub fn set_end_time<T>(self, time: T) -> Self
+    where
+        T: Into<InputTime>,
+    {
+        Self {
+            end_time: Some(time.into()),
+            ..self
+        }
+    }
+
+    /// Set the start size
+    ///
+    /// See [`GraphBuilder::add_deme_with`](crate::GraphBuilder::add_deme_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedEpoch::default().set_start_size(100.);
+    /// ```
+    pub fn set_start_size<S>(self, size: S) -> Self
+    where
+        S: Into<InputDemeSize>,
+    {
+        Self {
+            start_size: Some(size.into()),
+            ..self
+        }
+    }
+
+    /// Set the end size
+    ///
+    /// See [`GraphBuilder::add_deme_with`](crate::GraphBuilder::add_deme_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedEpoch::default().set_end_size(100.);
+    /// ```
+    pub fn set_end_size<S>(self, size: S) -> Self
+    where
+        S: Into<InputDemeSize>,
+    {
+        Self {
+            end_size: Some(size.into()),
+            ..self
+        }
+    }
+
+    /// Set the size function
+    ///
+    /// See [`GraphBuilder::add_deme_with`](crate::GraphBuilder::add_deme_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedEpoch::default().set_size_function(demes::SizeFunction::Linear);
+    /// ```
+    pub fn set_size_function(self, size_function: SizeFunction) -> Self {
+        Self {
+            size_function: Some(size_function),
+            ..self
+        }
+    }
+
+    /// Set the cloning rate
+    ///
+    /// See [`GraphBuilder::add_deme_with`](crate::GraphBuilder::add_deme_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedEpoch::default().set_cloning_rate(0.5);
+    /// ```
+    pub fn set_cloning_rate<R>(self, rate: R) -> Self
+    where
+        R: Into<InputCloningRate>,
+    {
+        Self {
+            cloning_rate: Some(rate.into()),
+            ..self
+        }
+    }
+
+    /// Set the selfing rate
+    ///
+    /// See [`GraphBuilder::add_deme_with`](crate::GraphBuilder::add_deme_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = demes::UnresolvedEpoch::default().set_selfing_rate(0.5);
+    /// ```
+    pub fn set_selfing_rate<R>(self, rate: R) -> Self
+    where
+        R: Into<InputSelfingRate>,
+    {
+        Self {
+            selfing_rate: Some(rate.into()),
+            ..self
+        }
+    }
+
     fn validate_as_default(&self) -> Result<(), DemesError> {
         if let Some(value) = self.end_time {
             Time::try_from(value)
-                .map_err(|_| DemesError::EpochError(format!("invalid end_time: {value:?}")))?;
+                .map_err(|_| DemesError::EpochError(invalid_value("end_time", value)))?;
         }
         if let Some(value) = self.start_size {
             DemeSize::try_from(value)
-                .map_err(|_| DemesError::EpochError(format!("invalid start_size: {value:?}")))?;
+                .map_err(|_| DemesError::EpochError(invalid_value("start_size", value)))?;
         }
         if let Some(value) = self.end_size {
             DemeSize::try_from(value)
-                .map_err(|_| DemesError::EpochError(format!("invalid end_size: {value:?}")))?;
+                .map_err(|_| DemesError::EpochError(invalid_value("end_size", value)))?;
         }
         if let Some(value) = self.cloning_rate {
             CloningRate::try_from(value)
-                .map_err(|_| DemesError::EpochError(format!("invalid cloning_rate: {value:?}")))?;
+                .map_err(|_| DemesError::EpochError(invalid_value("cloning_rate", value)))?;
         }
         if let Some(value) = self.selfing_rate {
             SelfingRate::try_from(value)
-                .map_err(|_| DemesError::EpochError(format!("invalid selfing_rate: {value:?}")))?;
+                .map_err(|_| DemesError::EpochError(invalid_value("selfing_rate", value)))?;
         }
         Ok(())
     }
 
-    fn rescale(&mut self, scaling_factor: f64) -> Result<(), DemesError> {
-        self.end_time = rescale_input_time(self.end_time, scaling_factor);
+    fn shift_times(&mut self, offset: f64) {
+        self.end_time = shift_input_time(self.end_time, offset);
+    }
+
+    fn rescale_sizes(&mut self, scaling_factor: f64) {
         self.start_size = rescale_input_size(self.start_size, scaling_factor);
         self.end_size = rescale_input_size(self.end_size, scaling_factor);
+    }
 
-        self.cloning_rate = self.cloning_rate.map_or_else(
-            || None,
+    fn rescale(&mut self, scaling_factor: f64) -> Result<(), DemesError> {
+        self.end_time = rescale_input_time(self.end_time, scaling_factor);
+        self.rescale_sizes(scaling_factor);
+
+        self.cloning_rate = self.cloning_rate.map_or_else(
+            || None,
             |c| {
                 if f64::from(c) > 0.0 {
                     Some(c)
@@ -1007,53 +1425,81 @@ impl Epoch {
         unresolved: UnresolvedEpoch,
     ) -> Result<Self, DemesError> {
         Ok(Self {
-            start_time: start_time.try_into().map_err(|_| {
-                DemesError::EpochError(format!("invalid start_time: {start_time:?}"))
-            })?,
+            start_time: start_time
+                .try_into()
+                .map_err(|_| DemesError::EpochError(invalid_value("start_time", start_time)))?,
             end_time: unresolved
                 .end_time
-                .ok_or_else(|| DemesError::EpochError("end_time unresolved".to_string()))?
+                .ok_or_else(|| {
+                    DemesError::EpochError(ErrorContext::new(
+                        "end_time unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
                 .try_into()
                 .map_err(|_| {
-                    DemesError::EpochError(format!("invalid end_time: {:?}", unresolved.end_time))
+                    DemesError::EpochError(ErrorContext::new(
+                        format!("invalid end_time: {:?}", unresolved.end_time),
+                        ErrorKind::Missing,
+                    ))
                 })?,
             start_size: unresolved
                 .start_size
-                .ok_or_else(|| DemesError::EpochError("end_time unresolved".to_string()))?
+                .ok_or_else(|| {
+                    DemesError::EpochError(ErrorContext::new(
+                        "end_time unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
                 .try_into()
                 .map_err(|_| {
-                    DemesError::EpochError(format!(
-                        "invalid start_size: {:?}",
-                        unresolved.start_size
+                    DemesError::EpochError(ErrorContext::new(
+                        format!("invalid start_size: {:?}", unresolved.start_size),
+                        ErrorKind::Missing,
                     ))
                 })?,
             end_size: unresolved
                 .end_size
-                .ok_or_else(|| DemesError::EpochError("end_time unresolved".to_string()))?
+                .ok_or_else(|| {
+                    DemesError::EpochError(ErrorContext::new(
+                        "end_time unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
                 .try_into()
                 .map_err(|_| {
-                    DemesError::EpochError(format!(
-                        "invalid cloning_rate: {:?}",
-                        unresolved.cloning_rate
+                    DemesError::EpochError(ErrorContext::new(
+                        format!("invalid cloning_rate: {:?}", unresolved.cloning_rate),
+                        ErrorKind::Missing,
                     ))
                 })?,
-            size_function: unresolved
-                .size_function
-                .ok_or_else(|| DemesError::EpochError("end_time unresolved".to_string()))?,
+            size_function: unresolved.size_function.ok_or_else(|| {
+                DemesError::EpochError(ErrorContext::new("end_time unresolved", ErrorKind::Missing))
+            })?,
             cloning_rate: unresolved
                 .cloning_rate
-                .ok_or_else(|| DemesError::EpochError("end_time unresolved".to_string()))?
+                .ok_or_else(|| {
+                    DemesError::EpochError(ErrorContext::new(
+                        "end_time unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
                 .try_into()
                 .map_err(|_| {
-                    DemesError::EpochError(format!(
-                        "invalid cloning_rate: {:?}",
-                        unresolved.cloning_rate
+                    DemesError::EpochError(ErrorContext::new(
+                        format!("invalid cloning_rate: {:?}", unresolved.cloning_rate),
+                        ErrorKind::Missing,
                     ))
                 })?,
 
             selfing_rate: unresolved
                 .selfing_rate
-                .ok_or_else(|| DemesError::EpochError("end_time unresolved".to_string()))?
+                .ok_or_else(|| {
+                    DemesError::EpochError(ErrorContext::new(
+                        "end_time unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
                 .try_into()?,
         })
     }
@@ -1061,7 +1507,7 @@ impl Epoch {
     fn resolved_time_to_generations(
         &mut self,
         generation_time: GenerationTime,
-        rounding: fn(Time, GenerationTime) -> Time,
+        rounding: &mut dyn FnMut(Time, GenerationTime) -> Time,
     ) -> Result<(), DemesError> {
         self.start_time = match convert_resolved_time_to_generations(
             generation_time,
@@ -1126,6 +1572,113 @@ impl Epoch {
         TimeInterval::new(self.start_time(), self.end_time())
     }
 
+    /// The rate of size change implied by the epoch's sizes and duration.
+    ///
+    /// Rates are per unit of the graph's time units, forwards in time.
+    /// For rates per generation, use an epoch of a graph converted by
+    /// [`Graph::into_generations`].
+    ///
+    /// # Returns
+    ///
+    /// * `None` for constant epochs.
+    /// * The growth rate `r` for exponential epochs, such that the size
+    ///   at `t` units of time after the epoch starts is `start_size * exp(r * t)`.
+    /// * The slope for linear epochs, such that the size
+    ///   at `t` units of time after the epoch starts is `start_size + slope * t`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 100
+    ///       end_size: 200
+    ///       size_function: linear
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert!(graph.deme(0).epochs()[0].growth_rate().is_none());
+    /// assert_eq!(graph.deme(0).epochs()[1].growth_rate(), Some(1.0));
+    /// ```
+    pub fn growth_rate(&self) -> Option<f64> {
+        let duration = f64::from(self.start_time) - f64::from(self.end_time);
+        let start_size = f64::from(self.start_size);
+        let end_size = f64::from(self.end_size);
+        match self.size_function {
+            SizeFunction::Constant => None,
+            SizeFunction::Linear => Some((end_size - start_size) / duration),
+            SizeFunction::Exponential => Some((end_size / start_size).ln() / duration),
+        }
+    }
+
+    /// The integral of the size function over the epoch.
+    ///
+    /// The integral is with respect to the graph's time units
+    /// and is infinite for an epoch with an infinite start time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 100
+    ///       end_size: 200
+    ///       size_function: linear
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert!(graph.deme(0).epochs()[0].size_integral().is_infinite());
+    /// assert_eq!(graph.deme(0).epochs()[1].size_integral(), 15000.0);
+    /// ```
+    pub fn size_integral(&self) -> f64 {
+        size_integral_details(
+            self.start_size.into(),
+            self.end_size.into(),
+            f64::from(self.start_time) - f64::from(self.end_time),
+            self.size_function,
+        )
+    }
+
+    /// The time-average of the size function over the epoch.
+    ///
+    /// For an epoch with an infinite start time, the
+    /// size is constant and this value is the start size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 100
+    ///       end_size: 200
+    ///       size_function: linear
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert_eq!(graph.deme(0).epochs()[0].average_size(), 100.0);
+    /// assert_eq!(graph.deme(0).epochs()[1].average_size(), 150.0);
+    /// ```
+    pub fn average_size(&self) -> f64 {
+        let duration = f64::from(self.start_time) - f64::from(self.end_time);
+        if duration.is_infinite() {
+            f64::from(self.start_size)
+        } else {
+            self.size_integral() / duration
+        }
+    }
+
     /// Size of Epoch at a given time
     ///
     /// # Returns
@@ -1151,12 +1704,65 @@ impl Epoch {
             None => Ok(None),
             Some(size) => match DemeSize::try_from(size) {
                 Ok(size) => Ok(Some(size)),
-                Err(_) => Err(DemesError::EpochError(format!(
-                    "size calculation led to invalid size: {size}"
-                ))),
+                Err(_) => Err(DemesError::EpochError(
+                    invalid(format!("size calculation led to invalid size: {size}"))
+                        .with_value(size),
+                )),
             },
         }
     }
+
+    /// Sizes of Epoch at many times
+    ///
+    /// This function is more efficient than repeated calls
+    /// to [`Epoch::size_at`].
+    ///
+    /// # Returns
+    ///
+    /// A vector with the same length as `times`.
+    /// Each element is the same as the value returned
+    /// by [`Epoch::size_at`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Epoch::size_at`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 100
+    ///       end_size: 200
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let epoch = graph.deme(0).epochs()[1];
+    /// let times = [150.0, 100.0, 50.0, 0.0];
+    /// let sizes = epoch.sizes_at(&times).unwrap();
+    /// assert!(sizes[0].is_none());
+    /// for (time, size) in times.iter().zip(sizes.iter()) {
+    ///     assert_eq!(size, &epoch.size_at(*time).unwrap());
+    /// }
+    /// ```
+    pub fn sizes_at(&self, times: &[f64]) -> Result<Vec<Option<DemeSize>>, DemesError> {
+        let sizes = EpochSizes::new(self);
+        times
+            .iter()
+            .map(|&time| {
+                Time::try_from(time).map_err(|_| {
+                    DemesError::EpochError(
+                        invalid(format!("invalid time value: {time:?}")).with_value(time),
+                    )
+                })?;
+                sizes.size_at(time)
+            })
+            .collect()
+    }
 }
 
 impl UnresolvedEpoch {
@@ -1211,14 +1817,15 @@ impl UnresolvedEpoch {
         self.resolve_selfing_rate(defaults, deme_defaults);
         self.resolve_cloning_rate(defaults, deme_defaults);
         self.resolve_size_function(defaults, deme_defaults)
-            .ok_or_else(|| DemesError::EpochError("failed to resolve size_function".to_string()))
+            .ok_or_else(|| DemesError::EpochError(missing("failed to resolve size_function")))
     }
 
     fn validate_end_time(&self, index: usize, deme_name: &str) -> Result<(), DemesError> {
         if self.end_time.is_none() {
-            Err(DemesError::EpochError(format!(
-                "deme {deme_name}, epoch {index}: end time is None",
-            )))
+            Err(DemesError::EpochError(
+                missing(format!("deme {deme_name}, epoch {index}: end time is None",))
+                    .with_deme(deme_name),
+            ))
         } else {
             Ok(())
         }
@@ -1228,27 +1835,36 @@ impl UnresolvedEpoch {
         match self.cloning_rate {
             Some(value) => {
                 if CloningRate::try_from(value).is_err() {
-                    Err(DemesError::EpochError(format!(
-                        "deme {deme_name}, epoch {index}: invalid cloning_rate: {value:?}"
-                    )))
+                    Err(DemesError::EpochError(
+                        invalid(format!(
+                            "deme {deme_name}, epoch {index}: invalid cloning_rate: {value:?}"
+                        ))
+                        .with_deme(deme_name),
+                    ))
                 } else {
                     Ok(())
                 }
             }
-            None => Err(DemesError::EpochError(format!(
-                "deme {deme_name}, epoch {index}:cloning_rate is None",
-            ))),
+            None => Err(DemesError::EpochError(
+                missing(format!(
+                    "deme {deme_name}, epoch {index}:cloning_rate is None",
+                ))
+                .with_deme(deme_name),
+            )),
         }
     }
 
     fn validate_selfing_rate(&self, index: usize, deme_name: &str) -> Result<(), DemesError> {
         match self.selfing_rate {
             Some(value) => SelfingRate::try_from(value)
-                .map_err(|_| DemesError::EpochError(format!("invalid selfing_rate: {value:?}")))
+                .map_err(|_| DemesError::EpochError(invalid_value("selfing_rate", value)))
                 .map(|_| ()),
-            None => Err(DemesError::EpochError(format!(
-                "deme {deme_name}, epoch {index}: selfing_rate is None",
-            ))),
+            None => Err(DemesError::EpochError(
+                missing(format!(
+                    "deme {deme_name}, epoch {index}: selfing_rate is None",
+                ))
+                .with_deme(deme_name),
+            )),
         }
     }
 
@@ -1260,36 +1876,62 @@ impl UnresolvedEpoch {
         end_size: InputDemeSize,
     ) -> Result<(), DemesError> {
         let size_function = self.size_function.ok_or_else(|| {
-            DemesError::EpochError(format!(
-                "deme {deme_name}, epoch {index}:size function is None",
-            ))
+            DemesError::EpochError(
+                missing(format!(
+                    "deme {deme_name}, epoch {index}:size function is None",
+                ))
+                .with_deme(deme_name),
+            )
         })?;
 
         let is_constant = matches!(size_function, SizeFunction::Constant);
 
         if (is_constant && start_size != end_size) || (!is_constant && start_size == end_size) {
-            Err(DemesError::EpochError(format!(
+            Err(DemesError::EpochError(inconsistent(format!(
                 "deme {}, index{}: start_size ({:?}) == end_size ({:?}) paired with invalid size_function: {}",
                 deme_name, index, self.start_size, self.end_size, size_function
-            )))
+            )).with_deme(deme_name)))
         } else {
             Ok(())
         }
     }
 
     fn validate(&self, index: usize, deme_name: &str) -> Result<(), DemesError> {
+        self.validate_details(index, deme_name)
+            .map_err(|e| e.in_epoch(deme_name, index))
+    }
+
+    fn validate_details(&self, index: usize, deme_name: &str) -> Result<(), DemesError> {
         let start_size = self.start_size.ok_or_else(|| {
-            DemesError::EpochError(format!(
-                "deme {deme_name}, epoch {index}: start_size is None",
+            DemesError::EpochError(ErrorContext::new(
+                format!("deme {deme_name}, epoch {index}: start_size is None"),
+                ErrorKind::Missing,
             ))
         })?;
-        DemeSize::try_from(start_size)
-            .map_err(|_| DemesError::EpochError(format!("invalid start_size: {start_size:?}")))?;
+        DemeSize::try_from(start_size).map_err(|_| {
+            DemesError::EpochError(
+                ErrorContext::new(
+                    format!("invalid start_size: {start_size:?}"),
+                    ErrorKind::InvalidValue,
+                )
+                .with_value(f64::from(start_size)),
+            )
+        })?;
         let end_size = self.end_size.ok_or_else(|| {
-            DemesError::EpochError(format!("deme {deme_name}, epoch {index}: end_size is None",))
+            DemesError::EpochError(ErrorContext::new(
+                format!("deme {deme_name}, epoch {index}: end_size is None"),
+                ErrorKind::Missing,
+            ))
+        })?;
+        DemeSize::try_from(end_size).map_err(|_| {
+            DemesError::EpochError(
+                ErrorContext::new(
+                    format!("invalid end_size: {end_size:?}"),
+                    ErrorKind::InvalidValue,
+                )
+                .with_value(f64::from(end_size)),
+            )
         })?;
-        DemeSize::try_from(end_size)
-            .map_err(|_| DemesError::EpochError(format!("invalid end_size: {end_size:?}")))?;
         self.validate_end_time(index, deme_name)?;
         self.validate_cloning_rate(index, deme_name)?;
         self.validate_selfing_rate(index, deme_name)?;
@@ -1297,9 +1939,16 @@ impl UnresolvedEpoch {
     }
 }
 
+/// A deme in the human data model (HDM).
+///
+/// Fields that are `None` have not been specified in the input
+/// and will be filled in from defaults or from other
+/// fields during resolution.
+///
+/// See [`hdm`](crate::hdm) for details.
 #[derive(Default, Clone, Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub(crate) struct UnresolvedDeme {
+pub struct UnresolvedDeme {
     name: String,
     #[serde(default = "String::default")]
     description: String,
@@ -1325,7 +1974,7 @@ pub(crate) struct UnresolvedDeme {
     #[serde(skip)]
     ancestor_map: DemeMap,
     #[serde(skip)]
-    ancestor_indexes: Vec<usize>,
+    ancestor_indexes: Vec<DemeIndex>,
 }
 
 impl From<Deme> for UnresolvedDeme {
@@ -1336,7 +1985,7 @@ impl From<Deme> for UnresolvedDeme {
             .map(UnresolvedEpoch::from)
             .collect::<Vec<_>>();
         Self {
-            name: value.name,
+            name: value.name.to_string(),
             description: value.description,
             ancestor_map: DemeMap::default(),
             ancestor_indexes: vec![],
@@ -1349,7 +1998,7 @@ impl From<Deme> for UnresolvedDeme {
                     .map(|p| f64::from(p).into())
                     .collect::<Vec<_>>(),
             ),
-            ancestors: Some(value.ancestors),
+            ancestors: Some(value.ancestors.iter().map(|a| a.to_string()).collect()),
             defaults: DemeDefaults::default(),
         }
     }
@@ -1358,14 +2007,14 @@ impl From<Deme> for UnresolvedDeme {
 /// A resolved deme.
 #[derive(Clone, Debug, Serialize)]
 pub struct Deme {
-    name: String,
+    name: Arc<str>,
     description: String,
     #[serde(skip)]
     ancestor_map: DemeMap,
     #[serde(skip)]
-    ancestor_indexes: Vec<usize>,
+    ancestor_indexes: Vec<DemeIndex>,
     epochs: Vec<Epoch>,
-    ancestors: Vec<String>,
+    ancestors: Vec<Arc<str>>,
     proportions: Vec<Proportion>,
     start_time: Time,
 }
@@ -1374,7 +2023,7 @@ impl Deme {
     fn resolved_time_to_generations(
         &mut self,
         generation_time: GenerationTime,
-        rounding: fn(Time, GenerationTime) -> Time,
+        rounding: &mut dyn FnMut(Time, GenerationTime) -> Time,
     ) -> Result<(), DemesError> {
         self.start_time = match convert_resolved_time_to_generations(
             generation_time,
@@ -1392,9 +2041,9 @@ impl Deme {
 
         let valid = |w: (Time, Time)| {
             if w.1 >= w.0 {
-                Err(DemesError::EpochError(
-                    "conversion to generations resulted in an invalid Epoch".to_string(),
-                ))
+                Err(DemesError::EpochError(invalid(
+                    "conversion to generations resulted in an invalid Epoch",
+                )))
             } else {
                 Ok(())
             }
@@ -1481,14 +2130,14 @@ impl Deme {
     /// Names of ancestor demes.
     ///
     /// Empty if no ancestors.
-    pub fn ancestor_names(&self) -> &[String] {
+    pub fn ancestor_names(&self) -> &[Arc<str>] {
         &self.ancestors
     }
 
     /// Indexes of ancestor demes.
     ///
     /// Empty if no ancestors.
-    pub fn ancestor_indexes(&self) -> &[usize] {
+    pub fn ancestor_indexes(&self) -> &[DemeIndex] {
         debug_assert_eq!(self.ancestor_indexes.len(), self.ancestors.len());
         &self.ancestor_indexes
     }
@@ -1526,6 +2175,15 @@ impl Deme {
         &self.proportions
     }
 
+    // true if time is in [end_time, start_time),
+    // or if both time and start_time are infinite.
+    pub(crate) fn exists_at(&self, time: Time) -> bool {
+        (time == f64::INFINITY && self.start_time == f64::INFINITY)
+            || self
+                .time_interval()
+                .contains_exclusive_start_inclusive_end(time)
+    }
+
     /// Size of Deme at a given time
     ///
     /// # Errors
@@ -1533,8 +2191,7 @@ impl Deme {
     /// See [`Epoch::size_at`] for details.
     pub fn size_at<F: Into<f64>>(&self, time: F) -> Result<Option<DemeSize>, DemesError> {
         let time: f64 = time.into();
-        Time::try_from(time)
-            .map_err(|_| DemesError::DemeError(format!("invalid time: {time:?}")))?;
+        Time::try_from(time).map_err(|_| DemesError::DemeError(invalid_value("time", time)))?;
 
         if time == f64::INFINITY && self.start_time == f64::INFINITY {
             return Ok(Some(self.epochs()[0].start_size));
@@ -1550,6 +2207,173 @@ impl Deme {
             Some(e) => Ok(e.size_at(time)?),
         }
     }
+
+    /// The integral of the deme's size over a time interval.
+    ///
+    /// The interval runs from `start` back to `end`, measured in
+    /// the graph's time units, and may span several epochs.
+    /// See [`Epoch::size_integral`].
+    ///
+    /// # Errors
+    ///
+    /// * If `start` or `end` fail to convert into [`Time`].
+    /// * If `start` is more recent than `end`.
+    /// * If the interval extends beyond the deme's existence,
+    ///   `[end_time, start_time]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 200
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let deme = graph.deme(0);
+    /// assert_eq!(deme.size_integral(150.0, 50.0).unwrap(), 50.0 * 100.0 + 50.0 * 200.0);
+    /// assert!(deme.size_integral(f64::INFINITY, 0.0).unwrap().is_infinite());
+    /// assert!(deme.size_integral(50.0, 150.0).is_err());
+    /// ```
+    pub fn size_integral<S: Into<f64>, E: Into<f64>>(
+        &self,
+        start: S,
+        end: E,
+    ) -> Result<f64, DemesError> {
+        let start: f64 = start.into();
+        let end: f64 = end.into();
+        Time::try_from(start).map_err(|_| DemesError::DemeError(invalid_value("start", start)))?;
+        Time::try_from(end).map_err(|_| DemesError::DemeError(invalid_value("end", end)))?;
+        if start < end {
+            return Err(DemesError::DemeError(inconsistent(format!(
+                "interval start {start} is more recent than its end {end}"
+            ))));
+        }
+        if start > self.start_time || end < self.end_time() {
+            return Err(DemesError::DemeError(
+                inconsistent(format!(
+                    "interval ({start}, {end}) extends beyond the existence of deme {}: ({}, {})",
+                    self.name,
+                    self.start_time,
+                    self.end_time()
+                ))
+                .with_deme(self.name.as_ref()),
+            ));
+        }
+        let mut integral = 0.0;
+        for epoch in &self.epochs {
+            let upper = start.min(epoch.start_time.into());
+            let lower = end.max(epoch.end_time.into());
+            if upper > lower {
+                let sizes = EpochSizes::new(epoch);
+                integral += size_integral_details(
+                    sizes.size_within(upper),
+                    sizes.size_within(lower),
+                    upper - lower,
+                    epoch.size_function,
+                );
+            }
+        }
+        Ok(integral)
+    }
+
+    /// Sizes of Deme at many times
+    ///
+    /// This function is more efficient than repeated calls
+    /// to [`Deme::size_at`].
+    ///
+    /// # Returns
+    ///
+    /// A vector with the same length as `times`.
+    /// Each element is the same as the value returned
+    /// by [`Deme::size_at`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Deme::size_at`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 100
+    ///       end_size: 200
+    ///       end_time: 10
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let deme = graph.deme(0);
+    /// let times = (0..200).map(|t| t as f64).collect::<Vec<_>>();
+    /// let sizes = deme.sizes_at(&times).unwrap();
+    /// for (time, size) in times.iter().zip(sizes.iter()) {
+    ///     assert_eq!(size, &deme.size_at(*time).unwrap());
+    /// }
+    /// ```
+    pub fn sizes_at(&self, times: &[f64]) -> Result<Vec<Option<DemeSize>>, DemesError> {
+        let sizes = self.epochs.iter().map(EpochSizes::new).collect::<Vec<_>>();
+        times
+            .iter()
+            .map(|&time| {
+                Time::try_from(time)
+                    .map_err(|_| DemesError::DemeError(invalid_value("time", time)))?;
+                if time == f64::INFINITY && self.start_time == f64::INFINITY {
+                    return Ok(Some(self.epochs[0].start_size));
+                }
+                // Epochs are sorted from most ancient to most recent
+                let index = self.epochs.partition_point(|e| e.end_time > time);
+                match sizes.get(index) {
+                    Some(epoch) => epoch.size_at(time),
+                    None => Ok(None),
+                }
+            })
+            .collect()
+    }
+}
+
+// A size, or a change of size, e.g. "100 -> 200"
+fn format_sizes(start_size: DemeSize, end_size: DemeSize) -> String {
+    if start_size == end_size {
+        format!("size {start_size}")
+    } else {
+        format!("size {start_size} -> {end_size}")
+    }
+}
+
+impl Display for Epoch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}",
+            self.time_interval(),
+            format_sizes(self.start_size, self.end_size),
+            self.size_function
+        )
+    }
+}
+
+impl Display for Deme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.name(), self.time_interval())?;
+        if !self.ancestor_names().is_empty() {
+            write!(f, " from {}", self.ancestor_names().join(", "))?;
+        }
+        let num_epochs = self.num_epochs();
+        write!(
+            f,
+            ", {num_epochs} epoch{}, {}",
+            if num_epochs == 1 { "" } else { "s" },
+            format_sizes(self.start_size(), self.end_size())
+        )
+    }
 }
 
 impl TryFrom<UnresolvedDeme> for Deme {
@@ -1558,19 +2382,28 @@ impl TryFrom<UnresolvedDeme> for Deme {
     fn try_from(value: UnresolvedDeme) -> Result<Self, Self::Error> {
         let mut epochs = vec![];
         let start_time = value.start_time.ok_or_else(|| {
-            DemesError::DemeError(format!("deme {} start_time is not resolved", value.name))
+            DemesError::DemeError(ErrorContext::new(
+                format!("deme {} start_time is not resolved", value.name),
+                ErrorKind::Missing,
+            ))
         })?;
         let mut epoch_start_time = start_time;
         for hdm_epoch in value.epochs.into_iter() {
-            let end_time = hdm_epoch
-                .end_time
-                .ok_or_else(|| DemesError::EpochError("epoch end time unresolved".to_string()))?;
+            let end_time = hdm_epoch.end_time.ok_or_else(|| {
+                DemesError::EpochError(ErrorContext::new(
+                    "epoch end time unresolved",
+                    ErrorKind::Missing,
+                ))
+            })?;
             let e = Epoch::new_from_unresolved(epoch_start_time, hdm_epoch)?;
             epoch_start_time = end_time;
             epochs.push(e);
         }
         let input_proportions = value.proportions.ok_or_else(|| {
-            DemesError::PulseError("pulse proportions are unresolved".to_string())
+            DemesError::PulseError(ErrorContext::new(
+                "pulse proportions are unresolved",
+                ErrorKind::Missing,
+            ))
         })?;
         let mut proportions = vec![];
         for p in input_proportions {
@@ -1581,14 +2414,22 @@ impl TryFrom<UnresolvedDeme> for Deme {
             ancestor_map: value.ancestor_map,
             ancestor_indexes: value.ancestor_indexes,
             epochs,
-            ancestors: value.ancestors.ok_or_else(|| {
-                DemesError::DemeError(format!("deme {} ancestors are not resolved", value.name))
-            })?,
+            ancestors: value
+                .ancestors
+                .ok_or_else(|| {
+                    DemesError::DemeError(ErrorContext::new(
+                        format!("deme {} ancestors are not resolved", value.name),
+                        ErrorKind::Missing,
+                    ))
+                })?
+                .into_iter()
+                .map(Arc::from)
+                .collect(),
             proportions,
-            start_time: start_time.try_into().map_err(|_| {
-                DemesError::DemeError(format!("invalid start_time: {start_time:?}"))
-            })?,
-            name: value.name,
+            start_time: start_time
+                .try_into()
+                .map_err(|_| DemesError::DemeError(invalid_value("start_time", start_time)))?,
+            name: value.name.into(),
         })
     }
 }
@@ -1627,6 +2468,27 @@ pub struct UnresolvedDemeHistory {
     pub defaults: DemeDefaults,
 }
 
+impl UnresolvedDemeHistory {
+    /// Set the epoch defaults of the deme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let history = demes::UnresolvedDemeHistory::default()
+    ///     .set_epoch_defaults(demes::UnresolvedEpoch::default().set_start_size(100.));
+    /// let mut b = demes::GraphBuilder::new_generations(None);
+    /// b.add_deme("A", vec![demes::UnresolvedEpoch::default()], history, None);
+    /// let graph = b.resolve().unwrap();
+    /// assert_eq!(graph.deme(0).start_size(), 100.);
+    /// ```
+    pub fn set_epoch_defaults(self, epoch: UnresolvedEpoch) -> Self {
+        Self {
+            defaults: DemeDefaults { epoch },
+            ..self
+        }
+    }
+}
+
 impl PartialEq for UnresolvedDeme {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name
@@ -1642,38 +2504,108 @@ impl PartialEq for UnresolvedDeme {
 impl Eq for UnresolvedDeme {}
 
 impl UnresolvedDeme {
-    pub(crate) fn new_via_builder(
-        name: &str,
-        epochs: Vec<UnresolvedEpoch>,
-        history: UnresolvedDemeHistory,
-        description: Option<&str>,
-    ) -> Self {
-        let description = match description {
-            Some(desc) => desc.to_string(),
-            None => String::default(),
-        };
-        Self {
-            name: name.to_string(),
-            epochs,
-            start_time: history.start_time,
-            ancestors: history.ancestors,
-            proportions: history.proportions,
-            defaults: history.defaults,
-            description,
-            ..Default::default()
-        }
+    /// The deme name
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    fn resolve_times(
-        &mut self,
-        deme_map: &DemeMap,
-        demes: &[UnresolvedDeme],
-        defaults: &GraphDefaults,
-    ) -> Result<(), DemesError> {
-        // apply top-level default if it exists
+    /// The deme description
+    pub fn description(&self) -> &str {
+        &self.description
+    }
 
-        self.start_time = match self.start_time {
-            Some(start_time) => Some(start_time),
+    /// Set the deme description
+    pub fn set_description<D: Into<String>>(&mut self, description: D) {
+        self.description = description.into();
+    }
+
+    /// The names of the deme's ancestors, if given
+    pub fn ancestors(&self) -> Option<&[String]> {
+        self.ancestors.as_deref()
+    }
+
+    /// Set the names of the deme's ancestors
+    ///
+    /// # Note
+    ///
+    /// Passing `None` means that the top-level
+    /// deme defaults, if any, are applied during resolution.
+    pub fn set_ancestors(&mut self, ancestors: Option<Vec<String>>) {
+        self.ancestors = ancestors;
+    }
+
+    /// The ancestry proportions of the deme, if given
+    pub fn proportions(&self) -> Option<&[InputProportion]> {
+        self.proportions.as_deref()
+    }
+
+    /// Set the ancestry proportions of the deme
+    pub fn set_proportions(&mut self, proportions: Option<Vec<InputProportion>>) {
+        self.proportions = proportions;
+    }
+
+    /// The start time of the deme, if given
+    pub fn start_time(&self) -> Option<InputTime> {
+        self.start_time
+    }
+
+    /// Set the start time of the deme
+    pub fn set_start_time<T: Into<InputTime>>(&mut self, start_time: Option<T>) {
+        self.start_time = start_time.map(|t| t.into());
+    }
+
+    /// The epochs of the deme
+    pub fn epochs(&self) -> &[UnresolvedEpoch] {
+        &self.epochs
+    }
+
+    /// Mutable access to the epochs of the deme
+    pub fn epochs_mut(&mut self) -> &mut Vec<UnresolvedEpoch> {
+        &mut self.epochs
+    }
+
+    /// The deme-level defaults
+    pub fn defaults(&self) -> &DemeDefaults {
+        &self.defaults
+    }
+
+    /// Mutable access to the deme-level defaults
+    pub fn defaults_mut(&mut self) -> &mut DemeDefaults {
+        &mut self.defaults
+    }
+
+    pub(crate) fn new_via_builder(
+        name: &str,
+        epochs: Vec<UnresolvedEpoch>,
+        history: UnresolvedDemeHistory,
+        description: Option<&str>,
+    ) -> Self {
+        let description = match description {
+            Some(desc) => desc.to_string(),
+            None => String::default(),
+        };
+        Self {
+            name: name.to_string(),
+            epochs,
+            start_time: history.start_time,
+            ancestors: history.ancestors,
+            proportions: history.proportions,
+            defaults: history.defaults,
+            description,
+            ..Default::default()
+        }
+    }
+
+    fn resolve_times(
+        &mut self,
+        deme_map: &DemeMap,
+        demes: &[UnresolvedDeme],
+        defaults: &GraphDefaults,
+    ) -> Result<(), DemesError> {
+        // apply top-level default if it exists
+
+        self.start_time = match self.start_time {
+            Some(start_time) => Some(start_time),
             None => match defaults.deme.start_time {
                 Some(start_time) => Some(start_time),
                 None => Some(InputTime::default_deme_start_time()),
@@ -1683,16 +2615,22 @@ impl UnresolvedDeme {
         if self
             .ancestors
             .as_ref()
-            .ok_or_else(|| DemesError::DemeError("unexpected None for deme ancestors".to_string()))?
+            .ok_or_else(|| DemesError::DemeError(missing("unexpected None for deme ancestors")))?
             .is_empty()
             && self.start_time_resolved_or(|| {
-                DemesError::DemeError(format!("deme {}: start_time unresolved", self.name))
+                DemesError::DemeError(ErrorContext::new(
+                    format!("deme {}: start_time unresolved", self.name),
+                    ErrorKind::Missing,
+                ))
             })? != InputTime::default_deme_start_time()
         {
-            return Err(DemesError::DemeError(format!(
-                "deme {} has finite start time but no ancestors",
-                self.name
-            )));
+            return Err(DemesError::DemeError(
+                inconsistent(format!(
+                    "deme {} has finite start time but no ancestors",
+                    self.name
+                ))
+                .with_deme(self.name.as_str()),
+            ));
         }
 
         if self.get_num_ancestors()? == 1 {
@@ -1704,7 +2642,7 @@ impl UnresolvedDeme {
                         let first_ancestor_deme = get_deme!(first_ancestor_name, deme_map, demes)
                             .ok_or_else(|| {
                             DemesError::DemeError(
-                                "fatal error: ancestor maps to no Deme object".to_string(),
+                                "fatal error: ancestor maps to no Deme object".into(),
                             )
                         })?;
                         first_ancestor_deme.get_end_time()?.into()
@@ -1721,16 +2659,20 @@ impl UnresolvedDeme {
 
         for ancestor in self.get_ancestor_names()?.iter() {
             let a = get_deme!(ancestor, deme_map, demes).ok_or_else(|| {
-                DemesError::DemeError(format!(
-                    "ancestor {ancestor} not present in global deme map",
+                DemesError::DemeError(unknown_deme(
+                    format!("ancestor {ancestor} not present in global deme map",),
+                    ancestor,
                 ))
             })?;
             let t = a.get_time_interval()?;
             if !t.contains_start_time(self.get_start_time()?) {
-                return Err(DemesError::DemeError(format!(
-                    "Ancestor {} does not exist at deme {}'s start_time",
-                    ancestor, self.name
-                )));
+                return Err(DemesError::DemeError(
+                    inconsistent(format!(
+                        "Ancestor {} does not exist at deme {}'s start_time",
+                        ancestor, self.name
+                    ))
+                    .with_deme(self.name.as_str()),
+                ));
             }
         }
 
@@ -1739,7 +2681,7 @@ impl UnresolvedDeme {
         let last_epoch_ref = self
             .epochs
             .last_mut()
-            .ok_or_else(|| DemesError::DemeError("epochs are empty".to_string()))?;
+            .ok_or_else(|| DemesError::DemeError(missing("epochs are empty")))?;
         if last_epoch_ref.end_time.is_none() {
             last_epoch_ref.end_time = match self.defaults.epoch.end_time {
                 Some(end_time) => Some(end_time),
@@ -1754,9 +2696,8 @@ impl UnresolvedDeme {
         for epoch in self.epochs.iter_mut() {
             match epoch.end_time {
                 Some(end_time) => {
-                    Time::try_from(end_time).map_err(|_| {
-                        DemesError::EpochError(format!("invalid end_time: {end_time:?}"))
-                    })?;
+                    Time::try_from(end_time)
+                        .map_err(|_| DemesError::EpochError(invalid_value("end_time", end_time)))?;
                 }
                 None => {
                     epoch.end_time = match self.defaults.epoch.end_time {
@@ -1770,31 +2711,33 @@ impl UnresolvedDeme {
         let mut last_time = f64::from(self.get_start_time()?);
         for (i, epoch) in self.epochs.iter().enumerate() {
             let end_time = f64::from(epoch.end_time.ok_or_else(|| {
-                DemesError::EpochError(format!(
-                    "deme: {}, epoch: {i} end time must be specified",
-                    self.name
-                ))
+                DemesError::EpochError(
+                    missing(format!(
+                        "deme: {}, epoch: {i} end time must be specified",
+                        self.name
+                    ))
+                    .with_deme(self.name.as_str()),
+                )
             })?);
 
             if !end_time.is_finite() {
-                return Err(DemesError::EpochError(format!(
-                    "invalid end_time: {end_time:?}"
-                )));
+                return Err(DemesError::EpochError(invalid_value("end_time", end_time)));
             }
 
             if end_time >= last_time {
                 return Err(DemesError::EpochError(
-                    "Epoch end times must be listed in decreasing order".to_string(),
+                    inconsistent("Epoch end times must be listed in decreasing order")
+                        .with_deme(self.name.as_str()),
                 ));
             }
             last_time = end_time;
             Time::try_from(
                 epoch
                     .end_time
-                    .ok_or_else(|| DemesError::EpochError("end_time is None".to_string()))?,
+                    .ok_or_else(|| DemesError::EpochError(missing("end_time is None")))?,
             )
             .map_err(|_| {
-                DemesError::EpochError(format!("invalid end_time: {:?}", epoch.end_time))
+                DemesError::EpochError(invalid(format!("invalid end_time: {:?}", epoch.end_time)))
             })?;
         }
 
@@ -1808,7 +2751,10 @@ impl UnresolvedDeme {
         let self_defaults = self.defaults.clone();
         let epoch_sizes = {
             let temp_epoch = self.epochs.get_mut(0).ok_or_else(|| {
-                DemesError::DemeError(format!("deme {} has no epochs", self.name))
+                DemesError::DemeError(
+                    missing(format!("deme {} has no epochs", self.name))
+                        .with_deme(self.name.as_str()),
+                )
             })?;
 
             temp_epoch.start_size = match temp_epoch.start_size {
@@ -1822,10 +2768,13 @@ impl UnresolvedDeme {
 
             defaults.apply_epoch_size_defaults(temp_epoch);
             if temp_epoch.start_size.is_none() && temp_epoch.end_size.is_none() {
-                return Err(DemesError::EpochError(format!(
-                    "first epoch of deme {} must define one or both of start_size and end_size",
-                    self.name
-                )));
+                return Err(DemesError::EpochError(
+                    missing(format!(
+                        "first epoch of deme {} must define one or both of start_size and end_size",
+                        self.name
+                    ))
+                    .with_deme(self.name.as_str()),
+                ));
             }
             if temp_epoch.start_size.is_none() {
                 temp_epoch.start_size = temp_epoch.end_size;
@@ -1838,20 +2787,23 @@ impl UnresolvedDeme {
         };
 
         let epoch_start_size = epoch_sizes.0.ok_or_else(|| {
-            DemesError::EpochError(format!(
-                "first epoch of {} has unresolved start_size",
-                self.name
+            DemesError::EpochError(ErrorContext::new(
+                format!("first epoch of {} has unresolved start_size", self.name),
+                ErrorKind::Missing,
             ))
         })?;
         let epoch_end_size = epoch_sizes.1.ok_or_else(|| {
-            DemesError::EpochError(format!(
-                "first epoch of {} has unresolved end_size",
-                self.name
+            DemesError::EpochError(ErrorContext::new(
+                format!("first epoch of {} has unresolved end_size", self.name),
+                ErrorKind::Missing,
             ))
         })?;
 
         let start_time = self.start_time.ok_or_else(|| {
-            DemesError::EpochError(format!("deme {} start_time is None", self.name))
+            DemesError::EpochError(
+                missing(format!("deme {} start_time is None", self.name))
+                    .with_deme(self.name.as_str()),
+            )
         })?;
 
         if start_time == InputTime::default_deme_start_time() && epoch_sizes.0 != epoch_sizes.1 {
@@ -1859,7 +2811,9 @@ impl UnresolvedDeme {
                     "first epoch of deme {} cannot have varying size and an infinite time interval: start_size = {}, end_size = {}",
                     self.name, f64::from(epoch_start_size), f64::from(epoch_end_size),
                 );
-            return Err(DemesError::EpochError(msg));
+            return Err(DemesError::EpochError(
+                inconsistent(msg).with_deme(self.name.as_str()),
+            ));
         }
 
         Ok(Some(epoch_end_size))
@@ -1900,17 +2854,20 @@ impl UnresolvedDeme {
         let proportions = self
             .proportions
             .as_mut()
-            .ok_or_else(|| DemesError::DemeError("proportions is None".to_string()))?;
+            .ok_or_else(|| DemesError::DemeError(missing("proportions is None")))?;
 
         if proportions.is_empty() && num_ancestors == 1 {
             proportions.push(InputProportion::from(1.0));
         }
 
         if num_ancestors != proportions.len() {
-            return Err(DemesError::DemeError(format!(
-                "deme {} ancestors and proportions have different lengths",
-                self.name
-            )));
+            return Err(DemesError::DemeError(
+                inconsistent(format!(
+                    "deme {} ancestors and proportions have different lengths",
+                    self.name
+                ))
+                .with_deme(self.name.as_str()),
+            ));
         }
         Ok(())
     }
@@ -1943,22 +2900,31 @@ impl UnresolvedDeme {
                 let mut ancestor_set = HashSet::<String>::default();
                 for ancestor in ancestors {
                     if ancestor == &self.name {
-                        return Err(DemesError::DemeError(format!(
-                            "deme: {} lists itself as an ancestor",
-                            self.name
-                        )));
+                        return Err(DemesError::DemeError(
+                            inconsistent(format!(
+                                "deme: {} lists itself as an ancestor",
+                                self.name
+                            ))
+                            .with_deme(self.name.as_str()),
+                        ));
                     }
-                    if !deme_map.contains_key(ancestor) {
-                        return Err(DemesError::DemeError(format!(
-                            "deme: {} lists invalid ancestor: {ancestor}",
-                            self.name
+                    if !deme_map.contains_key(ancestor.as_str()) {
+                        return Err(DemesError::DemeError(unknown_deme(
+                            format!("deme: {} lists invalid ancestor: {ancestor}", self.name),
+                            ancestor.as_str(),
                         )));
                     }
                     if ancestor_set.contains(ancestor) {
-                        return Err(DemesError::DemeError(format!(
-                            "deme: {} lists ancestor: {ancestor} multiple times",
-                            self.name
-                        )));
+                        return Err(DemesError::DemeError(
+                            ErrorContext::new(
+                                format!(
+                                    "deme: {} lists ancestor: {ancestor} multiple times",
+                                    self.name
+                                ),
+                                ErrorKind::DuplicateDeme,
+                            )
+                            .with_deme(ancestor.as_str()),
+                        ));
                     }
                     ancestor_set.insert(ancestor.clone());
                 }
@@ -1999,14 +2965,20 @@ impl UnresolvedDeme {
 
         let mut ancestor_map = DemeMap::default();
         let ancestors = self.ancestors.as_ref().ok_or_else(|| {
-            DemesError::DemeError(format!("deme {}: ancestors are None", self.name))
+            DemesError::DemeError(
+                missing(format!("deme {}: ancestors are None", self.name))
+                    .with_deme(self.name.as_str()),
+            )
         })?;
         for ancestor in ancestors {
-            let deme = deme_map.get(ancestor).ok_or_else(|| {
-                DemesError::DemeError(format!("invalid ancestor of {}: {ancestor}", self.name))
+            let deme = deme_map.get(ancestor.as_str()).ok_or_else(|| {
+                DemesError::DemeError(unknown_deme(
+                    format!("invalid ancestor of {}: {ancestor}", self.name),
+                    ancestor.as_str(),
+                ))
             })?;
-            ancestor_map.insert(ancestor.clone(), *deme);
-            self.ancestor_indexes.push(*deme);
+            ancestor_map.insert(ancestor.as_str().into(), *deme);
+            self.ancestor_indexes.push(DemeIndex(*deme));
         }
         self.ancestor_map = ancestor_map;
         Ok(())
@@ -2015,12 +2987,11 @@ impl UnresolvedDeme {
     fn validate_start_time(&self) -> Result<(), DemesError> {
         match self.start_time {
             Some(start_time) => {
-                Time::try_from(start_time).map_err(|_| {
-                    DemesError::DemeError(format!("invalid start_time: {start_time:?}"))
-                })?;
+                Time::try_from(start_time)
+                    .map_err(|_| DemesError::DemeError(invalid_value("start_time", start_time)))?;
                 start_time.err_if_not_valid_deme_start_time()
             }
-            None => Err(DemesError::DemeError("start_time is None".to_string())),
+            None => Err(DemesError::DemeError(missing("start_time is None"))),
         }
     }
 
@@ -2038,17 +3009,16 @@ impl UnresolvedDeme {
             Ok(p) => p,
             Err(_) => {
                 return Err(DemesError::DemeError(
-                    "failed to biuld python_identifier regex".to_string(),
+                    "failed to biuld python_identifier regex".into(),
                 ))
             }
         };
         if python_identifier.is_match(&self.name) {
             Ok(())
         } else {
-            Err(DemesError::DemeError(format!(
-                "invalid deme name: {}:",
-                self.name
-            )))
+            Err(DemesError::DemeError(
+                invalid(format!("invalid deme name: {}:", self.name)).with_deme(self.name.as_str()),
+            ))
         }
     }
 
@@ -2056,10 +3026,9 @@ impl UnresolvedDeme {
         self.validate_name()?;
         self.validate_start_time()?;
         if self.epochs.is_empty() {
-            return Err(DemesError::DemeError(format!(
-                "no epochs for deme {}",
-                self.name
-            )));
+            return Err(DemesError::DemeError(
+                missing(format!("no epochs for deme {}", self.name)).with_deme(self.name.as_str()),
+            ));
         }
 
         self.epochs
@@ -2070,7 +3039,7 @@ impl UnresolvedDeme {
         let proportions = self
             .proportions
             .as_ref()
-            .ok_or_else(|| DemesError::DemeError("proportions is None".to_string()))?;
+            .ok_or_else(|| DemesError::DemeError(missing("proportions is None")))?;
         for p in proportions.iter() {
             Proportion::try_from(*p)?;
         }
@@ -2079,10 +3048,14 @@ impl UnresolvedDeme {
             let sum_proportions: f64 = proportions.iter().map(|p| f64::from(*p)).sum();
             // NOTE: this is same default as Python's math.isclose().
             if (sum_proportions - 1.0).abs() > 1e-9 {
-                return Err(DemesError::DemeError(format!(
-                    "proportions for deme {} should sum to ~1.0, got: {sum_proportions}",
-                    self.name
-                )));
+                return Err(DemesError::DemeError(
+                    invalid(format!(
+                        "proportions for deme {} should sum to ~1.0, got: {sum_proportions}",
+                        self.name
+                    ))
+                    .with_deme(self.name.as_str())
+                    .with_value(sum_proportions),
+                ));
             }
         }
 
@@ -2098,16 +3071,19 @@ impl UnresolvedDeme {
     fn get_ancestor_names(&self) -> Result<&[String], DemesError> {
         match &self.ancestors {
             Some(ancestors) => Ok(ancestors),
-            None => Err(DemesError::DemeError(format!(
-                "deme {} ancestors are unresolved",
-                self.name
+            None => Err(DemesError::DemeError(ErrorContext::new(
+                format!("deme {} ancestors are unresolved", self.name),
+                ErrorKind::Missing,
             ))),
         }
     }
 
     fn get_start_time(&self) -> Result<Time, DemesError> {
         match self.start_time.ok_or_else(|| {
-            DemesError::DemeError(format!("deme {} start_time is unresolved", self.name))
+            DemesError::DemeError(ErrorContext::new(
+                format!("deme {} start_time is unresolved", self.name),
+                ErrorKind::Missing,
+            ))
         }) {
             Ok(value) => value.try_into(),
             Err(e) => Err(e),
@@ -2119,12 +3095,17 @@ impl UnresolvedDeme {
             .epochs
             .last()
             .as_ref()
-            .ok_or_else(|| DemesError::DemeError(format!("deme {} has no epochs", self.name)))?
+            .ok_or_else(|| {
+                DemesError::DemeError(
+                    missing(format!("deme {} has no epochs", self.name))
+                        .with_deme(self.name.as_str()),
+                )
+            })?
             .end_time
             .ok_or_else(|| {
-                DemesError::DemeError(format!(
-                    "last epoch of deme {} end_time unresolved",
-                    self.name
+                DemesError::DemeError(ErrorContext::new(
+                    format!("last epoch of deme {} end_time unresolved", self.name),
+                    ErrorKind::Missing,
                 ))
             }) {
             Ok(value) => value.try_into(),
@@ -2137,7 +3118,10 @@ impl UnresolvedDeme {
             .ancestors
             .as_ref()
             .ok_or_else(|| {
-                DemesError::DemeError(format!("deme {} ancestors are unresolved", self.name))
+                DemesError::DemeError(ErrorContext::new(
+                    format!("deme {} ancestors are unresolved", self.name),
+                    ErrorKind::Missing,
+                ))
             })?
             .len())
     }
@@ -2148,24 +3132,66 @@ impl UnresolvedDeme {
             .iter_mut()
             .try_for_each(|e| e.rescale(scaling_factor))
     }
+
+    fn rescale_sizes(&mut self, scaling_factor: f64) {
+        self.epochs
+            .iter_mut()
+            .for_each(|e| e.rescale_sizes(scaling_factor))
+    }
+
+    fn shift_times(&mut self, offset: f64) {
+        self.start_time = shift_input_time(self.start_time, offset);
+        self.epochs.iter_mut().for_each(|e| e.shift_times(offset))
+    }
+}
+
+type DemeMap = HashMap<Arc<str>, usize>;
+
+// Indexes into the resolved migrations of a Graph,
+// keyed by the indexes of the source and destination demes.
+type MigrationIndex = HashMap<(usize, usize), Vec<usize>>;
+
+fn unknown_deme<S: Into<String>>(message: S, deme: &str) -> ErrorContext {
+    ErrorContext::new(message, ErrorKind::UnknownDeme).with_deme(deme)
 }
 
-type DemeMap = HashMap<String, usize>;
+fn missing<S: Into<String>>(message: S) -> ErrorContext {
+    ErrorContext::new(message, ErrorKind::Missing)
+}
+
+fn invalid<S: Into<String>>(message: S) -> ErrorContext {
+    ErrorContext::new(message, ErrorKind::InvalidValue)
+}
+
+fn inconsistent<S: Into<String>>(message: S) -> ErrorContext {
+    ErrorContext::new(message, ErrorKind::Inconsistent)
+}
+
+fn invalid_value<V: Copy + std::fmt::Debug + Into<f64>>(name: &str, value: V) -> ErrorContext {
+    ErrorContext::new(
+        format!("invalid {name}: {value:?}"),
+        ErrorKind::InvalidValue,
+    )
+    .with_value(value.into())
+}
 
-fn deme_name_exists<F: FnOnce(String) -> DemesError>(
+fn deme_name_exists<F: FnOnce(ErrorContext) -> DemesError>(
     map: &DemeMap,
     name: &str,
     err: F,
 ) -> Result<(), DemesError> {
     if !map.contains_key(name) {
-        Err(err(format!("deme {name} does not exist")))
+        Err(err(unknown_deme(
+            format!("deme {name} does not exist"),
+            name,
+        )))
     } else {
         Ok(())
     }
 }
 
 /// Top-level defaults
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct GraphDefaults {
     #[allow(missing_docs)]
@@ -2297,15 +3323,15 @@ impl TopLevelDemeDefaults {
     fn validate(&self) -> Result<(), DemesError> {
         if let Some(value) = self.start_time {
             Time::try_from(value)
-                .map_err(|_| DemesError::DemeError(format!("invalid start_time: {value:?}")))?;
+                .map_err(|_| DemesError::DemeError(invalid_value("start_time", value)))?;
         }
 
         if let Some(proportions) = &self.proportions {
             for v in proportions {
                 if Proportion::try_from(*v).is_err() {
-                    return Err(DemesError::GraphError(format!(
+                    return Err(DemesError::GraphError(invalid(format!(
                         "invalid default proportion: {v:?}"
-                    )));
+                    ))));
                 }
             }
         }
@@ -2368,9 +3394,9 @@ impl TryFrom<std::collections::BTreeMap<String, serde_yaml::Value>> for Metadata
         value: std::collections::BTreeMap<String, serde_yaml::Value>,
     ) -> Result<Self, Self::Error> {
         if value.is_empty() {
-            Err(DemesError::GraphError(
-                "toplevel metadata must mot be empty".to_string(),
-            ))
+            Err(DemesError::GraphError(invalid(
+                "toplevel metadata must mot be empty",
+            )))
         } else {
             Ok(Metadata { metadata: value })
         }
@@ -2410,9 +3436,15 @@ impl Metadata {
     }
 }
 
-#[derive(Deserialize, Debug)]
+/// A graph in the human data model (HDM).
+///
+/// This is the data model of the input, before any defaults
+/// are applied or any values are inferred.
+///
+/// See [`hdm`](crate::hdm) for details.
+#[derive(Clone, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
-pub(crate) struct UnresolvedGraph {
+pub struct UnresolvedGraph {
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     #[serde(default = "Option::default")]
@@ -2446,6 +3478,29 @@ pub(crate) struct UnresolvedGraph {
     deme_map: DemeMap,
 }
 
+// An object in the input that fails to resolve.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum InputObject {
+    Deme(usize),
+    Migration(usize),
+    Pulse(usize),
+}
+
+impl InputObject {
+    // The location of the object in YAML input
+    fn locate(&self, yaml: &str) -> Option<crate::Location> {
+        let (key, index) = match self {
+            Self::Deme(i) => ("demes", *i),
+            Self::Migration(i) => ("migrations", *i),
+            Self::Pulse(i) => ("pulses", *i),
+        };
+        crate::location::locate_sequence_element(yaml, key, index)
+    }
+}
+
+// A field name and a test for whether that field is missing
+type MissingField<T> = (&'static str, fn(&T) -> bool);
+
 impl UnresolvedGraph {
     pub(crate) fn new(
         time_units: TimeUnits,
@@ -2493,17 +3548,66 @@ impl UnresolvedGraph {
         });
     }
 
+    // Checks that do not require resolution.
+    // Unlike resolution, all errors found are returned.
+    pub(crate) fn check_structure(&self) -> Vec<DemesError> {
+        let mut errors = vec![];
+        let mut names = HashSet::<&str>::default();
+        for deme in &self.demes {
+            if !names.insert(deme.name.as_str()) {
+                errors.push(DemesError::DemeError(
+                    ErrorContext::new(
+                        format!("duplicate deme name: {}", deme.name),
+                        ErrorKind::DuplicateDeme,
+                    )
+                    .with_deme(deme.name.as_str()),
+                ));
+            }
+        }
+        let has_size = |e: &UnresolvedEpoch| e.start_size.is_some() || e.end_size.is_some();
+        for deme in &self.demes {
+            if deme.epochs.is_empty()
+                && !has_size(&deme.defaults.epoch)
+                && !has_size(&self.defaults.epoch)
+            {
+                errors.push(DemesError::DemeError(
+                    ErrorContext::new(
+                        format!("deme {} has no epochs and no default size", deme.name),
+                        ErrorKind::Missing,
+                    )
+                    .with_deme(deme.name.as_str()),
+                ));
+            }
+            let ancestors = deme
+                .ancestors
+                .as_ref()
+                .or(self.defaults.deme.ancestors.as_ref());
+            for ancestor in ancestors.into_iter().flatten() {
+                if !names.contains(ancestor.as_str()) {
+                    errors.push(DemesError::DemeError(unknown_deme(
+                        format!("deme: {} lists invalid ancestor: {ancestor}", deme.name),
+                        ancestor,
+                    )));
+                }
+            }
+        }
+        errors
+    }
+
     fn build_deme_map(&self) -> Result<DemeMap, DemesError> {
         let mut rv = DemeMap::default();
 
         for (i, deme) in self.demes.iter().enumerate() {
-            if rv.contains_key(&deme.name) {
-                return Err(DemesError::DemeError(format!(
-                    "duplicate deme name: {}",
-                    deme.name,
-                )));
+            if rv.contains_key(deme.name.as_str()) {
+                return Err(DemesError::DemeError(
+                    ErrorContext::new(
+                        format!("duplicate deme name: {}", deme.name),
+                        ErrorKind::DuplicateDeme,
+                    )
+                    .with_deme(deme.name.as_str()),
+                ));
             }
-            rv.insert(deme.name.clone(), i);
+            rv.insert(deme.name.as_str().into(), i);
         }
 
         Ok(rv)
@@ -2518,10 +3622,16 @@ impl UnresolvedGraph {
         end_time: Option<InputTime>,
     ) -> Result<(), DemesError> {
         let source_deme = get_deme!(&source, &self.deme_map, &self.demes).ok_or_else(|| {
-            crate::DemesError::MigrationError(format!("invalid source deme name {source}"))
+            crate::DemesError::MigrationError(unknown_deme(
+                format!("invalid source deme name {source}"),
+                &source,
+            ))
         })?;
         let dest_deme = get_deme!(&dest, &self.deme_map, &self.demes).ok_or_else(|| {
-            crate::DemesError::MigrationError(format!("invalid dest deme name {dest}"))
+            crate::DemesError::MigrationError(unknown_deme(
+                format!("invalid dest deme name {dest}"),
+                &dest,
+            ))
         })?;
 
         let start_time = match start_time {
@@ -2540,15 +3650,15 @@ impl UnresolvedGraph {
         deme_name_exists(&self.deme_map, &dest, DemesError::MigrationError)?;
 
         let a = AsymmetricMigration {
-            source,
-            dest,
+            source: source.into(),
+            dest: dest.into(),
             rate: rate.try_into()?,
-            start_time: start_time.try_into().map_err(|_| {
-                DemesError::MigrationError(format!("invalid start_time: {start_time:?}"))
-            })?,
-            end_time: end_time.try_into().map_err(|_| {
-                DemesError::MigrationError(format!("invalid end_time: {end_time:?}"))
-            })?,
+            start_time: start_time
+                .try_into()
+                .map_err(|_| DemesError::MigrationError(invalid_value("start_time", start_time)))?,
+            end_time: end_time
+                .try_into()
+                .map_err(|_| DemesError::MigrationError(invalid_value("end_time", end_time)))?,
         };
 
         self.resolved_migrations.push(a);
@@ -2561,14 +3671,14 @@ impl UnresolvedGraph {
         u: &UnresolvedMigration,
     ) -> Result<(), DemesError> {
         self.resolve_asymmetric_migration(
-            u.source.clone().ok_or_else(|| {
-                DemesError::MigrationError("migration source is None".to_string())
-            })?,
+            u.source
+                .clone()
+                .ok_or_else(|| DemesError::MigrationError(missing("migration source is None")))?,
             u.dest
                 .clone()
-                .ok_or_else(|| DemesError::MigrationError("migration dest is None".to_string()))?,
+                .ok_or_else(|| DemesError::MigrationError(missing("migration dest is None")))?,
             u.rate
-                .ok_or_else(|| DemesError::MigrationError("migration rate is None".to_string()))?,
+                .ok_or_else(|| DemesError::MigrationError(missing("migration rate is None")))?,
             u.start_time,
             u.end_time,
         )
@@ -2581,25 +3691,29 @@ impl UnresolvedGraph {
         let demes = u
             .demes
             .as_ref()
-            .ok_or_else(|| DemesError::MigrationError("migration demes is None".to_string()))?;
+            .ok_or_else(|| DemesError::MigrationError(missing("migration demes is None")))?;
 
         if demes.len() < 2 {
-            return Err(DemesError::MigrationError(
-                "the demes field of a migration mut contain at least two demes".to_string(),
-            ));
+            return Err(DemesError::MigrationError(invalid(
+                "the demes field of a migration mut contain at least two demes",
+            )));
         }
 
         let rate = u
             .rate
-            .ok_or_else(|| DemesError::MigrationError("migration rate is None".to_string()))?;
+            .ok_or_else(|| DemesError::MigrationError(missing("migration rate is None")))?;
 
         // Each input symmetric migration becomes two AsymmetricMigration instances
         for (i, source_name) in demes.iter().enumerate().take(demes.len() - 1) {
             for dest_name in demes.iter().skip(i + 1) {
                 if source_name == dest_name {
-                    return Err(DemesError::MigrationError(format!(
-                        "source/dest demes must differ: {source_name}",
-                    )));
+                    return Err(DemesError::MigrationError(
+                        ErrorContext::new(
+                            format!("source/dest demes must differ: {source_name}",),
+                            ErrorKind::DuplicateDeme,
+                        )
+                        .with_deme(source_name.as_str()),
+                    ));
                 }
                 deme_name_exists(&self.deme_map, source_name, DemesError::MigrationError)?;
                 deme_name_exists(&self.deme_map, dest_name, DemesError::MigrationError)?;
@@ -2627,7 +3741,7 @@ impl UnresolvedGraph {
         Ok(())
     }
 
-    fn resolve_migrations(&mut self) -> Result<(), DemesError> {
+    fn resolve_migrations(&mut self, failed: &mut Option<InputObject>) -> Result<(), DemesError> {
         // NOTE: due to the borrow checker not trusting us, we
         // do the old "swap it out" trick to demonstrate
         // that we are not doing bad things.
@@ -2644,7 +3758,8 @@ impl UnresolvedGraph {
             }
         }
 
-        for input_mig in &input_migrations {
+        for (i, input_mig) in input_migrations.iter().enumerate() {
+            *failed = Some(InputObject::Migration(i));
             let mut input_mig_clone = input_mig.clone();
             self.defaults.apply_migration_defaults(&mut input_mig_clone);
             let m = Migration::try_from(input_mig_clone)?;
@@ -2654,6 +3769,8 @@ impl UnresolvedGraph {
             }
         }
 
+        *failed = None;
+
         // The spec states that we can discard the unresolved migration stuff,
         // but we'll swap it back. It does no harm to do so.
         std::mem::swap(&mut input_migrations, &mut self.input_migrations);
@@ -2684,10 +3801,10 @@ impl UnresolvedGraph {
 
         for (demes, epochs) in &mig_epochs {
             if epochs.windows(2).any(|w| w[0].overlaps(&w[1])) {
-                return Err(DemesError::MigrationError(format!(
+                return Err(DemesError::MigrationError(inconsistent(format!(
                     "overlapping migration epochs between source: {} and dest: {}",
                     demes.0, demes.1
-                )));
+                ))));
             }
         }
         Ok(())
@@ -2721,7 +3838,7 @@ impl UnresolvedGraph {
 
     fn validate_input_migration_rates(&self) -> Result<(), DemesError> {
         let intervals = self.get_non_overlapping_migration_intervals();
-        let mut input_rates = HashMap::<String, Vec<f64>>::default();
+        let mut input_rates = HashMap::<Arc<str>, Vec<f64>>::default();
 
         for deme in self.deme_map.keys() {
             input_rates.insert(deme.clone(), vec![0.0; intervals.len()]);
@@ -2737,7 +3854,9 @@ impl UnresolvedGraph {
                             if rate > 1.0 + 1e-9 {
                                 let msg = format!("migration rate into dest: {} is > 1 in the time interval ({:?}, {:?}]",
                                                   migration.dest(), ti.start_time(), ti.end_time());
-                                return Err(DemesError::MigrationError(msg));
+                                return Err(DemesError::MigrationError(
+                                    invalid(msg).with_deme(migration.dest()).with_value(rate),
+                                ));
                             }
                             rates[i] = rate;
                         }
@@ -2753,67 +3872,76 @@ impl UnresolvedGraph {
     fn validate_migrations(&self) -> Result<(), DemesError> {
         for m in &self.resolved_migrations {
             let source = get_deme!(&m.source, &self.deme_map, &self.demes).ok_or_else(|| {
-                DemesError::MigrationError(format!("invalid migration source: {}", m.source))
+                DemesError::MigrationError(unknown_deme(
+                    format!("invalid migration source: {}", m.source),
+                    &m.source,
+                ))
             })?;
             let dest = get_deme!(&m.dest, &self.deme_map, &self.demes).ok_or_else(|| {
-                DemesError::MigrationError(format!("invalid migration dest: {}", m.dest))
+                DemesError::MigrationError(unknown_deme(
+                    format!("invalid migration dest: {}", m.dest),
+                    &m.dest,
+                ))
             })?;
 
             if source.name == dest.name {
-                return Err(DemesError::MigrationError(format!(
-                    "source: {} == dest: {}",
-                    source.name, dest.name
-                )));
+                return Err(DemesError::MigrationError(
+                    inconsistent(format!("source: {} == dest: {}", source.name, dest.name))
+                        .with_deme(source.name.as_str()),
+                ));
             }
 
             {
                 let interval = source.get_time_interval()?;
                 if !interval.contains_inclusive_start_exclusive_end(m.start_time) {
-                    return Err(DemesError::MigrationError(format!(
+                    return Err(DemesError::MigrationError(inconsistent(format!(
                             "migration start_time: {:?} does not overlap with existence of source deme {}",
                             m.start_time,
                             source.name
-                        )));
+                        )).with_deme(source.name.as_str())));
                 }
                 let interval = dest.get_time_interval()?;
                 if !interval.contains_inclusive_start_exclusive_end(m.start_time) {
-                    return Err(DemesError::MigrationError(format!(
+                    return Err(DemesError::MigrationError(inconsistent(format!(
                             "migration start_time: {:?} does not overlap with existence of dest deme {}",
                             m.start_time,
                             dest.name
-                        )));
+                        )).with_deme(dest.name.as_str())));
                 }
             }
 
             {
                 if !f64::from(m.end_time).is_finite() {
-                    return Err(DemesError::MigrationError(format!(
+                    return Err(DemesError::MigrationError(invalid(format!(
                         "invalid migration end_time: {:?}",
                         m.end_time
-                    )));
+                    ))));
                 }
                 let interval = source.get_time_interval()?;
                 if !interval.contains_exclusive_start_inclusive_end(m.end_time) {
-                    return Err(DemesError::MigrationError(format!(
+                    return Err(DemesError::MigrationError(inconsistent(format!(
                             "migration end_time: {:?} does not overlap with existence of source deme {}",
                             m.end_time,
                             source.name
-                        )));
+                        )).with_deme(source.name.as_str())));
                 }
                 let interval = dest.get_time_interval()?;
                 if !interval.contains_exclusive_start_inclusive_end(m.end_time) {
-                    return Err(DemesError::MigrationError(format!(
+                    return Err(DemesError::MigrationError(
+                        inconsistent(format!(
                         "migration end_time: {:?} does not overlap with existence of dest deme {}",
                         m.end_time, dest.name
-                    )));
+                    ))
+                        .with_deme(dest.name.as_str()),
+                    ));
                 }
             }
 
             let interval = m.time_interval();
             if !interval.duration_greater_than_zero() {
-                return Err(DemesError::MigrationError(format!(
+                return Err(DemesError::MigrationError(invalid(format!(
                     "invalid migration duration: {interval:?} ",
-                )));
+                ))));
             }
         }
         self.check_migration_epoch_overlap()?;
@@ -2821,112 +3949,457 @@ impl UnresolvedGraph {
         Ok(())
     }
 
-    fn resolve_pulses(&mut self) -> Result<(), DemesError> {
+    fn resolve_pulses(&mut self, failed: &mut Option<InputObject>) -> Result<(), DemesError> {
         if self.pulses.is_empty() && self.defaults.pulse != UnresolvedPulse::default() {
             let c = self.defaults.pulse.clone();
             self.pulses.push(c);
         }
-        self.pulses
-            .iter_mut()
-            .try_for_each(|pulse| pulse.resolve(&self.defaults))?;
-        // NOTE: the sort_by flips the order to b, a
+        let mut times = vec![];
+        for (i, pulse) in self.pulses.iter_mut().enumerate() {
+            *failed = Some(InputObject::Pulse(i));
+            pulse.resolve(&self.defaults)?;
+            let time = pulse.get_time().map_err(|_| {
+                DemesError::PulseError(invalid(format!("invalid pulse time: {:?}", pulse.time)))
+            })?;
+            times.push(time);
+        }
+        *failed = None;
+        // NOTE: sort by reversed time
         // to put more ancient events at the front.
-        // FIXME: we cannot remove this unwrap
-        // unless we define Time as fully-ordered.
-        self.pulses
-            .sort_by(|a, b| b.time.partial_cmp(&a.time).unwrap());
+        let mut pulses = std::mem::take(&mut self.pulses)
+            .into_iter()
+            .zip(times)
+            .collect::<Vec<_>>();
+        pulses.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+        self.pulses = pulses.into_iter().map(|(pulse, _)| pulse).collect();
         Ok(())
     }
 
-    // NOTE: this function could output a resoled Graph
-    // type and maybe save some extra work/moves.
-    pub(crate) fn resolve(self) -> Result<Self, DemesError> {
-        let mut g = self;
-        if g.demes.is_empty() {
-            return Err(DemesError::DemeError(
-                "no demes have been specified".to_string(),
-            ));
-        }
-        g.defaults.validate()?;
-        g.deme_map = g.build_deme_map()?;
+    // A top-level default is unused if every object
+    // that it could apply to specifies the field.
+    // NOTE: must be called prior to resolution.
+    fn unused_default_warnings(&self) -> Vec<crate::Warning> {
+        let mut warnings = vec![];
+        let mut check = |field: &str, has_default: bool, used: bool| {
+            if has_default && !used {
+                warnings.push(crate::Warning::UnusedDefault(format!("defaults.{field}")));
+            }
+        };
 
-        let mut resolved_demes = vec![];
-        for deme in g.demes.iter_mut() {
-            deme.resolve(&g.deme_map, &resolved_demes, &g.defaults)?;
-            resolved_demes.push(deme.clone());
+        // Demes without epochs receive a single epoch during resolution.
+        let epoch_fields: [MissingField<UnresolvedEpoch>; 6] = [
+            ("end_time", |e| e.end_time.is_none()),
+            ("start_size", |e| e.start_size.is_none()),
+            ("end_size", |e| e.end_size.is_none()),
+            ("size_function", |e| e.size_function.is_none()),
+            ("cloning_rate", |e| e.cloning_rate.is_none()),
+            ("selfing_rate", |e| e.selfing_rate.is_none()),
+        ];
+        for (field, missing) in epoch_fields {
+            let used = self.demes.iter().any(|deme| {
+                missing(&deme.defaults.epoch)
+                    && (deme.epochs.is_empty() || deme.epochs.iter().any(missing))
+            });
+            check(
+                &format!("epoch.{field}"),
+                !missing(&self.defaults.epoch),
+                used,
+            );
         }
-        g.demes = resolved_demes;
-        g.demes.iter().try_for_each(|deme| deme.validate())?;
-        g.resolve_migrations()?;
-        g.resolve_pulses()?;
-        g.validate_migrations()?;
 
-        match g.generation_time {
-            Some(_) => (), //value.validate(DemesError::GraphError)?,
-            None => {
-                if matches!(g.time_units, TimeUnits::Generations) {
-                    g.generation_time = Some(InputGenerationTime::from(1.));
-                }
-            }
-        }
-        Ok(g)
-    }
+        check(
+            "deme.start_time",
+            self.defaults.deme.start_time.is_some(),
+            self.demes.iter().any(|d| d.start_time.is_none()),
+        );
+        check(
+            "deme.ancestors",
+            self.defaults.deme.ancestors.is_some(),
+            self.demes.iter().any(|d| d.ancestors.is_none()),
+        );
+        check(
+            "deme.proportions",
+            self.defaults.deme.proportions.is_some(),
+            self.demes.iter().any(|d| d.proportions.is_none()),
+        );
 
-    pub(crate) fn validate(&self) -> Result<(), DemesError> {
-        if self.demes.is_empty() {
-            return Err(DemesError::DemeError("no demes specified".to_string()));
+        let migration_fields: [MissingField<UnresolvedMigration>; 6] = [
+            ("demes", |m| m.demes.is_none()),
+            ("source", |m| m.source.is_none()),
+            ("dest", |m| m.dest.is_none()),
+            ("start_time", |m| m.start_time.is_none()),
+            ("end_time", |m| m.end_time.is_none()),
+            ("rate", |m| m.rate.is_none()),
+        ];
+        for (field, missing) in migration_fields {
+            check(
+                &format!("migration.{field}"),
+                !missing(&self.defaults.migration),
+                self.input_migrations.iter().any(missing),
+            );
         }
 
-        if !matches!(&self.time_units, TimeUnits::Generations) && self.generation_time.is_none() {
-            return Err(DemesError::GraphError(
-                "missing generation_time".to_string(),
-            ));
+        let pulse_fields: [MissingField<UnresolvedPulse>; 4] = [
+            ("sources", |p| p.sources.is_none()),
+            ("dest", |p| p.dest.is_none()),
+            ("time", |p| p.time.is_none()),
+            ("proportions", |p| p.proportions.is_none()),
+        ];
+        for (field, missing) in pulse_fields {
+            check(
+                &format!("pulse.{field}"),
+                !missing(&self.defaults.pulse),
+                self.pulses.iter().any(missing),
+            );
         }
 
-        if matches!(&self.time_units, TimeUnits::Generations) {
-            if let Some(value) = self.generation_time {
-                if !value.equals(1.0) {
-                    return Err(DemesError::GraphError(
-                        "time units are generations but generation_time != 1.0".to_string(),
-                    ));
-                }
-            }
-        }
-        self.pulses
+        warnings
+    }
+
+    // NOTE: must be called prior to resolution.
+    pub(crate) fn check_limits(&self, options: &crate::LoadOptions) -> Result<(), DemesError> {
+        use crate::ResourceLimit;
+        options.check(ResourceLimit::Demes, self.demes.len())?;
+        let num_epochs = self.demes.iter().map(|deme| deme.epochs.len().max(1)).sum();
+        options.check(ResourceLimit::Epochs, num_epochs)?;
+        let num_migrations = self
+            .input_migrations
             .iter()
-            .try_for_each(|pulse| pulse.validate(&self.deme_map, &self.demes))?;
+            .map(|m| match &m.demes {
+                Some(demes) => demes.len().saturating_mul(demes.len().saturating_sub(1)),
+                None => 1,
+            })
+            .fold(0_usize, |a, b| a.saturating_add(b));
+        options.check(ResourceLimit::Migrations, num_migrations)
+    }
 
-        Ok(())
+    // Resolve the graph, adding any warnings to the output.
+    pub(crate) fn resolve_with_warnings(
+        self,
+        warnings: &mut Vec<crate::Warning>,
+    ) -> Result<Graph, DemesError> {
+        let unused_defaults = self.unused_default_warnings();
+        let graph = self.resolve()?;
+        warnings.extend(unused_defaults);
+        warnings.extend(crate::warning::resolved_graph_warnings(&graph));
+        Ok(graph)
     }
 
-    pub(crate) fn set_metadata(&mut self, metadata: Metadata) {
-        assert!(!metadata.is_empty());
-        self.metadata = Some(metadata.metadata.clone())
+    /// Resolve the graph into the machine data model (MDM).
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError`] if the graph is not valid.
+    pub fn resolve(self) -> Result<Graph, DemesError> {
+        self.resolve_inputs()?.try_into()
     }
 
-    // Take our definition from
-    // https://momentsld.github.io/moments/api/api_demes.html#moments.Demes.DemesUtil.rescale
-    fn rescale(self, scaling_factor: f64) -> Result<Self, DemesError> {
-        if !scaling_factor.is_finite() || scaling_factor <= 0.0 {
-            return Err(DemesError::ValueError(format!(
-                "invalid scaling_factor: {scaling_factor}"
-            )));
-        }
-        let mut g = self;
+    /// The description of the graph.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 
-        g.demes
-            .iter_mut()
-            .try_for_each(|d| d.rescale(scaling_factor))?;
+    /// Set the description of the graph.
+    pub fn set_description<D: Into<String>>(&mut self, description: Option<D>) {
+        self.description = description.map(|d| d.into());
+    }
 
-        g.pulses
-            .iter_mut()
-            .try_for_each(|p| p.rescale(scaling_factor))?;
+    /// The DOI values of the graph.
+    pub fn doi(&self) -> Option<&[String]> {
+        self.doi.as_deref()
+    }
 
-        g.input_migrations
+    /// Mutable access to the DOI values of the graph.
+    pub fn doi_mut(&mut self) -> &mut Option<Vec<String>> {
+        &mut self.doi
+    }
+
+    /// The top-level defaults.
+    pub fn defaults(&self) -> &GraphDefaults {
+        &self.defaults
+    }
+
+    /// Mutable access to the top-level defaults.
+    pub fn defaults_mut(&mut self) -> &mut GraphDefaults {
+        &mut self.defaults
+    }
+
+    /// Get a copy of the top-level [`Metadata`](crate::Metadata).
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.metadata.as_ref().map(|md| Metadata {
+            metadata: md.clone(),
+        })
+    }
+
+    /// The time units of the graph.
+    pub fn time_units(&self) -> &TimeUnits {
+        &self.time_units
+    }
+
+    /// The generation time of the graph, if specified.
+    pub fn generation_time(&self) -> Option<InputGenerationTime> {
+        self.generation_time
+    }
+
+    /// The demes of the graph.
+    pub fn demes(&self) -> &[UnresolvedDeme] {
+        &self.demes
+    }
+
+    /// Mutable access to the demes of the graph.
+    pub fn demes_mut(&mut self) -> &mut Vec<UnresolvedDeme> {
+        &mut self.demes
+    }
+
+    /// The migrations of the graph, as given in the input.
+    pub fn migrations(&self) -> &[UnresolvedMigration] {
+        &self.input_migrations
+    }
+
+    /// Mutable access to the migrations of the graph.
+    pub fn migrations_mut(&mut self) -> &mut Vec<UnresolvedMigration> {
+        &mut self.input_migrations
+    }
+
+    /// The pulses of the graph.
+    pub fn pulses(&self) -> &[UnresolvedPulse] {
+        &self.pulses
+    }
+
+    /// Mutable access to the pulses of the graph.
+    pub fn pulses_mut(&mut self) -> &mut Vec<UnresolvedPulse> {
+        &mut self.pulses
+    }
+
+    // NOTE: this function could output a resoled Graph
+    // type and maybe save some extra work/moves.
+    pub(crate) fn resolve_inputs(self) -> Result<Self, DemesError> {
+        self.resolve_details(&mut None)
+    }
+
+    // If resolution fails due to a specific input deme,
+    // migration, or pulse, then `failed` records which one.
+    pub(crate) fn resolve_details(
+        self,
+        failed: &mut Option<InputObject>,
+    ) -> Result<Self, DemesError> {
+        let mut g = self;
+        if g.demes.is_empty() {
+            return Err(DemesError::DemeError(missing(
+                "no demes have been specified",
+            )));
+        }
+        g.defaults.validate()?;
+        g.deme_map = g.build_deme_map()?;
+
+        let mut resolved_demes = vec![];
+        for (i, deme) in g.demes.iter_mut().enumerate() {
+            *failed = Some(InputObject::Deme(i));
+            deme.resolve(&g.deme_map, &resolved_demes, &g.defaults)?;
+            resolved_demes.push(deme.clone());
+        }
+        g.demes = resolved_demes;
+        for (i, deme) in g.demes.iter().enumerate() {
+            *failed = Some(InputObject::Deme(i));
+            deme.validate()?;
+        }
+        *failed = None;
+        g.resolve_migrations(failed)?;
+        g.resolve_pulses(failed)?;
+        g.validate_migrations()?;
+
+        match g.generation_time {
+            Some(_) => (), //value.validate(DemesError::GraphError)?,
+            None => {
+                if matches!(g.time_units, TimeUnits::Generations) {
+                    g.generation_time = Some(InputGenerationTime::from(1.));
+                }
+            }
+        }
+        Ok(g)
+    }
+
+    // Remove an input object that failed to resolve, along with
+    // any objects that refer to it, so that resolution can continue.
+    // Returns false if the object does not exist in the input.
+    fn remove_input_object(&mut self, object: InputObject) -> bool {
+        match object {
+            InputObject::Deme(i) if i < self.demes.len() => {
+                let mut removed = HashSet::from([self.demes.remove(i).name]);
+                // Descendants of a removed deme would fail to resolve
+                loop {
+                    let num_removed = removed.len();
+                    let default_ancestors = self.defaults.deme.ancestors.as_ref();
+                    self.demes.retain(|deme| {
+                        let ancestors = deme.ancestors.as_ref().or(default_ancestors);
+                        if ancestors.into_iter().flatten().any(|a| removed.contains(a)) {
+                            removed.insert(deme.name.clone());
+                            false
+                        } else {
+                            true
+                        }
+                    });
+                    if removed.len() == num_removed {
+                        break;
+                    }
+                }
+                let refers = |name: Option<&String>| name.is_some_and(|n| removed.contains(n));
+                self.input_migrations.retain(|m| {
+                    !(refers(m.source.as_ref())
+                        || refers(m.dest.as_ref())
+                        || m.demes.iter().flatten().any(|d| refers(Some(d))))
+                });
+                self.pulses.retain(|p| {
+                    !(refers(p.dest.as_ref())
+                        || p.sources.iter().flatten().any(|s| refers(Some(s))))
+                });
+                true
+            }
+            InputObject::Migration(i) if i < self.input_migrations.len() => {
+                let _ = self.input_migrations.remove(i);
+                true
+            }
+            InputObject::Pulse(i) if i < self.pulses.len() => {
+                let _ = self.pulses.remove(i);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Resolve the graph, continuing past errors in individual
+    // demes, migrations, and pulses.
+    // Objects that refer to a deme that fails to resolve are
+    // not checked, as their errors would only repeat that of the deme.
+    pub(crate) fn resolve_all_errors(self) -> Result<Graph, Vec<DemesError>> {
+        let mut graph = self;
+        let mut errors = vec![];
+        loop {
+            let mut failed = None;
+            let result = graph
+                .clone()
+                .resolve_details(&mut failed)
+                .and_then(Graph::try_from);
+            match result {
+                Ok(resolved) if errors.is_empty() => return Ok(resolved),
+                Ok(_) => return Err(errors),
+                Err(e) => {
+                    errors.push(e);
+                    match failed {
+                        Some(object)
+                            if graph.remove_input_object(object) && !graph.demes.is_empty() => {}
+                        _ => return Err(errors),
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), DemesError> {
+        if self.demes.is_empty() {
+            return Err(DemesError::DemeError(missing("no demes specified")));
+        }
+
+        if !matches!(&self.time_units, TimeUnits::Generations) && self.generation_time.is_none() {
+            return Err(DemesError::GraphError(ErrorContext::new(
+                "missing generation_time",
+                ErrorKind::Missing,
+            )));
+        }
+
+        if matches!(&self.time_units, TimeUnits::Generations) {
+            if let Some(value) = self.generation_time {
+                if !value.equals(1.0) {
+                    return Err(DemesError::GraphError(inconsistent(
+                        "time units are generations but generation_time != 1.0",
+                    )));
+                }
+            }
+        }
+        self.pulses
+            .iter()
+            .try_for_each(|pulse| pulse.validate(&self.deme_map, &self.demes))?;
+
+        Ok(())
+    }
+
+    pub(crate) fn liftover_toplevel_data(&mut self, graph: &Graph) {
+        self.description = graph.description.clone();
+        self.doi = if graph.doi.is_empty() {
+            None
+        } else {
+            Some(graph.doi.clone())
+        };
+        self.metadata = graph.metadata.clone();
+    }
+
+    pub(crate) fn set_metadata(&mut self, metadata: Metadata) {
+        assert!(!metadata.is_empty());
+        self.metadata = Some(metadata.metadata.clone())
+    }
+
+    // Take our definition from
+    // https://momentsld.github.io/moments/api/api_demes.html#moments.Demes.DemesUtil.rescale
+    fn rescale(self, scaling_factor: f64) -> Result<Self, DemesError> {
+        if !scaling_factor.is_finite() || scaling_factor <= 0.0 {
+            return Err(DemesError::ValueError(
+                invalid(format!("invalid scaling_factor: {scaling_factor}"))
+                    .with_value(scaling_factor),
+            ));
+        }
+        let mut g = self;
+
+        g.demes
+            .iter_mut()
+            .try_for_each(|d| d.rescale(scaling_factor))?;
+
+        g.pulses
+            .iter_mut()
+            .try_for_each(|p| p.rescale(scaling_factor))?;
+
+        g.input_migrations
             .iter_mut()
             .try_for_each(|m| m.rescale(scaling_factor))?;
 
-        g.resolve()
+        g.resolve_inputs()
+    }
+
+    fn rescale_demes(self, scaling_factors: HashMap<String, f64>) -> Result<Self, DemesError> {
+        let mut g = self;
+        for (name, &scaling_factor) in scaling_factors.iter() {
+            if !scaling_factor.is_finite() || scaling_factor <= 0.0 {
+                return Err(DemesError::ValueError(
+                    invalid(format!(
+                        "invalid scaling_factor for deme {name}: {scaling_factor}"
+                    ))
+                    .with_deme(name)
+                    .with_value(scaling_factor),
+                ));
+            }
+            let deme = g
+                .demes
+                .iter_mut()
+                .find(|d| &d.name == name)
+                .ok_or_else(|| {
+                    DemesError::DemeError(unknown_deme(format!("deme {name} does not exist"), name))
+                })?;
+            deme.rescale_sizes(scaling_factor);
+            g.input_migrations
+                .iter_mut()
+                .filter(|m| m.dest.as_ref() == Some(name))
+                .for_each(|m| m.rate = rescale_input_migration_rate(m.rate, scaling_factor));
+        }
+        g.resolve_inputs()
+    }
+
+    fn shift_times(self, offset: f64) -> Result<Self, DemesError> {
+        let mut g = self;
+        g.demes.iter_mut().for_each(|d| d.shift_times(offset));
+        g.pulses.iter_mut().for_each(|p| p.shift_times(offset));
+        g.input_migrations
+            .iter_mut()
+            .for_each(|m| m.shift_times(offset));
+        g.resolve_inputs()
     }
 }
 
@@ -2971,6 +4444,65 @@ impl From<Graph> for UnresolvedGraph {
     }
 }
 
+/// An epoch removed by
+/// [`Graph::into_integer_generations_merged`](crate::Graph::into_integer_generations_merged).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergedEpoch {
+    deme: String,
+    epoch_index: usize,
+    start_time: Time,
+    end_time: Time,
+}
+
+impl MergedEpoch {
+    /// The name of the deme containing the epoch.
+    pub fn deme(&self) -> &str {
+        &self.deme
+    }
+
+    /// The index of the epoch in the input graph.
+    pub fn epoch_index(&self) -> usize {
+        self.epoch_index
+    }
+
+    /// The start time of the epoch in the input graph.
+    pub fn start_time(&self) -> Time {
+        self.start_time
+    }
+
+    /// The end time of the epoch in the input graph.
+    pub fn end_time(&self) -> Time {
+        self.end_time
+    }
+}
+
+/// Summary of the changes made by
+/// [`Graph::into_integer_generations_merged`](crate::Graph::into_integer_generations_merged).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntegerGenerationsReport {
+    merged_epochs: Vec<MergedEpoch>,
+    removed_migrations: Vec<AsymmetricMigration>,
+}
+
+impl IntegerGenerationsReport {
+    /// Epochs that were merged into their neighbors.
+    pub fn merged_epochs(&self) -> &[MergedEpoch] {
+        &self.merged_epochs
+    }
+
+    /// Migrations that were removed.
+    ///
+    /// Times are in the units of the input graph.
+    pub fn removed_migrations(&self) -> &[AsymmetricMigration] {
+        &self.removed_migrations
+    }
+
+    /// `true` if no changes were needed, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.merged_epochs.is_empty() && self.removed_migrations.is_empty()
+    }
+}
+
 /// A resolved demes Graph.
 ///
 /// Instances of this type will be fully-resolved according to
@@ -2981,8 +4513,39 @@ impl From<Graph> for UnresolvedGraph {
 /// * [`load`](crate::load)
 /// * [`loads`](crate::loads)
 /// * [`GraphBuilder`](crate::GraphBuilder)
+///
+/// # Deserialization
+///
+/// A graph can be deserialized as part of a larger data structure.
+/// The input is treated as HDM and is resolved during deserialization.
+///
+/// ```
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     replicates: usize,
+///     model: demes::Graph,
+/// }
+///
+/// let yaml = "
+/// replicates: 10
+/// model:
+///   time_units: generations
+///   demes:
+///    - name: A
+///      epochs:
+///       - start_size: 100
+/// ";
+/// let config: Config = serde_yaml::from_str(yaml).unwrap();
+/// assert_eq!(config.model.deme(0).start_size(), 100.0);
+/// ```
+///
+/// # Note
+///
+/// [`loads_json`](crate::loads_json) additionally accepts `null` as
+/// an infinite deme `start_time`.
+/// Deserializing a graph from JSON via `serde_json` does not.
 #[derive(Serialize, Debug, Clone)]
-#[serde(deny_unknown_fields, try_from = "UnresolvedGraph")]
+#[serde(deny_unknown_fields)]
 pub struct Graph {
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
@@ -3007,6 +4570,8 @@ pub struct Graph {
     pulses: Vec<Pulse>,
     #[serde(skip)]
     deme_map: DemeMap,
+    #[serde(skip)]
+    migration_index: MigrationIndex,
 }
 
 // NOTE: the manual implementation
@@ -3027,12 +4592,47 @@ impl PartialEq for Graph {
 
 impl Eq for Graph {}
 
+/// Load a graph from YAML.
+///
+/// Equivalent to [`loads`](crate::loads).
+///
+/// # Examples
+///
+/// ```
+/// let yaml = "
+/// time_units: generations
+/// demes:
+///  - name: A
+///    epochs:
+///     - start_size: 100
+/// ";
+/// let graph = yaml.parse::<demes::Graph>().unwrap();
+/// assert_eq!(graph, demes::loads(yaml).unwrap());
+/// ```
+impl std::str::FromStr for Graph {
+    type Err = DemesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new_resolved_from_str(s)
+    }
+}
+
 impl std::fmt::Display for Graph {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_string().unwrap())
     }
 }
 
+impl<'de> Deserialize<'de> for Graph {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let graph = UnresolvedGraph::deserialize(deserializer)?;
+        graph.resolve().map_err(serde::de::Error::custom)
+    }
+}
+
 impl TryFrom<UnresolvedGraph> for Graph {
     type Error = DemesError;
 
@@ -3047,7 +4647,7 @@ impl TryFrom<UnresolvedGraph> for Graph {
             let deme = Deme::try_from(hdm_deme)?;
             demes.push(deme);
         }
-        Ok(Self {
+        let mut graph = Self {
             input_string: value.input_string,
             description: value.description,
             doi: value.doi.unwrap_or_default(),
@@ -3055,13 +4655,22 @@ impl TryFrom<UnresolvedGraph> for Graph {
             time_units: value.time_units,
             generation_time: value
                 .generation_time
-                .ok_or_else(|| DemesError::GraphError("generation_time is unresolved".to_string()))?
+                .ok_or_else(|| {
+                    DemesError::GraphError(ErrorContext::new(
+                        "generation_time is unresolved",
+                        ErrorKind::Missing,
+                    ))
+                })?
                 .try_into()?,
             demes,
             resolved_migrations: value.resolved_migrations,
             pulses,
             deme_map: value.deme_map,
-        })
+            migration_index: MigrationIndex::default(),
+        };
+        graph.intern_names();
+        graph.build_migration_index();
+        Ok(graph)
     }
 }
 
@@ -3072,10 +4681,125 @@ fn string_from_reader<T: Read>(reader: T) -> Result<String, DemesError> {
     Ok(buf)
 }
 
+fn string_from_reader_with_options<T: Read>(
+    reader: T,
+    options: &crate::LoadOptions,
+) -> Result<String, DemesError> {
+    match options.max_input_size() {
+        // Read at most one byte more than the limit
+        // so that we never buffer arbitrarily large inputs.
+        Some(max) => {
+            let mut buf = vec![];
+            let _ = reader
+                .take((max as u64).saturating_add(1))
+                .read_to_end(&mut buf)?;
+            options.check(crate::ResourceLimit::InputSize, buf.len())?;
+            String::from_utf8(buf).map_err(|e| {
+                DemesError::IOerror(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            })
+        }
+        None => string_from_reader(reader),
+    }
+}
+
 impl Graph {
+    // Share one allocation of each deme name among the
+    // demes, migrations, and pulses of the graph.
+    fn intern_names(&mut self) {
+        let names = self
+            .demes
+            .iter()
+            .map(|deme| deme.name.clone())
+            .collect::<Vec<_>>();
+        let deme_map = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect::<DemeMap>();
+        let intern = |name: &Arc<str>| match deme_map.get(name) {
+            Some(&i) => names[i].clone(),
+            None => name.clone(),
+        };
+        for deme in self.demes.iter_mut() {
+            for ancestor in deme.ancestors.iter_mut() {
+                *ancestor = intern(ancestor);
+            }
+            deme.ancestor_map = deme
+                .ancestors
+                .iter()
+                .cloned()
+                .zip(deme.ancestor_indexes.iter().map(|a| a.0))
+                .collect();
+        }
+        for migration in self.resolved_migrations.iter_mut() {
+            migration.source = intern(&migration.source);
+            migration.dest = intern(&migration.dest);
+        }
+        for pulse in self.pulses.iter_mut() {
+            for source in pulse.sources.iter_mut() {
+                *source = intern(source);
+            }
+            pulse.dest = intern(&pulse.dest);
+        }
+        self.deme_map = deme_map;
+    }
+
+    // Must be called whenever resolved_migrations
+    // or the deme order changes.
+    fn build_migration_index(&mut self) {
+        let mut index = MigrationIndex::default();
+        for (i, migration) in self.resolved_migrations.iter().enumerate() {
+            if let (Some(&source), Some(&dest)) = (
+                self.deme_map.get(&migration.source),
+                self.deme_map.get(&migration.dest),
+            ) {
+                index.entry((source, dest)).or_default().push(i);
+            }
+        }
+        self.migration_index = index;
+    }
+
+    fn indexed_migrations(&self, source: usize, dest: usize) -> &[usize] {
+        self.migration_index
+            .get(&(source, dest))
+            .map(|indexes| indexes.as_slice())
+            .unwrap_or_default()
+    }
+
     pub(crate) fn new_from_str(yaml: &'_ str) -> Result<Self, DemesError> {
+        Self::new_from_str_with_options(yaml, &crate::LoadOptions::default())
+    }
+
+    pub(crate) fn new_from_str_with_warnings(
+        yaml: &'_ str,
+        warnings: &mut Vec<crate::Warning>,
+    ) -> Result<Self, DemesError> {
+        let g: UnresolvedGraph = serde_yaml::from_str(yaml)?;
+        let mut graph = g.resolve_with_warnings(warnings)?;
+        graph.input_string = Some(InputFormatInternal::Yaml(yaml.to_owned()));
+        Ok(graph)
+    }
+
+    pub(crate) fn new_from_str_with_options(
+        yaml: &'_ str,
+        options: &crate::LoadOptions,
+    ) -> Result<Self, DemesError> {
+        options.check(crate::ResourceLimit::InputSize, yaml.len())?;
         let g: UnresolvedGraph = serde_yaml::from_str(yaml)?;
-        let mut g = g.resolve()?;
+        g.check_limits(options)?;
+        let mut failed = None;
+        let mut g = match g.resolve_details(&mut failed) {
+            Ok(g) => g,
+            Err(error) => {
+                return Err(match failed.and_then(|f| f.locate(yaml)) {
+                    Some(location) if options.locate_errors() => DemesError::Located {
+                        location,
+                        error: Box::new(error),
+                    },
+                    _ => error,
+                })
+            }
+        };
         g.validate()?;
         g.input_string = Some(InputFormatInternal::Yaml(yaml.to_owned()));
         g.try_into()
@@ -3084,12 +4808,23 @@ impl Graph {
     #[cfg(feature = "json")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
     pub(crate) fn new_resolved_from_json_str(json: &'_ str) -> Result<Self, DemesError> {
+        Self::new_from_json_str_with_options(json, &crate::LoadOptions::default())
+    }
+
+    #[cfg(feature = "json")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+    pub(crate) fn new_from_json_str_with_options(
+        json: &'_ str,
+        options: &crate::LoadOptions,
+    ) -> Result<Self, DemesError> {
+        options.check(crate::ResourceLimit::InputSize, json.len())?;
         let json: std::collections::HashMap<String, serde_json::Value> =
             serde_json::from_str(json)?;
         let json = crate::process_json::fix_json_input(json)?;
         let json = serde_json::to_string(&json)?;
         let g: UnresolvedGraph = serde_json::from_str(&json)?;
-        let mut g = g.resolve()?;
+        g.check_limits(options)?;
+        let mut g = g.resolve_inputs()?;
         g.validate()?;
         g.input_string = Some(InputFormatInternal::Json(json.to_owned()));
         g.try_into()
@@ -3099,7 +4834,7 @@ impl Graph {
     #[cfg_attr(doc_cfg, doc(cfg(feature = "toml")))]
     pub(crate) fn new_resolved_from_toml_str(toml: &'_ str) -> Result<Self, DemesError> {
         let g: UnresolvedGraph = toml::from_str(toml)?;
-        let mut g = g.resolve()?;
+        let mut g = g.resolve_inputs()?;
         g.validate()?;
         g.input_string = Some(InputFormatInternal::Toml(toml.to_owned()));
         g.try_into()
@@ -3110,8 +4845,26 @@ impl Graph {
         Self::new_from_str(&yaml)
     }
 
-    #[cfg(feature = "json")]
-    #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+    pub(crate) fn new_from_reader_with_options<T: Read>(
+        reader: T,
+        options: &crate::LoadOptions,
+    ) -> Result<Self, DemesError> {
+        let yaml = string_from_reader_with_options(reader, options)?;
+        Self::new_from_str_with_options(&yaml, options)
+    }
+
+    #[cfg(feature = "json")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+    pub(crate) fn new_from_json_reader_with_options<T: Read>(
+        reader: T,
+        options: &crate::LoadOptions,
+    ) -> Result<Self, DemesError> {
+        let json = string_from_reader_with_options(reader, options)?;
+        Self::new_from_json_str_with_options(&json, options)
+    }
+
+    #[cfg(feature = "json")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
     pub(crate) fn new_from_json_reader<T: Read>(reader: T) -> Result<Self, DemesError> {
         let json = string_from_reader(reader)?;
         Self::new_resolved_from_json_str(&json)
@@ -3201,7 +4954,7 @@ impl Graph {
     /// * `None` otherwise
     pub fn get_deme<'name, I: Into<DemeId<'name>>>(&self, id: I) -> Option<&Deme> {
         match id.into() {
-            DemeId::Index(i) => self.demes.get(i),
+            DemeId::Index(i) => self.demes.get(i.0),
             DemeId::Name(name) => get_deme!(name, &self.deme_map, &self.demes),
         }
     }
@@ -3211,410 +4964,340 @@ impl Graph {
         &self.demes
     }
 
-    /// Get the [`GenerationTime`](crate::GenerationTime) for the graph.
-    pub fn generation_time(&self) -> GenerationTime {
-        self.generation_time
-    }
-
-    /// Get the [`TimeUnits`](crate::TimeUnits) for the graph.
-    pub fn time_units(&self) -> TimeUnits {
-        self.time_units.clone()
-    }
-
-    /// Get the migration events for the graph.
-    pub fn migrations(&self) -> &[AsymmetricMigration] {
-        &self.resolved_migrations
-    }
-
-    /// Get the pulse events for the graph.
-    pub fn pulses(&self) -> &[Pulse] {
-        &self.pulses
-    }
-
-    /// Get a copy of the top-level [`Metadata`](crate::Metadata).
-    pub fn metadata(&self) -> Option<Metadata> {
-        self.metadata.as_ref().map(|md| Metadata {
-            metadata: md.clone(),
-        })
-    }
-
-    fn convert_to_generations_details(
-        self,
-        round: fn(Time, GenerationTime) -> Time,
-    ) -> Result<Self, DemesError> {
-        let mut converted = self;
-
-        converted.demes.iter_mut().try_for_each(|deme| {
-            deme.resolved_time_to_generations(converted.generation_time, round)
-        })?;
-
-        converted.pulses.iter_mut().try_for_each(|pulse| {
-            pulse.resolved_time_to_generations(converted.generation_time, round)
-        })?;
-
-        converted
-            .resolved_migrations
-            .iter_mut()
-            .try_for_each(|pulse| {
-                pulse.resolved_time_to_generations(converted.generation_time, round)
-            })?;
-
-        converted.time_units = TimeUnits::Generations;
-        converted.generation_time = GenerationTime::try_from(1.0)?;
-
-        Ok(converted)
-    }
-
-    /// Convert the time units to generations.
+    /// Get all demes descending from a deme.
     ///
-    /// # Errors
+    /// A deme is a descendant if it lists `deme` as an ancestor,
+    /// or if it lists another descendant as an ancestor.
     ///
-    /// If the time unit of an event differs sufficiently in
-    /// magnitude from the `generation_time`, it is possible
-    /// that conversion results in epochs (or migration
-    /// durations) of length zero, which will return an error.
+    /// # Returns
     ///
-    /// If any field is unresolved, an error will be returned.
-    pub fn into_generations(self) -> Result<Self, DemesError> {
-        self.into_generations_with(crate::time::to_generations)
-    }
-
-    /// Convert the time units to generations, rounding the output to an integer value.
-    pub fn into_integer_generations(self) -> Result<Graph, DemesError> {
-        self.into_generations_with(crate::time::round_time_to_integer_generations)
-    }
-
-    /// Convert the time units to generations with a callback to specify the conversion
-    /// policy
-    pub fn into_generations_with(
-        self,
-        with: fn(Time, GenerationTime) -> Time,
-    ) -> Result<Graph, DemesError> {
-        self.convert_to_generations_details(with)
-    }
-
-    /// Return a representation of the graph as a string.
+    /// The index of each descendant deme and the time at which
+    /// it split from its ancestors, which is its
+    /// [`start_time`](crate::Deme::start_time).
+    /// The values are sorted by deme index.
     ///
-    /// The format is in YAML and corresponds to the MDM
-    /// representation of the data.
+    /// # Errors
     ///
-    /// # Error
+    /// [`DemesError::DemeError`] if `deme` is not in the graph.
     ///
-    /// Will return an error if `serde_yaml::to_string`
-    /// returns an error.
-    pub fn as_string(&self) -> Result<String, DemesError> {
-        match serde_yaml::to_string(self) {
-            Ok(string) => Ok(string),
-            Err(e) => Err(e.into()),
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///  - name: B
+    ///    ancestors: [A]
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 20
+    ///  - name: C
+    ///    ancestors: [B]
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: D
+    ///    ancestors: [A]
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let descendants = graph.descendants("B").unwrap();
+    /// assert_eq!(descendants.len(), 1);
+    /// assert_eq!(usize::from(descendants[0].0), 2);
+    /// assert_eq!(descendants[0].1, 20.0);
+    /// let descendants = graph.descendants("A").unwrap();
+    /// assert_eq!(
+    ///     descendants.iter().map(|(i, _)| usize::from(*i)).collect::<Vec<_>>(),
+    ///     [1, 2, 3]
+    /// );
+    /// ```
+    pub fn descendants<'name, I: Into<DemeId<'name>>>(
+        &self,
+        deme: I,
+    ) -> Result<Vec<(DemeIndex, Time)>, DemesError> {
+        let deme = self.existing_deme_index(deme.into())?;
+        let mut children = vec![vec![]; self.demes.len()];
+        for (i, d) in self.demes.iter().enumerate() {
+            for &DemeIndex(a) in d.ancestor_indexes() {
+                children[a].push(i);
+            }
+        }
+        let mut is_descendant = vec![false; self.demes.len()];
+        let mut stack = vec![deme];
+        while let Some(i) = stack.pop() {
+            for &c in &children[i] {
+                if !is_descendant[c] {
+                    is_descendant[c] = true;
+                    stack.push(c);
+                }
+            }
         }
+        Ok(self
+            .demes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| is_descendant[*i])
+            .map(|(i, d)| (DemeIndex(i), d.start_time()))
+            .collect())
     }
 
-    /// Return a representation of the graph as a string.
+    /// Get all ancestors of a deme.
     ///
-    /// The format is in JSON and corresponds to the MDM
-    /// representation of the data.
+    /// The ancestors are the demes listed in
+    /// [`Deme::ancestor_names`](crate::Deme::ancestor_names),
+    /// their ancestors, and so on.
     ///
-    /// # Error
+    /// # Returns
     ///
-    /// Will return an error if `serde_json::to_string`
-    /// returns an error.
-    #[cfg(feature = "json")]
-    #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
-    pub fn as_json_string(&self) -> Result<String, DemesError> {
-        match serde_json::to_string(self) {
-            Ok(string) => Ok(string),
-            Err(e) => Err(e.into()),
-        }
-    }
-
-    /// Return the most recent end time of any deme
-    /// in the Graph.
+    /// The index of each ancestor deme and the time at which
+    /// it begins contributing ancestry to `deme`.
+    /// This time is the start time of the most ancient deme that
+    /// descends directly from the ancestor and is either `deme`
+    /// or one of its ancestors.
+    /// The values are sorted by deme index.
     ///
-    /// This function is useful to check if the most
-    /// recent end time is greater than zero, meaning
-    /// that the model ends at a time point ancestral to
-    /// "now".
-    pub fn most_recent_deme_end_time(&self) -> Time {
-        let init = self.demes[0].end_time();
-        self.demes
-            .iter()
-            .skip(1)
-            .fold(init, |current_min, current_deme| {
-                std::cmp::min(current_min, current_deme.end_time())
-            })
-    }
-
-    /// Return the description field.
-    pub fn description(&self) -> Option<&str> {
-        match &self.description {
-            Some(x) => Some(x),
-            None => None,
-        }
-    }
-
-    /// Return an iterator over DOI information.
-    pub fn doi(&self) -> impl Iterator<Item = &str> {
-        self.doi.iter().map(|s| s.as_str())
-    }
-
-    /// Check if any epochs have non-integer
-    /// `start_size` or `end_size`.
+    /// # Errors
     ///
-    /// # Returns
+    /// [`DemesError::DemeError`] if `deme` is not in the graph.
     ///
-    /// * The deme name and epoch index where the first
-    ///   non-integer value is encountered
-    /// * None if non non-integer values are encountered
-    pub fn has_non_integer_sizes(&self) -> Option<(&str, usize)> {
-        for deme in &self.demes {
-            for (i, epoch) in deme.epochs.iter().enumerate() {
-                for size in [f64::from(epoch.start_size()), f64::from(epoch.end_size())] {
-                    if size.is_finite() && size.fract() != 0.0 {
-                        return Some((deme.name(), i));
-                    }
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///  - name: B
+    ///    ancestors: [A]
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 20
+    ///  - name: C
+    ///    ancestors: [B]
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let ancestors = graph.ancestors_recursive("C").unwrap();
+    /// assert_eq!(ancestors.len(), 2);
+    /// assert_eq!(usize::from(ancestors[0].0), 0);
+    /// assert_eq!(ancestors[0].1, 50.0);
+    /// assert_eq!(usize::from(ancestors[1].0), 1);
+    /// assert_eq!(ancestors[1].1, 20.0);
+    /// ```
+    pub fn ancestors_recursive<'name, I: Into<DemeId<'name>>>(
+        &self,
+        deme: I,
+    ) -> Result<Vec<(DemeIndex, Time)>, DemesError> {
+        let deme = self.existing_deme_index(deme.into())?;
+        let mut times: Vec<Option<Time>> = vec![None; self.demes.len()];
+        let mut visited = vec![false; self.demes.len()];
+        let mut stack = vec![deme];
+        visited[deme] = true;
+        while let Some(i) = stack.pop() {
+            let start_time = self.demes[i].start_time();
+            for &DemeIndex(a) in self.demes[i].ancestor_indexes() {
+                let time = times[a].get_or_insert(start_time);
+                *time = std::cmp::max(*time, start_time);
+                if !visited[a] {
+                    visited[a] = true;
+                    stack.push(a);
                 }
             }
         }
-        None
-    }
-
-    fn epoch_start_end_size_rounding_details(
-        old_size: DemeSize,
-        rounding_fn: fn(f64) -> f64,
-    ) -> Result<DemeSize, DemesError> {
-        let size = f64::from(old_size);
-        if size.is_finite() && size.fract() != 0.0 {
-            let new_size = rounding_fn(size);
-            if !new_size.is_finite() || new_size.fract() != 0.0 || new_size <= 0.0 {
-                let msg = format!("invalid size after rounding: {new_size}");
-                return Err(DemesError::EpochError(msg));
-            }
-            return new_size.try_into();
-        }
-        Ok(old_size)
-    }
-
-    fn round_epoch_start_end_sizes_with(
-        self,
-        rounding_fn: fn(f64) -> f64,
-    ) -> Result<Self, DemesError> {
-        let mut graph = self;
-
-        for deme in &mut graph.demes {
-            for epoch in &mut deme.epochs {
-                epoch.start_size =
-                    Graph::epoch_start_end_size_rounding_details(epoch.start_size, rounding_fn)?;
-                epoch.end_size =
-                    Graph::epoch_start_end_size_rounding_details(epoch.end_size, rounding_fn)?;
-            }
-        }
-
-        Ok(graph)
+        Ok(times
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, time)| time.map(|time| (DemeIndex(i), time)))
+            .collect())
     }
 
-    /// Round all epoch start/end sizes to nearest integer value.
+    /// Trace the ancestry of a lineage backwards in time.
     ///
-    /// # Returns
-    ///
-    /// A modified graph with rounded sizes.
+    /// A lineage is sampled from `deme` at time `from`.
+    /// This function calculates the probability that the ancestor
+    /// of that lineage is in each deme at the older time `to`.
     ///
-    /// # Error
+    /// # Returns
     ///
-    /// * [`EpochError`](crate::DemesError::EpochError) if rounding
-    ///   leads to a value of 0.
+    /// A vector of probabilities with one entry per deme,
+    /// in the same order as [`Graph::demes`].
     ///
-    /// # Note
+    /// # Model
     ///
-    /// Rounding uses [f64::round](f64::round)
-    pub fn into_integer_start_end_sizes(self) -> Result<Self, DemesError> {
-        self.round_epoch_start_end_sizes_with(f64::round)
-    }
-
-    /// Obtain names of all demes in the graph.
+    /// * When a deme with ancestors begins, the lineage moves
+    ///   into the ancestors according to the ancestry proportions.
+    /// * Each pulse moves the lineage from the destination into
+    ///   each source according to the pulse proportions.
+    ///   A pulse occurring at exactly time `from` has no effect
+    ///   while a pulse at exactly time `to` is applied.
+    /// * A lineage in the destination of a migration moves
+    ///   into the source at the given rate per generation.
+    ///   Migration is modeled as a continuous-time process,
+    ///   as in coalescent simulations.
     ///
-    /// # Note
+    /// # Errors
     ///
-    /// These are ordered by a deme's index in the model.
+    /// * [`DemesError::DemeError`] if `deme` is not in the graph or
+    ///   if it does not exist at time `from`.
+    /// * [`DemesError::ValueError`] if `to` is more recent than
+    ///   `from` or if `to` is infinite.
     ///
-    /// # Panics
-    ///
-    /// This function allocates space for the return value,
-    /// which may panic upon out-of-memory.
-    pub fn deme_names(&self) -> Box<[&str]> {
-        self.demes
-            .iter()
-            .map(|deme| deme.name())
-            .collect::<Vec<&str>>()
-            .into_boxed_slice()
-    }
-
-    /// Get a reference to the input string, if any.
-    ///
-    /// # Examples
+    /// # Examples
     ///
     /// ```
     /// let yaml = "
-    /// time_units: years
-    /// generation_time: 25
-    /// description:
-    ///   A deme of 50 individuals that grew to 100 individuals
-    ///   in the last 100 years.
-    ///   Default behavior is that size changes are exponential.
+    /// time_units: generations
     /// demes:
-    ///  - name: deme
+    ///  - name: A
     ///    epochs:
-    ///     - start_size: 50
-    ///       end_time: 100
-    ///     - start_size: 50
-    ///       end_size: 100
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: C
+    ///    start_time: 50
+    ///    ancestors: [A, B]
+    ///    proportions: [0.25, 0.75]
+    ///    epochs:
+    ///     - start_size: 100
     /// ";
     /// let graph = demes::loads(yaml).unwrap();
-    /// assert_eq!(graph.input_string().unwrap().to_str(), yaml);
+    /// let from = demes::Time::try_from(0.0).unwrap();
+    /// let to = demes::Time::try_from(100.0).unwrap();
+    /// let probabilities = graph.lineage_distribution("C", from, to).unwrap();
+    /// assert_eq!(probabilities, [0.25, 0.75, 0.0]);
     /// ```
-    ///
-    /// # Note
-    ///
-    /// The string is in the same format (YAML or JSON)
-    /// that was used to generate the graph.
-    pub fn input_string(&self) -> Option<InputFormat> {
-        match &self.input_string {
-            None => None,
-            Some(format) => match format {
-                InputFormatInternal::Yaml(string) => Some(InputFormat::Yaml(string.as_str())),
-                InputFormatInternal::Json(string) => Some(InputFormat::Json(string.as_str())),
-                InputFormatInternal::Toml(string) => Some(InputFormat::Toml(string.as_str())),
-            },
-        }
+    pub fn lineage_distribution<'name, I: Into<DemeId<'name>>>(
+        &self,
+        deme: I,
+        from: Time,
+        to: Time,
+    ) -> Result<Vec<f64>, DemesError> {
+        let deme = self.existing_deme_index(deme.into())?;
+        crate::graph_operations::lineage::lineage_distribution(self, deme, from, to)
     }
 
-    /// Rescale a model by a constant scaling factor.
-    ///
-    /// For a given scaling factor, `Q`:
-    /// 1. All input population sizes will be divided by `Q`.
-    /// 2. All times will be divided by `Q`.
-    /// 3. All rates (migration, etc.) will be multiplied by `Q.`
-    /// 4. Pulse proportions, selfing rates, and cloning rates all
-    ///    remaing unchanged.
-    ///
-    /// The result is a new [`Graph`] where the products of populations sizes
-    /// times rates and the timings of events divided by population sizes are the
-    /// same as in the input.
-    ///
-    /// # Parameters
+    /// Iterate over the demes that exist at a given time.
     ///
-    /// * `scaling_factor`: the value of `Q`. This must be > 0.0 and finite.
+    /// A deme exists at time `at` if `at` is in the half-open
+    /// interval `[end_time, start_time)` of the deme.
+    /// This is the same convention used by [`Deme::size_at`].
+    /// As a special case, demes with an infinite start time
+    /// exist at an infinite time.
     ///
     /// # Returns
     ///
-    /// * The rescaled [`Graph`]
-    ///
-    /// # Errors
-    ///
-    /// * [`DemesError`] if `scaling_factor` is invalid or if rescaling results
-    ///   in an invalid graph.  For example, rescaling with `scaling_factor << 1`
-    ///   could result in migration rates `> 1`, which is invalid.
-    pub fn rescale(self, scaling_factor: f64) -> Result<Self, DemesError> {
-        let g = UnresolvedGraph::from(self);
-        g.rescale(scaling_factor)?.try_into()
-    }
-
-    /// Remove recent history from a [Graph].
-    ///
-    /// For a given value of `when`, a new graph is created with all
-    /// history from `[0, when)` removed.
+    /// An iterator over `(index, &Deme)`, in the order that
+    /// demes appear in the graph.
     ///
     /// # Examples
     ///
-    /// Remove the first ten generations:
-    ///
     /// ```
     /// let yaml = "
     /// time_units: generations
     /// demes:
-    ///  - start_time: .inf
-    ///    name: deme
+    ///  - name: ancestor
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///  - name: A
+    ///    ancestors: [ancestor]
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    ancestors: [ancestor]
     ///    epochs:
     ///     - start_size: 100
     /// ";
     /// let graph = demes::loads(yaml).unwrap();
-    /// assert_eq!(graph.demes()[0].end_time(), 0.0);
-    /// let when = demes::Time::try_from(10.0).unwrap();
-    /// let sliced = graph.slice_until(when).unwrap();
-    /// assert_eq!(sliced.demes()[0].end_time(), 10.0);
+    /// let at = demes::Time::try_from(50.0).unwrap();
+    /// let names = graph.demes_at(at).map(|(_, deme)| deme.name()).collect::<Vec<_>>();
+    /// assert_eq!(names, ["ancestor"]);
+    /// let at = demes::Time::try_from(0.0).unwrap();
+    /// let indexes = graph.demes_at(at).map(|(i, _)| i).collect::<Vec<_>>();
+    /// assert_eq!(indexes, [1, 2]);
     /// ```
+    pub fn demes_at(&self, at: Time) -> impl Iterator<Item = (usize, &Deme)> + '_ {
+        self.demes
+            .iter()
+            .enumerate()
+            .filter(move |(_, deme)| deme.exists_at(at))
+    }
+
+    /// Get the [`GenerationTime`](crate::GenerationTime) for the graph.
+    pub fn generation_time(&self) -> GenerationTime {
+        self.generation_time
+    }
+
+    /// Get the [`TimeUnits`](crate::TimeUnits) for the graph.
+    pub fn time_units(&self) -> TimeUnits {
+        self.time_units.clone()
+    }
+
+    /// Get the migration events for the graph.
+    pub fn migrations(&self) -> &[AsymmetricMigration] {
+        &self.resolved_migrations
+    }
+
+    /// Iterate over the migrations that are active at a given time.
     ///
-    /// For the next example, removing the first 20 generations
-    /// removes the ancestral deme entirely:
+    /// A migration is active at time `at` if `at` is in the half-open
+    /// interval `[end_time, start_time)` of the migration.
+    /// This is the same convention used by [`Graph::demes_at`].
+    ///
+    /// # Examples
     ///
     /// ```
     /// let yaml = "
     /// time_units: generations
     /// demes:
-    ///  - start_time: .inf
-    ///    name: ancestor
+    ///  - name: A
     ///    epochs:
     ///     - start_size: 100
-    ///       end_time: 20
-    ///  - name: derived
-    ///    start_time: 20
-    ///    ancestors: [ancestor]
-    ///    proportions: [1.0]
+    ///  - name: B
     ///    epochs:
-    ///     - start_size: 50
+    ///     - start_size: 100
+    /// migrations:
+    ///  - source: A
+    ///    dest: B
+    ///    rate: 1e-3
+    ///    start_time: 100
+    ///    end_time: 50
     /// ";
     /// let graph = demes::loads(yaml).unwrap();
-    /// assert_eq!(graph.demes().len(), 2);
-    /// assert_eq!(graph.demes()[0].end_time(), 20.0);
-    /// assert_eq!(graph.demes()[1].end_time(), 0.0);
-    /// let when = demes::Time::try_from(20.0).unwrap();
-    /// let sliced = graph.slice_until(when).unwrap();
-    /// assert_eq!(sliced.demes().len(), 1);
-    /// assert_eq!(sliced.demes()[0].end_time(), 20.0);
-    /// assert_eq!(sliced.demes()[0].start_time(), f64::INFINITY);
-    /// assert_eq!(sliced.demes()[0].name(), "ancestor");
-    /// ```
-    ///
-    /// For the same input, removing the first 10 generations
-    /// simply truncates the duration of the derived deme:
-    ///
-    /// ```
-    /// # let yaml = "
-    /// # time_units: generations
-    /// # demes:
-    /// #  - start_time: .inf
-    /// #    name: ancestor
-    /// #    epochs:
-    /// #     - start_size: 100
-    /// #       end_time: 20
-    /// #  - name: derived
-    /// #    start_time: 20
-    /// #    ancestors: [ancestor]
-    /// #    proportions: [1.0]
-    /// #    epochs:
-    /// #     - start_size: 50
-    /// # ";
-    /// # let graph = demes::loads(yaml).unwrap();
-    /// # assert_eq!(graph.demes().len(), 2);
-    /// # assert_eq!(graph.demes()[0].end_time(), 20.0);
-    /// # assert_eq!(graph.demes()[1].end_time(), 0.0);
-    /// let when = demes::Time::try_from(10.0).unwrap();
-    /// let sliced = graph.slice_until(when).unwrap();
-    /// assert_eq!(sliced.demes().len(), 2);
-    /// assert_eq!(sliced.demes()[0].end_time(), 20.0);
-    /// assert_eq!(sliced.demes()[0].start_time(), f64::INFINITY);
-    /// assert_eq!(sliced.demes()[0].name(), "ancestor");
-    /// assert_eq!(sliced.demes()[1].end_time(), 10.0);
-    /// assert_eq!(sliced.demes()[1].start_time(), 20.0);
-    /// assert_eq!(sliced.demes()[1].name(), "derived");
+    /// for (time, num_migrations) in [(100.0, 0), (75.0, 1), (50.0, 1), (25.0, 0)] {
+    ///     let at = demes::Time::try_from(time).unwrap();
+    ///     assert_eq!(graph.migrations_at(at).count(), num_migrations);
+    /// }
     /// ```
-    pub fn slice_until(self, when: Time) -> Result<Self, DemesError> {
-        crate::graph_operations::slice::slice_until(self, when)
+    pub fn migrations_at(&self, at: Time) -> impl Iterator<Item = &AsymmetricMigration> + '_ {
+        self.resolved_migrations
+            .iter()
+            .filter(move |m| m.time_interval().contains_exclusive_start_inclusive_end(at))
     }
 
-    /// Remove ancient history from a [Graph].
+    /// Iterate over the migrations that are active at a given time,
+    /// optionally filtered by source and/or destination deme.
     ///
-    /// For a given value of `when`, a new graph is created with only
-    /// history from `[0, when)` retained.
+    /// See [`Graph::migrations_at`] for details.
+    ///
+    /// # Parameters
+    ///
+    /// * `at`: the time
+    /// * `source`: if not `None`, only include migrations from this deme.
+    /// * `dest`: if not `None`, only include migrations into this deme.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError::DemeError`] if `source` or `dest` is not a deme in the graph.
     ///
     /// # Examples
     ///
@@ -3622,418 +5305,3446 @@ impl Graph {
     /// let yaml = "
     /// time_units: generations
     /// demes:
-    ///  - start_time: .inf
-    ///    name: ancestor
+    ///  - name: A
     ///    epochs:
     ///     - start_size: 100
-    ///       end_time: 20
-    ///  - name: derived
-    ///    start_time: 20
-    ///    ancestors: [ancestor]
-    ///    proportions: [1.0]
+    ///  - name: B
     ///    epochs:
-    ///     - start_size: 50
+    ///     - start_size: 100
+    ///  - name: C
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - demes: [A, B, C]
+    ///    rate: 1e-3
     /// ";
     /// let graph = demes::loads(yaml).unwrap();
-    /// assert_eq!(graph.demes().len(), 2);
-    /// assert_eq!(graph.demes()[0].end_time(), 20.0);
-    /// assert_eq!(graph.demes()[1].end_time(), 0.0);
-    /// let when = demes::Time::try_from(20.0).unwrap();
-    /// let sliced = graph.slice_after(when).unwrap();
-    /// assert_eq!(sliced.demes().len(), 1);
-    /// assert_eq!(sliced.demes()[0].end_time(), 0.0);
-    /// assert_eq!(sliced.demes()[0].start_time(), f64::INFINITY);
-    /// assert_eq!(sliced.demes()[0].name(), "derived");
+    /// let at = demes::Time::try_from(0.0).unwrap();
+    /// let into_b = graph.migrations_at_filtered(at, None, Some("B".into())).unwrap();
+    /// assert_eq!(into_b.count(), 2);
+    /// let a_to_b = graph.migrations_at_filtered(at, Some(0.into()), Some("B".into())).unwrap();
+    /// assert_eq!(a_to_b.count(), 1);
+    /// assert!(graph.migrations_at_filtered(at, Some("D".into()), None).is_err());
     /// ```
+    pub fn migrations_at_filtered<'s, 'd>(
+        &self,
+        at: Time,
+        source: Option<DemeId<'s>>,
+        dest: Option<DemeId<'d>>,
+    ) -> Result<impl Iterator<Item = &AsymmetricMigration> + '_, DemesError> {
+        let source = self.optional_deme_name(source)?;
+        let dest = self.optional_deme_name(dest)?;
+        Ok(self.migrations_at(at).filter(move |m| {
+            source.map_or(true, |s| m.source() == s) && dest.map_or(true, |d| m.dest() == d)
+        }))
+    }
+
+    /// Iterate over the migrations from `source` into `dest`.
     ///
-    /// If `when` is within an epoch, we insert an extra epoch
-    /// that has constant size until infinity in the past.
-    /// The size is the epoch's size at `when`.
-    /// Let's look at an example involving population growth.
+    /// The migrations are returned in the same order as [`Graph::migrations`].
+    /// They are found using an index built when the graph is resolved,
+    /// so the cost of a query does not depend on the total number
+    /// of migrations in the graph.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError::DemeError`] if `source` or `dest` is not a deme in the graph.
+    ///
+    /// # Examples
     ///
     /// ```
     /// let yaml = "
     /// time_units: generations
     /// demes:
-    ///  - name: growing
+    ///  - name: A
     ///    epochs:
     ///     - start_size: 100
-    ///       end_time: 100
+    ///  - name: B
+    ///    epochs:
     ///     - start_size: 100
-    ///       end_size: 200
+    /// migrations:
+    ///  - demes: [A, B]
+    ///    rate: 1e-3
+    ///    start_time: 100
+    ///    end_time: 50
+    ///  - source: A
+    ///    dest: B
+    ///    rate: 1e-2
+    ///    start_time: 50
     /// ";
     /// let graph = demes::loads(yaml).unwrap();
-    /// let when = demes::Time::try_from(50.).unwrap();
-    /// let sliced = graph.clone().slice_after(when).unwrap();
-    /// let deme = &sliced.deme(0);
-    /// assert_eq!(deme.num_epochs(), 2);
-    /// let e = deme.epochs()[0];
-    /// assert_eq!(e.start_time(), f64::INFINITY);
-    /// assert_eq!(e.end_time(), when);
-    /// assert_eq!(e.start_size(), graph.deme(0).size_at(when).unwrap().unwrap());
-    /// assert_eq!(e.start_size(), e.end_size());
-    /// let e = deme.epochs()[1];
-    /// assert_eq!(e.start_time(), when);
-    /// assert_eq!(e.end_time(), 0.0);
-    /// assert_eq!(e.start_size(), graph.deme(0).size_at(when).unwrap().unwrap());
-    /// assert_eq!(e.end_size(), graph.deme(0).end_size());
+    /// let a_to_b = graph.migrations_between("A", "B").unwrap().collect::<Vec<_>>();
+    /// assert_eq!(a_to_b.len(), 2);
+    /// assert_eq!(a_to_b[0].end_time(), 50.0);
+    /// assert_eq!(a_to_b[1].end_time(), 0.0);
+    /// assert_eq!(graph.migrations_between(1, 0).unwrap().count(), 1);
+    /// assert!(graph.migrations_between("A", "C").is_err());
     /// ```
-    pub fn slice_after(self, when: Time) -> Result<Self, DemesError> {
-        crate::graph_operations::slice::slice_after(self, when)
+    pub fn migrations_between<'s, 'd, S: Into<DemeId<'s>>, D: Into<DemeId<'d>>>(
+        &self,
+        source: S,
+        dest: D,
+    ) -> Result<impl Iterator<Item = &AsymmetricMigration> + '_, DemesError> {
+        let source = self.existing_deme_index(source.into())?;
+        let dest = self.existing_deme_index(dest.into())?;
+        Ok(self
+            .indexed_migrations(source, dest)
+            .iter()
+            .map(|&i| &self.resolved_migrations[i]))
     }
 
-    /// Obtain a deme index from a deme name
-    ///
-    /// # Parameters
-    ///
-    /// * `name` - the deme name
-    ///
-    /// # Returns
+    /// Iterate over the migrations between two demes, in either direction.
     ///
-    /// * `Some(index)` if `name` is a valid deme name
-    /// * `None` Otherwise
+    /// The migrations are returned in the same order as [`Graph::migrations`].
+    /// See [`Graph::migrations_between`] for the cost of a query.
     ///
-    /// # Complexity
+    /// # Errors
     ///
-    /// * O(1)
+    /// [`DemesError::DemeError`] if `first` or `second` is not a deme in the graph.
     ///
     /// # Examples
     ///
     /// ```
     /// let yaml = "
-    ///  time_units: generations
-    ///  demes:
-    ///   - name: ancestor1
-    ///     epochs:
-    ///      - start_size: 50
-    ///        end_time: 20
-    ///   - name: ancestor2
-    ///     epochs:
-    ///      - start_size: 50
-    ///        end_time: 20
-    ///   - name: derived
-    ///     ancestors: [ancestor1, ancestor2]
-    ///     proportions: [0.5, 0.5]
-    ///     start_time: 20
-    ///     epochs:
-    ///      - start_size: 50
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: C
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - demes: [A, B, C]
+    ///    rate: 1e-3
     /// ";
     /// let graph = demes::loads(yaml).unwrap();
-    /// for (i, d) in graph.demes().iter().enumerate() {
-    ///     assert_eq!(graph.deme_index(d.name()), Some(i))
-    /// }
+    /// assert_eq!(graph.migrations_between_symmetric("A", "B").unwrap().count(), 2);
     /// ```
-    pub fn deme_index<S: AsRef<str>>(&self, name: S) -> Option<usize> {
-        self.deme_map.get(name.as_ref()).cloned()
+    pub fn migrations_between_symmetric<'f, 's, F: Into<DemeId<'f>>, S: Into<DemeId<'s>>>(
+        &self,
+        first: F,
+        second: S,
+    ) -> Result<impl Iterator<Item = &AsymmetricMigration> + '_, DemesError> {
+        let first = self.existing_deme_index(first.into())?;
+        let second = self.existing_deme_index(second.into())?;
+        let mut indexes = self.indexed_migrations(first, second).to_vec();
+        if first != second {
+            indexes.extend_from_slice(self.indexed_migrations(second, first));
+            indexes.sort_unstable();
+        }
+        Ok(indexes.into_iter().map(|i| &self.resolved_migrations[i]))
+    }
+
+    // Look up the index of a deme that must exist
+    fn existing_deme_index(&self, id: DemeId<'_>) -> Result<usize, DemesError> {
+        match id {
+            DemeId::Index(DemeIndex(i)) if i < self.demes.len() => Some(i),
+            DemeId::Index(_) => None,
+            DemeId::Name(name) => self.deme_map.get(name).copied(),
+        }
+        .ok_or_else(|| {
+            DemesError::DemeError(ErrorContext::new(
+                format!("invalid deme: {id:?}"),
+                ErrorKind::UnknownDeme,
+            ))
+        })
     }
 
-    /// Obtain the ancestry proportions for a deme at a given time.
+    // Look up the name of a deme that must exist
+    fn existing_deme_name(&self, id: DemeId<'_>) -> Result<&str, DemesError> {
+        self.get_deme(id).map(|deme| deme.name()).ok_or_else(|| {
+            DemesError::DemeError(ErrorContext::new(
+                format!("invalid deme: {id:?}"),
+                ErrorKind::UnknownDeme,
+            ))
+        })
+    }
+
+    // Look up the name of a deme, if an id is provided
+    fn optional_deme_name(&self, id: Option<DemeId<'_>>) -> Result<Option<&str>, DemesError> {
+        id.map(|id| self.existing_deme_name(id)).transpose()
+    }
+
+    /// Get the pulse events for the graph.
+    pub fn pulses(&self) -> &[Pulse] {
+        &self.pulses
+    }
+
+    /// Get the pulse events occurring within a time window.
     ///
-    /// # Parameters
+    /// A pulse is included if its time is in the half-open
+    /// interval `[end, start)`.
+    /// Successive windows, such as `[t - 1, t)` followed by `[t - 2, t - 1)`,
+    /// therefore include each pulse exactly once.
     ///
-    /// * `deme` - the "focal" deme whose ancestry proportions will be calculated.
-    /// * `at` - the [Time] at which to calculate ancestry proportions.
+    /// The pulses are returned in the same order as [`Graph::pulses`],
+    /// which is from most ancient to most recent.
     ///
-    /// # Returns
+    /// The slice is empty if `start <= end`.
     ///
-    /// * A boxed slice if `deme` exists in the graph and time `at` is a sensible
-    ///   parental time point
-    /// * None, otherwise
+    /// # Examples
     ///
-    /// # Details
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// pulses:
+    ///  - sources: [A]
+    ///    dest: B
+    ///    proportions: [0.1]
+    ///    time: 10
+    ///  - sources: [A]
+    ///    dest: B
+    ///    proportions: [0.2]
+    ///    time: 20
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let start = demes::Time::try_from(20.0).unwrap();
+    /// let end = demes::Time::try_from(10.0).unwrap();
+    /// let pulses = graph.pulses_between(start, end);
+    /// assert_eq!(pulses.len(), 1);
+    /// assert_eq!(pulses[0].time(), 10.0);
+    /// ```
+    pub fn pulses_between(&self, start: Time, end: Time) -> &[Pulse] {
+        let first = self.pulses.partition_point(|p| p.time() >= start);
+        let last = self.pulses.partition_point(|p| p.time() >= end);
+        &self.pulses[first..last.max(first)]
+    }
+
+    /// Get a copy of the top-level [`Metadata`](crate::Metadata).
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.metadata.as_ref().map(|md| Metadata {
+            metadata: md.clone(),
+        })
+    }
+
+    /// Replace the top-level metadata.
     ///
-    /// * `at` is treated as a time immediately before individuals are born
-    ///   into `deme`.
-    /// * This function allocates memory.
-    ///   See [Graph::fill_ancestry_proportions] for a function to reuse
-    ///   existing allocations.
+    /// # Parameters
     ///
-    /// # Complexity
+    /// * `metadata`: the metadata type.
+    ///   It must serialize to a non-empty mapping.
     ///
-    /// * See [Graph::fill_ancestry_proportions].
+    /// # Errors
     ///
-    /// # Panics
+    /// * [`DemesError::YamlError`] if `metadata` does not serialize
+    ///   to a mapping with string keys.
+    /// * [`DemesError::GraphError`] if `metadata` serializes to an empty mapping.
     ///
-    /// * See [Graph::fill_ancestry_proportions].
-    pub fn ancestry_proportions<'d, I: Into<DemeId<'d>>>(
-        &self,
-        deme: I,
-        at: Time,
-    ) -> Option<Box<[f64]>> {
-        let mut rv = vec![0.0; self.num_demes()];
-        self.fill_ancestry_proportions(deme, at, &mut rv)
-            .map(|_| rv.into_boxed_slice())
+    /// # Examples
+    ///
+    /// ```
+    /// #[derive(serde::Serialize, serde::Deserialize)]
+    /// struct Provenance {
+    ///    seed: u64,
+    ///    software: String,
+    /// }
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert!(graph.metadata().is_none());
+    /// let provenance = Provenance{seed: 42, software: "my_simulator".to_string()};
+    /// let graph = graph.with_metadata(&provenance).unwrap();
+    /// let metadata = graph.metadata().unwrap().as_yaml_string().unwrap();
+    /// let provenance: Provenance = serde_yaml::from_str(&metadata).unwrap();
+    /// assert_eq!(provenance.seed, 42);
+    /// let graph = graph.without_metadata();
+    /// assert!(graph.metadata().is_none());
+    /// ```
+    pub fn with_metadata<T: Serialize>(self, metadata: &T) -> Result<Self, DemesError> {
+        let value = serde_yaml::to_value(metadata)?;
+        let md: std::collections::BTreeMap<String, serde_yaml::Value> =
+            serde_yaml::from_value(value)?;
+        let metadata = Metadata::try_from(md)?;
+        Ok(Self {
+            metadata: Some(metadata.metadata),
+            ..self
+        })
     }
 
-    /// Obtain the ancestry proportions for a deme at a given time.
+    /// Remove the top-level metadata.
     ///
-    /// # Parameters
+    /// See [`Graph::with_metadata`].
+    pub fn without_metadata(self) -> Self {
+        Self {
+            metadata: None,
+            ..self
+        }
+    }
+
+    pub(crate) fn convert_to_generations_details(
+        self,
+        round: &mut dyn FnMut(Time, GenerationTime) -> Time,
+    ) -> Result<Self, DemesError> {
+        let mut converted = self;
+
+        converted.demes.iter_mut().try_for_each(|deme| {
+            deme.resolved_time_to_generations(converted.generation_time, round)
+        })?;
+
+        converted.pulses.iter_mut().try_for_each(|pulse| {
+            pulse.resolved_time_to_generations(converted.generation_time, round)
+        })?;
+
+        converted
+            .resolved_migrations
+            .iter_mut()
+            .try_for_each(|pulse| {
+                pulse.resolved_time_to_generations(converted.generation_time, round)
+            })?;
+
+        converted.time_units = TimeUnits::Generations;
+        converted.generation_time = GenerationTime::try_from(1.0)?;
+
+        Ok(converted)
+    }
+
+    /// Convert the time units to generations.
     ///
-    /// * `deme` - the "focal" deme whose ancestry proportions will be calculated.
-    /// * `at` - the [Time] at which to calculate ancestry proportions.
-    /// * `buffer` -  output location for the ancestry proportions.
-    ///    The buffer length must be at least the number of demes in
-    ///    the graph. (See [Graph::num_demes].)
+    /// # Errors
     ///
-    /// # Returns
+    /// If the time unit of an event differs sufficiently in
+    /// magnitude from the `generation_time`, it is possible
+    /// that conversion results in epochs (or migration
+    /// durations) of length zero, which will return an error.
     ///
-    /// * A unit type if `deme` exists in the graph and time `at` is a sensible
-    ///   parental time point
-    /// * None, otherwise
-    /// # Details
+    /// If any field is unresolved, an error will be returned.
+    pub fn into_generations(self) -> Result<Self, DemesError> {
+        self.into_generations_with(crate::time::to_generations)
+    }
+
+    /// Convert the time units to generations, rounding the output to an integer value.
+    pub fn into_integer_generations(self) -> Result<Graph, DemesError> {
+        self.into_generations_with(crate::time::round_time_to_integer_generations)
+    }
+
+    /// Check for changes to the model that would result from
+    /// converting its times to integer generations.
     ///
-    /// `at` is treated as a time immediately before individuals are born
-    /// into `deme`.
+    /// Unlike [`Graph::into_integer_generations`], which stops at the
+    /// first epoch or migration that would have length zero, all
+    /// issues are reported.
+    /// Changes to the order of events that do not cause conversion to fail,
+    /// such as pulses that would happen in the same generation,
+    /// are also reported.
     ///
-    /// # Complexity
+    /// # Returns
     ///
-    /// * Linear in the number of ancestor demes, pulses, and migration events
-    ///   affecting ancestry at time `at`.
+    /// The issues found, which is empty if conversion preserves the model.
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// * if any calculations result in invalid values (x < 0, x > 1, x not finite).
-    /// * if `buffer` does not contain sufficient space for the output
-    pub fn fill_ancestry_proportions<'d, I: Into<DemeId<'d>>>(
-        &self,
-        deme: I,
-        at: Time,
-        buffer: &mut [f64],
-    ) -> Option<()> {
-        let deme = self.get_deme(deme)?;
-        if at <= deme.start_time() && at > deme.end_time() {
-            self.fill_ancestry_proportions_details(deme, at, buffer);
-            Some(())
-        } else {
-            None
-        }
+    /// ```
+    /// let yaml = "
+    /// time_units: years
+    /// generation_time: 25
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 1000
+    ///     - start_size: 200
+    ///       end_time: 990
+    ///     - start_size: 300
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let issues = graph.check_integer_generations();
+    /// assert_eq!(issues.len(), 1);
+    /// assert!(matches!(
+    ///     &issues[0],
+    ///     demes::IntegerGenerationsIssue::ZeroLengthEpoch { deme, epoch: 1, .. } if deme == "A"
+    /// ));
+    /// assert!(graph.into_integer_generations().is_err());
+    /// ```
+    pub fn check_integer_generations(&self) -> Vec<crate::IntegerGenerationsIssue> {
+        crate::graph_operations::integer_generations::check_integer_generations(self)
     }
 
-    fn fill_ancestry_proportions_details(&self, deme: &Deme, at: Time, buffer: &mut [f64]) {
-        let deme_index = self.deme_map[deme.name()];
-        if at <= deme.start_time() && at > deme.end_time() {
-            buffer.fill_with(|| 0.0);
-            if at == deme.start_time() {
-                for (a, p) in deme
-                    .ancestor_indexes()
-                    .iter()
-                    .cloned()
-                    .zip(deme.proportions().iter().cloned())
-                {
-                    buffer[a] += f64::from(p);
+    /// Convert the time units to integer generations, merging epochs
+    /// that would otherwise have length zero.
+    ///
+    /// [`Graph::into_integer_generations`] returns an error when rounding
+    /// results in a zero-length epoch.
+    /// Here, such epochs are removed, with the neighboring epochs
+    /// covering the removed interval.
+    /// Migrations that would have length zero are also removed.
+    ///
+    /// # Returns
+    ///
+    /// The converted graph and a report of all epochs and migrations that
+    /// were removed.
+    ///
+    /// # Errors
+    ///
+    /// * If conversion results in a deme with no epochs of nonzero length.
+    /// * Any of the errors returned by [`Graph::into_integer_generations`] that
+    ///   are not due to zero-length epochs or migrations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: years
+    /// generation_time: 25
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 1000
+    ///     - start_size: 200
+    ///       end_time: 990
+    ///     - start_size: 300
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert!(graph.clone().into_integer_generations().is_err());
+    /// let (converted, report) = graph.into_integer_generations_merged().unwrap();
+    /// assert_eq!(converted.deme(0).num_epochs(), 2);
+    /// assert_eq!(converted.deme(0).end_times().collect::<Vec<_>>(), [40.0, 0.0]);
+    /// assert_eq!(report.merged_epochs().len(), 1);
+    /// assert_eq!(report.merged_epochs()[0].deme(), "A");
+    /// assert_eq!(report.merged_epochs()[0].epoch_index(), 1);
+    /// ```
+    pub fn into_integer_generations_merged(
+        self,
+    ) -> Result<(Graph, IntegerGenerationsReport), DemesError> {
+        let round = crate::time::round_time_to_integer_generations;
+        let generation_time = self.generation_time;
+        let collapses =
+            |start: Time, end: Time| round(start, generation_time) == round(end, generation_time);
+        let mut graph = self;
+        let mut report = IntegerGenerationsReport::default();
+        for deme in graph.demes.iter_mut() {
+            let mut epochs: Vec<Epoch> = Vec::with_capacity(deme.epochs.len());
+            let mut pending_start_time: Option<Time> = None;
+            let num_epochs = deme.epochs.len();
+            for (epoch_index, epoch) in deme.epochs.iter().enumerate() {
+                let mut epoch = *epoch;
+                if let Some(start_time) = pending_start_time.take() {
+                    epoch.start_time = start_time;
                 }
-            } else {
-                buffer[deme_index] = 1.0;
-            }
-            for pulse in self
-                .pulses()
-                .iter()
-                .filter(|&p| p.time() == at && p.dest() == deme.name())
-            {
-                let sum_pulses = pulse
-                    .proportions()
-                    .iter()
-                    .fold(0.0, |sum, &p| sum + f64::from(p));
-                buffer.iter_mut().for_each(|v| *v *= 1. - sum_pulses);
-                for (source, proportion) in pulse
-                    .sources()
-                    .iter()
-                    .zip(pulse.proportions().iter().cloned().map(f64::from))
-                {
-                    let source_index = self.deme_map[source];
-                    buffer[source_index] += proportion;
+                let is_last = epoch_index + 1 == num_epochs;
+                if collapses(epoch.start_time, epoch.end_time) && !(is_last && epochs.is_empty()) {
+                    report.merged_epochs.push(MergedEpoch {
+                        deme: deme.name.to_string(),
+                        epoch_index,
+                        start_time: epoch.start_time,
+                        end_time: epoch.end_time,
+                    });
+                    if is_last {
+                        // The previous epoch absorbs the interval.
+                        if let Some(previous) = epochs.last_mut() {
+                            previous.end_time = epoch.end_time;
+                        }
+                    } else {
+                        // The next epoch absorbs the interval.
+                        pending_start_time = Some(epoch.start_time);
+                    }
+                } else {
+                    epochs.push(epoch);
                 }
             }
-            let input_migrations = self
-                .migrations()
-                .iter()
-                .filter(|m| at <= m.start_time() && at > m.end_time() && m.dest() == deme.name())
-                .collect::<Vec<_>>();
-            let sum_migrates = input_migrations
-                .iter()
-                .fold(0.0, |sum, m| sum + f64::from(m.rate()));
-            buffer.iter_mut().for_each(|v| *v *= 1. - sum_migrates);
-            for i in input_migrations {
-                let source = self.deme_map[i.source()];
-                buffer[source] += f64::from(i.rate())
-            }
-            assert!(
-                buffer
-                    .iter()
-                    .all(|p| p.is_finite() && (0.0..=1.0).contains(p)),
-                "invalid value in {buffer:?}"
-            );
+            deme.epochs = epochs;
         }
+        let (kept, removed): (Vec<_>, Vec<_>) = std::mem::take(&mut graph.resolved_migrations)
+            .into_iter()
+            .partition(|m| !collapses(m.start_time, m.end_time));
+        graph.resolved_migrations = kept;
+        graph.build_migration_index();
+        report.removed_migrations = removed;
+        let converted = graph.into_generations_with(round)?;
+        Ok((converted, report))
     }
 
-    #[allow(missing_docs)]
-    pub fn ancestry_proportions_matrix(&self, at: Time) -> Result<Box<[f64]>, DemesError> {
-        let mut buffer = vec![0.; self.num_demes() * self.num_demes()];
-        self.fill_ancestry_proportions_matrix(at, &mut buffer)
-            .map(|_| buffer.into_boxed_slice())
+    /// Convert the time units to generations with a callback to specify the conversion
+    /// policy
+    ///
+    /// The callback may be a closure that captures state.
+    /// It is called for each deme start time, then for the start
+    /// and end times of each epoch of that deme, in the order of the
+    /// demes in the graph.
+    /// Pulse times and then migration start and end times follow.
+    /// Infinite times are not passed to the callback.
+    /// A time that is shared by adjacent epochs is passed once for each
+    /// epoch, so the callback must return the same value for the same input
+    /// in order for the converted epochs to remain contiguous.
+    ///
+    /// # Examples
+    ///
+    /// Count the number of conversions while rounding to integer generations:
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: years
+    /// generation_time: 10
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 25
+    ///     - start_size: 200
+    ///       end_time: 15
+    ///     - start_size: 300
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let mut calls = 0;
+    /// let graph = graph
+    ///     .into_generations_with(|time, generation_time| {
+    ///         calls += 1;
+    ///         demes::round_time_to_integer_generations(time, generation_time)
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(graph.deme(0).end_times().collect::<Vec<_>>(), [3.0, 2.0, 0.0]);
+    /// assert_eq!(calls, 5);
+    /// ```
+    pub fn into_generations_with<F>(self, mut with: F) -> Result<Graph, DemesError>
+    where
+        F: FnMut(Time, GenerationTime) -> Time,
+    {
+        self.convert_to_generations_details(&mut with)
     }
 
-    #[allow(missing_docs)]
-    pub fn fill_ancestry_proportions_matrix(
-        &self,
-        at: Time,
-        buffer: &mut [f64],
-    ) -> Result<(), DemesError> {
-        if at == 0.0 {
-            return Err(DemesError::ValueError(format!(
-                "time must be > 0.0, got {at:?}"
-            )));
-        }
-        buffer.fill_with(|| 0.);
-        for (deme_index, deme) in self.demes().iter().enumerate() {
-            if at == deme.start_time() {
-                for (a, p) in deme
-                    .ancestor_indexes()
-                    .iter()
-                    .cloned()
-                    .zip(deme.proportions().iter().cloned())
-                {
-                    buffer[deme_index * self.num_demes() + a] += f64::from(p);
-                }
-            } else if at > deme.end_time() && at <= deme.start_time() {
-                buffer[deme_index * self.num_demes() + deme_index] = 1.0;
+    /// Return a multi-line, human-readable description
+    /// of the model.
+    ///
+    /// The output is intended for logging and debugging.
+    /// Its format may change between releases.
+    /// Use [`Graph::as_string`] for a representation
+    /// that can be loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///     - start_size: 100
+    ///       end_size: 200
+    ///  - name: B
+    ///    ancestors: [A]
+    ///    start_time: 50
+    ///    epochs:
+    ///     - start_size: 10
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let summary = graph.summary();
+    /// assert!(summary.contains("A (inf, 0], 2 epochs, size 100 -> 200"));
+    /// assert!(summary.contains("B (50, 0] from A, 1 epoch, size 10"));
+    /// println!("{summary}");
+    /// ```
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "{} demes, {} migrations, {} pulses",
+            self.num_demes(),
+            self.migrations().len(),
+            self.pulses().len()
+        )];
+        if let Some(description) = self.description().filter(|d| !d.is_empty()) {
+            lines.push(format!("description: {description}"));
+        }
+        lines.push(format!(
+            "time units: {}, generation time: {}",
+            self.time_units(),
+            self.generation_time()
+        ));
+        lines.push("demes:".to_string());
+        for deme in self.demes() {
+            lines.push(format!("  {deme}"));
+            for (i, epoch) in deme.epochs().iter().enumerate() {
+                lines.push(format!("    epoch {i}: {epoch}"));
             }
         }
-        let mut temp = vec![0.0; self.num_demes() * self.num_demes()];
-        let pulses = self
-            .pulses()
-            .iter()
-            .filter(|&p| p.time() == at)
-            .collect::<Vec<_>>();
-        if !pulses.is_empty() {
-            for (deme_index, deme) in self.demes().iter().enumerate() {
-                for &pulse in pulses.iter().filter(|p| p.dest() == deme.name()) {
-                    for (a, p) in pulse
-                        .sources()
-                        .iter()
-                        .zip(pulse.proportions().iter().cloned())
-                    {
-                        let source_index = self.deme_index(a).unwrap();
-                        temp[deme_index * self.num_demes() + source_index] = p.into();
-                    }
-                }
+        if !self.migrations().is_empty() {
+            lines.push("migrations:".to_string());
+            for m in self.migrations() {
+                lines.push(format!(
+                    "  {} -> {} {}, rate {}",
+                    m.source(),
+                    m.dest(),
+                    m.time_interval(),
+                    m.rate()
+                ));
             }
-            for (i, t) in temp.chunks_exact(self.num_demes()).enumerate() {
-                let sum = t.iter().sum::<f64>();
-                for (j, tt) in buffer
-                    .iter_mut()
-                    .skip(i * self.num_demes())
-                    .take(self.num_demes())
-                    .zip(t.iter().cloned())
-                {
-                    *j *= 1. - sum;
-                    *j += tt;
-                }
+        }
+        if !self.pulses().is_empty() {
+            lines.push("pulses:".to_string());
+            for p in self.pulses() {
+                let sources = p
+                    .sources()
+                    .iter()
+                    .zip(p.proportions())
+                    .map(|(s, p)| format!("{s} ({p})"))
+                    .collect::<Vec<_>>();
+                lines.push(format!(
+                    "  time {}: {} -> {}",
+                    p.time(),
+                    sources.join(", "),
+                    p.dest()
+                ));
             }
         }
-        let input_migrations = self
-            .migrations()
+        lines.join("\n")
+    }
+
+    /// Return a representation of the graph as a string.
+    ///
+    /// The format is in YAML and corresponds to the MDM
+    /// representation of the data.
+    ///
+    /// # Error
+    ///
+    /// Will return an error if `serde_yaml::to_string`
+    /// returns an error.
+    pub fn as_string(&self) -> Result<String, DemesError> {
+        match serde_yaml::to_string(self) {
+            Ok(string) => Ok(string),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Return a compact representation of the graph as a string.
+    ///
+    /// The format is in YAML and is closer to the HDM
+    /// representation of the data than the output of
+    /// [`Graph::as_string`]:
+    ///
+    /// * Values that are equal to the defaults given by the
+    ///   specification are omitted.
+    ///   For example, an `end_time` of zero for the last epoch of a deme.
+    /// * Adjacent pairs of asymmetric migrations that differ only
+    ///   in their direction are written as a single symmetric migration.
+    ///
+    /// Loading the output results in a graph equal to `self`.
+    ///
+    /// # Error
+    ///
+    /// Will return an error if `serde_yaml::to_string`
+    /// returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - demes: [A, B]
+    ///    rate: 1e-3
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let compact = graph.as_compact_string().unwrap();
+    /// assert!(compact.len() < graph.as_string().unwrap().len());
+    /// assert!(!compact.contains("end_time"));
+    /// assert_eq!(demes::loads(&compact).unwrap(), graph);
+    /// ```
+    pub fn as_compact_string(&self) -> Result<String, DemesError> {
+        let value = crate::graph_operations::compact::compact_value(self)?;
+        Ok(serde_yaml::to_string(&value)?)
+    }
+
+    /// Return a representation of the graph as a YAML string,
+    /// with formatting controlled by [`SerializeOptions`](crate::SerializeOptions).
+    ///
+    /// # Error
+    ///
+    /// Will return an error if a value cannot be serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///     - start_size: 200
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let options = demes::SerializeOptions::default()
+    ///     .set_compact(true)
+    ///     .set_epoch_style(demes::YamlStyle::Flow);
+    /// let output = graph.serialize_with(options).unwrap();
+    /// assert!(output.contains("- {end_time: 50.0, start_size: 100.0}"));
+    /// assert!(output.contains("- {start_size: 200.0}"));
+    /// assert_eq!(demes::loads(&output).unwrap(), graph);
+    /// ```
+    pub fn serialize_with(&self, options: crate::SerializeOptions) -> Result<String, DemesError> {
+        crate::serialize::serialize_with(self, options)
+    }
+
+    /// Return a representation of the graph as a string.
+    ///
+    /// The format is in JSON and corresponds to the MDM
+    /// representation of the data.
+    ///
+    /// # Error
+    ///
+    /// Will return an error if `serde_json::to_string`
+    /// returns an error.
+    #[cfg(feature = "json")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+    pub fn as_json_string(&self) -> Result<String, DemesError> {
+        match serde_json::to_string(self) {
+            Ok(string) => Ok(string),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Return a representation of the graph as a string.
+    ///
+    /// The format is in TOML and corresponds to the MDM
+    /// representation of the data.
+    ///
+    /// # Error
+    ///
+    /// Will return an error if `toml::to_string`
+    /// returns an error.
+    /// For example, TOML cannot represent `null`
+    /// values in the top-level metadata.
+    #[cfg(feature = "toml")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "toml")))]
+    pub fn as_toml_string(&self) -> Result<String, DemesError> {
+        match toml::to_string(self) {
+            Ok(string) => Ok(string),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the graph to a file, choosing the format
+    /// from the file extension.
+    ///
+    /// See [`FileFormat::from_path`](crate::FileFormat::from_path)
+    /// for the recognized extensions.
+    ///
+    /// The output is first written to a temporary file in the
+    /// same directory as `path`, which is then renamed.
+    /// Thus, an existing file is never left partially written.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::ValueError`] if the extension is not recognized,
+    ///   or if the corresponding feature is not enabled.
+    /// * [`DemesError::IOerror`] if writing the file fails.
+    /// * [`DemesError`] if serialization fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let path = std::env::temp_dir().join(format!("saved_model_{}.yaml", std::process::id()));
+    /// graph.save(&path).unwrap();
+    /// assert_eq!(demes::load_path(&path).unwrap(), graph);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), DemesError> {
+        crate::save::save(self, path.as_ref())
+    }
+
+    /// Write the graph to a file in a given format.
+    ///
+    /// This function is the same as [`Graph::save`] except that
+    /// the file extension is ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::ValueError`] if the feature required by
+    ///   `format` is not enabled.
+    /// * [`DemesError::IOerror`] if writing the file fails.
+    /// * [`DemesError`] if serialization fails.
+    pub fn save_as<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        format: crate::FileFormat,
+    ) -> Result<(), DemesError> {
+        crate::save::save_as(self, path.as_ref(), format)
+    }
+
+    /// Return the most recent end time of any deme
+    /// in the Graph.
+    ///
+    /// This function is useful to check if the most
+    /// recent end time is greater than zero, meaning
+    /// that the model ends at a time point ancestral to
+    /// "now".
+    pub fn most_recent_deme_end_time(&self) -> Time {
+        let init = self.demes[0].end_time();
+        self.demes
             .iter()
-            .filter(|m| at <= m.start_time() && at > m.end_time())
-            .collect::<Vec<_>>();
-        if !input_migrations.is_empty() {
-            temp.fill_with(|| 0.);
-            for (deme_index, deme) in self.demes().iter().enumerate() {
-                for &m in input_migrations.iter().filter(|p| p.dest() == deme.name()) {
-                    let source_index = self.deme_index(m.source()).unwrap();
-                    temp[deme_index * self.num_demes() + source_index] = m.rate().into();
-                }
-            }
-            for (i, t) in temp.chunks_exact(self.num_demes()).enumerate() {
-                let sum = t.iter().sum::<f64>();
-                for (j, tt) in buffer
-                    .iter_mut()
-                    .skip(i * self.num_demes())
-                    .take(self.num_demes())
-                    .zip(t.iter().cloned())
-                {
-                    *j *= 1. - sum;
-                    *j += tt;
+            .skip(1)
+            .fold(init, |current_min, current_deme| {
+                std::cmp::min(current_min, current_deme.end_time())
+            })
+    }
+
+    /// Return the description field.
+    pub fn description(&self) -> Option<&str> {
+        match &self.description {
+            Some(x) => Some(x),
+            None => None,
+        }
+    }
+
+    /// Return an iterator over DOI information.
+    pub fn doi(&self) -> impl Iterator<Item = &str> {
+        self.doi.iter().map(|s| s.as_str())
+    }
+
+    /// Return the graph with a new description.
+    ///
+    /// `None` removes the description.
+    ///
+    /// The returned graph no longer has an
+    /// [`input_string`](Graph::input_string).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let graph = graph.with_description(Some("a single deme"));
+    /// assert_eq!(graph.description(), Some("a single deme"));
+    /// ```
+    pub fn with_description<D: Into<String>>(self, description: Option<D>) -> Self {
+        Self {
+            input_string: None,
+            description: description.map(|d| d.into()),
+            ..self
+        }
+    }
+
+    /// Return the graph with new DOI information,
+    /// replacing any existing values.
+    ///
+    /// The returned graph no longer has an
+    /// [`input_string`](Graph::input_string).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// doi:
+    ///  - https://doi.org/10.1000/old
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let graph = graph.with_doi(["https://doi.org/10.1000/new"]);
+    /// assert_eq!(
+    ///     graph.doi().collect::<Vec<_>>(),
+    ///     ["https://doi.org/10.1000/new"]
+    /// );
+    /// ```
+    pub fn with_doi<I, S>(self, doi: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            input_string: None,
+            doi: doi.into_iter().map(|d| d.into()).collect(),
+            ..self
+        }
+    }
+
+    /// Check if any epochs have non-integer
+    /// `start_size` or `end_size`.
+    ///
+    /// # Returns
+    ///
+    /// * The deme name and epoch index where the first
+    ///   non-integer value is encountered
+    /// * None if non non-integer values are encountered
+    pub fn has_non_integer_sizes(&self) -> Option<(&str, usize)> {
+        for deme in &self.demes {
+            for (i, epoch) in deme.epochs.iter().enumerate() {
+                for size in [f64::from(epoch.start_size()), f64::from(epoch.end_size())] {
+                    if size.is_finite() && size.fract() != 0.0 {
+                        return Some((deme.name(), i));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn epoch_start_end_size_rounding_details(
+        old_size: DemeSize,
+        rounding_fn: &mut dyn FnMut(f64) -> f64,
+    ) -> Result<DemeSize, DemesError> {
+        let size = f64::from(old_size);
+        if size.is_finite() && size.fract() != 0.0 {
+            let new_size = rounding_fn(size);
+            if !new_size.is_finite() || new_size.fract() != 0.0 || new_size <= 0.0 {
+                let msg = format!("invalid size after rounding: {new_size}");
+                return Err(DemesError::EpochError(invalid(msg).with_value(new_size)));
+            }
+            return new_size.try_into();
+        }
+        Ok(old_size)
+    }
+
+    fn round_epoch_start_end_sizes_with(
+        self,
+        rounding_fn: &mut dyn FnMut(f64) -> f64,
+    ) -> Result<Self, DemesError> {
+        let mut graph = self;
+
+        for deme in &mut graph.demes {
+            for epoch in &mut deme.epochs {
+                epoch.start_size =
+                    Graph::epoch_start_end_size_rounding_details(epoch.start_size, rounding_fn)?;
+                epoch.end_size =
+                    Graph::epoch_start_end_size_rounding_details(epoch.end_size, rounding_fn)?;
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Round all epoch start/end sizes to nearest integer value.
+    ///
+    /// # Returns
+    ///
+    /// A modified graph with rounded sizes.
+    ///
+    /// # Error
+    ///
+    /// * [`EpochError`](crate::DemesError::EpochError) if rounding
+    ///   leads to a value of 0.
+    ///
+    /// # Note
+    ///
+    /// Rounding uses [f64::round](f64::round)
+    pub fn into_integer_start_end_sizes(self) -> Result<Self, DemesError> {
+        self.round_epoch_start_end_sizes_with(&mut f64::round)
+    }
+
+    /// Round all non-integer epoch start/end sizes using a callback.
+    ///
+    /// The callback may be a closure that captures state.
+    /// It is called for the start and then the end size of each
+    /// epoch, in the order of the demes in the graph.
+    /// Sizes that are already integers are not passed to the callback.
+    ///
+    /// # Returns
+    ///
+    /// A modified graph with rounded sizes.
+    ///
+    /// # Error
+    ///
+    /// * [`EpochError`](crate::DemesError::EpochError) if the callback
+    ///   returns a value that is not a positive integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100.5
+    ///       end_time: 10
+    ///     - start_size: 200.5
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let mut rounded = vec![];
+    /// let graph = graph
+    ///     .into_integer_start_end_sizes_with(|size| {
+    ///         rounded.push(size);
+    ///         size.floor()
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(graph.deme(0).start_sizes().collect::<Vec<_>>(), [100.0, 200.0]);
+    /// assert_eq!(rounded, [100.5, 100.5, 200.5, 200.5]);
+    /// ```
+    pub fn into_integer_start_end_sizes_with<F>(self, mut rounding: F) -> Result<Self, DemesError>
+    where
+        F: FnMut(f64) -> f64,
+    {
+        self.round_epoch_start_end_sizes_with(&mut rounding)
+    }
+
+    /// Obtain names of all demes in the graph.
+    ///
+    /// # Note
+    ///
+    /// These are ordered by a deme's index in the model.
+    ///
+    /// # Panics
+    ///
+    /// This function allocates space for the return value,
+    /// which may panic upon out-of-memory.
+    pub fn deme_names(&self) -> Box<[&str]> {
+        self.demes
+            .iter()
+            .map(|deme| deme.name())
+            .collect::<Vec<&str>>()
+            .into_boxed_slice()
+    }
+
+    /// Get a reference to the input string, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: years
+    /// generation_time: 25
+    /// description:
+    ///   A deme of 50 individuals that grew to 100 individuals
+    ///   in the last 100 years.
+    ///   Default behavior is that size changes are exponential.
+    /// demes:
+    ///  - name: deme
+    ///    epochs:
+    ///     - start_size: 50
+    ///       end_time: 100
+    ///     - start_size: 50
+    ///       end_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert_eq!(graph.input_string().unwrap().to_str(), yaml);
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The string is in the same format (YAML or JSON)
+    /// that was used to generate the graph.
+    pub fn input_string(&self) -> Option<InputFormat> {
+        match &self.input_string {
+            None => None,
+            Some(format) => match format {
+                InputFormatInternal::Yaml(string) => Some(InputFormat::Yaml(string.as_str())),
+                InputFormatInternal::Json(string) => Some(InputFormat::Json(string.as_str())),
+                InputFormatInternal::Toml(string) => Some(InputFormat::Toml(string.as_str())),
+            },
+        }
+    }
+
+    /// Rescale a model by a constant scaling factor.
+    ///
+    /// For a given scaling factor, `Q`:
+    /// 1. All input population sizes will be divided by `Q`.
+    /// 2. All times will be divided by `Q`.
+    /// 3. All rates (migration, etc.) will be multiplied by `Q.`
+    /// 4. Pulse proportions, selfing rates, and cloning rates all
+    ///    remaing unchanged.
+    ///
+    /// The result is a new [`Graph`] where the products of populations sizes
+    /// times rates and the timings of events divided by population sizes are the
+    /// same as in the input.
+    ///
+    /// # Parameters
+    ///
+    /// * `scaling_factor`: the value of `Q`. This must be > 0.0 and finite.
+    ///
+    /// # Returns
+    ///
+    /// * The rescaled [`Graph`]
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError`] if `scaling_factor` is invalid or if rescaling results
+    ///   in an invalid graph.  For example, rescaling with `scaling_factor << 1`
+    ///   could result in migration rates `> 1`, which is invalid.
+    pub fn rescale(self, scaling_factor: f64) -> Result<Self, DemesError> {
+        let g = UnresolvedGraph::from(self);
+        g.rescale(scaling_factor)?.try_into()
+    }
+
+    /// Rescale the sizes of individual demes.
+    ///
+    /// For each `(deme, Q)` pair in `scaling_factors`:
+    /// 1. All sizes of `deme` will be divided by `Q`.
+    /// 2. The rates of all migrations whose destination is `deme`
+    ///    will be multiplied by `Q`.
+    ///
+    /// Demes not present in `scaling_factors` are unchanged.
+    /// Unlike [`Graph::rescale`], times are not changed because
+    /// they are shared by all demes in the graph.
+    /// Pulse proportions, selfing rates, and cloning rates all
+    /// remain unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `scaling_factors`: pairs of deme names and scaling factors.
+    ///   Each scaling factor must be > 0.0 and finite.
+    ///
+    /// # Returns
+    ///
+    /// * The rescaled [`Graph`]
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError`] if a deme name is not in the graph, if a name is given more than
+    ///   once, if a scaling factor is invalid, or if rescaling results
+    ///   in an invalid graph.  For example, migration rates into a deme
+    ///   may sum to a value `> 1` after rescaling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 1000
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - demes: [A, B]
+    ///    rate: 1e-3
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let rescaled = graph.rescale_demes([("A", 10.0)]).unwrap();
+    /// assert_eq!(rescaled.deme("A").start_size(), 100.0);
+    /// assert_eq!(rescaled.deme("B").start_size(), 100.0);
+    /// let into_a = rescaled.migrations().iter().find(|m| m.dest() == "A").unwrap();
+    /// assert_eq!(into_a.rate(), 1e-2);
+    /// let into_b = rescaled.migrations().iter().find(|m| m.dest() == "B").unwrap();
+    /// assert_eq!(into_b.rate(), 1e-3);
+    /// ```
+    pub fn rescale_demes<I, S>(self, scaling_factors: I) -> Result<Self, DemesError>
+    where
+        I: IntoIterator<Item = (S, f64)>,
+        S: AsRef<str>,
+    {
+        let mut factors = HashMap::new();
+        for (name, scaling_factor) in scaling_factors {
+            let name = name.as_ref().to_string();
+            if factors.insert(name.clone(), scaling_factor).is_some() {
+                return Err(DemesError::ValueError(
+                    ErrorContext::new(
+                        format!("scaling factor given more than once for deme {name}"),
+                        ErrorKind::DuplicateDeme,
+                    )
+                    .with_deme(name.as_str()),
+                ));
+            }
+        }
+        let g = UnresolvedGraph::from(self);
+        g.rescale_demes(factors)?.try_into()
+    }
+
+    /// Shift all finite times in the graph by a constant offset.
+    ///
+    /// Infinite times (e.g., the start time of root demes) are unchanged.
+    /// A positive `offset` moves all events further into the past.
+    /// A negative `offset` moves all events towards the present.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::ValueError`] if `offset` is not finite or if
+    ///   any time would become negative.
+    /// * [`DemesError`] if the shifted graph fails to resolve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: ancestor
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///  - name: derived
+    ///    start_time: 50
+    ///    ancestors: [ancestor]
+    ///    epochs:
+    ///     - start_size: 10
+    ///       end_time: 10
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let shifted = graph.clone().shift_times(-10.0).unwrap();
+    /// assert_eq!(shifted.deme("ancestor").start_time(), f64::INFINITY);
+    /// assert_eq!(shifted.deme("ancestor").end_time(), 40.0);
+    /// assert_eq!(shifted.deme("derived").start_time(), 40.0);
+    /// assert_eq!(shifted.deme("derived").end_time(), 0.0);
+    /// assert!(graph.shift_times(-10.1).is_err());
+    /// ```
+    pub fn shift_times(self, offset: f64) -> Result<Self, DemesError> {
+        if !offset.is_finite() {
+            return Err(DemesError::ValueError(
+                invalid(format!("invalid offset: {offset}")).with_value(offset),
+            ));
+        }
+        if f64::from(self.most_recent_deme_end_time()) + offset < 0.0 {
+            return Err(DemesError::ValueError(
+                invalid(format!("offset {offset} results in negative times")).with_value(offset),
+            ));
+        }
+        let g = UnresolvedGraph::from(self);
+        g.shift_times(offset)?.try_into()
+    }
+
+    /// Remove recent history from a [Graph].
+    ///
+    /// For a given value of `when`, a new graph is created with all
+    /// history from `[0, when)` removed.
+    ///
+    /// # Examples
+    ///
+    /// Remove the first ten generations:
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - start_time: .inf
+    ///    name: deme
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert_eq!(graph.demes()[0].end_time(), 0.0);
+    /// let when = demes::Time::try_from(10.0).unwrap();
+    /// let sliced = graph.slice_until(when).unwrap();
+    /// assert_eq!(sliced.demes()[0].end_time(), 10.0);
+    /// ```
+    ///
+    /// For the next example, removing the first 20 generations
+    /// removes the ancestral deme entirely:
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - start_time: .inf
+    ///    name: ancestor
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 20
+    ///  - name: derived
+    ///    start_time: 20
+    ///    ancestors: [ancestor]
+    ///    proportions: [1.0]
+    ///    epochs:
+    ///     - start_size: 50
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert_eq!(graph.demes().len(), 2);
+    /// assert_eq!(graph.demes()[0].end_time(), 20.0);
+    /// assert_eq!(graph.demes()[1].end_time(), 0.0);
+    /// let when = demes::Time::try_from(20.0).unwrap();
+    /// let sliced = graph.slice_until(when).unwrap();
+    /// assert_eq!(sliced.demes().len(), 1);
+    /// assert_eq!(sliced.demes()[0].end_time(), 20.0);
+    /// assert_eq!(sliced.demes()[0].start_time(), f64::INFINITY);
+    /// assert_eq!(sliced.demes()[0].name(), "ancestor");
+    /// ```
+    ///
+    /// For the same input, removing the first 10 generations
+    /// simply truncates the duration of the derived deme:
+    ///
+    /// ```
+    /// # let yaml = "
+    /// # time_units: generations
+    /// # demes:
+    /// #  - start_time: .inf
+    /// #    name: ancestor
+    /// #    epochs:
+    /// #     - start_size: 100
+    /// #       end_time: 20
+    /// #  - name: derived
+    /// #    start_time: 20
+    /// #    ancestors: [ancestor]
+    /// #    proportions: [1.0]
+    /// #    epochs:
+    /// #     - start_size: 50
+    /// # ";
+    /// # let graph = demes::loads(yaml).unwrap();
+    /// # assert_eq!(graph.demes().len(), 2);
+    /// # assert_eq!(graph.demes()[0].end_time(), 20.0);
+    /// # assert_eq!(graph.demes()[1].end_time(), 0.0);
+    /// let when = demes::Time::try_from(10.0).unwrap();
+    /// let sliced = graph.slice_until(when).unwrap();
+    /// assert_eq!(sliced.demes().len(), 2);
+    /// assert_eq!(sliced.demes()[0].end_time(), 20.0);
+    /// assert_eq!(sliced.demes()[0].start_time(), f64::INFINITY);
+    /// assert_eq!(sliced.demes()[0].name(), "ancestor");
+    /// assert_eq!(sliced.demes()[1].end_time(), 10.0);
+    /// assert_eq!(sliced.demes()[1].start_time(), 20.0);
+    /// assert_eq!(sliced.demes()[1].name(), "derived");
+    /// ```
+    pub fn slice_until(self, when: Time) -> Result<Self, DemesError> {
+        crate::graph_operations::slice::slice_until(self, when)
+    }
+
+    /// Remove ancient history from a [Graph].
+    ///
+    /// For a given value of `when`, a new graph is created with only
+    /// history from `[0, when)` retained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - start_time: .inf
+    ///    name: ancestor
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 20
+    ///  - name: derived
+    ///    start_time: 20
+    ///    ancestors: [ancestor]
+    ///    proportions: [1.0]
+    ///    epochs:
+    ///     - start_size: 50
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// assert_eq!(graph.demes().len(), 2);
+    /// assert_eq!(graph.demes()[0].end_time(), 20.0);
+    /// assert_eq!(graph.demes()[1].end_time(), 0.0);
+    /// let when = demes::Time::try_from(20.0).unwrap();
+    /// let sliced = graph.slice_after(when).unwrap();
+    /// assert_eq!(sliced.demes().len(), 1);
+    /// assert_eq!(sliced.demes()[0].end_time(), 0.0);
+    /// assert_eq!(sliced.demes()[0].start_time(), f64::INFINITY);
+    /// assert_eq!(sliced.demes()[0].name(), "derived");
+    /// ```
+    ///
+    /// If `when` is within an epoch, we insert an extra epoch
+    /// that has constant size until infinity in the past.
+    /// The size is the epoch's size at `when`.
+    /// Let's look at an example involving population growth.
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: growing
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 100
+    ///       end_size: 200
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let when = demes::Time::try_from(50.).unwrap();
+    /// let sliced = graph.clone().slice_after(when).unwrap();
+    /// let deme = &sliced.deme(0);
+    /// assert_eq!(deme.num_epochs(), 2);
+    /// let e = deme.epochs()[0];
+    /// assert_eq!(e.start_time(), f64::INFINITY);
+    /// assert_eq!(e.end_time(), when);
+    /// assert_eq!(e.start_size(), graph.deme(0).size_at(when).unwrap().unwrap());
+    /// assert_eq!(e.start_size(), e.end_size());
+    /// let e = deme.epochs()[1];
+    /// assert_eq!(e.start_time(), when);
+    /// assert_eq!(e.end_time(), 0.0);
+    /// assert_eq!(e.start_size(), graph.deme(0).size_at(when).unwrap().unwrap());
+    /// assert_eq!(e.end_size(), graph.deme(0).end_size());
+    /// ```
+    pub fn slice_after(self, when: Time) -> Result<Self, DemesError> {
+        crate::graph_operations::slice::slice_after(self, when)
+    }
+
+    /// Retain only the history from `[start, end)`.
+    ///
+    /// This is equivalent to removing recent history with
+    /// [`Graph::slice_until`] and ancient history with
+    /// [`Graph::slice_after`], but handles both edges in a single pass.
+    ///
+    /// * Epochs spanning `start` are truncated, with the end size being
+    ///   the epoch's size at `start`.
+    /// * Epochs spanning `end` become the first epoch of a root deme.
+    ///   If the size is not constant, a constant-size epoch extending
+    ///   to infinity in the past is inserted.
+    ///   The size is the epoch's size at `end`.
+    /// * Migrations are truncated to the interval.
+    /// * Pulses occurring at `start` are removed because the retained
+    ///   demes end at that time.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError::ValueError`] if `start >= end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: ancestor
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///  - name: derived
+    ///    ancestors: [ancestor]
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_size: 200
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let start = demes::Time::try_from(25.0).unwrap();
+    /// let end = demes::Time::try_from(75.0).unwrap();
+    /// let sliced = graph.clone().slice_between(start, end).unwrap();
+    /// assert_eq!(sliced.num_demes(), 1);
+    /// let deme = sliced.deme(0);
+    /// assert_eq!(deme.name(), "derived");
+    /// assert_eq!(deme.start_time(), f64::INFINITY);
+    /// assert_eq!(deme.end_time(), start);
+    /// assert_eq!(deme.num_epochs(), 2);
+    /// assert_eq!(deme.epochs()[0].end_time(), end);
+    /// assert_eq!(deme.start_size(), graph.deme(1).size_at(end).unwrap().unwrap());
+    /// assert_eq!(deme.end_size(), graph.deme(1).size_at(start).unwrap().unwrap());
+    /// ```
+    pub fn slice_between(self, start: Time, end: Time) -> Result<Self, DemesError> {
+        crate::graph_operations::slice::slice_between(self, start, end)
+    }
+
+    fn slice_deme<'name, I: Into<DemeId<'name>>>(
+        mut self,
+        deme: I,
+        start: Time,
+        end: Time,
+    ) -> Result<Self, DemesError> {
+        let deme = self.get_deme(deme).ok_or_else(|| {
+            DemesError::DemeError(ErrorContext::new(
+                "deme does not exist",
+                ErrorKind::UnknownDeme,
+            ))
+        })?;
+        if deme.start_time() <= start || deme.end_time() >= end {
+            return Err(DemesError::DemeError(
+                format!(
+                    "slicing deme {} to [{start:?}, {end:?}) removes it entirely",
+                    deme.name()
+                )
+                .into(),
+            ));
+        }
+        let name = deme.name().to_string();
+        let becomes_root = deme.start_time() > end;
+        let epochs = crate::graph_operations::slice::clip_deme_epochs(deme, start, end);
+        self.resolved_migrations.retain_mut(|m| {
+            if *m.source != *name && *m.dest != *name {
+                return true;
+            }
+            if m.start_time <= start || m.end_time >= end {
+                return false;
+            }
+            m.start_time = std::cmp::min(m.start_time, end);
+            m.end_time = std::cmp::max(m.end_time, start);
+            true
+        });
+        self.pulses.retain(|p| {
+            !(*p.dest == *name || p.sources.iter().any(|s| **s == *name))
+                || (p.time > start && p.time < end)
+        });
+        let mut g = UnresolvedGraph::from(self);
+        let deme = g.demes.iter_mut().find(|d| d.name == name).unwrap();
+        deme.epochs = epochs;
+        if becomes_root {
+            deme.ancestors = Some(vec![]);
+            deme.proportions = Some(vec![]);
+            deme.start_time = Some(f64::INFINITY.into());
+        }
+        g.resolve()
+    }
+
+    /// Remove recent history from a single deme.
+    ///
+    /// The deme's history from `[0, when)` is removed.
+    /// Migrations and pulses involving the deme during that
+    /// interval are removed or truncated.
+    /// The rest of the graph is unchanged.
+    ///
+    /// See [`Graph::slice_until`] for details.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::DemeError`] if the deme does not exist or
+    ///   if the deme does not exist prior to `when`.
+    /// * [`DemesError`] if the resulting graph is not valid.
+    ///   For example, another deme may be descended from this
+    ///   deme at a time more recent than `when`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// migrations:
+    ///  - demes: [A, B]
+    ///    rate: 1e-3
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let when = demes::Time::try_from(10.0).unwrap();
+    /// let sliced = graph.slice_deme_until("A", when).unwrap();
+    /// assert_eq!(sliced.deme("A").end_time(), 10.0);
+    /// assert_eq!(sliced.deme("B").end_time(), 0.0);
+    /// for m in sliced.migrations() {
+    ///     assert_eq!(m.end_time(), 10.0);
+    /// }
+    /// ```
+    pub fn slice_deme_until<'name, I: Into<DemeId<'name>>>(
+        self,
+        deme: I,
+        when: Time,
+    ) -> Result<Self, DemesError> {
+        let end = Time::try_from(f64::INFINITY).unwrap();
+        self.slice_deme(deme, when, end)
+    }
+
+    /// Remove ancient history from a single deme.
+    ///
+    /// Only the deme's history from `[0, when)` is retained.
+    /// If the deme exists prior to `when`, it no longer has ancestors
+    /// and its first epoch extends to infinity in the past.
+    /// Migrations and pulses involving the deme prior to `when` are
+    /// removed or truncated.
+    /// The rest of the graph is unchanged.
+    ///
+    /// See [`Graph::slice_after`] for details.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::DemeError`] if the deme does not exist or
+    ///   if the deme does not exist after `when`.
+    /// * [`DemesError`] if the resulting graph is not valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    ancestors: [A]
+    ///    start_time: 100
+    ///    epochs:
+    ///     - start_size: 50
+    ///       end_size: 500
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let when = demes::Time::try_from(50.0).unwrap();
+    /// let sliced = graph.clone().slice_deme_after("B", when).unwrap();
+    /// let b = sliced.deme("B");
+    /// assert!(b.ancestor_names().is_empty());
+    /// assert_eq!(b.start_time(), f64::INFINITY);
+    /// assert_eq!(b.num_epochs(), 2);
+    /// assert_eq!(b.start_size(), graph.deme("B").size_at(when).unwrap().unwrap());
+    /// assert_eq!(sliced.deme("A"), graph.deme("A"));
+    /// ```
+    pub fn slice_deme_after<'name, I: Into<DemeId<'name>>>(
+        self,
+        deme: I,
+        when: Time,
+    ) -> Result<Self, DemesError> {
+        let start = Time::try_from(0.0).unwrap();
+        self.slice_deme(deme, start, when)
+    }
+
+    /// Retain only the history from `[start, end)` for a single deme.
+    ///
+    /// See [`Graph::slice_between`], [`Graph::slice_deme_until`],
+    /// and [`Graph::slice_deme_after`] for details.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::ValueError`] if `start >= end`.
+    /// * [`DemesError::DemeError`] if the deme does not exist or
+    ///   if the deme does not exist during `[start, end)`.
+    /// * [`DemesError`] if the resulting graph is not valid.
+    pub fn slice_deme_between<'name, I: Into<DemeId<'name>>>(
+        self,
+        deme: I,
+        start: Time,
+        end: Time,
+    ) -> Result<Self, DemesError> {
+        if start >= end {
+            return Err(DemesError::ValueError(inconsistent(format!(
+                "slice start time ({start:?}) must be less than slice end time ({end:?})"
+            ))));
+        }
+        self.slice_deme(deme, start, end)
+    }
+
+    /// Obtain a deme index from a deme name
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - the deme name
+    ///
+    /// # Returns
+    ///
+    /// * `Some(index)` if `name` is a valid deme name
+    /// * `None` Otherwise
+    ///
+    /// # Complexity
+    ///
+    /// * O(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    ///  time_units: generations
+    ///  demes:
+    ///   - name: ancestor1
+    ///     epochs:
+    ///      - start_size: 50
+    ///        end_time: 20
+    ///   - name: ancestor2
+    ///     epochs:
+    ///      - start_size: 50
+    ///        end_time: 20
+    ///   - name: derived
+    ///     ancestors: [ancestor1, ancestor2]
+    ///     proportions: [0.5, 0.5]
+    ///     start_time: 20
+    ///     epochs:
+    ///      - start_size: 50
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// for (i, d) in graph.demes().iter().enumerate() {
+    ///     assert_eq!(graph.deme_index(d.name()), Some(i))
+    /// }
+    /// ```
+    pub fn deme_index<S: AsRef<str>>(&self, name: S) -> Option<usize> {
+        self.deme_map.get(name.as_ref()).cloned()
+    }
+
+    /// Obtain the ancestry proportions for a deme at a given time.
+    ///
+    /// # Parameters
+    ///
+    /// * `deme` - the "focal" deme whose ancestry proportions will be calculated.
+    /// * `at` - the [Time] at which to calculate ancestry proportions.
+    ///
+    /// # Returns
+    ///
+    /// * A boxed slice if `deme` exists in the graph and time `at` is a sensible
+    ///   parental time point
+    /// * None, otherwise
+    ///
+    /// # Details
+    ///
+    /// * `at` is treated as a time immediately before individuals are born
+    ///   into `deme`.
+    /// * This function allocates memory.
+    ///   See [Graph::fill_ancestry_proportions] for a function to reuse
+    ///   existing allocations.
+    ///
+    /// # Complexity
+    ///
+    /// * See [Graph::fill_ancestry_proportions].
+    ///
+    /// # Panics
+    ///
+    /// * See [Graph::fill_ancestry_proportions].
+    pub fn ancestry_proportions<'d, I: Into<DemeId<'d>>>(
+        &self,
+        deme: I,
+        at: Time,
+    ) -> Option<Box<[f64]>> {
+        let mut rv = vec![0.0; self.num_demes()];
+        self.fill_ancestry_proportions(deme, at, &mut rv)
+            .map(|_| rv.into_boxed_slice())
+    }
+
+    /// Obtain the ancestry proportions for a deme at a given time.
+    ///
+    /// # Parameters
+    ///
+    /// * `deme` - the "focal" deme whose ancestry proportions will be calculated.
+    /// * `at` - the [Time] at which to calculate ancestry proportions.
+    /// * `buffer` -  output location for the ancestry proportions.
+    ///    The buffer length must be at least the number of demes in
+    ///    the graph. (See [Graph::num_demes].)
+    ///
+    /// # Returns
+    ///
+    /// * A unit type if `deme` exists in the graph and time `at` is a sensible
+    ///   parental time point
+    /// * None, otherwise
+    /// # Details
+    ///
+    /// `at` is treated as a time immediately before individuals are born
+    /// into `deme`.
+    ///
+    /// # Complexity
+    ///
+    /// * Linear in the number of ancestor demes, pulses, and migration events
+    ///   affecting ancestry at time `at`.
+    ///
+    /// # Panics
+    ///
+    /// * if any calculations result in invalid values (x < 0, x > 1, x not finite).
+    /// * if `buffer` does not contain sufficient space for the output
+    pub fn fill_ancestry_proportions<'d, I: Into<DemeId<'d>>>(
+        &self,
+        deme: I,
+        at: Time,
+        buffer: &mut [f64],
+    ) -> Option<()> {
+        let deme = self.get_deme(deme)?;
+        if at <= deme.start_time() && at > deme.end_time() {
+            self.fill_ancestry_proportions_details(deme, at, buffer);
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn fill_ancestry_proportions_details(&self, deme: &Deme, at: Time, buffer: &mut [f64]) {
+        let deme_index = self.deme_map[deme.name()];
+        if at <= deme.start_time() && at > deme.end_time() {
+            buffer.fill_with(|| 0.0);
+            if at == deme.start_time() {
+                for (a, p) in deme
+                    .ancestor_indexes()
+                    .iter()
+                    .cloned()
+                    .zip(deme.proportions().iter().cloned())
+                {
+                    buffer[a.0] += f64::from(p);
+                }
+            } else {
+                buffer[deme_index] = 1.0;
+            }
+            for pulse in self
+                .pulses()
+                .iter()
+                .filter(|&p| p.time() == at && p.dest() == deme.name())
+            {
+                let sum_pulses = pulse
+                    .proportions()
+                    .iter()
+                    .fold(0.0, |sum, &p| sum + f64::from(p));
+                buffer.iter_mut().for_each(|v| *v *= 1. - sum_pulses);
+                for (source, proportion) in pulse
+                    .sources()
+                    .iter()
+                    .zip(pulse.proportions().iter().cloned().map(f64::from))
+                {
+                    let source_index = self.deme_map[source];
+                    buffer[source_index] += proportion;
+                }
+            }
+            let input_migrations = self
+                .migrations()
+                .iter()
+                .filter(|m| at <= m.start_time() && at > m.end_time() && m.dest() == deme.name())
+                .collect::<Vec<_>>();
+            let sum_migrates = input_migrations
+                .iter()
+                .fold(0.0, |sum, m| sum + f64::from(m.rate()));
+            buffer.iter_mut().for_each(|v| *v *= 1. - sum_migrates);
+            for i in input_migrations {
+                let source = self.deme_map[i.source()];
+                buffer[source] += f64::from(i.rate())
+            }
+            assert!(
+                buffer
+                    .iter()
+                    .all(|p| p.is_finite() && (0.0..=1.0).contains(p)),
+                "invalid value in {buffer:?}"
+            );
+        }
+    }
+
+    #[allow(missing_docs)]
+    pub fn ancestry_proportions_matrix(&self, at: Time) -> Result<Box<[f64]>, DemesError> {
+        let mut buffer = vec![0.; self.num_demes() * self.num_demes()];
+        self.fill_ancestry_proportions_matrix(at, &mut buffer)
+            .map(|_| buffer.into_boxed_slice())
+    }
+
+    #[allow(missing_docs)]
+    pub fn fill_ancestry_proportions_matrix(
+        &self,
+        at: Time,
+        buffer: &mut [f64],
+    ) -> Result<(), DemesError> {
+        if at == 0.0 {
+            return Err(DemesError::ValueError(
+                invalid(format!("time must be > 0.0, got {at:?}")).with_value(f64::from(at)),
+            ));
+        }
+        buffer.fill_with(|| 0.);
+        for (deme_index, deme) in self.demes().iter().enumerate() {
+            if at == deme.start_time() {
+                for (a, p) in deme
+                    .ancestor_indexes()
+                    .iter()
+                    .cloned()
+                    .zip(deme.proportions().iter().cloned())
+                {
+                    buffer[deme_index * self.num_demes() + a.0] += f64::from(p);
+                }
+            } else if at > deme.end_time() && at <= deme.start_time() {
+                buffer[deme_index * self.num_demes() + deme_index] = 1.0;
+            }
+        }
+        let mut temp = vec![0.0; self.num_demes() * self.num_demes()];
+        let pulses = self
+            .pulses()
+            .iter()
+            .filter(|&p| p.time() == at)
+            .collect::<Vec<_>>();
+        if !pulses.is_empty() {
+            for (deme_index, deme) in self.demes().iter().enumerate() {
+                for &pulse in pulses.iter().filter(|p| p.dest() == deme.name()) {
+                    for (a, p) in pulse
+                        .sources()
+                        .iter()
+                        .zip(pulse.proportions().iter().cloned())
+                    {
+                        let source_index = self.deme_index(a).unwrap();
+                        temp[deme_index * self.num_demes() + source_index] = p.into();
+                    }
+                }
+            }
+            for (i, t) in temp.chunks_exact(self.num_demes()).enumerate() {
+                let sum = t.iter().sum::<f64>();
+                for (j, tt) in buffer
+                    .iter_mut()
+                    .skip(i * self.num_demes())
+                    .take(self.num_demes())
+                    .zip(t.iter().cloned())
+                {
+                    *j *= 1. - sum;
+                    *j += tt;
+                }
+            }
+        }
+        let input_migrations = self
+            .migrations()
+            .iter()
+            .filter(|m| at <= m.start_time() && at > m.end_time())
+            .collect::<Vec<_>>();
+        if !input_migrations.is_empty() {
+            temp.fill_with(|| 0.);
+            for (deme_index, deme) in self.demes().iter().enumerate() {
+                for &m in input_migrations.iter().filter(|p| p.dest() == deme.name()) {
+                    let source_index = self.deme_index(m.source()).unwrap();
+                    temp[deme_index * self.num_demes() + source_index] = m.rate().into();
+                }
+            }
+            for (i, t) in temp.chunks_exact(self.num_demes()).enumerate() {
+                let sum = t.iter().sum::<f64>();
+                for (j, tt) in buffer
+                    .iter_mut()
+                    .skip(i * self.num_demes())
+                    .take(self.num_demes())
+                    .zip(t.iter().cloned())
+                {
+                    *j *= 1. - sum;
+                    *j += tt;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare two graphs using tolerances for floating-point values.
+    ///
+    /// Unlike [`PartialEq`], times, sizes, and rates are compared
+    /// using the tolerances in `options`.
+    /// All other data (names, time units, description, etc.) must
+    /// be exactly equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let rescaled = graph.clone().rescale(3.0).unwrap().rescale(1. / 3.).unwrap();
+    /// assert!(graph.approx_eq(&rescaled, demes::ToleranceOptions::default()));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, options: crate::ToleranceOptions) -> bool {
+        crate::graph_operations::approx_eq::approx_eq(self, other, options)
+    }
+
+    /// Return a canonical representation of the graph.
+    ///
+    /// Two graphs describing the same model but differing in the
+    /// order in which demes and migrations were specified will
+    /// have identical canonical forms.
+    ///
+    /// In canonical form:
+    ///
+    /// * Demes are ordered so that ancestors come before their descendants.
+    ///   Remaining ties are broken by deme name.
+    /// * Migrations are sorted by source name, dest name, and then
+    ///   by start time from most to least ancient.
+    /// * Pulses are sorted from most to least ancient.
+    ///   The relative order of pulses occurring at the same time is retained
+    ///   because it affects the model.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError`] if re-resolving the graph fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let a = demes::loads("
+    /// time_units: generations
+    /// demes:
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ").unwrap();
+    /// let b = demes::loads("
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    epochs:
+    ///     - start_size: 100
+    /// ").unwrap();
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.clone().canonicalize().unwrap(), b.clone().canonicalize().unwrap());
+    /// assert_eq!(a.content_hash().unwrap(), b.content_hash().unwrap());
+    /// ```
+    pub fn canonicalize(self) -> Result<Self, DemesError> {
+        let order = crate::graph_operations::canonical::canonical_deme_order(&self);
+        let mut graph = self;
+        let mut demes = std::mem::take(&mut graph.demes)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        graph.demes = order
+            .into_iter()
+            .map(|i| demes[i].take().unwrap())
+            .collect::<Vec<_>>();
+        graph.resolved_migrations.sort_by(|a, b| {
+            a.source
+                .cmp(&b.source)
+                .then_with(|| a.dest.cmp(&b.dest))
+                .then_with(|| b.start_time.cmp(&a.start_time))
+                .then_with(|| b.end_time.cmp(&a.end_time))
+        });
+        UnresolvedGraph::from(graph).resolve()
+    }
+
+    /// A hash of the canonical YAML representation of the graph.
+    ///
+    /// The hash is calculated from the output of [`Graph::as_string`]
+    /// applied to the output of [`Graph::canonicalize`].
+    /// The hash function is 64-bit FNV-1a, so the value is stable
+    /// across platforms and across versions of rust.
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError`] if canonicalization or serialization fails.
+    ///
+    /// # Note
+    ///
+    /// The hash is *not* cryptographically secure.
+    pub fn content_hash(&self) -> Result<u64, DemesError> {
+        let canonical = self.clone().canonicalize()?;
+        let yaml = canonical.as_string()?;
+        Ok(crate::graph_operations::canonical::fnv1a(yaml.as_bytes()))
+    }
+
+    /// Convert the graph into a [`GraphBuilder`](crate::GraphBuilder).
+    ///
+    /// The builder contains the fully-resolved data of the graph,
+    /// allowing programmatic editing (adding demes, migrations, etc.)
+    /// followed by re-resolution via [`GraphBuilder::resolve`](crate::GraphBuilder::resolve).
+    ///
+    /// The input string, if any, is not retained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let mut builder = graph.into_builder();
+    /// let epoch = demes::UnresolvedEpoch{start_size: Some(50.0.into()), ..Default::default()};
+    /// builder.add_deme("B", [epoch], demes::UnresolvedDemeHistory::default(), None);
+    /// let graph = builder.resolve().unwrap();
+    /// assert_eq!(graph.num_demes(), 2);
+    /// ```
+    pub fn into_builder(self) -> crate::GraphBuilder {
+        let mut graph = UnresolvedGraph::from(self);
+        graph.input_string = None;
+        crate::GraphBuilder::new_from_unresolved(graph)
+    }
+
+    /// Replace non-constant size functions with piecewise-constant sizes.
+    ///
+    /// Each epoch with an exponential or linear size function is
+    /// replaced by a sequence of constant-size epochs of duration `step`,
+    /// starting from the epoch's start time.
+    /// The most recent of these epochs is shortened if needed so that it
+    /// ends at the original epoch's end time.
+    /// The size of each new epoch is the size of the original epoch at the
+    /// midpoint of the new epoch's time interval.
+    ///
+    /// Constant-size epochs are unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `step`: the duration of the new epochs, in the time units of the graph.
+    ///   Must be > 0.0 and finite.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::ValueError`] if `step` is invalid.
+    /// * [`DemesError`] if the new graph fails to resolve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: A
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 100
+    ///     - start_size: 100
+    ///       end_size: 200
+    ///       size_function: linear
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let discretized = graph.discretize_sizes(25.0).unwrap();
+    /// let deme = discretized.deme("A");
+    /// assert_eq!(deme.num_epochs(), 5);
+    /// assert_eq!(deme.end_times().collect::<Vec<_>>(), [100., 75., 50., 25., 0.]);
+    /// assert!(deme
+    ///     .epochs()
+    ///     .iter()
+    ///     .all(|e| matches!(e.size_function(), demes::SizeFunction::Constant)));
+    /// assert_eq!(deme.epochs()[1].start_size(), 112.5);
+    /// assert_eq!(deme.epochs()[4].start_size(), 187.5);
+    /// ```
+    pub fn discretize_sizes(self, step: f64) -> Result<Self, DemesError> {
+        if !step.is_finite() || step <= 0.0 {
+            return Err(DemesError::ValueError(
+                invalid(format!("invalid step: {step}")).with_value(step),
+            ));
+        }
+        let epochs = self
+            .demes
+            .iter()
+            .map(|deme| {
+                deme.epochs
+                    .iter()
+                    .flat_map(|e| crate::graph_operations::discretize::discretize_epoch(e, step))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut g = UnresolvedGraph::from(self);
+        for (deme, epochs) in g.demes.iter_mut().zip(epochs) {
+            deme.epochs = epochs;
+        }
+        g.resolve()
+    }
+
+    /// Collapse a set of demes into a single deme.
+    ///
+    /// This is a common model-reduction step, for example
+    /// when comparing a model of structured populations to
+    /// the results of single-population inference.
+    ///
+    /// # Parameters
+    ///
+    /// * `demes`: the names of the demes to aggregate.
+    ///   All demes must have identical start and end times.
+    /// * `name`: the name of the new deme. This may be the name of
+    ///   one of the aggregated demes.
+    /// * `policy`: how to calculate the size of the new deme from
+    ///   the sizes of the aggregated demes.
+    ///
+    /// # Details
+    ///
+    /// * The new deme replaces the first of the aggregated demes in the list of demes.
+    ///   Other demes may be reordered so that ancestors precede descendants.
+    /// * The epochs of the new deme are the union of the epoch boundaries of the
+    ///   aggregated demes.
+    ///   Start and end sizes are calculated using `policy`.
+    ///   If the aggregated demes have different non-constant size functions during
+    ///   an epoch, a linear size function is used.
+    /// * Selfing and cloning rates are averaged, weighted by deme sizes.
+    /// * Migrations and pulses among the aggregated demes are removed.
+    /// * Migration rates from the new deme are the sum of the rates from the
+    ///   aggregated demes.
+    ///   Migration rates into the new deme, pulse proportions into the new deme,
+    ///   and ancestry proportions of the new deme
+    ///   are weighted by the relative sizes of the aggregated demes.
+    ///   Migration rates are evaluated at the midpoint of each
+    ///   interval between changes in rates or epochs.
+    /// * Descendants of the aggregated demes now descend from the new deme.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError`] if `demes` is empty, contains duplicate or invalid names,
+    ///   or if the demes do not have identical start and end times.
+    /// * [`DemesError`] if `name` is an existing deme not being aggregated.
+    /// * [`DemesError`] if the new graph fails to resolve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: ancestor
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///  - name: A
+    ///    ancestors: [ancestor]
+    ///    epochs:
+    ///     - start_size: 100
+    ///  - name: B
+    ///    ancestors: [ancestor]
+    ///    epochs:
+    ///     - start_size: 300
+    /// migrations:
+    ///  - demes: [A, B]
+    ///    rate: 1e-2
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let aggregated = graph
+    ///     .aggregate_demes(&["A", "B"], "AB", demes::AggregationPolicy::Sum)
+    ///     .unwrap();
+    /// assert_eq!(aggregated.num_demes(), 2);
+    /// assert_eq!(aggregated.deme("AB").start_size(), 400.0);
+    /// assert!(aggregated.migrations().is_empty());
+    /// ```
+    pub fn aggregate_demes<S: AsRef<str>>(
+        self,
+        demes: &[S],
+        name: &str,
+        policy: crate::AggregationPolicy,
+    ) -> Result<Self, DemesError> {
+        crate::graph_operations::aggregate::aggregate_demes(self, demes, name, policy)
+    }
+
+    /// Add a new deme that diverges from an existing deme.
+    ///
+    /// This is a convenience function for adding "ghost" or outgroup
+    /// demes to existing models.
+    /// The new deme has a single epoch of constant size that
+    /// ends at time zero.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: the name of the new deme.
+    /// * `ancestor`: the deme from which the new deme diverges.
+    /// * `start_time`: the divergence time.
+    /// * `size`: the size of the new deme.
+    ///
+    /// # Errors
+    ///
+    /// * [`DemesError::DemeError`] if `ancestor` does not exist.
+    /// * [`DemesError`] if the new graph fails to resolve.
+    ///   For example, `name` may already exist, `size` may be invalid,
+    ///   or `ancestor` may not exist at `start_time`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let yaml = "
+    /// time_units: generations
+    /// demes:
+    ///  - name: ancestor
+    ///    epochs:
+    ///     - start_size: 100
+    ///       end_time: 50
+    ///  - name: derived
+    ///    ancestors: [ancestor]
+    ///    epochs:
+    ///     - start_size: 100
+    /// ";
+    /// let graph = demes::loads(yaml).unwrap();
+    /// let graph = graph.insert_outgroup("ghost", "ancestor", 200.0, 1000.0).unwrap();
+    /// assert_eq!(graph.num_demes(), 3);
+    /// let ghost = graph.deme("ghost");
+    /// assert_eq!(&*ghost.ancestor_names()[0], "ancestor");
+    /// assert_eq!(ghost.start_time(), 200.0);
+    /// assert_eq!(ghost.end_time(), 0.0);
+    /// assert_eq!(ghost.start_size(), 1000.0);
+    /// ```
+    pub fn insert_outgroup<'name, I, T, S>(
+        self,
+        name: &str,
+        ancestor: I,
+        start_time: T,
+        size: S,
+    ) -> Result<Self, DemesError>
+    where
+        I: Into<DemeId<'name>>,
+        T: Into<InputTime>,
+        S: Into<InputDemeSize>,
+    {
+        let ancestor = self
+            .get_deme(ancestor)
+            .ok_or_else(|| {
+                DemesError::DemeError(ErrorContext::new(
+                    "ancestor deme does not exist",
+                    ErrorKind::UnknownDeme,
+                ))
+            })?
+            .name()
+            .to_string();
+        let mut builder = self.into_builder();
+        let size = size.into();
+        builder.add_deme(
+            name,
+            [UnresolvedEpoch {
+                start_size: Some(size),
+                end_size: Some(size),
+                end_time: Some(0.0.into()),
+                ..Default::default()
+            }],
+            UnresolvedDemeHistory {
+                ancestors: Some(vec![ancestor]),
+                proportions: Some(vec![1.0.into()]),
+                start_time: Some(start_time.into()),
+                ..Default::default()
+            },
+            None,
+        );
+        builder.resolve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_function() {
+        let yaml = "---\nexponential\n".to_string();
+        let sf: SizeFunction = serde_yaml::from_str(&yaml).unwrap();
+        assert!(matches!(sf, SizeFunction::Exponential));
+
+        let yaml = "---\nconstant\n".to_string();
+        let sf: SizeFunction = serde_yaml::from_str(&yaml).unwrap();
+        assert!(matches!(sf, SizeFunction::Constant));
+    }
+
+    #[test]
+    fn test_display() {
+        let t = Time::try_from(1.0).unwrap();
+        let f = format!("{t}");
+        assert_eq!(f, String::from("1"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_time_validity() {
+        let _ = Time::try_from(f64::NAN).unwrap();
+    }
+
+    #[test]
+    fn test_newtype_compare_to_f64() {
+        {
+            let v = Time::try_from(100.0).unwrap();
+            assert_eq!(v, 100.0);
+            assert_eq!(100.0, v);
+            assert!(v > 50.0);
+            assert!(50.0 < v);
+        }
+
+        {
+            let v = DemeSize::try_from(100.0).unwrap();
+            assert_eq!(v, 100.0);
+            assert_eq!(100.0, v);
+        }
+
+        {
+            let v = SelfingRate::try_from(1.0).unwrap();
+            assert_eq!(v, 1.0);
+            assert_eq!(1.0, v);
+        }
+
+        {
+            let v = CloningRate::try_from(1.0).unwrap();
+            assert_eq!(v, 1.0);
+            assert_eq!(1.0, v);
+        }
+
+        {
+            let v = Proportion::try_from(1.0).unwrap();
+            assert_eq!(v, 1.0);
+            assert_eq!(1.0, v);
+        }
+
+        {
+            let v = MigrationRate::try_from(1.0).unwrap();
+            assert_eq!(v, 1.0);
+            assert_eq!(1.0, v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_graph {
+    use super::*;
+
+    #[test]
+    fn test_deme_size_integral() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 100
+      end_size: 200
+      end_time: 500
+    - end_size: 100
+      size_function: linear
+      end_time: 100
+ - name: B
+   ancestors: [A]
+   start_time: 700
+   epochs:
+    - start_size: 50
+";
+        let graph = crate::loads(yaml).unwrap();
+        let deme = graph.deme(0);
+        let epochs = deme.epochs();
+        assert_eq!(deme.size_integral(1000.0, 1000.0).unwrap(), 0.0);
+        // Whole epochs
+        assert_eq!(
+            deme.size_integral(1000.0, 100.0).unwrap(),
+            epochs[1].size_integral() + epochs[2].size_integral()
+        );
+        assert_eq!(deme.size_integral(1500.0, 1000.0).unwrap(), 500.0 * 100.0);
+        // Partial epochs
+        let integral = deme.size_integral(750.0, 300.0).unwrap();
+        let steps = 100000;
+        let dt = 450.0 / steps as f64;
+        let numerical = (0..steps)
+            .map(|i| {
+                f64::from(
+                    deme.size_at(750.0 - (i as f64 + 0.5) * dt)
+                        .unwrap()
+                        .unwrap(),
+                ) * dt
+            })
+            .sum::<f64>();
+        assert!((integral - numerical).abs() / numerical < 1e-8);
+
+        assert!(deme.size_integral(f64::INFINITY, 0.0).is_err());
+        assert!(deme
+            .size_integral(f64::INFINITY, 100.0)
+            .unwrap()
+            .is_infinite());
+        assert!(deme.size_integral(100.0, 200.0).is_err());
+        assert!(deme.size_integral(-1.0, 200.0).is_err());
+        assert!(deme.size_integral(f64::NAN, 200.0).is_err());
+        let deme = graph.deme(1);
+        assert_eq!(deme.size_integral(700.0, 0.0).unwrap(), 700.0 * 50.0);
+        assert!(deme.size_integral(701.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_epoch_size_integral() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 100
+      end_size: 200
+      end_time: 500
+    - start_size: 300
+      end_time: 100
+    - end_size: 100
+      size_function: exponential
+";
+        let graph = crate::loads(yaml).unwrap();
+        let epochs = graph.deme(0).epochs();
+        assert!(epochs[0].size_integral().is_infinite());
+        assert_eq!(epochs[0].average_size(), 100.0);
+
+        // Compare to numerical integration
+        for epoch in &epochs[1..] {
+            let start = f64::from(epoch.start_time());
+            let end = f64::from(epoch.end_time());
+            let steps = 100000;
+            let dt = (start - end) / steps as f64;
+            let numerical = (0..steps)
+                .map(|i| {
+                    f64::from(
+                        epoch
+                            .size_at(start - (i as f64 + 0.5) * dt)
+                            .unwrap()
+                            .unwrap(),
+                    ) * dt
+                })
+                .sum::<f64>();
+            assert!((epoch.size_integral() - numerical).abs() / numerical < 1e-8);
+            assert!((epoch.average_size() * (start - end) - numerical).abs() / numerical < 1e-8);
+        }
+        assert_eq!(epochs[2].size_integral(), 300.0 * 400.0);
+        assert_eq!(epochs[2].average_size(), 300.0);
+    }
+
+    #[test]
+    fn test_epoch_growth_rate() {
+        let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 100
+      end_size: 200
+      end_time: 500
+    - end_size: 100
+      size_function: linear
+";
+        let graph = crate::loads(yaml).unwrap();
+        let epochs = graph.deme(0).epochs();
+        assert!(epochs[0].growth_rate().is_none());
+        let r = epochs[1].growth_rate().unwrap();
+        assert!((r - 2.0_f64.ln() / 500.0).abs() < 1e-15);
+        assert_eq!(epochs[1].size_at(500.0).unwrap().unwrap(), 200.0);
+        assert_eq!(epochs[2].growth_rate(), Some(-0.2));
+
+        let graph = graph.into_generations().unwrap();
+        let epochs = graph.deme(0).epochs();
+        let r = epochs[1].growth_rate().unwrap();
+        assert!((r - 2.0_f64.ln() / 20.0).abs() < 1e-15);
+        assert_eq!(epochs[2].growth_rate(), Some(-5.0));
+    }
+
+    #[test]
+    fn test_error_context() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: A
+   epochs:
+    - start_size: 100
+";
+        let error = crate::loads(yaml).unwrap_err();
+        let context = error.context().unwrap();
+        assert_eq!(context.kind(), ErrorKind::DuplicateDeme);
+        assert_eq!(context.deme(), Some("A"));
+        assert_eq!(context.epoch(), None);
+        assert_eq!(error.to_string(), "\"duplicate deme name: A\"");
+
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 10
+    - start_size: 100
+      selfing_rate: 1.5
+";
+        let error = crate::loads(yaml).unwrap_err();
+        let context = error.context().unwrap();
+        assert_eq!(context.kind(), ErrorKind::InvalidValue);
+        assert_eq!(context.deme(), Some("A"));
+        assert_eq!(context.epoch(), Some(1));
+        assert_eq!(context.value(), Some(1.5));
+
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 1e-3
+";
+        let error = crate::loads(yaml).unwrap_err();
+        assert!(matches!(error, DemesError::MigrationError(_)));
+        assert_eq!(error.context().unwrap().kind(), ErrorKind::UnknownDeme);
+        assert_eq!(error.context().unwrap().deme(), Some("B"));
+    }
+
+    #[test]
+    fn test_error_kinds_of_validation_errors() {
+        let kind = |yaml: &str| {
+            let error = crate::loads(yaml).unwrap_err();
+            let context = error.context().unwrap();
+            (context.kind(), context.deme().map(|d| d.to_string()))
+        };
+
+        // Finite start time and no ancestors
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   start_time: 100
+   epochs:
+    - start_size: 100
+";
+        assert_eq!(kind(yaml), (ErrorKind::Inconsistent, Some("A".to_string())));
+
+        // The first epoch has no size
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - end_time: 0
+";
+        assert_eq!(kind(yaml), (ErrorKind::Missing, Some("A".to_string())));
+
+        // Ancestor listed twice
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   ancestors: [A, A]
+   proportions: [0.5, 0.5]
+   start_time: 10
+   epochs:
+    - start_size: 100
+";
+        assert_eq!(
+            kind(yaml),
+            (ErrorKind::DuplicateDeme, Some("A".to_string()))
+        );
+
+        // Ancestry proportions do not sum to one
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+ - name: C
+   ancestors: [A, B]
+   proportions: [0.5, 0.25]
+   start_time: 10
+   epochs:
+    - start_size: 100
+";
+        assert_eq!(kind(yaml), (ErrorKind::InvalidValue, Some("C".to_string())));
+        assert_eq!(
+            crate::loads(yaml).unwrap_err().context().unwrap().value(),
+            Some(0.75)
+        );
+
+        // Overlapping migrations
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 1e-3
+ - source: A
+   dest: B
+   rate: 1e-3
+   end_time: 10
+";
+        assert!(matches!(
+            crate::loads(yaml).unwrap_err(),
+            DemesError::MigrationError(_)
+        ));
+        assert_eq!(kind(yaml), (ErrorKind::Inconsistent, None));
+
+        // Pulse time outside of the dest deme's existence
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+ - name: B
+   ancestors: [A]
+   start_time: 10
+   epochs:
+    - start_size: 100
+pulses:
+ - sources: [A]
+   dest: B
+   proportions: [0.5]
+   time: 20
+";
+        assert!(matches!(
+            crate::loads(yaml).unwrap_err(),
+            DemesError::PulseError(_)
+        ));
+        assert_eq!(kind(yaml).0, ErrorKind::Inconsistent);
+    }
+
+    #[test]
+    fn test_deme_names_are_shared() {
+        let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 100
+ - name: B
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+ - name: C
+   ancestors: [A]
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [B, C]
+   rate: 1e-3
+pulses:
+ - sources: [B]
+   dest: C
+   proportions: [0.5]
+   time: 10
+";
+        let graph = crate::loads(yaml).unwrap();
+        let a = &graph.demes[0].name;
+        let b = &graph.demes[1].name;
+        let c = &graph.demes[2].name;
+        assert!(Arc::ptr_eq(a, &graph.demes[1].ancestors[0]));
+        assert!(Arc::ptr_eq(a, &graph.demes[2].ancestors[0]));
+        assert!(graph.demes[1]
+            .ancestor_map
+            .keys()
+            .all(|k| Arc::ptr_eq(k, a)));
+        assert!(Arc::ptr_eq(b, &graph.resolved_migrations[0].source));
+        assert!(Arc::ptr_eq(c, &graph.resolved_migrations[0].dest));
+        assert!(Arc::ptr_eq(b, &graph.pulses[0].sources[0]));
+        assert!(Arc::ptr_eq(c, &graph.pulses[0].dest));
+        assert!(graph
+            .deme_map
+            .iter()
+            .all(|(k, &i)| Arc::ptr_eq(k, &graph.demes[i].name)));
+    }
+
+    #[test]
+    fn test_with_description_and_doi() {
+        let yaml = "
+time_units: generations
+description: old
+doi: [a, b]
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+";
+        let graph = crate::loads(yaml).unwrap();
+        let updated = graph
+            .clone()
+            .with_description(Some("new"))
+            .with_doi(vec!["c".to_string()]);
+        assert_eq!(updated.description(), Some("new"));
+        assert_eq!(updated.doi().collect::<Vec<_>>(), ["c"]);
+        assert!(updated.input_string().is_none());
+        assert_eq!(updated.demes(), graph.demes());
+        let reloaded = crate::loads(&updated.as_string().unwrap()).unwrap();
+        assert_eq!(reloaded, updated);
+
+        let cleared = updated
+            .with_description(None::<String>)
+            .with_doi(Vec::<String>::new());
+        assert!(cleared.description().is_none());
+        assert_eq!(cleared.doi().count(), 0);
+        assert!(!cleared.as_string().unwrap().contains("doi"));
+    }
+
+    #[test]
+    fn test_display_and_summary() {
+        let yaml = "
+time_units: years
+generation_time: 25
+description: a model
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 500
+    - start_size: 100
+      end_size: 200
+ - name: B
+   ancestors: [A]
+   start_time: 1000
+   epochs:
+    - start_size: 50
+ - name: C
+   ancestors: [A, B]
+   proportions: [0.5, 0.5]
+   start_time: 100
+   epochs:
+    - start_size: 10
+      end_size: 20
+      size_function: linear
+migrations:
+ - source: A
+   dest: B
+   rate: 1e-3
+   start_time: 200
+pulses:
+ - sources: [A]
+   dest: B
+   proportions: [0.25]
+   time: 150
+";
+        let graph = crate::loads(yaml).unwrap();
+        let a = graph.get_deme("A").unwrap();
+        assert_eq!(a.to_string(), "A (inf, 0], 2 epochs, size 100 -> 200");
+        assert_eq!(a.epochs()[0].to_string(), "(inf, 500], size 100, constant");
+        assert_eq!(
+            a.epochs()[1].to_string(),
+            "(500, 0], size 100 -> 200, exponential"
+        );
+        assert_eq!(
+            graph.get_deme("C").unwrap().to_string(),
+            "C (100, 0] from A, B, 1 epoch, size 10 -> 20"
+        );
+        let expected = "3 demes, 1 migrations, 1 pulses
+description: a model
+time units: years, generation time: 25
+demes:
+  A (inf, 0], 2 epochs, size 100 -> 200
+    epoch 0: (inf, 500], size 100, constant
+    epoch 1: (500, 0], size 100 -> 200, exponential
+  B (1000, 0] from A, 1 epoch, size 50
+    epoch 0: (1000, 0], size 50, constant
+  C (100, 0] from A, B, 1 epoch, size 10 -> 20
+    epoch 0: (100, 0], size 10 -> 20, linear
+migrations:
+  A -> B (200, 0], rate 0.001
+pulses:
+  time 150: A (0.25) -> B";
+        assert_eq!(graph.summary(), expected);
+    }
+
+    #[test]
+    fn test_from_str() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+";
+        let graph = crate::loads(yaml).unwrap();
+        assert_eq!(yaml.parse::<Graph>().unwrap(), graph);
+        assert_eq!(graph.to_string().parse::<Graph>().unwrap(), graph);
+        assert!("time_units: generations".parse::<Graph>().is_err());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        #[derive(Deserialize)]
+        struct Config {
+            models: Vec<Graph>,
+        }
+        let yaml = "
+models:
+  - time_units: generations
+    demes:
+      - name: A
+        epochs:
+          - start_size: 100
+  - time_units: years
+    generation_time: 25
+    demes:
+      - name: B
+        epochs:
+          - start_size: 200
+";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.models.len(), 2);
+        assert_eq!(config.models[1].generation_time(), 25.0);
+        let graph = crate::loads(&config.models[0].as_string().unwrap()).unwrap();
+        assert_eq!(
+            serde_yaml::from_str::<Graph>(&graph.as_string().unwrap()).unwrap(),
+            graph
+        );
+
+        // Resolution errors are deserialization errors
+        let yaml = "
+models:
+  - time_units: years
+    demes:
+      - name: A
+        epochs:
+          - start_size: 100
+";
+        let error = serde_yaml::from_str::<Config>(yaml).err().unwrap();
+        assert!(error.to_string().contains("generation_time"));
+    }
+
+    #[test]
+    fn test_ancestors_recursive() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+        end_time: 100
+  - name: B
+    ancestors: [A]
+    epochs:
+      - start_size: 100
+        end_time: 50
+  - name: C
+    ancestors: [A]
+    epochs:
+      - start_size: 100
+  - name: D
+    ancestors: [B, C]
+    proportions: [0.5, 0.5]
+    start_time: 50
+    epochs:
+      - start_size: 100
+  - name: E
+    ancestors: [C]
+    start_time: 20
+    epochs:
+      - start_size: 100
+  - name: F
+    epochs:
+      - start_size: 100
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let ancestors = |deme: &str| {
+            g.ancestors_recursive(deme)
+                .unwrap()
+                .into_iter()
+                .map(|(i, t)| (usize::from(i), f64::from(t)))
+                .collect::<Vec<_>>()
+        };
+        assert!(ancestors("A").is_empty());
+        assert!(ancestors("F").is_empty());
+        assert_eq!(ancestors("B"), [(0, 100.0)]);
+        assert_eq!(ancestors("D"), [(0, 100.0), (1, 50.0), (2, 50.0)]);
+        assert_eq!(ancestors("E"), [(0, 100.0), (2, 20.0)]);
+        assert!(g.ancestors_recursive("G").is_err());
+        assert!(g.ancestors_recursive(6).is_err());
+    }
+
+    #[test]
+    fn test_descendants() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+        end_time: 100
+  - name: B
+    ancestors: [A]
+    epochs:
+      - start_size: 100
+        end_time: 50
+  - name: C
+    ancestors: [A]
+    epochs:
+      - start_size: 100
+        end_time: 50
+  - name: D
+    ancestors: [B, C]
+    proportions: [0.5, 0.5]
+    start_time: 50
+    epochs:
+      - start_size: 100
+  - name: E
+    start_time: 75
+    ancestors: [C]
+    epochs:
+      - start_size: 100
+  - name: F
+    epochs:
+      - start_size: 100
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let indexes = |deme: &str| {
+            g.descendants(deme)
+                .unwrap()
+                .into_iter()
+                .map(|(i, _)| usize::from(i))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(indexes("A"), [1, 2, 3, 4]);
+        assert_eq!(indexes("B"), [3]);
+        assert_eq!(indexes("C"), [3, 4]);
+        assert!(indexes("D").is_empty());
+        assert!(indexes("F").is_empty());
+        let times = g.descendants(0).unwrap();
+        assert_eq!(times[0].1, 100.0);
+        assert_eq!(times[2].1, 50.0);
+        assert_eq!(times[3].1, 75.0);
+        assert!(g.descendants("G").is_err());
+        assert!(g.descendants(6).is_err());
+    }
+
+    #[test]
+    fn test_migrations_between() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+  - name: B
+    epochs:
+      - start_size: 100
+  - name: C
+    epochs:
+      - start_size: 100
+migrations:
+  - demes: [A, B, C]
+    rate: 1e-3
+    start_time: 100
+  - source: C
+    dest: A
+    rate: 1e-2
+    end_time: 100
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let rates = |s: &str, d: &str| {
+            g.migrations_between(s, d)
+                .unwrap()
+                .map(|m| f64::from(m.rate()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rates("C", "A"), [1e-3, 1e-2]);
+        assert_eq!(rates("A", "C"), [1e-3]);
+        assert!(rates("A", "A").is_empty());
+        assert!(g.migrations_between("A", "D").is_err());
+        assert!(g.migrations_between(3, 0).is_err());
+        assert_eq!(g.migrations_between_symmetric(2, "A").unwrap().count(), 3);
+        assert_eq!(g.migrations_between_symmetric("A", 2).unwrap().count(), 3);
+        assert_eq!(g.migrations_between_symmetric("A", "B").unwrap().count(), 2);
+        assert!(g.migrations_between_symmetric("D", "B").is_err());
+    }
+
+    #[test]
+    fn test_migration_index_matches_linear_search() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+  - name: B
+    epochs:
+      - start_size: 100
+  - name: C
+    epochs:
+      - start_size: 100
+migrations:
+  - demes: [A, B, C]
+    rate: 1e-3
+    start_time: 100
+  - source: C
+    dest: A
+    rate: 1e-2
+    start_time: 100.2
+    end_time: 100
+  - source: B
+    dest: A
+    rate: 1e-2
+    end_time: 100
+";
+        let check = |g: &Graph| {
+            for source in 0..g.num_demes() {
+                for dest in 0..g.num_demes() {
+                    let s = g.demes()[source].name();
+                    let d = g.demes()[dest].name();
+                    let expected = g
+                        .migrations()
+                        .iter()
+                        .filter(|m| m.source() == s && m.dest() == d)
+                        .collect::<Vec<_>>();
+                    assert_eq!(
+                        g.migrations_between(source, dest)
+                            .unwrap()
+                            .collect::<Vec<_>>(),
+                        expected
+                    );
+                    let expected = g
+                        .migrations()
+                        .iter()
+                        .filter(|m| {
+                            (m.source() == s && m.dest() == d) || (m.source() == d && m.dest() == s)
+                        })
+                        .collect::<Vec<_>>();
+                    assert_eq!(
+                        g.migrations_between_symmetric(source, dest)
+                            .unwrap()
+                            .collect::<Vec<_>>(),
+                        expected
+                    );
                 }
             }
-        }
-        Ok(())
+        };
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        assert_eq!(g.migrations().len(), 8);
+        check(&g);
+        // Merging removes the migration from C to A
+        // that is shorter than one generation.
+        let (merged, report) = g.into_integer_generations_merged().unwrap();
+        assert_eq!(report.removed_migrations().len(), 1);
+        assert_eq!(merged.migrations().len(), 7);
+        check(&merged);
+        assert_eq!(merged.migrations_between("C", "A").unwrap().count(), 1);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_size_function() {
-        let yaml = "---\nexponential\n".to_string();
-        let sf: SizeFunction = serde_yaml::from_str(&yaml).unwrap();
-        assert!(matches!(sf, SizeFunction::Exponential));
-
-        let yaml = "---\nconstant\n".to_string();
-        let sf: SizeFunction = serde_yaml::from_str(&yaml).unwrap();
-        assert!(matches!(sf, SizeFunction::Constant));
+    fn test_pulses_between() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+  - name: B
+    epochs:
+      - start_size: 100
+pulses:
+  - sources: [A]
+    dest: B
+    proportions: [0.1]
+    time: 10
+  - sources: [B]
+    dest: A
+    proportions: [0.2]
+    time: 30
+  - sources: [A]
+    dest: B
+    proportions: [0.3]
+    time: 20
+  - sources: [B]
+    dest: A
+    proportions: [0.4]
+    time: 20
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let t = |x: f64| Time::try_from(x).unwrap();
+        let proportions = |start: f64, end: f64| {
+            g.pulses_between(t(start), t(end))
+                .iter()
+                .map(|p| f64::from(p.proportions()[0]))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(proportions(f64::INFINITY, 0.0), [0.2, 0.3, 0.4, 0.1]);
+        assert_eq!(proportions(30.0, 10.0), [0.3, 0.4, 0.1]);
+        assert_eq!(proportions(31.0, 20.0), [0.2, 0.3, 0.4]);
+        assert_eq!(proportions(21.0, 20.0), [0.3, 0.4]);
+        assert!(proportions(10.0, 0.0).is_empty());
+        assert!(proportions(20.0, 20.0).is_empty());
+        assert!(proportions(10.0, 20.0).is_empty());
+        // Stepping one generation at a time visits each pulse once
+        let visited = (1..=40)
+            .rev()
+            .map(|t| proportions(t as f64, (t - 1) as f64).len())
+            .sum::<usize>();
+        assert_eq!(visited, g.pulses().len());
     }
 
     #[test]
-    fn test_display() {
-        let t = Time::try_from(1.0).unwrap();
-        let f = format!("{t}");
-        assert_eq!(f, String::from("1"));
+    fn test_migrations_at() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+  - name: B
+    epochs:
+      - start_size: 100
+migrations:
+  - demes: [A, B]
+    rate: 1e-3
+    start_time: 100
+    end_time: 50
+  - source: A
+    dest: B
+    rate: 1e-2
+    start_time: 50
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let t = |x: f64| Time::try_from(x).unwrap();
+        let rates = |at: f64, source: Option<DemeId>, dest: Option<DemeId>| {
+            g.migrations_at_filtered(t(at), source, dest)
+                .unwrap()
+                .map(|m| f64::from(m.rate()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(g.migrations_at(t(f64::INFINITY)).count(), 0);
+        assert_eq!(g.migrations_at(t(100.0)).count(), 0);
+        assert_eq!(g.migrations_at(t(99.0)).count(), 2);
+        assert_eq!(rates(50.0, None, None), [1e-3, 1e-3]);
+        assert_eq!(rates(49.0, None, None), [1e-2]);
+        assert_eq!(rates(60.0, Some("B".into()), None), [1e-3]);
+        assert_eq!(rates(60.0, None, Some(1.into())), [1e-3]);
+        assert!(rates(0.0, Some("B".into()), None).is_empty());
+        assert_eq!(rates(0.0, Some("A".into()), Some("B".into())), [1e-2]);
+        assert!(g
+            .migrations_at_filtered(t(0.0), None, Some(2.into()))
+            .is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn test_time_validity() {
-        let _ = Time::try_from(f64::NAN).unwrap();
+    fn test_demes_at() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+        end_time: 100
+  - name: B
+    ancestors: [A]
+    epochs:
+      - start_size: 100
+        end_time: 10
+  - name: C
+    ancestors: [A]
+    epochs:
+      - start_size: 100
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let names_at = |t: f64| {
+            g.demes_at(Time::try_from(t).unwrap())
+                .map(|(i, d)| {
+                    assert_eq!(g.deme(i).name(), d.name());
+                    d.name()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names_at(f64::INFINITY), ["A"]);
+        assert_eq!(names_at(100.0), ["A"]);
+        assert_eq!(names_at(99.0), ["B", "C"]);
+        assert_eq!(names_at(10.0), ["B", "C"]);
+        assert_eq!(names_at(9.0), ["C"]);
+        assert_eq!(names_at(0.0), ["C"]);
+        for t in [0.0, 5.0, 10.0, 50.0, 100.0, 150.0, f64::INFINITY] {
+            let at = Time::try_from(t).unwrap();
+            let extant = g.demes_at(at).map(|(i, _)| i).collect::<Vec<_>>();
+            let with_size = (0..g.num_demes())
+                .filter(|&i| g.deme(i).size_at(t).unwrap().is_some())
+                .collect::<Vec<_>>();
+            assert_eq!(extant, with_size);
+        }
     }
 
     #[test]
-    fn test_newtype_compare_to_f64() {
-        {
-            let v = Time::try_from(100.0).unwrap();
-            assert_eq!(v, 100.0);
-            assert_eq!(100.0, v);
-            assert!(v > 50.0);
-            assert!(50.0 < v);
+    fn test_sizes_at() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+        end_time: 100
+      - start_size: 100
+        end_size: 200
+        end_time: 50
+      - end_size: 10
+        size_function: linear
+        end_time: 10
+  - name: B
+    ancestors: [A]
+    start_time: 75
+    epochs:
+      - start_size: 10
+        end_size: 100
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let mut times = (0..=1000).map(|t| t as f64 / 5.0).collect::<Vec<_>>();
+        times.push(f64::INFINITY);
+        for deme in g.demes() {
+            let sizes = deme.sizes_at(&times).unwrap();
+            for (time, size) in times.iter().zip(sizes.iter()) {
+                assert_eq!(size, &deme.size_at(*time).unwrap(), "{time}");
+            }
+            for epoch in deme.epochs() {
+                let sizes = epoch.sizes_at(&times).unwrap();
+                for (time, size) in times.iter().zip(sizes.iter()) {
+                    assert_eq!(size, &epoch.size_at(*time).unwrap(), "{time}");
+                }
+            }
+            for bad in [-1.0, f64::NAN] {
+                assert!(deme.sizes_at(&[1.0, bad]).is_err());
+                assert!(deme.epochs()[0].sizes_at(&[1.0, bad]).is_err());
+            }
         }
+        assert!(g.deme(0).sizes_at(&[]).unwrap().is_empty());
+    }
 
-        {
-            let v = DemeSize::try_from(100.0).unwrap();
-            assert_eq!(v, 100.0);
-            assert_eq!(100.0, v);
-        }
+    #[test]
+    fn test_with_metadata() {
+        let yaml = "
+time_units: generations
+metadata:
+  x: 1
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let mut md = std::collections::BTreeMap::new();
+        md.insert("y".to_string(), 2);
+        let g = g.with_metadata(&md).unwrap();
+        let metadata = g.metadata().unwrap();
+        assert!(!metadata.as_raw_ref().contains_key("x"));
+        assert_eq!(metadata.as_raw_ref()["y"], serde_yaml::Value::from(2));
+        let round_trip = Graph::new_resolved_from_str(&serde_yaml::to_string(&g).unwrap()).unwrap();
+        assert_eq!(round_trip.metadata(), g.metadata());
+
+        // Not a mapping
+        assert!(g.clone().with_metadata(&vec![1, 2]).is_err());
+        assert!(g.clone().with_metadata(&1).is_err());
+        // Empty mapping
+        md.clear();
+        assert!(g.with_metadata(&md).is_err());
+    }
 
-        {
-            let v = SelfingRate::try_from(1.0).unwrap();
-            assert_eq!(v, 1.0);
-            assert_eq!(1.0, v);
-        }
+    #[test]
+    fn test_unused_default_warnings() {
+        let yaml = "
+time_units: generations
+defaults:
+  epoch:
+    start_size: 1000
+    end_time: 10
+    selfing_rate: 0.5
+  deme:
+    ancestors: [A]
+  migration:
+    rate: 1e-3
+demes:
+  - name: A
+    ancestors: []
+    epochs:
+      - start_size: 100
+        end_time: 0
+        selfing_rate: 0
+  - name: B
+    start_time: 100
+    defaults:
+      epoch:
+        selfing_rate: 0.1
+    epochs:
+      - end_time: 0
+migrations:
+  - demes: [A, B]
+    rate: 1e-2
+";
+        let g: UnresolvedGraph = serde_yaml::from_str(yaml).unwrap();
+        let mut warnings = vec![];
+        let _ = g.resolve_with_warnings(&mut warnings).unwrap();
+        let fields = warnings
+            .iter()
+            .map(|w| match w {
+                crate::Warning::UnusedDefault(field) => field.as_str(),
+                _ => panic!("unexpected warning: {w}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                "defaults.epoch.end_time",
+                "defaults.epoch.selfing_rate",
+                "defaults.migration.rate"
+            ]
+        );
+    }
 
-        {
-            let v = CloningRate::try_from(1.0).unwrap();
-            assert_eq!(v, 1.0);
-            assert_eq!(1.0, v);
-        }
+    #[test]
+    fn test_slice_deme() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+  - name: B
+    ancestors: [A]
+    start_time: 100
+    epochs:
+      - start_size: 100
+  - name: C
+    ancestors: [B]
+    start_time: 20
+    epochs:
+      - start_size: 100
+migrations:
+  - demes: [A, B]
+    rate: 1e-3
+    start_time: 80
+pulses:
+  - sources: [A]
+    dest: B
+    time: 90
+    proportions: [0.1]
+  - sources: [A]
+    dest: B
+    time: 10
+    proportions: [0.1]
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let t = |x: f64| Time::try_from(x).unwrap();
+
+        let sliced = g.clone().slice_deme_until("A", t(50.0)).unwrap();
+        assert_eq!(sliced.deme("A").end_time(), 50.0);
+        assert_eq!(sliced.deme("B"), g.deme("B"));
+        assert_eq!(sliced.pulses().len(), 1);
+        assert_eq!(sliced.pulses()[0].time(), 90.0);
+        assert_eq!(sliced.migrations().len(), 2);
+        for m in sliced.migrations() {
+            assert_eq!(m.start_time(), 80.0);
+            assert_eq!(m.end_time(), 50.0);
+        }
+
+        // C descends from B after B ends
+        assert!(g.clone().slice_deme_until("B", t(50.0)).is_err());
+        // B does not exist prior to 100
+        assert!(matches!(
+            g.clone().slice_deme_until("B", t(100.0)),
+            Err(DemesError::DemeError(_))
+        ));
 
-        {
-            let v = Proportion::try_from(1.0).unwrap();
-            assert_eq!(v, 1.0);
-            assert_eq!(1.0, v);
-        }
+        let sliced = g.clone().slice_deme_after("B", t(85.0)).unwrap();
+        assert!(sliced.deme("B").ancestor_names().is_empty());
+        assert_eq!(sliced.deme("B").start_time(), f64::INFINITY);
+        assert_eq!(sliced.pulses().len(), 1);
+        assert_eq!(sliced.pulses()[0].time(), 10.0);
+        assert_eq!(sliced.migrations().len(), 2);
+        assert_eq!(sliced.deme("C"), g.deme("C"));
 
-        {
-            let v = MigrationRate::try_from(1.0).unwrap();
-            assert_eq!(v, 1.0);
-            assert_eq!(1.0, v);
-        }
+        let sliced = g.clone().slice_deme_between("B", t(15.0), t(95.0)).unwrap();
+        assert_eq!(sliced.deme("B").end_time(), 15.0);
+        assert!(sliced.deme("B").ancestor_names().is_empty());
+        assert_eq!(sliced.pulses().len(), 1);
+        assert_eq!(sliced.pulses()[0].time(), 90.0);
+        assert!(g.clone().slice_deme_between("B", t(25.0), t(25.0)).is_err());
+        assert!(g.slice_deme_until("X", t(25.0)).is_err());
     }
-}
 
-#[cfg(test)]
-mod test_graph {
-    use super::*;
+    #[test]
+    fn test_insert_outgroup() {
+        let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 100
+        end_time: 50
+  - name: B
+    ancestors: [A]
+    epochs:
+      - start_size: 100
+";
+        let g = Graph::new_resolved_from_str(yaml).unwrap();
+        let og = g.clone().insert_outgroup("C", 1, 10.0, 50.0).unwrap();
+        assert_eq!(og.deme(2).ancestor_indexes(), &[DemeIndex::from(1)]);
+        assert_eq!(og.deme(2).end_size(), 50.0);
+        // Unknown ancestor
+        assert!(g.clone().insert_outgroup("C", "X", 10.0, 50.0).is_err());
+        // Name collision
+        assert!(g.clone().insert_outgroup("B", "A", 100.0, 50.0).is_err());
+        // Ancestor does not exist at the start time
+        assert!(g.clone().insert_outgroup("C", "A", 10.0, 50.0).is_err());
+        // Invalid size
+        assert!(g.insert_outgroup("C", "A", 100.0, 0.0).is_err());
+    }
 
     #[test]
     fn test_round_trip_with_default_epoch_sizes() {
@@ -4250,7 +8961,7 @@ demes:
 ";
         let g = Graph::new_resolved_from_str(yaml).unwrap();
         assert_eq!(g.pulses().len(), 1);
-        assert_eq!(g.pulses()[0].sources(), vec!["A".to_string()]);
+        assert_eq!(g.pulses()[0].sources(), [Arc::from("A")]);
         assert_eq!(g.pulses()[0].dest(), "B");
         assert_eq!(
             g.pulses()[0].proportions(),
@@ -4530,6 +9241,79 @@ demes:
         g.into_integer_generations().unwrap();
     }
 
+    #[test]
+    fn test_merged_conversion_cannot_remove_deme() {
+        let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: ancestor
+   epochs:
+    - start_size: 100
+      end_time: 10
+ - name: derived
+   ancestors: [ancestor]
+   epochs:
+    - start_size: 100
+";
+        let g = crate::loads(yaml).unwrap();
+        assert!(g.into_integer_generations_merged().is_err());
+    }
+
+    #[test]
+    fn test_merged_conversion() {
+        let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 1000
+    - start_size: 200
+      end_time: 10
+    - start_size: 300
+ - name: B
+   epochs:
+    - start_size: 100
+      end_time: 1010
+    - start_size: 200
+      end_time: 1000
+    - start_size: 300
+migrations:
+ - demes: [A, B]
+   start_time: 500
+   end_time: 495
+   rate: 1e-3
+ - demes: [A, B]
+   start_time: 100
+   rate: 1e-3
+";
+        let g = crate::loads(yaml).unwrap();
+        let (converted, report) = g.into_integer_generations_merged().unwrap();
+        assert_eq!(report.merged_epochs().len(), 2);
+        assert_eq!(report.merged_epochs()[0].deme(), "A");
+        assert_eq!(report.merged_epochs()[0].epoch_index(), 2);
+        assert_eq!(report.merged_epochs()[1].deme(), "B");
+        assert_eq!(report.merged_epochs()[1].epoch_index(), 1);
+        assert_eq!(report.removed_migrations().len(), 2);
+        assert!(report
+            .removed_migrations()
+            .iter()
+            .all(|m| m.start_time() == 500.0));
+
+        let a = converted.deme("A");
+        assert_eq!(a.num_epochs(), 2);
+        assert_eq!(a.end_times().collect::<Vec<_>>(), [40.0, 0.0]);
+        assert_eq!(a.epochs()[1].start_size(), 200.0);
+        let b = converted.deme("B");
+        assert_eq!(b.num_epochs(), 2);
+        assert_eq!(b.end_times().collect::<Vec<_>>(), [40.0, 0.0]);
+        assert_eq!(b.epochs()[1].start_size(), 300.0);
+        assert_eq!(converted.migrations().len(), 2);
+        assert!(converted.migrations().iter().all(|m| m.start_time() == 4.0));
+    }
+
     #[test]
     fn test_demelevel_epoch_conversion_non_integer_input_times() {
         let yaml = "
@@ -4654,7 +9438,7 @@ demes:
                 let yaml = $yaml;
                 let graph = crate::loads(yaml).unwrap();
                 let u = UnresolvedGraph::from(graph.clone());
-                let graph_roundtrip = Graph::try_from(u.resolve().unwrap()).unwrap();
+                let graph_roundtrip = u.resolve().unwrap();
                 assert_eq!(graph, graph_roundtrip);
             }
         };
@@ -4692,7 +9476,7 @@ fn test_toml() {
     assert_eq!(m.demes.len(), 2);
     assert_eq!(m.demes[0].epochs.len(), 1);
     assert_eq!(m.demes[1].epochs.len(), 1);
-    let _: Graph = m.resolve().unwrap().try_into().unwrap();
+    let _: Graph = m.resolve().unwrap();
 }
 
 #[test]
@@ -4711,10 +9495,32 @@ fn test_roundtrip() {
     let toml_string = toml::to_string(&toml_from_yaml).unwrap();
 
     let u: UnresolvedGraph = toml::from_str(&toml_string).unwrap();
-    let graph_from_toml = Graph::try_from(u.resolve().unwrap()).unwrap();
+    let graph_from_toml = u.resolve().unwrap();
     assert_eq!(graph, graph_from_toml);
 }
 
+#[test]
+#[cfg(feature = "toml")]
+fn test_as_toml_string() {
+    let mut f = std::fs::File::open("examples/jouganous.yaml").unwrap();
+    let mut buf = String::default();
+    let _ = f.read_to_string(&mut buf).unwrap();
+    let graph = crate::loads(&buf)
+        .unwrap()
+        .with_metadata(&std::collections::BTreeMap::from([("x", vec![1, 2])]))
+        .unwrap();
+    let toml_string = graph.as_toml_string().unwrap();
+    assert_eq!(crate::loads_toml(&toml_string).unwrap(), graph);
+
+    let with_null = graph
+        .with_metadata(&std::collections::BTreeMap::from([("x", None::<i32>)]))
+        .unwrap();
+    assert!(matches!(
+        with_null.as_toml_string(),
+        Err(DemesError::TomlSerError(_))
+    ));
+}
+
 #[test]
 #[should_panic]
 fn test_negative_epoch_end_time() {
@@ -5230,6 +10036,91 @@ mod test_rescaling {
             }
         }
     }
+
+    #[test]
+    fn test_rescale_demes() {
+        let graph = crate::loads(SIMPLE_TEST_GRAPH_1).unwrap();
+        let rescaled = graph
+            .clone()
+            .rescale_demes([("derived1", 2.0), ("ancestor2", 5.0)])
+            .unwrap();
+        for (di, dj) in graph.demes().iter().zip(rescaled.demes().iter()) {
+            let scaling_factor = match di.name() {
+                "derived1" => 2.0,
+                "ancestor2" => 5.0,
+                _ => 1.0,
+            };
+            assert_eq!(di.start_time(), dj.start_time());
+            for (ei, ej) in di.epochs().iter().zip(dj.epochs().iter()) {
+                assert_eq!(ei.end_time(), ej.end_time());
+                assert_eq!(f64::from(ei.start_size()) / scaling_factor, ej.start_size());
+                assert_eq!(f64::from(ei.end_size()) / scaling_factor, ej.end_size());
+            }
+        }
+        for (mi, mj) in graph.migrations().iter().zip(rescaled.migrations().iter()) {
+            let scaling_factor = if mi.dest() == "derived1" { 2.0 } else { 1.0 };
+            assert_eq!(mi.dest(), mj.dest());
+            assert_eq!(mi.start_time(), mj.start_time());
+            assert_eq!(f64::from(mi.rate()) * scaling_factor, mj.rate());
+        }
+    }
+
+    #[test]
+    fn test_rescale_demes_errors() {
+        let graph = crate::loads(SIMPLE_TEST_GRAPH_1).unwrap();
+        for bad in [-1.0, f64::INFINITY, 0.0, f64::NAN] {
+            assert!(matches!(
+                graph.clone().rescale_demes([("derived1", bad)]),
+                Err(crate::DemesError::ValueError(_))
+            ));
+        }
+        assert!(matches!(
+            graph
+                .clone()
+                .rescale_demes([("derived1", 2.0), ("derived1", 2.0)]),
+            Err(crate::DemesError::ValueError(_))
+        ));
+        assert!(matches!(
+            graph.clone().rescale_demes([("unknown", 2.0)]),
+            Err(crate::DemesError::DemeError(_))
+        ));
+        // Results in a migration rate > 1
+        assert!(graph.rescale_demes([("derived2", 5.0)]).is_err());
+    }
+
+    #[test]
+    fn test_shift_times() {
+        for yaml in [SIMPLE_TEST_GRAPH_1, SIMPLE_TEST_GRAPH_2] {
+            let graph = crate::loads(yaml).unwrap();
+            let shifted = graph.clone().shift_times(5.0).unwrap();
+            for (di, dj) in graph.demes().iter().zip(shifted.demes().iter()) {
+                assert_eq!(f64::from(di.start_time()) + 5.0, dj.start_time());
+                for (ei, ej) in di.epochs().iter().zip(dj.epochs().iter()) {
+                    assert_eq!(f64::from(ei.end_time()) + 5.0, ej.end_time());
+                    assert_eq!(ei.start_size(), ej.start_size());
+                }
+            }
+            for (mi, mj) in graph.migrations().iter().zip(shifted.migrations().iter()) {
+                assert_eq!(f64::from(mi.start_time()) + 5.0, mj.start_time());
+                assert_eq!(f64::from(mi.end_time()) + 5.0, mj.end_time());
+            }
+            for (pi, pj) in graph.pulses().iter().zip(shifted.pulses().iter()) {
+                assert_eq!(f64::from(pi.time()) + 5.0, pj.time());
+            }
+            assert_eq!(shifted.shift_times(-5.0).unwrap(), graph);
+        }
+    }
+
+    #[test]
+    fn test_shift_times_errors() {
+        let graph = crate::loads(SIMPLE_TEST_GRAPH_1).unwrap();
+        for bad in [-1.0, f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+            assert!(matches!(
+                graph.clone().shift_times(bad),
+                Err(crate::DemesError::ValueError(_))
+            ));
+        }
+    }
 }
 
 #[cfg(test)]
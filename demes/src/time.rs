@@ -1,4 +1,6 @@
 use crate::error::DemesError;
+use crate::ErrorContext;
+use crate::ErrorKind;
 use serde::{Deserialize, Serialize};
 
 pub(crate) fn to_generations(time: Time, generation_time: GenerationTime) -> Time {
@@ -54,12 +56,34 @@ pub fn round_time_to_integer_generations(time: Time, generation_time: Generation
 ///
 /// ## Using rust code
 ///
-/// The only method to create a `Time` is to
-/// apply `TryFrom<f64>`:
+/// A `Time` is created by applying `TryFrom<f64>`,
+/// which validates the value:
 ///
 /// ```
 /// let t = demes::Time::try_from(0.0).unwrap();
 /// assert_eq!(t, 0.0);
+/// assert!(demes::Time::try_from(-1.0).is_err());
+/// ```
+///
+/// Constants may be defined using [`Time::ZERO`], [`Time::INFINITY`],
+/// and [`Time::new_unchecked`]:
+///
+/// ```
+/// const BOTTLENECK: demes::Time = demes::Time::new_unchecked(100.0);
+/// assert!(demes::Time::ZERO < BOTTLENECK);
+/// assert!(BOTTLENECK < demes::Time::INFINITY);
+/// ```
+///
+/// ## Ordering
+///
+/// `NaN` is not a valid `Time`, so `Time` implements [`Ord`]
+/// and may be used as a key in ordered collections:
+///
+/// ```
+/// let mut events = std::collections::BTreeMap::new();
+/// events.insert(demes::Time::try_from(f64::INFINITY).unwrap(), "ancient");
+/// events.insert(demes::Time::try_from(0.0).unwrap(), "present");
+/// assert_eq!(events.into_values().collect::<Vec<_>>(), ["present", "ancient"]);
 /// ```
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 #[repr(transparent)]
@@ -111,13 +135,64 @@ impl InputTime {
             Ok(())
         } else {
             let msg = format!("start_time must be > 0.0, got: {}", self.0);
-            Err(DemesError::DemeError(msg))
+            Err(DemesError::DemeError(msg.into()))
         }
     }
 }
 
 impl_input_newtype_traits!(InputTime);
 
+fn checked_time(value: f64) -> Result<f64, DemesError> {
+    Time(value).validate(DemesError::ValueError)?;
+    Ok(value)
+}
+
+// Arithmetic that returns an error rather than an invalid time.
+macro_rules! impl_checked_time_arithmetic {
+    ($type: ty) => {
+        impl $type {
+            /// Checked addition.
+            ///
+            /// # Errors
+            ///
+            /// [`DemesError::ValueError`] if the result is negative or `NaN`.
+            pub fn checked_add<T: Into<f64>>(self, rhs: T) -> Result<Self, DemesError> {
+                checked_time(self.0 + rhs.into()).map(Self)
+            }
+
+            /// Checked subtraction.
+            ///
+            /// # Errors
+            ///
+            /// [`DemesError::ValueError`] if the result is negative or `NaN`.
+            pub fn checked_sub<T: Into<f64>>(self, rhs: T) -> Result<Self, DemesError> {
+                checked_time(self.0 - rhs.into()).map(Self)
+            }
+
+            /// Checked multiplication.
+            ///
+            /// # Errors
+            ///
+            /// [`DemesError::ValueError`] if the result is negative or `NaN`.
+            pub fn checked_mul<T: Into<f64>>(self, rhs: T) -> Result<Self, DemesError> {
+                checked_time(self.0 * rhs.into()).map(Self)
+            }
+
+            /// Checked division.
+            ///
+            /// # Errors
+            ///
+            /// [`DemesError::ValueError`] if the result is negative or `NaN`.
+            pub fn checked_div<T: Into<f64>>(self, rhs: T) -> Result<Self, DemesError> {
+                checked_time(self.0 / rhs.into()).map(Self)
+            }
+        }
+    };
+}
+
+impl_checked_time_arithmetic!(Time);
+impl_checked_time_arithmetic!(InputTime);
+
 impl TryFrom<InputTime> for Time {
     type Error = DemesError;
 
@@ -164,7 +239,7 @@ pub enum TimeUnits {
 pub(crate) struct HashableTime(Time);
 
 /// A half-open time interval `[present, past)`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct TimeInterval {
     start_time: Time,
     end_time: Time,
@@ -186,13 +261,13 @@ enum TimeTrampoline {
 // Workhorse behing Graph::to_generations
 pub(crate) fn convert_resolved_time_to_generations<F>(
     generation_time: GenerationTime,
-    rounding: fn(Time, GenerationTime) -> Time,
+    rounding: &mut dyn FnMut(Time, GenerationTime) -> Time,
     f: F,
     message: &str,
     input: Option<Time>,
 ) -> Result<Time, DemesError>
 where
-    F: std::ops::FnOnce(String) -> DemesError,
+    F: std::ops::FnOnce(ErrorContext) -> DemesError,
 {
     match input {
         Some(value) => {
@@ -204,24 +279,51 @@ where
             if time.0.is_finite() && time >= 0.0 {
                 Ok(time)
             } else {
-                Err(f("rounding resulted in invalid time".to_string()))
+                Err(f(ErrorContext::new(
+                    "rounding resulted in invalid time",
+                    ErrorKind::InvalidValue,
+                )
+                .with_value(time.0)))
             }
         }
-        None => Err(f(message.to_string())),
+        None => Err(f(ErrorContext::new(message, ErrorKind::Missing))),
     }
 }
 
 impl Time {
+    /// The present.
+    pub const ZERO: Self = Self(0.0);
+
+    /// Infinitely long ago.
+    ///
+    /// This is the start time of demes with no ancestors.
+    pub const INFINITY: Self = Self(f64::INFINITY);
+
+    /// Create a `Time` without validating the value.
+    ///
+    /// This function exists to allow defining constants.
+    /// The caller is responsible for `value` being neither
+    /// negative nor `NaN`.
+    /// Otherwise, comparisons between times are not meaningful.
+    /// Prefer `TryFrom<f64>` when the value is not a literal.
+    pub const fn new_unchecked(value: f64) -> Self {
+        Self(value)
+    }
+
     pub(crate) fn is_valid_pulse_time(&self) -> bool {
         self.0.is_sign_positive() && !self.0.is_infinite()
     }
 
     fn validate<F>(&self, f: F) -> Result<(), DemesError>
     where
-        F: std::ops::FnOnce(String) -> DemesError,
+        F: std::ops::FnOnce(ErrorContext) -> DemesError,
     {
         if self.0.is_nan() || self.0.is_sign_negative() {
-            Err(f(format!("invalid time value: {}", self.0)))
+            Err(f(ErrorContext::new(
+                format!("invalid time value: {}", self.0),
+                ErrorKind::InvalidValue,
+            )
+            .with_value(self.0)))
         } else {
             Ok(())
         }
@@ -229,9 +331,13 @@ impl Time {
 }
 
 impl GenerationTime {
-    fn validate<F: FnOnce(String) -> DemesError>(&self, err: F) -> Result<(), DemesError> {
+    fn validate<F: FnOnce(ErrorContext) -> DemesError>(&self, err: F) -> Result<(), DemesError> {
         if !self.0.is_finite() || !self.0.is_sign_positive() || !self.gt(&0.0) {
-            Err(err(format!("generation time must be > 0.0, got: {self}")))
+            Err(err(ErrorContext::new(
+                format!("generation time must be > 0.0, got: {self}"),
+                ErrorKind::InvalidValue,
+            )
+            .with_value(self.0)))
         } else {
             Ok(())
         }
@@ -290,6 +396,38 @@ impl TimeInterval {
         }
     }
 
+    /// Create a new interval.
+    ///
+    /// # Parameters
+    ///
+    /// * `start_time`: the start (past) of the interval
+    /// * `end_time`: the end (present) of the interval
+    ///
+    /// # Errors
+    ///
+    /// [`DemesError::ValueError`] if `start_time <= end_time`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let start = demes::Time::try_from(10.0).unwrap();
+    /// let end = demes::Time::try_from(5.0).unwrap();
+    /// let interval = demes::TimeInterval::try_new(start, end).unwrap();
+    /// assert_eq!(interval.duration(), 5.0);
+    /// assert!(demes::TimeInterval::try_new(end, start).is_err());
+    /// ```
+    pub fn try_new(start_time: Time, end_time: Time) -> Result<Self, DemesError> {
+        if start_time <= end_time {
+            return Err(DemesError::ValueError(
+                format!(
+                    "interval start time {start_time} must be greater than end time {end_time}"
+                )
+                .into(),
+            ));
+        }
+        Ok(Self::new(start_time, end_time))
+    }
+
     // true if other is in (start_time, end_time]
     pub(crate) fn contains_inclusive_start_exclusive_end<F>(&self, other: F) -> bool
     where
@@ -335,9 +473,79 @@ impl TimeInterval {
         self.end_time
     }
 
-    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+    /// Return the duration of the interval.
+    ///
+    /// The duration is infinite if the start time is infinite.
+    pub fn duration(&self) -> f64 {
+        f64::from(self.start_time) - f64::from(self.end_time)
+    }
+
+    /// Return `true` if the two intervals share any time points.
+    ///
+    /// Intervals that only share a boundary, such as
+    /// `[0, 10)` and `[10, 20)`, do not overlap.
+    pub fn overlaps(&self, other: &Self) -> bool {
         self.start_time() > other.end_time() && other.start_time() > self.end_time()
     }
+
+    /// Return the intersection of two intervals.
+    ///
+    /// Returns `None` if the intervals do not overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use demes::{Time, TimeInterval};
+    /// let t = |x: f64| Time::try_from(x).unwrap();
+    /// let a = TimeInterval::try_new(t(f64::INFINITY), t(10.0)).unwrap();
+    /// let b = TimeInterval::try_new(t(20.0), t(0.0)).unwrap();
+    /// let c = a.intersection(&b).unwrap();
+    /// assert_eq!(c.start_time(), 20.0);
+    /// assert_eq!(c.end_time(), 10.0);
+    /// let d = TimeInterval::try_new(t(10.0), t(0.0)).unwrap();
+    /// assert!(a.intersection(&d).is_none());
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Self::new(
+                std::cmp::min(self.start_time, other.start_time),
+                std::cmp::max(self.end_time, other.end_time),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Return the union of two intervals if the union is
+    /// itself an interval.
+    ///
+    /// This is the case if the intervals overlap or
+    /// if they share a boundary.
+    /// Otherwise, returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use demes::{Time, TimeInterval};
+    /// let t = |x: f64| Time::try_from(x).unwrap();
+    /// let a = TimeInterval::try_new(t(20.0), t(10.0)).unwrap();
+    /// let b = TimeInterval::try_new(t(10.0), t(0.0)).unwrap();
+    /// let c = a.union_if_contiguous(&b).unwrap();
+    /// assert_eq!(c.start_time(), 20.0);
+    /// assert_eq!(c.end_time(), 0.0);
+    /// let d = TimeInterval::try_new(t(30.0), t(25.0)).unwrap();
+    /// assert!(a.union_if_contiguous(&d).is_none());
+    /// ```
+    pub fn union_if_contiguous(&self, other: &Self) -> Option<Self> {
+        if self.start_time >= other.end_time && other.start_time >= self.end_time {
+            Some(Self::new(
+                std::cmp::max(self.start_time, other.start_time),
+                std::cmp::min(self.end_time, other.end_time),
+            ))
+        } else {
+            None
+        }
+    }
 }
 
 impl TryFrom<TimeTrampoline> for Time {
@@ -350,7 +558,7 @@ impl TryFrom<TimeTrampoline> for Time {
                 if &string == "Infinity" {
                     Ok(Self(f64::INFINITY))
                 } else {
-                    Err(DemesError::GraphError(string))
+                    Err(DemesError::GraphError(string.into()))
                 }
             }
             // Fall back to valid YAML representations
@@ -379,7 +587,7 @@ impl TryFrom<TimeTrampoline> for InputTime {
                 if &string == "Infinity" {
                     Ok(Self(f64::INFINITY))
                 } else {
-                    Err(DemesError::GraphError(string))
+                    Err(DemesError::GraphError(string.into()))
                 }
             }
             // Fall back to valid YAML representations
@@ -471,3 +679,94 @@ mod test_infinity {
         assert!(f64::from(time).is_sign_positive());
     }
 }
+
+#[cfg(test)]
+mod test_checked_arithmetic {
+    use super::*;
+
+    #[test]
+    fn test_checked_time_arithmetic() {
+        let t = Time::try_from(10.0).unwrap();
+        assert_eq!(t.checked_add(5.0).unwrap(), 15.0);
+        assert_eq!(t.checked_add(t).unwrap(), 20.0);
+        assert_eq!(t.checked_sub(10.0).unwrap(), Time::ZERO);
+        assert_eq!(t.checked_mul(0.5).unwrap(), 5.0);
+        assert_eq!(t.checked_div(4.0).unwrap(), 2.5);
+        assert_eq!(Time::INFINITY.checked_sub(t).unwrap(), Time::INFINITY);
+        assert!(matches!(
+            t.checked_sub(11.0),
+            Err(DemesError::ValueError(_))
+        ));
+        assert!(t.checked_mul(-1.0).is_err());
+        assert!(t.checked_add(f64::NAN).is_err());
+        assert!(Time::INFINITY.checked_sub(Time::INFINITY).is_err());
+        assert!(Time::ZERO.checked_div(0.0).is_err());
+    }
+
+    #[test]
+    fn test_checked_input_time_arithmetic() {
+        let t = InputTime::from(10.0);
+        assert_eq!(t.checked_add(5.0).unwrap(), 15.0);
+        assert_eq!(t.checked_sub(Time::try_from(4.0).unwrap()).unwrap(), 6.0);
+        assert_eq!(t.checked_mul(2.0).unwrap(), 20.0);
+        assert_eq!(t.checked_div(t).unwrap(), 1.0);
+        assert!(t.checked_sub(20.0).is_err());
+        assert!(InputTime::from(-1.0).checked_add(0.0).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_time_constants {
+    use super::*;
+
+    const T: Time = Time::new_unchecked(10.0);
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(T, Time::try_from(10.0).unwrap());
+        assert_eq!(Time::ZERO, Time::try_from(0.0).unwrap());
+        assert_eq!(Time::INFINITY, Time::try_from(f64::INFINITY).unwrap());
+        assert!(Time::ZERO < T && T < Time::INFINITY);
+        assert!(TimeInterval::try_new(Time::INFINITY, Time::ZERO).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod test_time_interval {
+    use super::*;
+
+    fn interval(start: f64, end: f64) -> TimeInterval {
+        TimeInterval::try_new(start.try_into().unwrap(), end.try_into().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_try_new() {
+        let t = |x: f64| Time::try_from(x).unwrap();
+        assert!(TimeInterval::try_new(t(1.0), t(1.0)).is_err());
+        assert!(TimeInterval::try_new(t(1.0), t(2.0)).is_err());
+        assert!(TimeInterval::try_new(t(f64::INFINITY), t(f64::INFINITY)).is_err());
+        assert!(interval(f64::INFINITY, 0.0).duration().is_infinite());
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = interval(20.0, 10.0);
+        let b = interval(10.0, 0.0);
+        let c = interval(15.0, 5.0);
+        assert!(!a.overlaps(&b));
+        assert!(a.overlaps(&c));
+        assert!(c.overlaps(&b));
+        assert!(a.intersection(&b).is_none());
+        assert_eq!(a.intersection(&c), Some(interval(15.0, 10.0)));
+        assert_eq!(c.intersection(&a), Some(interval(15.0, 10.0)));
+        assert_eq!(a.intersection(&a), Some(a));
+        assert_eq!(a.union_if_contiguous(&b), Some(interval(20.0, 0.0)));
+        assert_eq!(b.union_if_contiguous(&a), Some(interval(20.0, 0.0)));
+        assert_eq!(a.union_if_contiguous(&c), Some(interval(20.0, 5.0)));
+        assert!(interval(30.0, 21.0).union_if_contiguous(&a).is_none());
+        assert_eq!(
+            interval(f64::INFINITY, 10.0).union_if_contiguous(&c),
+            Some(interval(f64::INFINITY, 5.0))
+        );
+    }
+}
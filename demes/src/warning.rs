@@ -0,0 +1,140 @@
+use crate::Graph;
+use crate::MigrationRate;
+
+// Migration rates at or above this value generate a warning.
+const MIGRATION_RATE_WARNING_THRESHOLD: f64 = 0.99;
+
+// Epochs shorter than this many generations generate a warning.
+const SHORT_EPOCH_WARNING_THRESHOLD: f64 = 1e-6;
+
+/// Non-fatal issues found during resolution.
+///
+/// A warning means that a model is valid according to the
+/// [specification](https://popsim-consortium.github.io/demes-spec-docs/main/specification.html)
+/// but may not be what the author intended.
+///
+/// See [`loads_with_warnings`](crate::loads_with_warnings) and
+/// [`GraphBuilder::resolve_with_warnings`](crate::GraphBuilder::resolve_with_warnings).
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// A migration rate is very close to one.
+    ///
+    /// A warning is generated for rates of at least `0.99`.
+    MigrationRateNearOne {
+        /// The source deme
+        source: String,
+        /// The destination deme
+        dest: String,
+        /// The migration rate
+        rate: MigrationRate,
+    },
+    /// An epoch has a duration of nearly zero.
+    ///
+    /// A warning is generated for durations of less
+    /// than `1e-6` generations.
+    ShortEpoch {
+        /// The deme name
+        deme: String,
+        /// The index of the epoch in the deme
+        epoch: usize,
+        /// The duration of the epoch, in the time units of the graph.
+        duration: f64,
+    },
+    /// A field of the top-level `defaults` is never applied
+    /// because every relevant object specifies the field.
+    ///
+    /// The value is the path to the field, e.g. `defaults.epoch.start_size`.
+    UnusedDefault(String),
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MigrationRateNearOne { source, dest, rate } => write!(
+                f,
+                "migration rate from {source} to {dest} is close to one: {}",
+                f64::from(*rate)
+            ),
+            Self::ShortEpoch {
+                deme,
+                epoch,
+                duration,
+            } => write!(
+                f,
+                "epoch {epoch} of deme {deme} has a duration of nearly zero: {duration}"
+            ),
+            Self::UnusedDefault(field) => write!(f, "default value is never used: {field}"),
+        }
+    }
+}
+
+// Warnings that we can detect from a resolved graph.
+pub(crate) fn resolved_graph_warnings(graph: &Graph) -> Vec<Warning> {
+    let mut warnings = vec![];
+    let generation_time = f64::from(graph.generation_time());
+    for deme in graph.demes() {
+        for (i, epoch) in deme.epochs().iter().enumerate() {
+            let duration = f64::from(epoch.start_time()) - f64::from(epoch.end_time());
+            if duration / generation_time < SHORT_EPOCH_WARNING_THRESHOLD {
+                warnings.push(Warning::ShortEpoch {
+                    deme: deme.name().to_string(),
+                    epoch: i,
+                    duration,
+                });
+            }
+        }
+    }
+    for m in graph.migrations() {
+        if m.rate() >= MIGRATION_RATE_WARNING_THRESHOLD {
+            warnings.push(Warning::MigrationRateNearOne {
+                source: m.source().to_string(),
+                dest: m.dest().to_string(),
+                rate: m.rate(),
+            });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolved_graph_warnings() {
+        let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 100.000001
+    - start_size: 200
+      end_time: 100
+    - start_size: 100
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - source: A
+   dest: B
+   rate: 0.995
+ - source: B
+   dest: A
+   rate: 0.5
+";
+        let graph = crate::loads(yaml).unwrap();
+        let warnings = resolved_graph_warnings(&graph);
+        assert_eq!(warnings.len(), 2);
+        assert!(matches!(
+            &warnings[0],
+            Warning::ShortEpoch { deme, epoch: 1, .. } if deme == "A"
+        ));
+        assert!(matches!(
+            &warnings[1],
+            Warning::MigrationRateNearOne { source, dest, .. } if source == "A" && dest == "B"
+        ));
+    }
+}
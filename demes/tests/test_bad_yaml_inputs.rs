@@ -980,3 +980,63 @@ migrations:
         Err(e) => assert!(matches!(e, demes::DemesError::MigrationError(_))),
     }
 }
+
+#[test]
+fn test_error_locations() {
+    // Unknown field is reported by the parser
+    let yaml = "
+time_units: generations
+demes:
+  - name: A
+    epochs:
+      - start_size: 1000
+        bad_field: 1
+";
+    let e = demes::loads(yaml).unwrap_err();
+    let location = e.location().unwrap();
+    assert_eq!(location.line(), 7);
+
+    let yaml = "
+time_units: generations
+defaults:
+  epoch: {start_size: 1}
+demes:
+- {name: A}
+- {name: B}
+migrations:
+- {rate: 0.1, source: A, dest: B}
+- {rate: 0.1, source: A, dest: C}
+";
+    // Locations of resolution errors are opt-in
+    let e = demes::loads(yaml).unwrap_err();
+    assert!(matches!(e, demes::DemesError::MigrationError(_)));
+    assert!(e.location().is_none());
+    let options = demes::LoadOptions::default().set_locate_errors(true);
+    match demes::loads_with_options(yaml, &options) {
+        Err(demes::DemesError::Located { location, error }) => {
+            assert_eq!(location.line(), 10);
+            assert_eq!(location.column(), 3);
+            assert!(matches!(*error, demes::DemesError::MigrationError(_)));
+        }
+        _ => panic!("expected a located error"),
+    }
+}
+
+#[test]
+fn nan_pulse_time() {
+    let yaml = "
+time_units: generations
+defaults:
+  epoch: {start_size: 1}
+demes:
+- {name: A}
+- {name: B}
+pulses:
+- {sources: [A], dest: B, proportions: [0.1], time: 10}
+- {sources: [A], dest: B, proportions: [0.1], time: .nan}
+";
+    match demes::loads(yaml) {
+        Ok(_) => panic!("expected Err!"),
+        Err(e) => assert!(matches!(e, demes::DemesError::PulseError(_))),
+    }
+}
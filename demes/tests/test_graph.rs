@@ -1,4 +1,5 @@
 use demes::{AsymmetricMigration, MigrationRate, SizeFunction, Time};
+use std::sync::Arc;
 
 #[derive(Eq, PartialEq)]
 struct ExpectedMigration {
@@ -141,13 +142,13 @@ demes:
             // iterate over ancestor HashMap of {ancestor name => ancestor Deme index}
             for (name, index) in d.ancestor_names().iter().zip(d.ancestor_indexes().iter()) {
                 let deme = g.deme(*index);
-                assert_eq!(name, "A");
-                assert_eq!(*deme.name(), *name);
+                assert_eq!(&**name, "A");
+                assert_eq!(deme.name(), &**name);
                 assert_eq!(deme.num_ancestors(), 0);
             }
 
             // Iterate over just the names
-            assert!(d.ancestor_names().iter().all(|ancestor| *ancestor == "A"));
+            assert!(d.ancestor_names().iter().all(|ancestor| &**ancestor == "A"));
 
             // With only 1 ancestor, there is exactly 1 proportion
             // represeting 100% ancestry
@@ -231,7 +232,7 @@ migrations:
     assert_eq!(g.time_units().to_string(), "years".to_string());
     assert_eq!(g.migrations().len(), 8);
 
-    let expected_resolved_migrations = vec![
+    let expected_resolved_migrations = [
         ExpectedMigration::new("YRI", "OOA", 25e-5, 140e3, 21.2e3).unwrap(),
         ExpectedMigration::new("OOA", "YRI", 25e-5, 140e3, 21.2e3).unwrap(),
         ExpectedMigration::new("YRI", "CEU", 3e-5, 21.2e3, 0.0).unwrap(),
@@ -489,9 +490,9 @@ pulses:
         .map(|pulse| pulse.time())
         .collect::<Vec<Time>>();
     assert_eq!(pulse_times, expected_pulse_times);
-    assert_eq!(g.pulses()[0].sources(), &["A".to_string()]);
+    assert_eq!(g.pulses()[0].sources(), &[Arc::from("A")]);
     assert_eq!(g.pulses()[0].dest(), "B");
-    assert_eq!(g.pulses()[1].sources(), &["B".to_string()]);
+    assert_eq!(g.pulses()[1].sources(), &[Arc::from("B")]);
     assert_eq!(g.pulses()[1].dest(), "A");
     assert_graph_equality_after_round_trip!(g);
 
@@ -502,9 +503,9 @@ pulses:
         .map(|pulse| pulse.time())
         .collect::<Vec<Time>>();
     assert_eq!(pulse_times, expected_pulse_times);
-    assert_eq!(g2.pulses()[0].sources(), &["B".to_string()]);
+    assert_eq!(g2.pulses()[0].sources(), &[Arc::from("B")]);
     assert_eq!(g2.pulses()[0].dest(), "A");
-    assert_eq!(g2.pulses()[1].sources(), &["A".to_string()]);
+    assert_eq!(g2.pulses()[1].sources(), &[Arc::from("A")]);
     assert_eq!(g2.pulses()[1].dest(), "B");
 
     // The two graphs are not equal b/c the pulses
@@ -1027,3 +1028,197 @@ demes:
     assert!(g.deme(0).epochs()[0].size_at(-10).is_err());
     assert!(g.deme(0).epochs()[0].size_at(10).unwrap().is_none()); // time outside of epoch
 }
+
+#[test]
+fn test_metadata_validator() {
+    let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+";
+    let require_metadata = |md: Option<&demes::Metadata>| match md {
+        Some(_) => Ok(()),
+        None => Err("missing metadata"),
+    };
+    match demes::load_with_metadata_validator(yaml.as_bytes(), require_metadata) {
+        Err(demes::DemesError::MetadataError(msg)) => assert_eq!(msg.message(), "missing metadata"),
+        _ => panic!("expected a MetadataError"),
+    }
+    let g = demes::load_with_metadata_validator(yaml.as_bytes(), |md| {
+        assert!(md.is_none());
+        Ok::<(), String>(())
+    })
+    .unwrap();
+    assert_eq!(g, demes::loads(yaml).unwrap());
+}
+
+#[test]
+fn test_load_options() {
+    let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 10
+    - start_size: 200
+ - name: B
+   epochs:
+    - start_size: 100
+ - name: C
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B, C]
+   rate: 1e-3
+";
+    let graph = demes::loads_with_options(yaml, &demes::LoadOptions::default()).unwrap();
+    assert_eq!(graph, demes::loads(yaml).unwrap());
+    let options = demes::LoadOptions::default()
+        .set_max_demes(3)
+        .set_max_epochs(4)
+        .set_max_migrations(6)
+        .set_max_input_size(yaml.len());
+    assert!(demes::loads_with_options(yaml, &options).is_ok());
+    assert!(demes::load_with_options(yaml.as_bytes(), &options).is_ok());
+
+    let check = |result: Result<demes::Graph, demes::DemesError>, expected, found| match result {
+        Err(demes::DemesError::ResourceLimitExceeded {
+            limit,
+            max: _,
+            found: f,
+        }) => {
+            assert_eq!(limit, expected);
+            assert_eq!(f, found);
+        }
+        _ => panic!("expected a ResourceLimitExceeded error"),
+    };
+    for (options, limit, found) in [
+        (options.set_max_demes(2), demes::ResourceLimit::Demes, 3),
+        (options.set_max_epochs(3), demes::ResourceLimit::Epochs, 4),
+        (
+            options.set_max_migrations(5),
+            demes::ResourceLimit::Migrations,
+            6,
+        ),
+    ] {
+        check(demes::loads_with_options(yaml, &options), limit, found);
+        check(
+            demes::load_with_options(yaml.as_bytes(), &options),
+            limit,
+            found,
+        );
+    }
+    let options = options.set_max_input_size(10);
+    check(
+        demes::loads_with_options(yaml, &options),
+        demes::ResourceLimit::InputSize,
+        yaml.len(),
+    );
+    // The reader stops one byte past the limit
+    check(
+        demes::load_with_options(yaml.as_bytes(), &options),
+        demes::ResourceLimit::InputSize,
+        11,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_load_json_options() {
+    let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 10
+    - start_size: 200
+ - name: B
+   epochs:
+    - start_size: 100
+migrations:
+ - demes: [A, B]
+   rate: 1e-3
+";
+    let graph = demes::loads(yaml).unwrap();
+    let json = graph.as_json_string().unwrap();
+    let options = demes::LoadOptions::default()
+        .set_max_demes(2)
+        .set_max_epochs(3)
+        .set_max_migrations(2)
+        .set_max_input_size(json.len());
+    assert_eq!(
+        demes::loads_json_with_options(&json, &options).unwrap(),
+        graph
+    );
+    assert_eq!(
+        demes::load_json_with_options(json.as_bytes(), &options).unwrap(),
+        graph
+    );
+
+    for (options, limit) in [
+        (options.set_max_demes(1), demes::ResourceLimit::Demes),
+        (options.set_max_epochs(2), demes::ResourceLimit::Epochs),
+        (
+            options.set_max_migrations(1),
+            demes::ResourceLimit::Migrations,
+        ),
+        (
+            options.set_max_input_size(10),
+            demes::ResourceLimit::InputSize,
+        ),
+    ] {
+        for result in [
+            demes::loads_json_with_options(&json, &options),
+            demes::load_json_with_options(json.as_bytes(), &options),
+        ] {
+            match result {
+                Err(demes::DemesError::ResourceLimitExceeded { limit: l, .. }) => {
+                    assert_eq!(l, limit)
+                }
+                _ => panic!("expected a ResourceLimitExceeded error"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_loads_with_warnings() {
+    let yaml = "
+time_units: years
+generation_time: 25
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+      end_time: 100.000001
+    - start_size: 200
+      end_time: 100
+    - start_size: 100
+";
+    let (graph, warnings) = demes::loads_with_warnings(yaml).unwrap();
+    assert_eq!(graph, demes::loads(yaml).unwrap());
+    assert_eq!(
+        graph.as_string().unwrap(),
+        demes::loads(yaml).unwrap().as_string().unwrap()
+    );
+    assert_eq!(warnings.len(), 1);
+    assert!(matches!(
+        &warnings[0],
+        demes::Warning::ShortEpoch { deme, epoch: 1, .. } if deme == "A"
+    ));
+
+    let yaml = "
+time_units: generations
+demes:
+ - name: A
+   epochs:
+    - start_size: 100
+";
+    let (_, warnings) = demes::loads_with_warnings(yaml).unwrap();
+    assert!(warnings.is_empty());
+    assert!(demes::loads_with_warnings("time_units: generations").is_err());
+}